@@ -0,0 +1,625 @@
+//! A multi-producer, single-consumer queue for sending values between
+//! asynchronous tasks, backed by a fixed-capacity array.
+//!
+//! Unlike [`mpsc`](crate::mpsc), this channel doesn't need the standard
+//! library: it only takes the `alloc` feature, and never blocks a thread.
+//! The messages are kept in a ring buffer allocated when the channel is
+//! created, which the senders write to with atomic operations only. The
+//! senders waiting for room are kept in a list behind a spin lock, which is
+//! only held to add or take them.
+//!
+//! Each [`Sender`] reserves a slot of the buffer when it's
+//! [ready](Sender::poll_ready) to send a message, so that the message it
+//! sends next is always accepted. A task waiting for a slot is woken up once
+//! the receiver takes a message out of the channel.
+//!
+//! The stream of the [`Receiver`] ends once all the senders are dropped and
+//! all messages have been received. Sending fails once the [`Receiver`] is
+//! dropped or [closed](Receiver::close).
+
+use crate::lock::{Lock, TryLock};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::{self, MaybeUninit};
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::__internal::AtomicWaker;
+use futures_core::task::{Context, Poll};
+
+/// Creates a bounded multi-producer, single-consumer channel, which holds at
+/// most `capacity` messages.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::channel::bounded;
+/// use futures::future;
+/// use futures::sink::SinkExt;
+/// use futures::stream::StreamExt;
+///
+/// let (mut tx1, rx) = bounded::channel(2);
+/// let mut tx2 = tx1.clone();
+///
+/// let producers = future::join(
+///     async move {
+///         for i in 0..5 {
+///             tx1.send(i).await.unwrap();
+///         }
+///     },
+///     async move {
+///         for i in 5..10 {
+///             tx2.send(i).await.unwrap();
+///         }
+///     },
+/// );
+/// let (_, mut received) = future::join(producers, rx.collect::<Vec<i32>>()).await;
+/// received.sort();
+/// assert_eq!(received, (0..10).collect::<Vec<_>>());
+/// # });
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "bounded channel capacity must be greater than zero");
+
+    let shared = Arc::new(Shared {
+        buffer: (0..capacity.next_power_of_two())
+            .map(|pos| Slot {
+                pos: AtomicUsize::new(pos),
+                msg: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect(),
+        capacity,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        reserved: AtomicUsize::new(0),
+        num_senders: AtomicUsize::new(1),
+        closed: AtomicBool::new(false),
+        recv_task: AtomicWaker::new(),
+        send_tasks: Lock::new(Vec::new()),
+    });
+    (Sender::new(shared.clone()), Receiver { shared, terminated: false })
+}
+
+struct Shared<T> {
+    // The slots of the ring buffer, rounded up to a power of two so that a
+    // position still maps to the same slot once it wraps around.
+    buffer: Box<[Slot<T>]>,
+    capacity: usize,
+    // The positions of the next message to receive and of the next one to
+    // send, counting all messages ever sent. `head` is only written by the
+    // receiver.
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    // The number of messages in the channel, plus the number of slots
+    // reserved by the senders. It never exceeds the capacity.
+    reserved: AtomicUsize,
+    num_senders: AtomicUsize,
+    // Whether all the senders or the receiver have been dropped, or the
+    // receiver closed.
+    closed: AtomicBool,
+    recv_task: AtomicWaker,
+    // The senders waiting for a slot.
+    send_tasks: Lock<Vec<Arc<SenderTask>>>,
+}
+
+struct Slot<T> {
+    // One past the position of the message in the slot once it's written.
+    pos: AtomicUsize,
+    msg: UnsafeCell<MaybeUninit<T>>,
+}
+
+struct SenderTask {
+    task: AtomicWaker,
+    // Whether the sender is in `send_tasks`.
+    is_waiting: AtomicBool,
+}
+
+// A slot is written by the sender which reserved it and claimed its
+// position, and then read by the receiver once its position is updated, the
+// ownership of the message being transferred with that release store.
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    fn slot(&self, pos: usize) -> &Slot<T> {
+        &self.buffer[pos & (self.buffer.len() - 1)]
+    }
+
+    fn reserve(&self) -> bool {
+        let mut reserved = self.reserved.load(Ordering::Relaxed);
+        loop {
+            if reserved == self.capacity {
+                return false;
+            }
+            match self.reserved.compare_exchange_weak(
+                reserved,
+                reserved + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => reserved = actual,
+            }
+        }
+    }
+
+    fn unreserve(&self) {
+        self.reserved.fetch_sub(1, Ordering::Release);
+        self.wake_senders();
+    }
+
+    // Must only be called with a reserved slot.
+    fn push(&self, msg: T) {
+        let pos = self.tail.fetch_add(1, Ordering::Relaxed);
+        let slot = self.slot(pos);
+        unsafe { (*slot.msg.get()).as_mut_ptr().write(msg) };
+        slot.pos.store(pos.wrapping_add(1), Ordering::Release);
+    }
+
+    // Must only be called by the receiver.
+    fn pop(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let slot = self.slot(head);
+        // The slot may also hold a message a lap behind, already received,
+        // or one whose sender hasn't finished writing it.
+        if slot.pos.load(Ordering::Acquire) != head.wrapping_add(1) {
+            return None;
+        }
+
+        let msg = unsafe { (*slot.msg.get()).as_ptr().read() };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        self.unreserve();
+        Some(msg)
+    }
+
+    fn len(&self) -> usize {
+        self.tail.load(Ordering::Acquire).wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+    }
+
+    fn send_tasks(&self) -> TryLock<'_, Vec<Arc<SenderTask>>> {
+        // The lock is only held to push or take the tasks, so it's never
+        // contended for long.
+        loop {
+            if let Some(send_tasks) = self.send_tasks.try_lock() {
+                return send_tasks;
+            }
+        }
+    }
+
+    fn wake_senders(&self) {
+        let send_tasks = mem::replace(&mut *self.send_tasks(), Vec::new());
+        for task in send_tasks {
+            task.is_waiting.store(false, Ordering::SeqCst);
+            task.task.wake();
+        }
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// The transmission end of a bounded channel.
+///
+/// This value is created by the [`channel`] function.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+    task: Arc<SenderTask>,
+    // Whether this sender holds a reserved slot.
+    has_slot: bool,
+}
+
+/// The receiving end of a bounded channel.
+///
+/// This value is created by the [`channel`] function.
+#[must_use = "streams do nothing unless polled"]
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    terminated: bool,
+}
+
+/// The error type for [`Sender`s](Sender) used as `Sink`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SendError {
+    kind: SendErrorKind,
+}
+
+/// The error type returned from [`try_send`](Sender::try_send).
+#[derive(Clone, PartialEq, Eq)]
+pub struct TrySendError<T> {
+    err: SendError,
+    val: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SendErrorKind {
+    Full,
+    Disconnected,
+}
+
+/// The error type returned from [`try_next`](Receiver::try_next).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TryRecvError {
+    _priv: (),
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_full() {
+            write!(f, "send failed because channel is full")
+        } else {
+            write!(f, "send failed because receiver is gone")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SendError {}
+
+impl SendError {
+    /// Returns `true` if this error is a result of the channel being full.
+    pub fn is_full(&self) -> bool {
+        self.kind == SendErrorKind::Full
+    }
+
+    /// Returns `true` if this error is a result of the receiver being dropped
+    /// or closed.
+    pub fn is_disconnected(&self) -> bool {
+        self.kind == SendErrorKind::Disconnected
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrySendError").field("kind", &self.err.kind).finish()
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.err, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: core::any::Any> std::error::Error for TrySendError<T> {}
+
+impl<T> TrySendError<T> {
+    /// Returns `true` if this error is a result of the channel being full.
+    pub fn is_full(&self) -> bool {
+        self.err.is_full()
+    }
+
+    /// Returns `true` if this error is a result of the receiver being dropped
+    /// or closed.
+    pub fn is_disconnected(&self) -> bool {
+        self.err.is_disconnected()
+    }
+
+    /// Returns the message that was attempted to be sent but failed.
+    pub fn into_inner(self) -> T {
+        self.val
+    }
+
+    /// Drops the message and converts into a `SendError`.
+    pub fn into_send_error(self) -> SendError {
+        self.err
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiver channel is empty")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryRecvError {}
+
+impl<T> Sender<T> {
+    fn new(shared: Arc<Shared<T>>) -> Self {
+        let task =
+            Arc::new(SenderTask { task: AtomicWaker::new(), is_waiting: AtomicBool::new(false) });
+        Self { shared, task, has_slot: false }
+    }
+
+    /// Polls the channel to determine if there is room to send a message.
+    ///
+    /// Once this returns `Poll::Ready(Ok(_))`, a slot of the channel is
+    /// reserved for the next message sent by this sender. Otherwise, the
+    /// current task is woken up once a slot may be available. An error is
+    /// returned if the receiver has been dropped or closed.
+    pub fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SendError>> {
+        if self.shared.is_closed() {
+            return Poll::Ready(Err(SendError { kind: SendErrorKind::Disconnected }));
+        }
+        if self.has_slot || self.try_reserve() {
+            return Poll::Ready(Ok(()));
+        }
+
+        self.task.task.register(cx.waker());
+        if !self.task.is_waiting.swap(true, Ordering::SeqCst) {
+            self.shared.send_tasks().push(self.task.clone());
+        }
+
+        // Check again, in case the receiver made room before the task was
+        // registered.
+        if self.shared.is_closed() {
+            Poll::Ready(Err(SendError { kind: SendErrorKind::Disconnected }))
+        } else if self.try_reserve() {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn try_reserve(&mut self) -> bool {
+        self.has_slot = self.shared.reserve();
+        self.has_slot
+    }
+
+    /// Attempts to send a message on this `Sender`, returning the message
+    /// if there was an error.
+    pub fn try_send(&mut self, msg: T) -> Result<(), TrySendError<T>> {
+        if self.shared.is_closed() {
+            return Err(TrySendError {
+                err: SendError { kind: SendErrorKind::Disconnected },
+                val: msg,
+            });
+        }
+        if !self.has_slot && !self.try_reserve() {
+            return Err(TrySendError { err: SendError { kind: SendErrorKind::Full }, val: msg });
+        }
+
+        self.has_slot = false;
+        self.shared.push(msg);
+        self.shared.recv_task.wake();
+        Ok(())
+    }
+
+    /// Send a message on the channel.
+    ///
+    /// This function should only be called after
+    /// [`poll_ready`](Sender::poll_ready) has reported that the channel is
+    /// ready to receive a message.
+    pub fn start_send(&mut self, msg: T) -> Result<(), SendError> {
+        self.try_send(msg).map_err(|e| e.err)
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    /// Returns `true` if there are no messages in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of messages the channel can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// Returns whether the receiver has been dropped or closed.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.num_senders.fetch_add(1, Ordering::Relaxed);
+        Self::new(self.shared.clone())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.has_slot {
+            self.shared.unreserve();
+        }
+        if self.shared.num_senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.close();
+            self.shared.recv_task.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").field("len", &self.len()).finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Closes the receiving half of the channel, preventing any further
+    /// messages from being sent.
+    ///
+    /// The messages already in the channel can still be received.
+    pub fn close(&mut self) {
+        self.shared.close();
+        self.shared.wake_senders();
+    }
+
+    /// Tries to receive the next message without notifying a context if
+    /// empty.
+    ///
+    /// This function returns:
+    /// * `Ok(Some(t))` when a message is fetched
+    /// * `Ok(None)` when all the senders are dropped and no messages are left
+    /// * `Err(e)` when there are no messages available, but the channel is not
+    ///   closed yet
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        self.next_message().ok_or(TryRecvError { _priv: () })
+    }
+
+    fn next_message(&mut self) -> Option<Option<T>> {
+        if self.terminated {
+            return Some(None);
+        }
+
+        if let Some(msg) = self.shared.pop() {
+            return Some(Some(msg));
+        }
+
+        if self.shared.is_closed() && self.shared.len() == 0 {
+            // The senders may have sent last messages before the channel
+            // was closed.
+            if let Some(msg) = self.shared.pop() {
+                return Some(Some(msg));
+            }
+            self.terminated = true;
+            return Some(None);
+        }
+
+        None
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    /// Returns `true` if there are no messages in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of messages the channel can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if let Some(msg) = self.next_message() {
+            return Poll::Ready(msg);
+        }
+
+        self.shared.recv_task.register(cx.waker());
+
+        // Check again, in case a message was sent before the task was
+        // registered.
+        match self.next_message() {
+            Some(msg) => Poll::Ready(msg),
+            None => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.terminated {
+            (0, Some(0))
+        } else {
+            (self.len(), None)
+        }
+    }
+}
+
+impl<T> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.close();
+        self.shared.wake_senders();
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+#[cfg(feature = "sink")]
+mod sink_impl {
+    use super::{SendError, Sender};
+    use core::pin::Pin;
+    use futures_core::task::{Context, Poll};
+    use futures_sink::Sink;
+
+    impl<T> Sink<T> for Sender<T> {
+        type Error = SendError;
+
+        fn poll_ready(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            (*self).poll_ready(cx)
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, msg: T) -> Result<(), Self::Error> {
+            (*self).start_send(msg)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::channel;
+    use core::sync::atomic::Ordering;
+
+    #[test]
+    fn positions_wrap_around() {
+        let (tx, rx) = channel(3);
+        let start = usize::MAX - 4;
+        rx.shared.head.store(start, Ordering::Relaxed);
+        tx.shared.tail.store(start, Ordering::Relaxed);
+        // Stamp the slots as if they were last written a lap before `start`.
+        for (i, slot) in tx.shared.buffer.iter().enumerate() {
+            slot.pos.store((start & !(tx.shared.buffer.len() - 1)) | i, Ordering::Relaxed);
+        }
+
+        for i in 0..10 {
+            for msg in &[i, i + 100] {
+                assert!(tx.shared.reserve());
+                tx.shared.push(*msg);
+            }
+            assert_eq!(tx.shared.len(), 2);
+            assert_eq!(rx.shared.pop(), Some(i));
+            assert_eq!(rx.shared.pop(), Some(i + 100));
+            assert_eq!(rx.shared.pop(), None);
+        }
+        for i in 0..3 {
+            assert!(tx.shared.reserve());
+            tx.shared.push(i);
+        }
+        assert!(!tx.shared.reserve());
+    }
+}
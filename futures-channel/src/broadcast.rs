@@ -0,0 +1,366 @@
+//! A multi-producer, multi-consumer broadcast channel.
+//!
+//! Every value sent on a broadcast channel is seen by every [`Receiver`]
+//! subscribed to it at the time it was sent. Values are kept in a ring buffer
+//! of a fixed capacity, which is shared by all receivers: sending never
+//! waits, and once the buffer is full, each new value overwrites the oldest
+//! one. A receiver which falls so far behind that values it hasn't seen yet
+//! are overwritten is notified with a [`Lagged`] error carrying the number of
+//! values it missed, and then continues with the oldest value still
+//! available.
+//!
+//! The stream of a [`Receiver`] ends once all [`Sender`]s are dropped and it
+//! has received all remaining values.
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll, Waker};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::sync::SgxMutex as Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(not(feature = "phala-sgx"))]
+use std::sync::Mutex;
+
+/// Creates a new broadcast channel, returning its sending half and a first
+/// receiving half.
+///
+/// The channel keeps at most `capacity` values around for receivers which
+/// haven't seen them yet. More receivers can be created by cloning a
+/// [`Receiver`] or with [`Sender::subscribe`].
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::channel::broadcast;
+/// use futures::stream::StreamExt;
+///
+/// let (tx, mut rx1) = broadcast::channel(16);
+/// let mut rx2 = tx.subscribe();
+///
+/// tx.send(1).unwrap();
+/// tx.send(2).unwrap();
+/// drop(tx);
+///
+/// assert_eq!(rx1.next().await, Some(Ok(1)));
+/// assert_eq!(rx1.next().await, Some(Ok(2)));
+/// assert_eq!(rx1.next().await, None);
+/// assert_eq!(rx2.next().await, Some(Ok(1)));
+/// # });
+/// ```
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "broadcast channel capacity must be greater than zero");
+
+    let shared = Arc::new(Shared {
+        capacity,
+        state: Mutex::new(State {
+            buffer: VecDeque::with_capacity(capacity),
+            head: 0,
+            num_senders: 1,
+            num_receivers: 1,
+            next_receiver_id: 1,
+            wakers: HashMap::new(),
+        }),
+    });
+    let receiver = Receiver { shared: shared.clone(), id: 0, pos: 0, terminated: false };
+    (Sender { shared }, receiver)
+}
+
+struct Shared<T> {
+    capacity: usize,
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    // Values which can still be received, `buffer[0]` being the value at
+    // position `head` in the sequence of all values sent.
+    buffer: VecDeque<T>,
+    head: u64,
+
+    num_senders: usize,
+    num_receivers: usize,
+
+    // Wakers of the receivers waiting for a value, by receiver id.
+    next_receiver_id: usize,
+    wakers: HashMap<usize, Waker>,
+}
+
+impl<T> State<T> {
+    // The position of the next value to be sent.
+    fn tail(&self) -> u64 {
+        self.head + self.buffer.len() as u64
+    }
+
+    // Receives the value at `pos`, moving `pos` to the next value.
+    fn recv(&self, pos: &mut u64) -> Result<T, TryRecvError>
+    where
+        T: Clone,
+    {
+        if *pos < self.head {
+            let missed = self.head - *pos;
+            *pos = self.head;
+            return Err(TryRecvError::Lagged(missed));
+        }
+
+        match self.buffer.get((*pos - self.head) as usize) {
+            Some(value) => {
+                *pos += 1;
+                Ok(value.clone())
+            }
+            None if self.num_senders == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    fn wake_all(&mut self) {
+        for (_, waker) in self.wakers.drain() {
+            waker.wake();
+        }
+    }
+}
+
+/// The transmission end of a broadcast channel.
+///
+/// This value is created by the [`channel`] function, and can be cloned to
+/// send values from several places.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The receiving end of a broadcast channel.
+///
+/// This value is created by the [`channel`] function or by
+/// [`Sender::subscribe`]. Cloning a receiver creates a new receiver which
+/// will see the same values as the original one from then on.
+#[must_use = "streams do nothing unless polled"]
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    id: usize,
+    // The position of the next value to receive.
+    pos: u64,
+    terminated: bool,
+}
+
+/// The error returned by [`Sender::send`] when there are no receivers left,
+/// containing the value which couldn't be sent.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+/// The error produced by a [`Receiver`] which fell behind the senders,
+/// containing the number of values it missed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lagged(pub u64);
+
+/// The error returned from [`Receiver::try_recv`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// There is no value to receive yet.
+    Empty,
+    /// All senders have been dropped and all values have been received.
+    Closed,
+    /// The receiver fell behind the senders and missed the given number of
+    /// values. The next call receives the oldest value still available.
+    Lagged(u64),
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SendError").finish()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "send failed because all receivers are gone")
+    }
+}
+
+impl<T: core::any::Any> std::error::Error for SendError<T> {}
+
+impl fmt::Display for Lagged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiver lagged behind and missed {} values", self.0)
+    }
+}
+
+impl std::error::Error for Lagged {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "receiver channel is empty"),
+            TryRecvError::Closed => write!(f, "receiver channel is closed"),
+            TryRecvError::Lagged(n) => {
+                write!(f, "receiver lagged behind and missed {} values", n)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+impl<T> Sender<T> {
+    /// Sends a value to all receivers, returning the number of receivers it
+    /// was sent to.
+    ///
+    /// This never waits: if the channel is full, the oldest value is
+    /// overwritten. An error containing the value is returned if there are no
+    /// receivers left.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.num_receivers == 0 {
+            return Err(SendError(value));
+        }
+
+        if state.buffer.len() == self.shared.capacity {
+            state.buffer.pop_front();
+            state.head += 1;
+        }
+        state.buffer.push_back(value);
+        state.wake_all();
+        Ok(state.num_receivers)
+    }
+
+    /// Creates a new receiver, which receives all values sent after this call.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut state = self.shared.state.lock().unwrap();
+        let id = state.next_receiver_id;
+        state.next_receiver_id += 1;
+        state.num_receivers += 1;
+        Receiver { shared: self.shared.clone(), id, pos: state.tail(), terminated: false }
+    }
+
+    /// Returns the number of receivers currently subscribed to the channel.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.state.lock().unwrap().num_receivers
+    }
+
+    /// Returns whether all receivers have been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.receiver_count() == 0
+    }
+
+    /// Returns whether the senders send to the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.state.lock().unwrap().num_senders += 1;
+        Self { shared: self.shared.clone() }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.num_senders -= 1;
+        if state.num_senders == 0 {
+            state.wake_all();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Tries to receive the next value, without waiting for one to be sent.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.shared.state.lock().unwrap().recv(&mut self.pos)
+    }
+}
+
+impl<T: Clone> Stream for Receiver<T> {
+    type Item = Result<T, Lagged>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.terminated {
+            return Poll::Ready(None);
+        }
+
+        let mut state = this.shared.state.lock().unwrap();
+        match state.recv(&mut this.pos) {
+            Ok(value) => Poll::Ready(Some(Ok(value))),
+            Err(TryRecvError::Lagged(missed)) => Poll::Ready(Some(Err(Lagged(missed)))),
+            Err(TryRecvError::Closed) => {
+                this.terminated = true;
+                Poll::Ready(None)
+            }
+            Err(TryRecvError::Empty) => {
+                state.wakers.insert(this.id, cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T: Clone> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        let mut state = self.shared.state.lock().unwrap();
+        let id = state.next_receiver_id;
+        state.next_receiver_id += 1;
+        state.num_receivers += 1;
+        Self { shared: self.shared.clone(), id, pos: self.pos, terminated: self.terminated }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.num_receivers -= 1;
+        state.wakers.remove(&self.id);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").field("terminated", &self.terminated).finish()
+    }
+}
+
+#[cfg(feature = "sink")]
+mod sink_impl {
+    use super::{SendError, Sender};
+    use futures_core::task::{Context, Poll};
+    use futures_sink::Sink;
+    use std::pin::Pin;
+
+    impl<T> Sink<T> for Sender<T> {
+        type Error = SendError<T>;
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, msg: T) -> Result<(), Self::Error> {
+            self.send(msg).map(drop)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! A multi-producer channel routing each message to the mailbox of its key.
+//!
+//! Senders tag each message with a key, and the message is delivered to the
+//! [`Receiver`] subscribed to that key, if any. Each key is its own mailbox:
+//! messages sent to the same key are received in order, independently of the
+//! other keys. This is a building block for actor-style routing, without a
+//! map of channels and a router task in between.
+//!
+//! A key has at most one receiver at a time. Dropping the receiver removes
+//! the key's mailbox and the messages left in it, after which messages sent
+//! to the key are rejected until a new receiver subscribes to it.
+//!
+//! The stream of a [`Receiver`] ends once all [`Sender`]s are dropped and its
+//! mailbox is empty.
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll, Waker};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::sync::SgxMutex as Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(not(feature = "phala-sgx"))]
+use std::sync::Mutex;
+
+/// Creates a new keyed channel, returning its first sender.
+///
+/// Receivers are created by [subscribing](Sender::subscribe) to keys.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::channel::keyed;
+/// use futures::stream::StreamExt;
+///
+/// let tx = keyed::channel();
+/// let mut alice = tx.subscribe("alice").unwrap();
+/// let mut bob = tx.subscribe("bob").unwrap();
+///
+/// tx.send("alice", 1).unwrap();
+/// tx.send("bob", 2).unwrap();
+/// tx.send("alice", 3).unwrap();
+/// assert!(tx.send("carol", 4).is_err());
+/// drop(tx);
+///
+/// assert_eq!(alice.by_ref().collect::<Vec<_>>().await, vec![1, 3]);
+/// assert_eq!(bob.next().await, Some(2));
+/// # });
+/// ```
+pub fn channel<K, T>() -> Sender<K, T>
+where
+    K: Eq + Hash + Clone,
+{
+    let shared =
+        Arc::new(Shared { state: Mutex::new(State { mailboxes: HashMap::new(), num_senders: 1 }) });
+    Sender { shared }
+}
+
+struct Shared<K, T> {
+    state: Mutex<State<K, T>>,
+}
+
+struct State<K, T> {
+    // The mailboxes of the keys which have a receiver.
+    mailboxes: HashMap<K, Mailbox<T>>,
+    num_senders: usize,
+}
+
+struct Mailbox<T> {
+    queue: VecDeque<T>,
+    // The task of the receiver waiting for a message.
+    task: Option<Waker>,
+}
+
+impl<T> Mailbox<T> {
+    fn wake(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.wake();
+        }
+    }
+}
+
+/// The transmission end of a keyed channel.
+///
+/// This value is created by the [`channel`] function, and can be cloned to
+/// send messages from several places.
+pub struct Sender<K, T> {
+    shared: Arc<Shared<K, T>>,
+}
+
+/// The receiving end of a keyed channel, receiving the messages sent to one
+/// key.
+///
+/// This value is created by the [`Sender::subscribe`] method.
+#[must_use = "streams do nothing unless polled"]
+pub struct Receiver<K: Eq + Hash, T> {
+    shared: Arc<Shared<K, T>>,
+    key: K,
+    terminated: bool,
+}
+
+// `Pin<&mut Receiver<K, T>>` is never projected to `Pin<&mut K>`
+impl<K: Eq + Hash, T> Unpin for Receiver<K, T> {}
+
+/// The error returned by [`Sender::send`] when no receiver is subscribed to
+/// the key, containing the key and the message which couldn't be sent.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<K, T>(pub K, pub T);
+
+/// The error type returned from [`try_next`](Receiver::try_next).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TryRecvError {
+    _priv: (),
+}
+
+impl<K, T> fmt::Debug for SendError<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SendError").finish()
+    }
+}
+
+impl<K, T> fmt::Display for SendError<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "send failed because no receiver is subscribed to the key")
+    }
+}
+
+impl<K: core::any::Any, T: core::any::Any> std::error::Error for SendError<K, T> {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiver channel is empty")
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+impl<K: Eq + Hash + Clone, T> Sender<K, T> {
+    /// Sends a message to the mailbox of the given key.
+    ///
+    /// An error containing the key and the message is returned if no
+    /// receiver is subscribed to the key.
+    pub fn send(&self, key: K, msg: T) -> Result<(), SendError<K, T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.mailboxes.get_mut(&key) {
+            Some(mailbox) => {
+                mailbox.queue.push_back(msg);
+                mailbox.wake();
+                Ok(())
+            }
+            None => Err(SendError(key, msg)),
+        }
+    }
+
+    /// Creates the receiver of the mailbox of the given key.
+    ///
+    /// Returns `None` if a receiver is already subscribed to the key.
+    pub fn subscribe(&self, key: K) -> Option<Receiver<K, T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.mailboxes.contains_key(&key) {
+            return None;
+        }
+
+        state.mailboxes.insert(key.clone(), Mailbox { queue: VecDeque::new(), task: None });
+        Some(Receiver { shared: self.shared.clone(), key, terminated: false })
+    }
+
+    /// Returns whether a receiver is subscribed to the given key.
+    pub fn is_subscribed(&self, key: &K) -> bool {
+        self.shared.state.lock().unwrap().mailboxes.contains_key(key)
+    }
+
+    /// Returns the number of keys with a receiver subscribed to them.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.state.lock().unwrap().mailboxes.len()
+    }
+
+    /// Returns whether the senders send to the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<K, T> Clone for Sender<K, T> {
+    fn clone(&self) -> Self {
+        self.shared.state.lock().unwrap().num_senders += 1;
+        Self { shared: self.shared.clone() }
+    }
+}
+
+impl<K, T> Drop for Sender<K, T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.num_senders -= 1;
+        if state.num_senders == 0 {
+            for mailbox in state.mailboxes.values_mut() {
+                mailbox.wake();
+            }
+        }
+    }
+}
+
+impl<K, T> fmt::Debug for Sender<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<K: Eq + Hash, T> Receiver<K, T> {
+    /// Returns the key this receiver is subscribed to.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Tries to receive the next message without notifying a context if
+    /// empty.
+    ///
+    /// This function returns:
+    /// * `Ok(Some(t))` when a message is fetched
+    /// * `Ok(None)` when all senders are dropped and the mailbox is empty
+    /// * `Err(e)` when there are no messages available, but the channel is not
+    ///   closed yet
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        let shared = self.shared.clone();
+        let mut state = shared.state.lock().unwrap();
+        self.next_message(&mut state).ok_or(TryRecvError { _priv: () })
+    }
+
+    fn next_message(&mut self, state: &mut State<K, T>) -> Option<Option<T>> {
+        if self.terminated {
+            return Some(None);
+        }
+
+        let num_senders = state.num_senders;
+        let mailbox = state.mailboxes.get_mut(&self.key).expect("mailbox of a live receiver");
+        match mailbox.queue.pop_front() {
+            Some(msg) => Some(Some(msg)),
+            None if num_senders == 0 => {
+                self.terminated = true;
+                Some(None)
+            }
+            None => None,
+        }
+    }
+
+    /// Returns the number of messages waiting in the mailbox.
+    pub fn len(&self) -> usize {
+        let state = self.shared.state.lock().unwrap();
+        state.mailboxes.get(&self.key).map_or(0, |mailbox| mailbox.queue.len())
+    }
+
+    /// Returns `true` if there are no messages waiting in the mailbox.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Eq + Hash, T> Stream for Receiver<K, T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = &mut *self;
+        let shared = this.shared.clone();
+        let mut state = shared.state.lock().unwrap();
+        match this.next_message(&mut state) {
+            Some(msg) => Poll::Ready(msg),
+            None => {
+                let mailbox = state.mailboxes.get_mut(&this.key).unwrap();
+                mailbox.task = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<K: Eq + Hash, T> FusedStream for Receiver<K, T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<K: Eq + Hash, T> Drop for Receiver<K, T> {
+    fn drop(&mut self) {
+        let mailbox = self.shared.state.lock().unwrap().mailboxes.remove(&self.key);
+        // Drop the messages left in the mailbox outside of the lock.
+        drop(mailbox);
+    }
+}
+
+impl<K: Eq + Hash + fmt::Debug, T> fmt::Debug for Receiver<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("key", &self.key)
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+#[cfg(feature = "sink")]
+mod sink_impl {
+    use super::{SendError, Sender};
+    use futures_core::task::{Context, Poll};
+    use futures_sink::Sink;
+    use std::hash::Hash;
+    use std::pin::Pin;
+
+    impl<K: Eq + Hash + Clone, T> Sink<(K, T)> for Sender<K, T> {
+        type Error = SendError<K, T>;
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, (key, msg): (K, T)) -> Result<(), Self::Error> {
+            self.send(key, msg)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
@@ -7,9 +7,32 @@
 //! - [mpsc], a multi-producer, single-consumer channel for sending values
 //!   between tasks, analogous to the similarly-named structure in the standard
 //!   library.
+//! - [keyed], a multi-producer channel routing each value to the receiver
+//!   subscribed to its key.
+//! - [mpmc], a multi-producer, multi-consumer channel delivering each value
+//!   to one of its receivers.
+//! - [priority], a multi-producer, single-consumer channel with several
+//!   priority lanes.
+//! - [broadcast], a multi-producer, multi-consumer channel delivering every
+//!   value to every receiver.
+//! - [spsc], a single-producer, single-consumer channel backed by a
+//!   fixed-capacity ring buffer.
+//! - [bounded], a multi-producer, single-consumer channel backed by a
+//!   fixed-capacity ring buffer.
+//! - [watch], a single-producer, multi-consumer channel retaining only the
+//!   latest value.
 //!
 //! All items are only available when the `std` or `alloc` feature of this
 //! library is activated, and it is activated by default.
+//!
+//! Without the `std` feature, such as inside an SGX enclave built without
+//! the standard library, only [oneshot], [spsc] and [bounded] are available.
+//! They are built on atomic operations and spin locks alone, and never block
+//! a thread.
+//!
+//! With the `phala-sgx` feature, or its `mesalock_sgx` alias, the other
+//! channels lock the mutexes of `sgx_tstd` and yield their thread through it,
+//! so that they can be used inside an enclave as well.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(
@@ -31,12 +54,33 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+pub mod bounded;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub mod broadcast;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub mod keyed;
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
 mod lock;
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "std")]
+pub mod mpmc;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
 pub mod mpsc;
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
 pub mod oneshot;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub mod priority;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+pub mod spsc;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub mod watch;
@@ -0,0 +1,490 @@
+//! A multi-producer, multi-consumer queue for distributing values across
+//! asynchronous tasks.
+//!
+//! Unlike with [`mpsc`](crate::mpsc), the [`Receiver`] of this channel can be
+//! cloned, and all receivers compete for the messages: each message is
+//! delivered to exactly one of them. This makes it possible to share work
+//! between a pool of tasks without a distributor in front of them. Receivers
+//! waiting for a message are woken up in the order they started waiting.
+//!
+//! Channels are either bounded, created by [`channel`], in which case
+//! [`Sender`]s wait for room in the channel before sending, or unbounded,
+//! created by [`unbounded`].
+//!
+//! The stream of a [`Receiver`] ends once all [`Sender`]s are dropped and no
+//! messages are left. Sending fails once all receivers are dropped.
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll, Waker};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::sync::SgxMutex as Mutex;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(not(feature = "phala-sgx"))]
+use std::sync::Mutex;
+
+/// Creates a bounded multi-producer, multi-consumer channel, which holds at
+/// most `buffer` messages.
+///
+/// # Panics
+///
+/// Panics if `buffer` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::channel::mpmc;
+/// use futures::future;
+/// use futures::sink::SinkExt;
+/// use futures::stream::StreamExt;
+///
+/// let (mut tx, rx) = mpmc::channel(4);
+///
+/// let producer = async move {
+///     for i in 0..10 {
+///         tx.send(i).await.unwrap();
+///     }
+/// };
+/// let workers = future::join_all((0..2).map(|_| rx.clone().collect::<Vec<i32>>()));
+/// drop(rx);
+///
+/// let ((), received) = future::join(producer, workers).await;
+/// let mut all = received.concat();
+/// all.sort();
+/// assert_eq!(all, (0..10).collect::<Vec<_>>());
+/// # });
+/// ```
+pub fn channel<T>(buffer: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(buffer > 0, "mpmc channel buffer must be greater than zero");
+    new(Some(buffer))
+}
+
+/// Creates an unbounded multi-producer, multi-consumer channel.
+///
+/// Sending on an unbounded channel never waits, so it can grow without limit
+/// if the receivers don't keep up.
+pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
+    new(None)
+}
+
+fn new<T>(capacity: Option<usize>) -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        capacity,
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            reserved: 0,
+            num_senders: 1,
+            num_receivers: 1,
+            next_id: 2,
+            recv_wakers: VecDeque::new(),
+            send_wakers: VecDeque::new(),
+        }),
+    });
+    let sender = Sender { shared: shared.clone(), id: 0, reserved: false };
+    (sender, Receiver { shared, id: 1, terminated: false })
+}
+
+struct Shared<T> {
+    // `None` for unbounded channels.
+    capacity: Option<usize>,
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    // Room reserved by senders for which `poll_ready` succeeded.
+    reserved: usize,
+
+    num_senders: usize,
+    num_receivers: usize,
+
+    // Wakers of the receivers and senders waiting on the channel, by handle
+    // id, in the order they started waiting.
+    next_id: usize,
+    recv_wakers: VecDeque<(usize, Waker)>,
+    send_wakers: VecDeque<(usize, Waker)>,
+}
+
+impl<T> State<T> {
+    fn has_room(&self, capacity: Option<usize>) -> bool {
+        capacity.map_or(true, |capacity| self.queue.len() + self.reserved < capacity)
+    }
+
+    fn next_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+fn register(wakers: &mut VecDeque<(usize, Waker)>, id: usize, waker: &Waker) {
+    match wakers.iter_mut().find(|(i, _)| *i == id) {
+        Some((_, w)) => {
+            if !w.will_wake(waker) {
+                *w = waker.clone();
+            }
+        }
+        None => wakers.push_back((id, waker.clone())),
+    }
+}
+
+fn unregister(wakers: &mut VecDeque<(usize, Waker)>, id: usize) {
+    wakers.retain(|(i, _)| *i != id);
+}
+
+fn wake_one(wakers: &mut VecDeque<(usize, Waker)>) {
+    if let Some((_, waker)) = wakers.pop_front() {
+        waker.wake();
+    }
+}
+
+fn wake_all(wakers: &mut VecDeque<(usize, Waker)>) {
+    for (_, waker) in wakers.drain(..) {
+        waker.wake();
+    }
+}
+
+/// The transmission end of an mpmc channel.
+///
+/// This value is created by the [`channel`] and [`unbounded`] functions.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+    id: usize,
+    // Whether this sender holds room reserved by `poll_ready`.
+    reserved: bool,
+}
+
+/// The receiving end of an mpmc channel.
+///
+/// This value is created by the [`channel`] and [`unbounded`] functions.
+/// Cloning it creates another receiver competing for the same messages.
+#[must_use = "streams do nothing unless polled"]
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    id: usize,
+    terminated: bool,
+}
+
+/// The error type for [`Sender`s](Sender) used as `Sink`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SendError {
+    kind: SendErrorKind,
+}
+
+/// The error type returned from [`try_send`](Sender::try_send).
+#[derive(Clone, PartialEq, Eq)]
+pub struct TrySendError<T> {
+    err: SendError,
+    val: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SendErrorKind {
+    Full,
+    Disconnected,
+}
+
+/// The error type returned from [`try_next`](Receiver::try_next).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TryRecvError {
+    _priv: (),
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_full() {
+            write!(f, "send failed because channel is full")
+        } else {
+            write!(f, "send failed because all receivers are gone")
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+impl SendError {
+    /// Returns `true` if this error is a result of the channel being full.
+    pub fn is_full(&self) -> bool {
+        self.kind == SendErrorKind::Full
+    }
+
+    /// Returns `true` if this error is a result of all receivers being
+    /// dropped.
+    pub fn is_disconnected(&self) -> bool {
+        self.kind == SendErrorKind::Disconnected
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrySendError").field("kind", &self.err.kind).finish()
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.err, f)
+    }
+}
+
+impl<T: core::any::Any> std::error::Error for TrySendError<T> {}
+
+impl<T> TrySendError<T> {
+    /// Returns `true` if this error is a result of the channel being full.
+    pub fn is_full(&self) -> bool {
+        self.err.is_full()
+    }
+
+    /// Returns `true` if this error is a result of all receivers being
+    /// dropped.
+    pub fn is_disconnected(&self) -> bool {
+        self.err.is_disconnected()
+    }
+
+    /// Returns the message that was attempted to be sent but failed.
+    pub fn into_inner(self) -> T {
+        self.val
+    }
+
+    /// Drops the message and converts into a `SendError`.
+    pub fn into_send_error(self) -> SendError {
+        self.err
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiver channel is empty")
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+impl<T> Sender<T> {
+    /// Polls the channel to determine if there is room to send a message.
+    ///
+    /// The room reported by `Poll::Ready(Ok(_))` is reserved for this
+    /// `Sender` until the next call to [`start_send`](Sender::start_send) or
+    /// [`try_send`](Sender::try_send). Otherwise, the current task is woken
+    /// up once room is available. An error is returned if all receivers have
+    /// been dropped.
+    pub fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SendError>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.num_receivers == 0 {
+            return Poll::Ready(Err(SendError { kind: SendErrorKind::Disconnected }));
+        }
+
+        if !self.reserved {
+            if !state.has_room(self.shared.capacity) {
+                register(&mut state.send_wakers, self.id, cx.waker());
+                return Poll::Pending;
+            }
+            state.reserved += 1;
+            self.reserved = true;
+        }
+        unregister(&mut state.send_wakers, self.id);
+        Poll::Ready(Ok(()))
+    }
+
+    /// Attempts to send a message on this `Sender`, returning the message
+    /// if there was an error.
+    pub fn try_send(&mut self, msg: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.num_receivers == 0 {
+            return Err(TrySendError {
+                err: SendError { kind: SendErrorKind::Disconnected },
+                val: msg,
+            });
+        }
+
+        if self.reserved {
+            state.reserved -= 1;
+            self.reserved = false;
+        } else if !state.has_room(self.shared.capacity) {
+            return Err(TrySendError { err: SendError { kind: SendErrorKind::Full }, val: msg });
+        }
+
+        state.queue.push_back(msg);
+        wake_one(&mut state.recv_wakers);
+        Ok(())
+    }
+
+    /// Send a message on the channel.
+    ///
+    /// This function should only be called after
+    /// [`poll_ready`](Sender::poll_ready) has reported that the channel is
+    /// ready to receive a message.
+    pub fn start_send(&mut self, msg: T) -> Result<(), SendError> {
+        self.try_send(msg).map_err(|e| e.err)
+    }
+
+    /// Returns whether all receivers have been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.shared.state.lock().unwrap().num_receivers == 0
+    }
+
+    /// Returns whether the senders send to the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        let mut state = self.shared.state.lock().unwrap();
+        state.num_senders += 1;
+        let id = state.next_id();
+        Self { shared: self.shared.clone(), id, reserved: false }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        unregister(&mut state.send_wakers, self.id);
+        if self.reserved {
+            state.reserved -= 1;
+            wake_one(&mut state.send_wakers);
+        }
+        state.num_senders -= 1;
+        if state.num_senders == 0 {
+            wake_all(&mut state.recv_wakers);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").field("reserved", &self.reserved).finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to receive the next message without notifying a context if
+    /// empty.
+    ///
+    /// This function returns:
+    /// * `Ok(Some(t))` when a message is fetched
+    /// * `Ok(None)` when all senders are dropped and no messages are left
+    /// * `Err(e)` when there are no messages available, but the channel is not
+    ///   closed yet
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        let shared = self.shared.clone();
+        let mut state = shared.state.lock().unwrap();
+        self.next_message(&mut state).ok_or(TryRecvError { _priv: () })
+    }
+
+    fn next_message(&mut self, state: &mut State<T>) -> Option<Option<T>> {
+        if self.terminated {
+            return Some(None);
+        }
+
+        match state.queue.pop_front() {
+            Some(msg) => {
+                // There is room in the channel for one more message.
+                wake_one(&mut state.send_wakers);
+                Some(Some(msg))
+            }
+            None if state.num_senders == 0 => {
+                self.terminated = true;
+                Some(None)
+            }
+            None => None,
+        }
+    }
+
+    /// Returns whether the receivers receive from the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = &mut *self;
+        let shared = this.shared.clone();
+        let mut state = shared.state.lock().unwrap();
+        match this.next_message(&mut state) {
+            Some(msg) => {
+                unregister(&mut state.recv_wakers, this.id);
+                Poll::Ready(msg)
+            }
+            None => {
+                register(&mut state.recv_wakers, this.id, cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        let mut state = self.shared.state.lock().unwrap();
+        state.num_receivers += 1;
+        let id = state.next_id();
+        Self { shared: self.shared.clone(), id, terminated: self.terminated }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        unregister(&mut state.recv_wakers, self.id);
+        state.num_receivers -= 1;
+        if state.num_receivers == 0 {
+            wake_all(&mut state.send_wakers);
+        } else if !state.queue.is_empty() {
+            // This receiver may have been woken up for a message it won't
+            // receive anymore, so hand it over to another one.
+            wake_one(&mut state.recv_wakers);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").field("terminated", &self.terminated).finish()
+    }
+}
+
+#[cfg(feature = "sink")]
+mod sink_impl {
+    use super::{SendError, Sender};
+    use futures_core::task::{Context, Poll};
+    use futures_sink::Sink;
+    use std::pin::Pin;
+
+    impl<T> Sink<T> for Sender<T> {
+        type Error = SendError;
+
+        fn poll_ready(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            (*self).poll_ready(cx)
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, msg: T) -> Result<(), Self::Error> {
+            (*self).start_send(msg)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
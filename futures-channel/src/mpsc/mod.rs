@@ -53,6 +53,11 @@
 // If the sender is unable to process a send operation, then the current
 // task is parked and the handle is sent on the parked task queue.
 //
+// Neither sending nor receiving takes a lock: the message count is updated
+// with a CAS loop, both queues are lock-free, and a parked task handle is an
+// atomic flag paired with an `AtomicWaker`, so the receiver can unpark a
+// sender while the sender is registering its task.
+//
 // Note that the implementation guarantees that the channel capacity will never
 // exceed the configured limit, however there is no *strict* guarantee that the
 // receiver will wake up a parked task *immediately* when a slot becomes
@@ -78,14 +83,24 @@
 // happens-before semantics required for the acquire / release semantics used
 // by the queue structure.
 
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
 use futures_core::stream::{FusedStream, Stream};
-use futures_core::task::__internal::AtomicWaker;
+use futures_core::task::__internal::{poll_budget, AtomicWaker};
 use futures_core::task::{Context, Poll, Waker};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::sync::SgxMutex as Mutex;
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::thread;
+use std::collections::HashMap;
 use std::fmt;
 use std::pin::Pin;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+#[cfg(not(feature = "phala-sgx"))]
+use std::sync::Mutex;
+use std::sync::{Arc, Weak};
+#[cfg(not(feature = "phala-sgx"))]
 use std::thread;
 
 use crate::mpsc::queue::Queue;
@@ -98,6 +113,10 @@ mod sink_impl;
 struct UnboundedSenderInner<T> {
     // Channel state shared between the sender and receiver.
     inner: Arc<UnboundedInner<T>>,
+
+    // The key of the task waiting in `poll_closed` for the channel to be
+    // closed, if any.
+    closed_key: AtomicUsize,
 }
 
 #[derive(Debug)]
@@ -108,11 +127,16 @@ struct BoundedSenderInner<T> {
     // Handle to the task that is blocked on this sender. This handle is sent
     // to the receiver half in order to be notified when the sender becomes
     // unblocked.
-    sender_task: Arc<Mutex<SenderTask>>,
+    sender_task: Arc<SenderTask>,
 
     // `true` if the sender might be blocked. This is an optimization to avoid
-    // having to lock the mutex most of the time.
+    // having to load the atomic `is_parked` flag of `sender_task` most of the
+    // time.
     maybe_parked: bool,
+
+    // The key of the task waiting in `poll_closed` for the channel to be
+    // closed, if any.
+    closed_key: AtomicUsize,
 }
 
 // We never project Pin<&mut SenderInner> to `Pin<&mut T>`
@@ -131,6 +155,27 @@ pub struct Sender<T>(Option<BoundedSenderInner<T>>);
 #[derive(Debug)]
 pub struct UnboundedSender<T>(Option<UnboundedSenderInner<T>>);
 
+/// A bounded mpsc sender which doesn't keep the channel open.
+///
+/// This value is created by the [`downgrade`](Sender::downgrade) method, and
+/// can be turned back into a [`Sender`] with [`upgrade`](WeakSender::upgrade)
+/// as long as another `Sender` of the channel is still alive.
+#[derive(Debug)]
+pub struct WeakSender<T> {
+    inner: Weak<BoundedInner<T>>,
+}
+
+/// An unbounded mpsc sender which doesn't keep the channel open.
+///
+/// This value is created by the [`downgrade`](UnboundedSender::downgrade)
+/// method, and can be turned back into an [`UnboundedSender`] with
+/// [`upgrade`](WeakUnboundedSender::upgrade) as long as another
+/// `UnboundedSender` of the channel is still alive.
+#[derive(Debug)]
+pub struct WeakUnboundedSender<T> {
+    inner: Weak<UnboundedInner<T>>,
+}
+
 trait AssertKinds: Send + Sync + Clone {}
 impl AssertKinds for UnboundedSender<u32> {}
 
@@ -275,12 +320,15 @@ struct UnboundedInner<T> {
 
     // Handle to the receiver's task.
     recv_task: AtomicWaker,
+
+    // Handles to the tasks waiting for the channel to be closed.
+    closed_tasks: Mutex<ClosedTasks>,
 }
 
 #[derive(Debug)]
 struct BoundedInner<T> {
-    // Max buffer size of the channel. If `None` then the channel is unbounded.
-    buffer: usize,
+    // Max buffer size of the channel, which can be changed by the receiver.
+    buffer: AtomicUsize,
 
     // Internal channel state. Consists of the number of messages stored in the
     // channel as well as a flag signalling that the channel is closed.
@@ -290,13 +338,16 @@ struct BoundedInner<T> {
     message_queue: Queue<T>,
 
     // Atomic, FIFO queue used to send parked task handles to the receiver.
-    parked_queue: Queue<Arc<Mutex<SenderTask>>>,
+    parked_queue: Queue<Arc<SenderTask>>,
 
     // Number of senders in existence
     num_senders: AtomicUsize,
 
     // Handle to the receiver's task.
     recv_task: AtomicWaker,
+
+    // Handles to the tasks waiting for the channel to be closed.
+    closed_tasks: Mutex<ClosedTasks>,
 }
 
 // Struct representation of `Inner::state`.
@@ -326,21 +377,22 @@ const MAX_BUFFER: usize = MAX_CAPACITY >> 1;
 // Sent to the consumer to wake up blocked producers
 #[derive(Debug)]
 struct SenderTask {
-    task: Option<Waker>,
-    is_parked: bool,
+    task: AtomicWaker,
+    is_parked: AtomicBool,
 }
 
 impl SenderTask {
     fn new() -> Self {
-        Self { task: None, is_parked: false }
+        Self { task: AtomicWaker::new(), is_parked: AtomicBool::new(false) }
     }
 
-    fn notify(&mut self) {
-        self.is_parked = false;
+    fn is_parked(&self) -> bool {
+        self.is_parked.load(SeqCst)
+    }
 
-        if let Some(task) = self.task.take() {
-            task.wake();
-        }
+    fn notify(&self) {
+        self.is_parked.store(false, SeqCst);
+        self.task.wake();
     }
 }
 
@@ -361,18 +413,20 @@ pub fn channel<T>(buffer: usize) -> (Sender<T>, Receiver<T>) {
     assert!(buffer < MAX_BUFFER, "requested buffer size too large");
 
     let inner = Arc::new(BoundedInner {
-        buffer,
+        buffer: AtomicUsize::new(buffer),
         state: AtomicUsize::new(INIT_STATE),
         message_queue: Queue::new(),
         parked_queue: Queue::new(),
         num_senders: AtomicUsize::new(1),
         recv_task: AtomicWaker::new(),
+        closed_tasks: Mutex::new(ClosedTasks::default()),
     });
 
     let tx = BoundedSenderInner {
         inner: inner.clone(),
-        sender_task: Arc::new(Mutex::new(SenderTask::new())),
+        sender_task: Arc::new(SenderTask::new()),
         maybe_parked: false,
+        closed_key: AtomicUsize::new(NO_CLOSED_KEY),
     };
 
     let rx = Receiver { inner: Some(inner) };
@@ -396,9 +450,11 @@ pub fn unbounded<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
         message_queue: Queue::new(),
         num_senders: AtomicUsize::new(1),
         recv_task: AtomicWaker::new(),
+        closed_tasks: Mutex::new(ClosedTasks::default()),
     });
 
-    let tx = UnboundedSenderInner { inner: inner.clone() };
+    let tx =
+        UnboundedSenderInner { inner: inner.clone(), closed_key: AtomicUsize::new(NO_CLOSED_KEY) };
 
     let rx = UnboundedReceiver { inner: Some(inner) };
 
@@ -526,7 +582,7 @@ impl<T> BoundedSenderInner<T> {
             Some(num_messages) => {
                 // Block if the current number of pending messages has exceeded
                 // the configured buffer size
-                num_messages > self.inner.buffer
+                num_messages > self.inner.buffer.load(SeqCst)
             }
             None => {
                 return Err(TrySendError {
@@ -594,11 +650,7 @@ impl<T> BoundedSenderInner<T> {
     }
 
     fn park(&mut self) {
-        {
-            let mut sender = self.sender_task.lock().unwrap();
-            sender.task = None;
-            sender.is_parked = true;
-        }
+        self.sender_task.is_parked.store(true, SeqCst);
 
         // Send handle over queue
         let t = self.sender_task.clone();
@@ -664,13 +716,10 @@ impl<T> BoundedSenderInner<T> {
     }
 
     fn poll_unparked(&mut self, cx: Option<&mut Context<'_>>) -> Poll<()> {
-        // First check the `maybe_parked` variable. This avoids acquiring the
-        // lock in most cases
+        // First check the `maybe_parked` variable. This avoids touching the
+        // shared task handle in most cases
         if self.maybe_parked {
-            // Get a lock on the task handle
-            let mut task = self.sender_task.lock().unwrap();
-
-            if !task.is_parked {
+            if !self.sender_task.is_parked() {
                 self.maybe_parked = false;
                 return Poll::Ready(());
             }
@@ -681,7 +730,18 @@ impl<T> BoundedSenderInner<T> {
             //
             // Update the task in case the `Sender` has been moved to another
             // task
-            task.task = cx.map(|cx| cx.waker().clone());
+            let cx = match cx {
+                Some(cx) => cx,
+                None => return Poll::Pending,
+            };
+            self.sender_task.task.register(cx.waker());
+
+            // Check again, in case the receiver unparked this sender before
+            // the task was registered.
+            if !self.sender_task.is_parked() {
+                self.maybe_parked = false;
+                return Poll::Ready(());
+            }
 
             Poll::Pending
         } else {
@@ -705,7 +765,9 @@ impl<T> Sender<T> {
     ///
     /// This function should only be called after
     /// [`poll_ready`](Sender::poll_ready) has reported that the channel is
-    /// ready to receive a message.
+    /// ready to receive a message. In that case it only fails if the channel
+    /// has been closed in the meantime; called on a sender which isn't ready,
+    /// it fails with an error for which [`SendError::is_full`] returns `true`.
     pub fn start_send(&mut self, msg: T) -> Result<(), SendError> {
         self.try_send(msg).map_err(|e| e.err)
     }
@@ -722,16 +784,102 @@ impl<T> Sender<T> {
     ///   capacity, in which case the current task is queued to be notified once
     ///   capacity is available;
     /// - `Poll::Ready(Err(SendError))` if the receiver has been dropped.
+    ///
+    /// The capacity reported by `Poll::Ready(Ok(_))` is reserved for this
+    /// `Sender`: each sender has its own slot in the channel on top of the
+    /// shared buffer, so other clones of it sending concurrently can't use it
+    /// up before the next call to [`start_send`](Sender::start_send).
     pub fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SendError>> {
         let inner = self.0.as_mut().ok_or(SendError { kind: SendErrorKind::Disconnected })?;
         inner.poll_ready(cx)
     }
 
+    /// Waits for capacity to send a message on this `Sender`, returning a
+    /// [`Permit`] which can then send it without waiting.
+    ///
+    /// This allows producing the message only once it's certain that there
+    /// is room for it in the channel, and holding on to that room across
+    /// `.await` points. The room is reserved for this `Sender` until the
+    /// permit is used or dropped.
+    ///
+    /// The returned future resolves to an error if the receiver has been
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::mpsc;
+    /// use futures::stream::StreamExt;
+    ///
+    /// let (mut tx, mut rx) = mpsc::channel(0);
+    ///
+    /// let permit = tx.reserve().await?;
+    /// permit.send("hello").unwrap();
+    ///
+    /// assert_eq!(rx.next().await, Some("hello"));
+    /// # Ok::<(), mpsc::SendError>(()) }).unwrap();
+    /// ```
+    pub fn reserve(&mut self) -> Reserve<'_, T> {
+        Reserve { sender: Some(self) }
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.0.as_ref().map_or(0, |inner| inner.inner.len())
+    }
+
+    /// Returns `true` if there are no messages in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of messages which can currently be sent before the
+    /// channel is at capacity.
+    ///
+    /// This is the [`max_capacity`](Sender::max_capacity) of the channel
+    /// minus the number of messages in it, and it is only a snapshot: other
+    /// senders and the receiver may change it right away.
+    pub fn capacity(&self) -> usize {
+        self.max_capacity().saturating_sub(self.len())
+    }
+
+    /// Returns the maximum number of messages the channel can hold, which is
+    /// its buffer size plus one guaranteed slot for each sender.
+    ///
+    /// Returns `0` if this sender is disconnected.
+    pub fn max_capacity(&self) -> usize {
+        self.0.as_ref().map_or(0, |inner| inner.inner.max_capacity())
+    }
+
     /// Returns whether this channel is closed without needing a context.
     pub fn is_closed(&self) -> bool {
         self.0.as_ref().map(BoundedSenderInner::is_closed).unwrap_or(true)
     }
 
+    /// Polls whether this channel is closed, scheduling the current task to
+    /// be woken up once it is otherwise.
+    ///
+    /// The channel is closed once the receiver is dropped or closed, or when
+    /// [`close_channel`](Sender::close_channel) is called.
+    ///
+    /// Only the task which polled this sender last is woken up. Several tasks
+    /// can wait for the channel to be closed with [`closed`](Sender::closed).
+    pub fn poll_closed(&self, cx: &mut Context<'_>) -> Poll<()> {
+        match &self.0 {
+            Some(inner) => inner.inner.poll_closed(&inner.closed_key, cx),
+            None => Poll::Ready(()),
+        }
+    }
+
+    /// Creates a future that resolves when this channel is closed, for
+    /// instance once the receiver is dropped.
+    ///
+    /// This allows producers to stop generating messages nobody will receive.
+    pub fn closed(&self) -> Closed<'_, T> {
+        Closed { sender: self, key: AtomicUsize::new(NO_CLOSED_KEY) }
+    }
+
     /// Closes this channel from the sender side, preventing any new messages.
     pub fn close_channel(&mut self) {
         if let Some(inner) = &mut self.0 {
@@ -744,6 +892,31 @@ impl<T> Sender<T> {
         self.0 = None;
     }
 
+    /// Creates a [`WeakSender`] for this channel.
+    ///
+    /// The weak sender doesn't count towards the senders keeping the channel
+    /// open: once all `Sender`s are dropped, the receiver sees the end of the
+    /// stream and the weak sender can't be upgraded anymore.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::channel::mpsc;
+    ///
+    /// let (tx, mut rx) = mpsc::channel::<i32>(1);
+    /// let weak = tx.downgrade();
+    /// assert!(weak.upgrade().is_some());
+    ///
+    /// drop(tx);
+    /// assert!(weak.upgrade().is_none());
+    /// assert_eq!(rx.try_next().unwrap(), None);
+    /// ```
+    pub fn downgrade(&self) -> WeakSender<T> {
+        WeakSender {
+            inner: self.0.as_ref().map_or_else(Weak::new, |inner| Arc::downgrade(&inner.inner)),
+        }
+    }
+
     /// Returns whether the senders send to the same receiver.
     pub fn same_receiver(&self, other: &Self) -> bool {
         match (&self.0, &other.0) {
@@ -784,6 +957,29 @@ impl<T> UnboundedSender<T> {
         self.0.as_ref().map(UnboundedSenderInner::is_closed).unwrap_or(true)
     }
 
+    /// Polls whether this channel is closed, scheduling the current task to
+    /// be woken up once it is otherwise.
+    ///
+    /// The channel is closed once the receiver is dropped or closed, or when
+    /// [`close_channel`](UnboundedSender::close_channel) is called.
+    ///
+    /// Only the task which polled this sender last is woken up. Several tasks
+    /// can wait for the channel to be closed with [`closed`](UnboundedSender::closed).
+    pub fn poll_closed(&self, cx: &mut Context<'_>) -> Poll<()> {
+        match &self.0 {
+            Some(inner) => inner.inner.poll_closed(&inner.closed_key, cx),
+            None => Poll::Ready(()),
+        }
+    }
+
+    /// Creates a future that resolves when this channel is closed, for
+    /// instance once the receiver is dropped.
+    ///
+    /// This allows producers to stop generating messages nobody will receive.
+    pub fn closed(&self) -> UnboundedClosed<'_, T> {
+        UnboundedClosed { sender: self, key: AtomicUsize::new(NO_CLOSED_KEY) }
+    }
+
     /// Closes this channel from the sender side, preventing any new messages.
     pub fn close_channel(&self) {
         if let Some(inner) = &self.0 {
@@ -825,6 +1021,17 @@ impl<T> UnboundedSender<T> {
         self.do_send_nb(msg)
     }
 
+    /// Creates a [`WeakUnboundedSender`] for this channel.
+    ///
+    /// The weak sender doesn't count towards the senders keeping the channel
+    /// open: once all `UnboundedSender`s are dropped, the receiver sees the
+    /// end of the stream and the weak sender can't be upgraded anymore.
+    pub fn downgrade(&self) -> WeakUnboundedSender<T> {
+        WeakUnboundedSender {
+            inner: self.0.as_ref().map_or_else(Weak::new, |inner| Arc::downgrade(&inner.inner)),
+        }
+    }
+
     /// Returns whether the senders send to the same receiver.
     pub fn same_receiver(&self, other: &Self) -> bool {
         match (&self.0, &other.0) {
@@ -853,6 +1060,151 @@ impl<T> UnboundedSender<T> {
     }
 }
 
+/// Future for the [`reserve`](Sender::reserve) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Reserve<'a, T> {
+    sender: Option<&'a mut Sender<T>>,
+}
+
+impl<'a, T> Future for Reserve<'a, T> {
+    type Output = Result<Permit<'a, T>, SendError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let sender = self.sender.as_mut().expect("Reserve polled after completion");
+        ready!(sender.poll_ready(cx))?;
+        let sender = self.sender.take().unwrap();
+        Poll::Ready(Ok(Permit { sender }))
+    }
+}
+
+impl<T> FusedFuture for Reserve<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.sender.is_none()
+    }
+}
+
+/// Capacity reserved in a bounded channel to send one message, returned by
+/// [`Sender::reserve`].
+#[derive(Debug)]
+#[must_use = "a permit does nothing unless used to send a message"]
+pub struct Permit<'a, T> {
+    sender: &'a mut Sender<T>,
+}
+
+impl<T> Permit<'_, T> {
+    /// Sends a message using the reserved capacity.
+    ///
+    /// This only fails, giving the message back, if the receiver has been
+    /// dropped since the capacity was reserved.
+    pub fn send(self, msg: T) -> Result<(), TrySendError<T>> {
+        self.sender.try_send(msg)
+    }
+}
+
+/// Future for the [`closed`](Sender::closed) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Closed<'a, T> {
+    sender: &'a Sender<T>,
+    key: AtomicUsize,
+}
+
+impl<T> Future for Closed<'_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match &self.sender.0 {
+            Some(inner) => inner.inner.poll_closed(&self.key, cx),
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+impl<T> Drop for Closed<'_, T> {
+    fn drop(&mut self) {
+        if let Some(inner) = &self.sender.0 {
+            remove_closed_task(&inner.inner.closed_tasks, self.key.get_mut());
+        }
+    }
+}
+
+/// Future for the [`closed`](UnboundedSender::closed) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct UnboundedClosed<'a, T> {
+    sender: &'a UnboundedSender<T>,
+    key: AtomicUsize,
+}
+
+impl<T> Future for UnboundedClosed<'_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match &self.sender.0 {
+            Some(inner) => inner.inner.poll_closed(&self.key, cx),
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+impl<T> Drop for UnboundedClosed<'_, T> {
+    fn drop(&mut self) {
+        if let Some(inner) = &self.sender.0 {
+            remove_closed_task(&inner.inner.closed_tasks, self.key.get_mut());
+        }
+    }
+}
+
+impl<T> WeakSender<T> {
+    /// Tries to turn this weak sender into a [`Sender`].
+    ///
+    /// Returns `None` if all `Sender`s of the channel have been dropped.
+    pub fn upgrade(&self) -> Option<Sender<T>> {
+        let inner = self.inner.upgrade()?;
+        if !inc_num_senders_if_alive(&inner.num_senders, inner.max_senders()) {
+            return None;
+        }
+
+        Some(Sender(Some(BoundedSenderInner {
+            inner,
+            sender_task: Arc::new(SenderTask::new()),
+            maybe_parked: false,
+            closed_key: AtomicUsize::new(NO_CLOSED_KEY),
+        })))
+    }
+}
+
+impl<T> WeakUnboundedSender<T> {
+    /// Tries to turn this weak sender into an [`UnboundedSender`].
+    ///
+    /// Returns `None` if all `UnboundedSender`s of the channel have been
+    /// dropped.
+    pub fn upgrade(&self) -> Option<UnboundedSender<T>> {
+        let inner = self.inner.upgrade()?;
+        if !inc_num_senders_if_alive(&inner.num_senders, MAX_BUFFER) {
+            return None;
+        }
+
+        Some(UnboundedSender(Some(UnboundedSenderInner {
+            inner,
+            closed_key: AtomicUsize::new(NO_CLOSED_KEY),
+        })))
+    }
+}
+
+impl<T> Clone for WeakSender<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T> Clone for WeakUnboundedSender<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
         Self(self.0.clone())
@@ -885,7 +1237,10 @@ impl<T> Clone for UnboundedSenderInner<T> {
                 Ok(_) => {
                     // The ABA problem doesn't matter here. We only care that the
                     // number of senders never exceeds the maximum.
-                    return Self { inner: self.inner.clone() };
+                    return Self {
+                        inner: self.inner.clone(),
+                        closed_key: AtomicUsize::new(NO_CLOSED_KEY),
+                    };
                 }
                 Err(actual) => curr = actual,
             }
@@ -915,8 +1270,9 @@ impl<T> Clone for BoundedSenderInner<T> {
                     // number of senders never exceeds the maximum.
                     return Self {
                         inner: self.inner.clone(),
-                        sender_task: Arc::new(Mutex::new(SenderTask::new())),
+                        sender_task: Arc::new(SenderTask::new()),
                         maybe_parked: false,
+                        closed_key: AtomicUsize::new(NO_CLOSED_KEY),
                     };
                 }
                 Err(actual) => curr = actual,
@@ -927,6 +1283,8 @@ impl<T> Clone for BoundedSenderInner<T> {
 
 impl<T> Drop for UnboundedSenderInner<T> {
     fn drop(&mut self) {
+        remove_closed_task(&self.inner.closed_tasks, self.closed_key.get_mut());
+
         // Ordering between variables don't matter here
         let prev = self.inner.num_senders.fetch_sub(1, SeqCst);
 
@@ -938,6 +1296,8 @@ impl<T> Drop for UnboundedSenderInner<T> {
 
 impl<T> Drop for BoundedSenderInner<T> {
     fn drop(&mut self) {
+        remove_closed_task(&self.inner.closed_tasks, self.closed_key.get_mut());
+
         // Ordering between variables don't matter here
         let prev = self.inner.num_senders.fetch_sub(1, SeqCst);
 
@@ -965,7 +1325,7 @@ impl<T> Receiver<T> {
             // Wake up any threads waiting as they'll see that we've closed the
             // channel and will continue on their merry way.
             while let Some(task) = unsafe { inner.parked_queue.pop_spin() } {
-                task.lock().unwrap().notify();
+                task.notify();
             }
         }
     }
@@ -987,6 +1347,128 @@ impl<T> Receiver<T> {
         }
     }
 
+    /// Returns an iterator over the messages already buffered in the channel,
+    /// receiving them without waiting for more.
+    ///
+    /// Once the iterator is exhausted, [`is_terminated`](FusedStream::is_terminated)
+    /// tells whether the channel is merely empty or closed, with all senders
+    /// dropped and no messages left.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain { receiver: self }
+    }
+
+    /// Receives up to `limit` messages at once, appending them to `buf`.
+    ///
+    /// The returned future waits until at least one message is available,
+    /// then moves all messages already buffered in the channel, up to
+    /// `limit` of them, into `buf`, and resolves to their number. It resolves
+    /// to `0` if `limit` is zero, or if the channel is closed and no messages
+    /// are left.
+    ///
+    /// The `ready_chunks` adapter of `StreamExt` provides the same batching
+    /// as a stream.
+    pub fn recv_many<'a>(&'a mut self, buf: &'a mut Vec<T>, limit: usize) -> RecvMany<'a, T> {
+        RecvMany { receiver: self, buf, limit }
+    }
+
+    /// Polls for up to `limit` messages at once, appending them to `buf`.
+    ///
+    /// This is the poll-based version of [`recv_many`](Receiver::recv_many): it
+    /// returns `Poll::Pending` and schedules the current task to be woken up
+    /// if no message is available yet.
+    pub fn poll_recv_many(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<T>,
+        limit: usize,
+    ) -> Poll<usize> {
+        if limit == 0 {
+            return Poll::Ready(0);
+        }
+
+        ready!(poll_budget(cx));
+
+        match self.next_messages(buf, limit) {
+            Poll::Ready(count) => Poll::Ready(count),
+            Poll::Pending => {
+                // Park, and check the queue again like `poll_next` does.
+                self.inner.as_ref().unwrap().recv_task.register(cx.waker());
+                self.next_messages(buf, limit)
+            }
+        }
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.inner.as_ref().map_or(0, |inner| inner.len())
+    }
+
+    /// Returns `true` if there are no messages in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of messages which can currently be sent before the
+    /// channel is at capacity.
+    ///
+    /// This is the [`max_capacity`](Receiver::max_capacity) of the channel
+    /// minus the number of messages in it.
+    pub fn capacity(&self) -> usize {
+        self.max_capacity().saturating_sub(self.len())
+    }
+
+    /// Returns the maximum number of messages the channel can hold, which is
+    /// its buffer size plus one guaranteed slot for each sender.
+    ///
+    /// Returns `0` once the stream has terminated.
+    pub fn max_capacity(&self) -> usize {
+        self.inner.as_ref().map_or(0, |inner| inner.max_capacity())
+    }
+
+    /// Changes the buffer size of the channel, as passed to [`channel`].
+    ///
+    /// The new size applies to the messages sent from then on: when it is
+    /// larger, senders waiting for capacity are woken up to use the new room,
+    /// and when it is smaller, the messages already in the channel stay there
+    /// but senders wait for the channel to drain below the new size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is too large, like [`channel`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::channel::mpsc;
+    ///
+    /// let (mut tx, mut rx) = mpsc::channel::<i32>(0);
+    /// assert_eq!(rx.max_capacity(), 1);
+    ///
+    /// rx.set_buffer(4);
+    /// assert_eq!(tx.max_capacity(), 5);
+    /// for i in 0..5 {
+    ///     tx.try_send(i).unwrap();
+    /// }
+    /// assert!(tx.try_send(5).unwrap_err().is_full());
+    /// assert_eq!(rx.len(), 5);
+    /// ```
+    pub fn set_buffer(&mut self, buffer: usize) {
+        assert!(buffer < MAX_BUFFER, "requested buffer size too large");
+
+        if let Some(inner) = &self.inner {
+            let prev = inner.buffer.swap(buffer, SeqCst);
+
+            // Let the senders parked because of the previous size use the
+            // added room.
+            for _ in prev..buffer {
+                match unsafe { inner.parked_queue.pop_spin() } {
+                    Some(task) => task.notify(),
+                    None => break,
+                }
+            }
+        }
+    }
+
     fn next_message(&mut self) -> Poll<Option<T>> {
         let inner = match self.inner.as_mut() {
             None => return Poll::Ready(None),
@@ -1025,11 +1507,50 @@ impl<T> Receiver<T> {
         }
     }
 
+    // Pops up to `limit` messages into `buf`, updating the state and
+    // unparking senders once for the whole batch.
+    fn next_messages(&mut self, buf: &mut Vec<T>, limit: usize) -> Poll<usize> {
+        let inner = match self.inner.as_mut() {
+            None => return Poll::Ready(0),
+            Some(inner) => inner,
+        };
+        let mut count = 0;
+        while count < limit {
+            match unsafe { inner.message_queue.pop_spin() } {
+                Some(msg) => buf.push(msg),
+                None => break,
+            }
+            count += 1;
+        }
+
+        if count == 0 {
+            // Like `next_message`, the channel has ended only once it is
+            // closed and no message is left.
+            if decode_state(inner.state.load(SeqCst)).is_closed() {
+                self.inner = None;
+                return Poll::Ready(0);
+            }
+            return Poll::Pending;
+        }
+
+        // Unpark as many senders as there were messages received.
+        for _ in 0..count {
+            match unsafe { inner.parked_queue.pop_spin() } {
+                Some(task) => task.notify(),
+                None => break,
+            }
+        }
+        // There's no underflow, since the state counts at least the messages
+        // which were popped.
+        inner.state.fetch_sub(count, SeqCst);
+        Poll::Ready(count)
+    }
+
     // Unpark a single task handle if there is one pending in the parked queue
     fn unpark_one(&mut self) {
         if let Some(inner) = &mut self.inner {
             if let Some(task) = unsafe { inner.parked_queue.pop_spin() } {
-                task.lock().unwrap().notify();
+                task.notify();
             }
         }
     }
@@ -1047,6 +1568,38 @@ impl<T> Receiver<T> {
 // The receiver does not ever take a Pin to the inner T
 impl<T> Unpin for Receiver<T> {}
 
+/// Iterator for the [`drain`](Receiver::drain) method.
+#[derive(Debug)]
+pub struct Drain<'a, T> {
+    receiver: &'a mut Receiver<T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.try_next().ok().flatten()
+    }
+}
+
+/// Future for the [`recv_many`](Receiver::recv_many) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RecvMany<'a, T> {
+    receiver: &'a mut Receiver<T>,
+    buf: &'a mut Vec<T>,
+    limit: usize,
+}
+
+impl<T> Future for RecvMany<'_, T> {
+    type Output = usize;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+        let this = &mut *self;
+        this.receiver.poll_recv_many(cx, this.buf, this.limit)
+    }
+}
+
 impl<T> FusedStream for Receiver<T> {
     fn is_terminated(&self) -> bool {
         self.inner.is_none()
@@ -1057,6 +1610,10 @@ impl<T> Stream for Receiver<T> {
     type Item = T;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // Yield to the other tasks if this one has received many messages
+        // without being rescheduled.
+        ready!(poll_budget(cx));
+
         // Try to read a message off of the message queue.
         match self.next_message() {
             Poll::Ready(msg) => {
@@ -1135,6 +1692,61 @@ impl<T> UnboundedReceiver<T> {
         }
     }
 
+    /// Returns an iterator over the messages already buffered in the channel,
+    /// receiving them without waiting for more.
+    ///
+    /// Once the iterator is exhausted, [`is_terminated`](FusedStream::is_terminated)
+    /// tells whether the channel is merely empty or closed, with all senders
+    /// dropped and no messages left.
+    pub fn drain(&mut self) -> UnboundedDrain<'_, T> {
+        UnboundedDrain { receiver: self }
+    }
+
+    /// Receives up to `limit` messages at once, appending them to `buf`.
+    ///
+    /// The returned future waits until at least one message is available,
+    /// then moves all messages already buffered in the channel, up to
+    /// `limit` of them, into `buf`, and resolves to their number. It resolves
+    /// to `0` if `limit` is zero, or if the channel is closed and no messages
+    /// are left.
+    ///
+    /// The `ready_chunks` adapter of `StreamExt` provides the same batching
+    /// as a stream.
+    pub fn recv_many<'a>(
+        &'a mut self,
+        buf: &'a mut Vec<T>,
+        limit: usize,
+    ) -> UnboundedRecvMany<'a, T> {
+        UnboundedRecvMany { receiver: self, buf, limit }
+    }
+
+    /// Polls for up to `limit` messages at once, appending them to `buf`.
+    ///
+    /// This is the poll-based version of [`recv_many`](UnboundedReceiver::recv_many): it
+    /// returns `Poll::Pending` and schedules the current task to be woken up
+    /// if no message is available yet.
+    pub fn poll_recv_many(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<T>,
+        limit: usize,
+    ) -> Poll<usize> {
+        if limit == 0 {
+            return Poll::Ready(0);
+        }
+
+        ready!(poll_budget(cx));
+
+        match self.next_messages(buf, limit) {
+            Poll::Ready(count) => Poll::Ready(count),
+            Poll::Pending => {
+                // Park, and check the queue again like `poll_next` does.
+                self.inner.as_ref().unwrap().recv_task.register(cx.waker());
+                self.next_messages(buf, limit)
+            }
+        }
+    }
+
     fn next_message(&mut self) -> Poll<Option<T>> {
         let inner = match self.inner.as_mut() {
             None => return Poll::Ready(None),
@@ -1169,6 +1781,34 @@ impl<T> UnboundedReceiver<T> {
         }
     }
 
+    // Pops up to `limit` messages into `buf`, updating the state once for
+    // the whole batch.
+    fn next_messages(&mut self, buf: &mut Vec<T>, limit: usize) -> Poll<usize> {
+        let inner = match self.inner.as_mut() {
+            None => return Poll::Ready(0),
+            Some(inner) => inner,
+        };
+        let mut count = 0;
+        while count < limit {
+            match unsafe { inner.message_queue.pop_spin() } {
+                Some(msg) => buf.push(msg),
+                None => break,
+            }
+            count += 1;
+        }
+
+        if count == 0 {
+            if decode_state(inner.state.load(SeqCst)).is_closed() {
+                self.inner = None;
+                return Poll::Ready(0);
+            }
+            return Poll::Pending;
+        }
+
+        inner.state.fetch_sub(count, SeqCst);
+        Poll::Ready(count)
+    }
+
     fn dec_num_messages(&self) {
         if let Some(inner) = &self.inner {
             // OPEN_MASK is highest bit, so it's unaffected by subtraction
@@ -1179,6 +1819,38 @@ impl<T> UnboundedReceiver<T> {
     }
 }
 
+/// Iterator for the [`drain`](UnboundedReceiver::drain) method.
+#[derive(Debug)]
+pub struct UnboundedDrain<'a, T> {
+    receiver: &'a mut UnboundedReceiver<T>,
+}
+
+impl<T> Iterator for UnboundedDrain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.try_next().ok().flatten()
+    }
+}
+
+/// Future for the [`recv_many`](UnboundedReceiver::recv_many) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct UnboundedRecvMany<'a, T> {
+    receiver: &'a mut UnboundedReceiver<T>,
+    buf: &'a mut Vec<T>,
+    limit: usize,
+}
+
+impl<T> Future for UnboundedRecvMany<'_, T> {
+    type Output = usize;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+        let this = &mut *self;
+        this.receiver.poll_recv_many(cx, this.buf, this.limit)
+    }
+}
+
 impl<T> FusedStream for UnboundedReceiver<T> {
     fn is_terminated(&self) -> bool {
         self.inner.is_none()
@@ -1189,6 +1861,10 @@ impl<T> Stream for UnboundedReceiver<T> {
     type Item = T;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // Yield to the other tasks if this one has received many messages
+        // without being rescheduled.
+        ready!(poll_budget(cx));
+
         // Try to read a message off of the message queue.
         match self.next_message() {
             Poll::Ready(msg) => {
@@ -1254,6 +1930,11 @@ impl<T> UnboundedInner<T> {
         }
 
         self.state.fetch_and(!OPEN_MASK, SeqCst);
+        wake_closed_tasks(&self.closed_tasks);
+    }
+
+    fn poll_closed(&self, key: &AtomicUsize, cx: &mut Context<'_>) -> Poll<()> {
+        poll_closed(&self.state, &self.closed_tasks, key, cx)
     }
 }
 
@@ -1261,7 +1942,7 @@ impl<T> BoundedInner<T> {
     // The return value is such that the total number of messages that can be
     // enqueued into the channel will never exceed MAX_CAPACITY
     fn max_senders(&self) -> usize {
-        MAX_CAPACITY - self.buffer
+        MAX_CAPACITY - self.buffer.load(SeqCst)
     }
 
     // Clear `open` flag in the state, keep `num_messages` intact.
@@ -1272,6 +1953,95 @@ impl<T> BoundedInner<T> {
         }
 
         self.state.fetch_and(!OPEN_MASK, SeqCst);
+        wake_closed_tasks(&self.closed_tasks);
+    }
+
+    fn poll_closed(&self, key: &AtomicUsize, cx: &mut Context<'_>) -> Poll<()> {
+        poll_closed(&self.state, &self.closed_tasks, key, cx)
+    }
+
+    fn len(&self) -> usize {
+        decode_state(self.state.load(SeqCst)).num_messages
+    }
+
+    fn max_capacity(&self) -> usize {
+        self.buffer.load(SeqCst) + self.num_senders.load(SeqCst)
+    }
+}
+
+// The key of a task waiting for the channel to be closed which wasn't
+// registered yet.
+const NO_CLOSED_KEY: usize = usize::MAX;
+
+// The tasks waiting for a channel to be closed, by the key of the `Closed`
+// future or sender which registered them, so that each of them is removed
+// once it stops waiting.
+#[derive(Debug, Default)]
+struct ClosedTasks {
+    next_key: usize,
+    tasks: HashMap<usize, Waker>,
+}
+
+fn poll_closed(
+    state: &AtomicUsize,
+    closed_tasks: &Mutex<ClosedTasks>,
+    key: &AtomicUsize,
+    cx: &mut Context<'_>,
+) -> Poll<()> {
+    // Check the state while holding the lock, so that the task can't be
+    // registered after `set_closed` has woken up the registered ones. The key
+    // is only assigned while holding the lock too.
+    let mut closed_tasks = closed_tasks.lock().unwrap();
+    if !decode_state(state.load(SeqCst)).is_open {
+        closed_tasks.tasks.remove(&key.swap(NO_CLOSED_KEY, SeqCst));
+        return Poll::Ready(());
+    }
+
+    let mut k = key.load(SeqCst);
+    if k == NO_CLOSED_KEY {
+        k = closed_tasks.next_key;
+        closed_tasks.next_key += 1;
+        key.store(k, SeqCst);
+    }
+    match closed_tasks.tasks.get_mut(&k) {
+        Some(task) if task.will_wake(cx.waker()) => {}
+        Some(task) => *task = cx.waker().clone(),
+        None => {
+            closed_tasks.tasks.insert(k, cx.waker().clone());
+        }
+    }
+    Poll::Pending
+}
+
+fn remove_closed_task(closed_tasks: &Mutex<ClosedTasks>, key: &mut usize) {
+    if *key != NO_CLOSED_KEY {
+        closed_tasks.lock().unwrap().tasks.remove(key);
+        *key = NO_CLOSED_KEY;
+    }
+}
+
+fn wake_closed_tasks(closed_tasks: &Mutex<ClosedTasks>) {
+    for (_, task) in closed_tasks.lock().unwrap().tasks.drain() {
+        task.wake();
+    }
+}
+
+// Registers a new sender, unless all senders have been dropped already.
+fn inc_num_senders_if_alive(num_senders: &AtomicUsize, max_senders: usize) -> bool {
+    let mut curr = num_senders.load(SeqCst);
+
+    loop {
+        if curr == 0 {
+            return false;
+        }
+        if curr == max_senders {
+            panic!("cannot upgrade `WeakSender` -- too many outstanding senders");
+        }
+
+        match num_senders.compare_exchange(curr, curr + 1, SeqCst, SeqCst) {
+            Ok(_) => return true,
+            Err(actual) => curr = actual,
+        }
     }
 }
 
@@ -43,9 +43,12 @@
 
 pub(super) use self::PopResult::*;
 
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::thread;
 use std::cell::UnsafeCell;
 use std::ptr;
 use std::sync::atomic::{AtomicPtr, Ordering};
+#[cfg(not(feature = "phala-sgx"))]
 use std::thread;
 
 /// A result of the `pop` function.
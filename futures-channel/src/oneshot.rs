@@ -363,6 +363,30 @@ impl<T> Sender<T> {
     ///
     /// This is a utility wrapping [`poll_canceled`](Sender::poll_canceled)
     /// to expose a [`Future`](core::future::Future).
+    ///
+    /// # Examples
+    ///
+    /// Abandoning expensive work once nobody is waiting for its result:
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::oneshot;
+    /// use futures::future::{self, Either};
+    /// use futures::pin_mut;
+    ///
+    /// let (mut tx, rx) = oneshot::channel::<u32>();
+    /// drop(rx);
+    ///
+    /// let work = future::pending::<u32>();
+    /// pin_mut!(work);
+    /// let result = match future::select(tx.cancellation(), work).await {
+    ///     Either::Left(((), _)) => None,
+    ///     Either::Right((result, _)) => Some(result),
+    /// };
+    ///
+    /// assert_eq!(result, None);
+    /// # });
+    /// ```
     pub fn cancellation(&mut self) -> Cancellation<'_, T> {
         Cancellation { inner: self }
     }
@@ -447,6 +471,22 @@ impl<T> Receiver<T> {
     /// date) unless [`close`](Receiver::close) has been called first.
     ///
     /// Returns an error if the sender was dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::channel::oneshot;
+    ///
+    /// let (tx, mut rx) = oneshot::channel::<i32>();
+    /// assert_eq!(rx.try_recv(), Ok(None));
+    ///
+    /// tx.send(3).unwrap();
+    /// assert_eq!(rx.try_recv(), Ok(Some(3)));
+    ///
+    /// let (tx, mut rx) = oneshot::channel::<i32>();
+    /// drop(tx);
+    /// assert_eq!(rx.try_recv(), Err(oneshot::Canceled));
+    /// ```
     pub fn try_recv(&mut self) -> Result<Option<T>, Canceled> {
         self.inner.try_recv()
     }
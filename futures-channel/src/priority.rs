@@ -0,0 +1,479 @@
+//! A multi-producer, single-consumer channel with several priority lanes.
+//!
+//! The channel is made of a fixed number of lanes, numbered from `0`, the
+//! lane with the highest priority. Each [`Sender`] sends on one lane, and the
+//! [`Receiver`] always receives the pending message from the lane with the
+//! highest priority first, so that control messages can overtake bulk data.
+//! Messages sent on the same lane are received in order.
+//!
+//! Every lane holds at most the number of messages the channel was created
+//! with, and senders wait for room in their own lane only: a full lane
+//! doesn't hold back senders on other lanes.
+//!
+//! The stream of the [`Receiver`] ends once all [`Sender`]s are dropped and
+//! no messages are left.
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll, Waker};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::sync::SgxMutex as Mutex;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(not(feature = "phala-sgx"))]
+use std::sync::Mutex;
+
+/// Creates a channel with `lanes` priority lanes, each holding at most
+/// `buffer` messages.
+///
+/// The returned [`Sender`] sends on lane `0`, the lane with the highest
+/// priority; senders for the other lanes are created with
+/// [`Sender::for_lane`].
+///
+/// # Panics
+///
+/// Panics if `lanes` or `buffer` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::channel::priority;
+/// use futures::sink::SinkExt;
+/// use futures::stream::StreamExt;
+///
+/// let (mut control, rx) = priority::channel(2, 16);
+/// let mut data = control.for_lane(1);
+///
+/// data.send("data 1").await.unwrap();
+/// data.send("data 2").await.unwrap();
+/// control.send("stop").await.unwrap();
+/// drop((control, data));
+///
+/// assert_eq!(rx.collect::<Vec<_>>().await, vec!["stop", "data 1", "data 2"]);
+/// # });
+/// ```
+pub fn channel<T>(lanes: usize, buffer: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(lanes > 0, "priority channel must have at least one lane");
+    assert!(buffer > 0, "priority channel buffer must be greater than zero");
+
+    let shared = Arc::new(Shared {
+        buffer,
+        state: Mutex::new(State {
+            lanes: (0..lanes).map(|_| Lane::default()).collect(),
+            num_senders: 1,
+            next_sender_id: 1,
+            is_open: true,
+            recv_task: None,
+        }),
+    });
+    let receiver = Receiver { shared: Some(shared.clone()) };
+    (Sender { shared, lane: 0, id: 0, reserved: false }, receiver)
+}
+
+struct Shared<T> {
+    buffer: usize,
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    lanes: Vec<Lane<T>>,
+    num_senders: usize,
+    next_sender_id: usize,
+    // Whether the receiver is still alive and hasn't closed the channel.
+    is_open: bool,
+    recv_task: Option<Waker>,
+}
+
+struct Lane<T> {
+    queue: VecDeque<T>,
+    // Room reserved by senders for which `poll_ready` succeeded.
+    reserved: usize,
+    // Wakers of the senders waiting for room in this lane, by sender id.
+    send_wakers: VecDeque<(usize, Waker)>,
+}
+
+impl<T> Default for Lane<T> {
+    fn default() -> Self {
+        Self { queue: VecDeque::new(), reserved: 0, send_wakers: VecDeque::new() }
+    }
+}
+
+impl<T> Lane<T> {
+    fn has_room(&self, buffer: usize) -> bool {
+        self.queue.len() + self.reserved < buffer
+    }
+
+    fn wake_one(&mut self) {
+        if let Some((_, waker)) = self.send_wakers.pop_front() {
+            waker.wake();
+        }
+    }
+
+    fn unregister(&mut self, id: usize) {
+        self.send_wakers.retain(|(i, _)| *i != id);
+    }
+}
+
+impl<T> State<T> {
+    fn close(&mut self) {
+        self.is_open = false;
+        for lane in &mut self.lanes {
+            for (_, waker) in lane.send_wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// The transmission end of a priority channel, sending on one of its lanes.
+///
+/// This value is created by the [`channel`] function, and by
+/// [`Sender::for_lane`] for the other lanes.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+    lane: usize,
+    id: usize,
+    // Whether this sender holds room reserved by `poll_ready`.
+    reserved: bool,
+}
+
+/// The receiving end of a priority channel.
+///
+/// This value is created by the [`channel`] function.
+#[must_use = "streams do nothing unless polled"]
+pub struct Receiver<T> {
+    // `None` once the stream has terminated.
+    shared: Option<Arc<Shared<T>>>,
+}
+
+/// The error type for [`Sender`s](Sender) used as `Sink`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SendError {
+    kind: SendErrorKind,
+}
+
+/// The error type returned from [`try_send`](Sender::try_send).
+#[derive(Clone, PartialEq, Eq)]
+pub struct TrySendError<T> {
+    err: SendError,
+    val: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SendErrorKind {
+    Full,
+    Disconnected,
+}
+
+/// The error type returned from [`try_next`](Receiver::try_next).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TryRecvError {
+    _priv: (),
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_full() {
+            write!(f, "send failed because channel lane is full")
+        } else {
+            write!(f, "send failed because receiver is gone")
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+impl SendError {
+    /// Returns `true` if this error is a result of the lane being full.
+    pub fn is_full(&self) -> bool {
+        self.kind == SendErrorKind::Full
+    }
+
+    /// Returns `true` if this error is a result of the receiver being dropped.
+    pub fn is_disconnected(&self) -> bool {
+        self.kind == SendErrorKind::Disconnected
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrySendError").field("kind", &self.err.kind).finish()
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.err, f)
+    }
+}
+
+impl<T: core::any::Any> std::error::Error for TrySendError<T> {}
+
+impl<T> TrySendError<T> {
+    /// Returns `true` if this error is a result of the lane being full.
+    pub fn is_full(&self) -> bool {
+        self.err.is_full()
+    }
+
+    /// Returns `true` if this error is a result of the receiver being dropped.
+    pub fn is_disconnected(&self) -> bool {
+        self.err.is_disconnected()
+    }
+
+    /// Returns the message that was attempted to be sent but failed.
+    pub fn into_inner(self) -> T {
+        self.val
+    }
+
+    /// Drops the message and converts into a `SendError`.
+    pub fn into_send_error(self) -> SendError {
+        self.err
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiver channel is empty")
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+impl<T> Sender<T> {
+    /// Creates a sender for the given lane of the same channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel has no such lane.
+    pub fn for_lane(&self, lane: usize) -> Self {
+        let mut state = self.shared.state.lock().unwrap();
+        assert!(lane < state.lanes.len(), "priority channel has no lane {}", lane);
+        state.num_senders += 1;
+        let id = state.next_sender_id;
+        state.next_sender_id += 1;
+        Self { shared: self.shared.clone(), lane, id, reserved: false }
+    }
+
+    /// Returns the lane this sender sends on.
+    pub fn lane(&self) -> usize {
+        self.lane
+    }
+
+    /// Polls the channel to determine if there is room in the lane of this
+    /// sender to send a message.
+    ///
+    /// The room reported by `Poll::Ready(Ok(_))` is reserved for this
+    /// `Sender` until the next call to [`start_send`](Sender::start_send) or
+    /// [`try_send`](Sender::try_send). Otherwise, the current task is woken
+    /// up once room is available. An error is returned if the receiver has
+    /// been dropped.
+    pub fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SendError>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.is_open {
+            return Poll::Ready(Err(SendError { kind: SendErrorKind::Disconnected }));
+        }
+
+        let lane = &mut state.lanes[self.lane];
+        if !self.reserved {
+            if !lane.has_room(self.shared.buffer) {
+                match lane.send_wakers.iter_mut().find(|(i, _)| *i == self.id) {
+                    Some((_, waker)) => *waker = cx.waker().clone(),
+                    None => lane.send_wakers.push_back((self.id, cx.waker().clone())),
+                }
+                return Poll::Pending;
+            }
+            lane.reserved += 1;
+            self.reserved = true;
+        }
+        lane.unregister(self.id);
+        Poll::Ready(Ok(()))
+    }
+
+    /// Attempts to send a message on the lane of this `Sender`, returning
+    /// the message if there was an error.
+    pub fn try_send(&mut self, msg: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.is_open {
+            return Err(TrySendError {
+                err: SendError { kind: SendErrorKind::Disconnected },
+                val: msg,
+            });
+        }
+
+        let lane = &mut state.lanes[self.lane];
+        if self.reserved {
+            lane.reserved -= 1;
+            self.reserved = false;
+        } else if !lane.has_room(self.shared.buffer) {
+            return Err(TrySendError { err: SendError { kind: SendErrorKind::Full }, val: msg });
+        }
+
+        lane.queue.push_back(msg);
+        if let Some(waker) = state.recv_task.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Send a message on the channel.
+    ///
+    /// This function should only be called after
+    /// [`poll_ready`](Sender::poll_ready) has reported that the channel is
+    /// ready to receive a message.
+    pub fn start_send(&mut self, msg: T) -> Result<(), SendError> {
+        self.try_send(msg).map_err(|e| e.err)
+    }
+
+    /// Returns whether this channel is closed.
+    pub fn is_closed(&self) -> bool {
+        !self.shared.state.lock().unwrap().is_open
+    }
+
+    /// Returns whether the senders send to the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.for_lane(self.lane)
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        let lane = &mut state.lanes[self.lane];
+        lane.unregister(self.id);
+        if self.reserved {
+            lane.reserved -= 1;
+            lane.wake_one();
+        }
+        state.num_senders -= 1;
+        if state.num_senders == 0 {
+            if let Some(waker) = state.recv_task.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").field("lane", &self.lane).finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Closes the receiving half of the channel, without dropping it.
+    ///
+    /// This prevents any further messages from being sent on the channel
+    /// while still enabling the receiver to drain messages that are buffered.
+    pub fn close(&mut self) {
+        if let Some(shared) = &self.shared {
+            shared.state.lock().unwrap().close();
+        }
+    }
+
+    /// Tries to receive the next message without notifying a context if
+    /// empty.
+    ///
+    /// This function returns:
+    /// * `Ok(Some(t))` when a message is fetched
+    /// * `Ok(None)` when all senders are dropped and no messages are left
+    /// * `Err(e)` when there are no messages available, but the channel is not
+    ///   closed yet
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        match self.next_message(None) {
+            Poll::Ready(msg) => Ok(msg),
+            Poll::Pending => Err(TryRecvError { _priv: () }),
+        }
+    }
+
+    fn next_message(&mut self, cx: Option<&mut Context<'_>>) -> Poll<Option<T>> {
+        let shared = match &self.shared {
+            Some(shared) => shared,
+            None => return Poll::Ready(None),
+        };
+        let mut state = shared.state.lock().unwrap();
+
+        // Lanes are ordered by decreasing priority.
+        for lane in &mut state.lanes {
+            if let Some(msg) = lane.queue.pop_front() {
+                lane.wake_one();
+                return Poll::Ready(Some(msg));
+            }
+        }
+
+        if state.num_senders == 0 || !state.is_open {
+            drop(state);
+            self.shared = None;
+            Poll::Ready(None)
+        } else {
+            if let Some(cx) = cx {
+                state.recv_task = Some(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.next_message(Some(cx))
+    }
+}
+
+impl<T> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.shared.is_none()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").field("terminated", &self.shared.is_none()).finish()
+    }
+}
+
+#[cfg(feature = "sink")]
+mod sink_impl {
+    use super::{SendError, Sender};
+    use futures_core::task::{Context, Poll};
+    use futures_sink::Sink;
+    use std::pin::Pin;
+
+    impl<T> Sink<T> for Sender<T> {
+        type Error = SendError;
+
+        fn poll_ready(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            (*self).poll_ready(cx)
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, msg: T) -> Result<(), Self::Error> {
+            (*self).start_send(msg)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
@@ -0,0 +1,524 @@
+//! A single-producer, single-consumer queue for sending values between two
+//! asynchronous tasks.
+//!
+//! This channel is a lighter alternative to a bounded [`mpsc`](crate::mpsc)
+//! channel when there is only ever one sender: the messages are kept in a
+//! fixed-capacity ring buffer allocated when the channel is created, and
+//! sending or receiving a message only takes a couple of atomic loads and
+//! stores, with no locking and no allocation. Neither the [`Sender`] nor the
+//! [`Receiver`] can be cloned.
+//!
+//! Like [`bounded`](crate::bounded), this channel only needs the `alloc`
+//! feature.
+//!
+//! The stream of the [`Receiver`] ends once the [`Sender`] is dropped and all
+//! messages have been received. Sending fails once the [`Receiver`] is
+//! dropped or [closed](Receiver::close).
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::__internal::AtomicWaker;
+use futures_core::task::{Context, Poll};
+
+/// Creates a single-producer, single-consumer channel, which holds at most
+/// `capacity` messages.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::channel::spsc;
+/// use futures::future;
+/// use futures::sink::SinkExt;
+/// use futures::stream::StreamExt;
+///
+/// let (mut tx, rx) = spsc::channel(4);
+///
+/// let producer = async move {
+///     for i in 0..10 {
+///         tx.send(i).await.unwrap();
+///     }
+/// };
+/// let ((), received) = future::join(producer, rx.collect::<Vec<i32>>()).await;
+/// assert_eq!(received, (0..10).collect::<Vec<_>>());
+/// # });
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "spsc channel capacity must be greater than zero");
+
+    let shared = Arc::new(Shared {
+        buffer: (0..capacity.next_power_of_two())
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect(),
+        capacity,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        closed: AtomicBool::new(false),
+        recv_task: AtomicWaker::new(),
+        send_task: AtomicWaker::new(),
+    });
+    (Sender { shared: shared.clone() }, Receiver { shared, terminated: false })
+}
+
+struct Shared<T> {
+    // The slots of the ring buffer, rounded up to a power of two so that a
+    // position still maps to the same slot once it wraps around.
+    buffer: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    capacity: usize,
+    // The positions of the next message to receive and of the next one to
+    // send, counting all messages ever sent. `head` is only written by the
+    // receiver and `tail` by the sender, so each side can read its own
+    // position with a relaxed load.
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    // Whether either side has been dropped, or the receiver closed.
+    closed: AtomicBool,
+    recv_task: AtomicWaker,
+    send_task: AtomicWaker,
+}
+
+// The slots between `head` and `tail` are only accessed by the receiver, and
+// the others only by the sender, the ownership of a slot being transferred
+// with the release stores of `head` and `tail`.
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    fn slot(&self, pos: usize) -> *mut MaybeUninit<T> {
+        self.buffer[pos & (self.buffer.len() - 1)].get()
+    }
+
+    // Must only be called by the sender.
+    fn push(&self, msg: T) -> Result<(), T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == self.capacity {
+            return Err(msg);
+        }
+
+        unsafe { (*self.slot(tail)).as_mut_ptr().write(msg) };
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    // Must only be called by the receiver.
+    fn pop(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+
+        let msg = unsafe { (*self.slot(head)).as_ptr().read() };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(msg)
+    }
+
+    fn is_full(&self) -> bool {
+        let tail = self.tail.load(Ordering::Relaxed);
+        tail.wrapping_sub(self.head.load(Ordering::Acquire)) == self.capacity
+    }
+
+    fn len(&self) -> usize {
+        self.tail.load(Ordering::Acquire).wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// The transmission end of an spsc channel.
+///
+/// This value is created by the [`channel`] function.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The receiving end of an spsc channel.
+///
+/// This value is created by the [`channel`] function.
+#[must_use = "streams do nothing unless polled"]
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    terminated: bool,
+}
+
+/// The error type for [`Sender`s](Sender) used as `Sink`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SendError {
+    kind: SendErrorKind,
+}
+
+/// The error type returned from [`try_send`](Sender::try_send).
+#[derive(Clone, PartialEq, Eq)]
+pub struct TrySendError<T> {
+    err: SendError,
+    val: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SendErrorKind {
+    Full,
+    Disconnected,
+}
+
+/// The error type returned from [`try_next`](Receiver::try_next).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TryRecvError {
+    _priv: (),
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_full() {
+            write!(f, "send failed because channel is full")
+        } else {
+            write!(f, "send failed because receiver is gone")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SendError {}
+
+impl SendError {
+    /// Returns `true` if this error is a result of the channel being full.
+    pub fn is_full(&self) -> bool {
+        self.kind == SendErrorKind::Full
+    }
+
+    /// Returns `true` if this error is a result of the receiver being dropped
+    /// or closed.
+    pub fn is_disconnected(&self) -> bool {
+        self.kind == SendErrorKind::Disconnected
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrySendError").field("kind", &self.err.kind).finish()
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.err, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: core::any::Any> std::error::Error for TrySendError<T> {}
+
+impl<T> TrySendError<T> {
+    /// Returns `true` if this error is a result of the channel being full.
+    pub fn is_full(&self) -> bool {
+        self.err.is_full()
+    }
+
+    /// Returns `true` if this error is a result of the receiver being dropped
+    /// or closed.
+    pub fn is_disconnected(&self) -> bool {
+        self.err.is_disconnected()
+    }
+
+    /// Returns the message that was attempted to be sent but failed.
+    pub fn into_inner(self) -> T {
+        self.val
+    }
+
+    /// Drops the message and converts into a `SendError`.
+    pub fn into_send_error(self) -> SendError {
+        self.err
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiver channel is empty")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryRecvError {}
+
+impl<T> Sender<T> {
+    /// Polls the channel to determine if there is room to send a message.
+    ///
+    /// As this is the only sender, the room reported by `Poll::Ready(Ok(_))`
+    /// stays available until the next message is sent. Otherwise, the current
+    /// task is woken up once room is available. An error is returned if the
+    /// receiver has been dropped or closed.
+    pub fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SendError>> {
+        if self.shared.is_closed() {
+            return Poll::Ready(Err(SendError { kind: SendErrorKind::Disconnected }));
+        }
+        if !self.shared.is_full() {
+            return Poll::Ready(Ok(()));
+        }
+
+        self.shared.send_task.register(cx.waker());
+
+        // Check again, in case the receiver made room before the task was
+        // registered.
+        if self.shared.is_closed() {
+            Poll::Ready(Err(SendError { kind: SendErrorKind::Disconnected }))
+        } else if !self.shared.is_full() {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Attempts to send a message on this `Sender`, returning the message
+    /// if there was an error.
+    pub fn try_send(&mut self, msg: T) -> Result<(), TrySendError<T>> {
+        if self.shared.is_closed() {
+            return Err(TrySendError {
+                err: SendError { kind: SendErrorKind::Disconnected },
+                val: msg,
+            });
+        }
+
+        match self.shared.push(msg) {
+            Ok(()) => {
+                self.shared.recv_task.wake();
+                Ok(())
+            }
+            Err(msg) => {
+                Err(TrySendError { err: SendError { kind: SendErrorKind::Full }, val: msg })
+            }
+        }
+    }
+
+    /// Send a message on the channel.
+    ///
+    /// This function should only be called after
+    /// [`poll_ready`](Sender::poll_ready) has reported that the channel is
+    /// ready to receive a message.
+    pub fn start_send(&mut self, msg: T) -> Result<(), SendError> {
+        self.try_send(msg).map_err(|e| e.err)
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    /// Returns `true` if there are no messages in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of messages the channel can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// Returns whether the receiver has been dropped or closed.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.close();
+        self.shared.recv_task.wake();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").field("len", &self.len()).finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Closes the receiving half of the channel, preventing any further
+    /// messages from being sent.
+    ///
+    /// The messages already in the channel can still be received.
+    pub fn close(&mut self) {
+        self.shared.close();
+        self.shared.send_task.wake();
+    }
+
+    /// Tries to receive the next message without notifying a context if
+    /// empty.
+    ///
+    /// This function returns:
+    /// * `Ok(Some(t))` when a message is fetched
+    /// * `Ok(None)` when the sender is dropped and no messages are left
+    /// * `Err(e)` when there are no messages available, but the channel is not
+    ///   closed yet
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        self.next_message().ok_or(TryRecvError { _priv: () })
+    }
+
+    fn next_message(&mut self) -> Option<Option<T>> {
+        if self.terminated {
+            return Some(None);
+        }
+
+        if let Some(msg) = self.shared.pop() {
+            self.shared.send_task.wake();
+            return Some(Some(msg));
+        }
+
+        if self.shared.is_closed() {
+            // The sender may have sent a last message before closing the
+            // channel.
+            if let Some(msg) = self.shared.pop() {
+                return Some(Some(msg));
+            }
+            self.terminated = true;
+            return Some(None);
+        }
+
+        None
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    /// Returns `true` if there are no messages in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of messages the channel can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if let Some(msg) = self.next_message() {
+            return Poll::Ready(msg);
+        }
+
+        self.shared.recv_task.register(cx.waker());
+
+        // Check again, in case a message was sent before the task was
+        // registered.
+        match self.next_message() {
+            Some(msg) => Poll::Ready(msg),
+            None => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.terminated {
+            (0, Some(0))
+        } else {
+            (self.len(), None)
+        }
+    }
+}
+
+impl<T> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.close();
+        self.shared.send_task.wake();
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+#[cfg(feature = "sink")]
+mod sink_impl {
+    use super::{SendError, Sender};
+    use core::pin::Pin;
+    use futures_core::task::{Context, Poll};
+    use futures_sink::Sink;
+
+    impl<T> Sink<T> for Sender<T> {
+        type Error = SendError;
+
+        fn poll_ready(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            (*self).poll_ready(cx)
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, msg: T) -> Result<(), Self::Error> {
+            (*self).start_send(msg)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::channel;
+    use core::sync::atomic::Ordering;
+
+    #[test]
+    fn positions_wrap_around() {
+        let (tx, rx) = channel(3);
+        let start = usize::MAX - 4;
+        rx.shared.head.store(start, Ordering::Relaxed);
+        tx.shared.tail.store(start, Ordering::Relaxed);
+
+        for i in 0..10 {
+            assert!(tx.shared.push(i).is_ok());
+            assert!(tx.shared.push(i + 100).is_ok());
+            assert_eq!(tx.shared.len(), 2);
+            assert_eq!(rx.shared.pop(), Some(i));
+            assert_eq!(rx.shared.pop(), Some(i + 100));
+            assert_eq!(rx.shared.pop(), None);
+        }
+        for i in 0..3 {
+            assert!(tx.shared.push(i).is_ok());
+        }
+        assert!(tx.shared.is_full());
+        assert_eq!(tx.shared.push(3), Err(3));
+    }
+}
@@ -0,0 +1,330 @@
+//! A single-producer, multi-consumer channel that only retains the latest
+//! value.
+//!
+//! A watch channel holds a single value, which the [`Sender`] replaces with
+//! every send. [`Receiver`]s can [`borrow`](Receiver::borrow) the current
+//! value at any time, and wait with [`changed`](Receiver::changed) for it to
+//! be replaced since they last looked at it. Receivers which don't keep up
+//! only see the latest value, never the intermediate ones, which makes this
+//! channel a good fit for distributing state such as configuration or a
+//! shutdown signal.
+
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll, Waker};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::sync::{
+    SgxMutex as Mutex, SgxRwLock as RwLock, SgxRwLockReadGuard as RwLockReadGuard,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(not(feature = "phala-sgx"))]
+use std::sync::{Mutex, RwLock, RwLockReadGuard};
+
+/// Creates a new watch channel holding the given initial value, returning
+/// its sending half and a first receiving half.
+///
+/// More receivers can be created by cloning a [`Receiver`] or with
+/// [`Sender::subscribe`].
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::channel::watch;
+///
+/// let (tx, mut rx) = watch::channel("initial");
+/// assert_eq!(*rx.borrow(), "initial");
+///
+/// tx.send("first").unwrap();
+/// tx.send("second").unwrap();
+///
+/// // Only the latest value is observed.
+/// rx.changed().await.unwrap();
+/// assert_eq!(*rx.borrow_and_update(), "second");
+///
+/// drop(tx);
+/// assert!(rx.changed().await.is_err());
+/// # });
+/// ```
+pub fn channel<T>(init: T) -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        value: RwLock::new(init),
+        state: Mutex::new(State {
+            version: 0,
+            closed: false,
+            num_receivers: 1,
+            next_receiver_id: 1,
+            wakers: HashMap::new(),
+        }),
+    });
+    let receiver = Receiver { shared: shared.clone(), id: 0, version: 0 };
+    (Sender { shared }, receiver)
+}
+
+struct Shared<T> {
+    value: RwLock<T>,
+    // Always locked after `value` when both are needed, since a `Ref` holds
+    // the lock of the value while its receiver may lock the state.
+    state: Mutex<State>,
+}
+
+struct State {
+    // Incremented on each send.
+    version: u64,
+    // Whether the sender has been dropped.
+    closed: bool,
+    num_receivers: usize,
+
+    // Wakers of the receivers waiting for a change, by receiver id.
+    next_receiver_id: usize,
+    wakers: HashMap<usize, Waker>,
+}
+
+impl State {
+    fn wake_all(&mut self) {
+        for (_, waker) in self.wakers.drain() {
+            waker.wake();
+        }
+    }
+}
+
+/// The sending half of a watch channel.
+///
+/// This value is created by the [`channel`] function.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The receiving half of a watch channel.
+///
+/// This value is created by the [`channel`] function or by
+/// [`Sender::subscribe`]. A cloned receiver has seen the same values as the
+/// original one.
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    id: usize,
+    // The version of the value this receiver has seen last.
+    version: u64,
+}
+
+/// A reference to the value of a watch channel, returned by
+/// [`Receiver::borrow`] and [`Sender::borrow`].
+///
+/// The sender blocks when sending a new value while a `Ref` exists, so it
+/// should not be held for long, and never across a send on the same thread.
+pub struct Ref<'a, T> {
+    guard: RwLockReadGuard<'a, T>,
+}
+
+/// Future for the [`changed`](Receiver::changed) method.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Changed<'a, T> {
+    receiver: Option<&'a mut Receiver<T>>,
+}
+
+/// The error returned by [`Sender::send`] when there are no receivers left,
+/// containing the value which couldn't be sent.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+/// The error returned by [`Receiver::changed`] and
+/// [`Receiver::has_changed`] when the sender has been dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SendError").finish()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "send failed because all receivers are gone")
+    }
+}
+
+impl<T: core::any::Any> std::error::Error for SendError<T> {}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sender is gone")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+impl<T> Sender<T> {
+    /// Replaces the value of the channel, notifying all receivers.
+    ///
+    /// An error containing the value is returned if there are no receivers
+    /// left.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut guard = self.shared.value.write().unwrap();
+        let mut state = self.shared.state.lock().unwrap();
+        if state.num_receivers == 0 {
+            return Err(SendError(value));
+        }
+
+        *guard = value;
+        state.version += 1;
+        state.wake_all();
+        Ok(())
+    }
+
+    /// Returns a reference to the current value.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref { guard: self.shared.value.read().unwrap() }
+    }
+
+    /// Creates a new receiver, for which the current value counts as seen.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut state = self.shared.state.lock().unwrap();
+        let id = state.next_receiver_id;
+        state.next_receiver_id += 1;
+        state.num_receivers += 1;
+        Receiver { shared: self.shared.clone(), id, version: state.version }
+    }
+
+    /// Returns the number of receivers currently subscribed to the channel.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.state.lock().unwrap().num_receivers
+    }
+
+    /// Returns whether all receivers have been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.receiver_count() == 0
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.closed = true;
+        state.wake_all();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").field("value", &*self.borrow()).finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Returns a reference to the current value, without marking it as seen.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref { guard: self.shared.value.read().unwrap() }
+    }
+
+    /// Returns a reference to the current value, and marks it as seen.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        let guard = self.shared.value.read().unwrap();
+        self.version = self.shared.state.lock().unwrap().version;
+        Ref { guard }
+    }
+
+    /// Returns whether the value has been replaced since it was last seen by
+    /// this receiver.
+    ///
+    /// An error is returned if the sender has been dropped.
+    pub fn has_changed(&self) -> Result<bool, RecvError> {
+        let state = self.shared.state.lock().unwrap();
+        if state.closed {
+            Err(RecvError)
+        } else {
+            Ok(state.version != self.version)
+        }
+    }
+
+    /// Waits for the value to be replaced since it was last seen by this
+    /// receiver, and marks it as seen.
+    ///
+    /// The returned future resolves to an error if the sender has been
+    /// dropped and the last value it sent has been seen already.
+    pub fn changed(&mut self) -> Changed<'_, T> {
+        Changed { receiver: Some(self) }
+    }
+
+    /// Returns whether the receivers receive from the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+
+    fn poll_changed(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), RecvError>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.version != self.version {
+            self.version = state.version;
+            Poll::Ready(Ok(()))
+        } else if state.closed {
+            Poll::Ready(Err(RecvError))
+        } else {
+            state.wakers.insert(self.id, cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        let mut state = self.shared.state.lock().unwrap();
+        let id = state.next_receiver_id;
+        state.next_receiver_id += 1;
+        state.num_receivers += 1;
+        Self { shared: self.shared.clone(), id, version: self.version }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.num_receivers -= 1;
+        state.wakers.remove(&self.id);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").field("value", &*self.borrow()).finish()
+    }
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Future for Changed<'_, T> {
+    type Output = Result<(), RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let receiver = self.receiver.as_mut().expect("Changed polled after completion");
+        let res = futures_core::ready!(receiver.poll_changed(cx));
+        self.receiver = None;
+        Poll::Ready(res)
+    }
+}
+
+impl<T> FusedFuture for Changed<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.receiver.is_none()
+    }
+}
+
+impl<T> fmt::Debug for Changed<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Changed").finish()
+    }
+}
@@ -0,0 +1,156 @@
+use futures::channel::bounded;
+use futures::executor::block_on;
+use futures::future::poll_fn;
+use futures::stream::{FusedStream, StreamExt};
+use futures_test::task::{new_count_waker, noop_context};
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::thread;
+
+#[test]
+fn send_recv_many_senders() {
+    const AMT: usize = 1000;
+    const SENDERS: usize = 4;
+
+    let (tx, rx) = bounded::channel(4);
+    let threads: Vec<_> = (0..SENDERS)
+        .map(|n| {
+            let mut tx = tx.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    for i in 0..AMT {
+                        poll_fn(|cx| tx.poll_ready(cx)).await.unwrap();
+                        tx.start_send(n * AMT + i).unwrap();
+                    }
+                })
+            })
+        })
+        .collect();
+    drop(tx);
+
+    let received = block_on(rx.collect::<Vec<_>>());
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    // The messages of each sender are received in order.
+    for n in 0..SENDERS {
+        let from_n: Vec<_> = received.iter().filter(|&&i| i / AMT == n).cloned().collect();
+        assert_eq!(from_n, (n * AMT..(n + 1) * AMT).collect::<Vec<_>>());
+    }
+    assert_eq!(received.len(), SENDERS * AMT);
+}
+
+#[test]
+fn poll_ready_reserves_slot() {
+    let (mut tx1, mut rx) = bounded::channel(2);
+    let mut tx2 = tx1.clone();
+    assert_eq!(tx1.capacity(), 2);
+
+    assert_eq!(tx1.poll_ready(&mut noop_context()), Poll::Ready(Ok(())));
+    tx2.try_send(1).unwrap();
+
+    // The last slot is reserved by `tx1`.
+    let err = tx2.try_send(2).unwrap_err();
+    assert!(err.is_full());
+    assert_eq!(err.into_inner(), 2);
+    tx1.start_send(3).unwrap();
+
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    assert_eq!(rx.try_next().unwrap(), Some(3));
+    assert!(rx.try_next().is_err());
+    assert!(rx.is_empty());
+}
+
+#[test]
+fn poll_ready_wakes_senders() {
+    let (waker1, counter1) = new_count_waker();
+    let (waker2, counter2) = new_count_waker();
+
+    let (mut tx1, mut rx) = bounded::channel(1);
+    let mut tx2 = tx1.clone();
+    tx1.try_send(1).unwrap();
+    assert_eq!(tx1.poll_ready(&mut Context::from_waker(&waker1)), Poll::Pending);
+    assert_eq!(tx2.poll_ready(&mut Context::from_waker(&waker2)), Poll::Pending);
+
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    assert_eq!(counter1, 1);
+    assert_eq!(counter2, 1);
+    assert_eq!(tx2.poll_ready(&mut Context::from_waker(&waker2)), Poll::Ready(Ok(())));
+    assert_eq!(tx1.poll_ready(&mut Context::from_waker(&waker1)), Poll::Pending);
+
+    // Dropping a sender releases its reserved slot.
+    drop(tx2);
+    assert_eq!(counter1, 2);
+    assert_eq!(tx1.poll_ready(&mut Context::from_waker(&waker1)), Poll::Ready(Ok(())));
+}
+
+#[test]
+fn receiver_woken_on_send() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (mut tx, mut rx) = bounded::channel(1);
+    assert_eq!(rx.poll_next_unpin(&mut cx), Poll::Pending);
+
+    tx.try_send(1).unwrap();
+    assert_eq!(counter, 1);
+    assert_eq!(rx.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+}
+
+#[test]
+fn drop_senders_ends_stream() {
+    let (mut tx1, mut rx) = bounded::channel(2);
+    let mut tx2 = tx1.clone();
+    tx1.try_send(1).unwrap();
+    drop(tx1);
+    assert_eq!(block_on(rx.next()), Some(1));
+    assert!(rx.try_next().is_err());
+
+    tx2.try_send(2).unwrap();
+    drop(tx2);
+    assert_eq!(block_on(rx.next()), Some(2));
+    assert_eq!(block_on(rx.next()), None);
+    assert!(rx.is_terminated());
+}
+
+#[test]
+fn drop_receiver_disconnects() {
+    let (waker, counter) = new_count_waker();
+
+    let (mut tx1, rx) = bounded::channel(1);
+    let mut tx2 = tx1.clone();
+    tx1.try_send(1).unwrap();
+    assert_eq!(tx2.poll_ready(&mut Context::from_waker(&waker)), Poll::Pending);
+    drop(rx);
+
+    assert_eq!(counter, 1);
+    assert!(tx1.is_closed());
+    assert!(tx1.try_send(2).unwrap_err().is_disconnected());
+    assert!(matches!(tx2.poll_ready(&mut Context::from_waker(&waker)), Poll::Ready(Err(_))));
+}
+
+#[test]
+fn close_receiver() {
+    let (mut tx, mut rx) = bounded::channel(2);
+    tx.try_send(1).unwrap();
+    rx.close();
+
+    assert!(tx.try_send(2).unwrap_err().is_disconnected());
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    assert_eq!(rx.try_next().unwrap(), None);
+}
+
+#[test]
+fn drops_unreceived_messages() {
+    let (mut tx, rx) = bounded::channel(4);
+    let msg = Rc::new(());
+
+    tx.try_send(msg.clone()).unwrap();
+    tx.try_send(msg.clone()).unwrap();
+    assert_eq!(Rc::strong_count(&msg), 3);
+
+    drop(tx);
+    drop(rx);
+    assert_eq!(Rc::strong_count(&msg), 1);
+}
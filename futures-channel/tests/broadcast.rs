@@ -0,0 +1,91 @@
+use futures::channel::broadcast::{self, Lagged, TryRecvError};
+use futures::executor::{block_on, block_on_stream};
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+use futures_test::task::new_count_waker;
+use std::task::{Context, Poll};
+use std::thread;
+
+#[test]
+fn every_receiver_sees_every_value() {
+    let (tx, rx1) = broadcast::channel(4);
+    let rx2 = rx1.clone();
+
+    let t = thread::spawn(move || {
+        for i in 0..3 {
+            assert_eq!(tx.send(i), Ok(2));
+        }
+    });
+
+    let rx1 = block_on_stream(rx1);
+    let rx2 = block_on_stream(rx2);
+    assert_eq!(rx1.collect::<Vec<_>>(), vec![Ok(0), Ok(1), Ok(2)]);
+    assert_eq!(rx2.collect::<Vec<_>>(), vec![Ok(0), Ok(1), Ok(2)]);
+
+    t.join().unwrap();
+}
+
+#[test]
+fn subscribe_sees_later_values() {
+    let (tx, mut rx1) = broadcast::channel(4);
+    tx.send(0).unwrap();
+    let mut rx2 = tx.subscribe();
+    tx.send(1).unwrap();
+
+    assert_eq!(rx1.try_recv(), Ok(0));
+    assert_eq!(rx1.try_recv(), Ok(1));
+    assert_eq!(rx1.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(rx2.try_recv(), Ok(1));
+    assert_eq!(rx2.try_recv(), Err(TryRecvError::Empty));
+
+    drop(tx);
+    assert_eq!(rx2.try_recv(), Err(TryRecvError::Closed));
+}
+
+#[test]
+fn slow_receiver_lags() {
+    let (tx, mut rx) = broadcast::channel(2);
+    for i in 0..5 {
+        tx.send(i).unwrap();
+    }
+
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Lagged(3)));
+    assert_eq!(rx.try_recv(), Ok(3));
+    tx.send(5).unwrap();
+    tx.send(6).unwrap();
+    drop(tx);
+
+    assert_eq!(block_on(rx.collect::<Vec<_>>()), vec![Err(Lagged(1)), Ok(5), Ok(6)]);
+}
+
+#[test]
+fn send_wakes_receivers() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (tx, mut rx1) = broadcast::channel(1);
+    let mut rx2 = tx.subscribe();
+    assert_eq!(rx1.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(rx2.poll_next_unpin(&mut cx), Poll::Pending);
+
+    tx.send(1).unwrap();
+    assert_eq!(counter, 2);
+    assert_eq!(rx1.poll_next_unpin(&mut cx), Poll::Ready(Some(Ok(1))));
+    assert_eq!(rx1.poll_next_unpin(&mut cx), Poll::Pending);
+
+    drop(tx);
+    assert_eq!(counter, 3);
+    assert_eq!(rx1.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert_eq!(rx2.poll_next_unpin(&mut cx), Poll::Ready(Some(Ok(1))));
+}
+
+#[test]
+fn send_without_receivers() {
+    let (mut tx, rx) = broadcast::channel(1);
+    assert_eq!(tx.receiver_count(), 1);
+    drop(rx);
+
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(1).unwrap_err().0, 1);
+    assert!(block_on(SinkExt::send(&mut tx, 2)).is_err());
+}
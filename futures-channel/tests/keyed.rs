@@ -0,0 +1,109 @@
+use futures::channel::keyed;
+use futures::executor::block_on;
+use futures::sink::SinkExt;
+use futures::stream::{FusedStream, StreamExt};
+use futures_test::task::new_count_waker;
+use std::task::{Context, Poll};
+use std::thread;
+
+#[test]
+fn routes_by_key() {
+    let tx = keyed::channel();
+    let mut a = tx.subscribe('a').unwrap();
+    let mut b = tx.subscribe('b').unwrap();
+    assert_eq!(tx.receiver_count(), 2);
+
+    tx.send('a', 1).unwrap();
+    tx.send('b', 2).unwrap();
+    tx.send('a', 3).unwrap();
+
+    assert_eq!(a.len(), 2);
+    assert_eq!(a.try_next().unwrap(), Some(1));
+    assert_eq!(a.try_next().unwrap(), Some(3));
+    assert!(a.try_next().is_err());
+    assert_eq!(b.try_next().unwrap(), Some(2));
+}
+
+#[test]
+fn send_to_unsubscribed_key() {
+    let tx = keyed::channel::<&str, i32>();
+    let err = tx.send("nobody", 1).unwrap_err();
+    assert_eq!((err.0, err.1), ("nobody", 1));
+    assert!(!tx.is_subscribed(&"nobody"));
+}
+
+#[test]
+fn one_receiver_per_key() {
+    let tx = keyed::channel::<u32, ()>();
+    let rx = tx.subscribe(1).unwrap();
+    assert_eq!(*rx.key(), 1);
+    assert!(tx.subscribe(1).is_none());
+
+    drop(rx);
+    assert!(tx.subscribe(1).is_some());
+}
+
+#[test]
+fn drop_receiver_removes_mailbox() {
+    let tx = keyed::channel();
+    let rx = tx.subscribe(1).unwrap();
+    tx.send(1, "pending").unwrap();
+
+    drop(rx);
+    assert!(!tx.is_subscribed(&1));
+    assert_eq!(tx.receiver_count(), 0);
+    assert!(tx.send(1, "late").is_err());
+
+    // A new receiver starts with an empty mailbox.
+    let mut rx = tx.subscribe(1).unwrap();
+    assert!(rx.try_next().is_err());
+}
+
+#[test]
+fn stream_ends_when_senders_dropped() {
+    let tx = keyed::channel();
+    let tx2 = tx.clone();
+    let mut rx = tx.subscribe("k").unwrap();
+
+    tx.send("k", 1).unwrap();
+    drop(tx);
+    tx2.send("k", 2).unwrap();
+    drop(tx2);
+
+    assert_eq!(block_on(rx.by_ref().collect::<Vec<_>>()), vec![1, 2]);
+    assert!(rx.is_terminated());
+}
+
+#[test]
+fn send_wakes_receiver() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let tx = keyed::channel();
+    let mut a = tx.subscribe(0).unwrap();
+    let mut b = tx.subscribe(1).unwrap();
+    assert_eq!(a.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(b.poll_next_unpin(&mut cx), Poll::Pending);
+
+    tx.send(1, ()).unwrap();
+    assert_eq!(counter, 1);
+    assert_eq!(b.poll_next_unpin(&mut cx), Poll::Ready(Some(())));
+    assert_eq!(a.poll_next_unpin(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn sink_sends_keyed_messages() {
+    let mut tx = keyed::channel();
+    let rx = tx.subscribe(7).unwrap();
+
+    let sender = thread::spawn(move || {
+        block_on(async {
+            for i in 0..10 {
+                SinkExt::send(&mut tx, (7, i)).await.unwrap();
+            }
+        })
+    });
+
+    assert_eq!(block_on(rx.collect::<Vec<_>>()), (0..10).collect::<Vec<_>>());
+    sender.join().unwrap();
+}
@@ -0,0 +1,100 @@
+use futures::channel::mpmc;
+use futures::executor::block_on;
+use futures::future::{self, FutureExt};
+use futures::sink::SinkExt;
+use futures::stream::{FusedStream, StreamExt};
+use futures_test::task::{new_count_waker, noop_context};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::thread;
+
+#[test]
+fn each_message_received_once() {
+    const AMT: usize = 1000;
+    const NTHREADS: usize = 4;
+
+    let (mut tx, rx) = mpmc::channel(8);
+    let sum = Arc::new(AtomicUsize::new(0));
+    let threads: Vec<_> = (0..NTHREADS)
+        .map(|_| {
+            let rx = rx.clone();
+            let sum = sum.clone();
+            thread::spawn(move || {
+                block_on(rx.for_each(|i| {
+                    sum.fetch_add(i, Ordering::SeqCst);
+                    future::ready(())
+                }))
+            })
+        })
+        .collect();
+    drop(rx);
+
+    block_on(async {
+        for i in 0..AMT {
+            tx.send(i).await.unwrap();
+        }
+    });
+    drop(tx);
+
+    for t in threads {
+        t.join().unwrap();
+    }
+    assert_eq!(sum.load(Ordering::SeqCst), AMT * (AMT - 1) / 2);
+}
+
+#[test]
+fn receivers_woken_in_order() {
+    let (waker1, counter1) = new_count_waker();
+    let (waker2, counter2) = new_count_waker();
+
+    let (mut tx, mut rx1) = mpmc::unbounded();
+    let mut rx2 = rx1.clone();
+
+    assert_eq!(rx1.poll_next_unpin(&mut Context::from_waker(&waker1)), Poll::Pending);
+    assert_eq!(rx2.poll_next_unpin(&mut Context::from_waker(&waker2)), Poll::Pending);
+
+    tx.try_send(1).unwrap();
+    assert_eq!((counter1.get(), counter2.get()), (1, 0));
+    tx.try_send(2).unwrap();
+    assert_eq!((counter1.get(), counter2.get()), (1, 1));
+
+    assert_eq!(rx2.try_next(), Ok(Some(1)));
+    assert_eq!(rx1.try_next(), Ok(Some(2)));
+    assert!(rx1.try_next().is_err());
+
+    drop(tx);
+    assert_eq!(rx1.try_next(), Ok(None));
+    assert!(rx1.is_terminated());
+}
+
+#[test]
+fn poll_ready_reserves_room() {
+    let mut cx = noop_context();
+    let (mut tx1, mut rx) = mpmc::channel(1);
+    let mut tx2 = tx1.clone();
+
+    assert_eq!(tx1.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    assert!(tx2.poll_ready(&mut cx).is_pending());
+    assert!(tx2.try_send(2).unwrap_err().is_full());
+    tx1.start_send(1).unwrap();
+
+    let mut send = tx2.send(2);
+    assert!(send.poll_unpin(&mut cx).is_pending());
+    assert_eq!(block_on(rx.next()), Some(1));
+    assert_eq!(send.poll_unpin(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(block_on(rx.next()), Some(2));
+}
+
+#[test]
+fn send_fails_without_receivers() {
+    let (mut tx, rx) = mpmc::channel::<i32>(1);
+    let rx2 = rx.clone();
+    drop(rx);
+    assert!(!tx.is_closed());
+    drop(rx2);
+
+    assert!(tx.is_closed());
+    assert!(tx.try_send(1).unwrap_err().is_disconnected());
+    assert!(block_on(tx.send(2)).unwrap_err().is_disconnected());
+}
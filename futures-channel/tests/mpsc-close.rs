@@ -4,6 +4,7 @@ use futures::future::Future;
 use futures::sink::SinkExt;
 use futures::stream::StreamExt;
 use futures::task::{Context, Poll};
+use futures_test::task::new_count_waker;
 use std::pin::Pin;
 use std::sync::{Arc, Weak};
 use std::thread;
@@ -296,3 +297,68 @@ fn bounded_try_next_after_none() {
     // None received, check we can call `try_next` again.
     assert_eq!(Ok(None), rx.try_next().map_err(|_| ()));
 }
+
+#[test]
+fn closed_resolves_on_receiver_drop() {
+    let (tx, rx) = mpsc::channel::<i32>(1);
+    let tx2 = tx.clone();
+    assert!(!tx.is_closed());
+
+    let t = thread::spawn(move || block_on(tx2.closed()));
+    thread::sleep(Duration::from_millis(10));
+    drop(rx);
+    t.join().unwrap();
+
+    assert!(tx.is_closed());
+    block_on(tx.closed());
+}
+
+#[test]
+fn unbounded_closed_resolves_on_receiver_close() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (tx, mut rx) = mpsc::unbounded::<i32>();
+    {
+        let mut closed = tx.closed();
+        assert_eq!(Pin::new(&mut closed).poll(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut closed).poll(&mut cx), Poll::Pending);
+        rx.close();
+        assert_eq!(counter, 1);
+        assert_eq!(Pin::new(&mut closed).poll(&mut cx), Poll::Ready(()));
+    }
+
+    let mut tx2 = tx.clone();
+    tx2.disconnect();
+    assert_eq!(tx2.poll_closed(&mut cx), Poll::Ready(()));
+}
+
+#[test]
+fn closed_dropped_while_pending() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (waker2, counter2) = new_count_waker();
+    let mut cx2 = Context::from_waker(&waker2);
+
+    let (tx, rx) = mpsc::channel::<i32>(1);
+    let mut closed = tx.closed();
+    assert_eq!(Pin::new(&mut closed).poll(&mut cx), Poll::Pending);
+    drop(closed);
+
+    // The task of a dropped `closed()` future is no longer woken up.
+    let mut closed = tx.closed();
+    assert_eq!(Pin::new(&mut closed).poll(&mut cx2), Poll::Pending);
+    drop(rx);
+    assert_eq!(counter, 0);
+    assert_eq!(counter2, 1);
+    assert_eq!(Pin::new(&mut closed).poll(&mut cx2), Poll::Ready(()));
+
+    let (tx, mut rx) = mpsc::unbounded::<i32>();
+    let mut closed = tx.closed();
+    assert_eq!(Pin::new(&mut closed).poll(&mut cx), Poll::Pending);
+    drop(closed);
+    assert_eq!(tx.poll_closed(&mut cx2), Poll::Pending);
+    rx.close();
+    assert_eq!(counter, 0);
+    assert_eq!(counter2, 2);
+}
@@ -3,7 +3,7 @@ use futures::executor::{block_on, block_on_stream};
 use futures::future::{poll_fn, FutureExt};
 use futures::pin_mut;
 use futures::sink::{Sink, SinkExt};
-use futures::stream::{Stream, StreamExt};
+use futures::stream::{FusedStream, Stream, StreamExt};
 use futures::task::{Context, Poll};
 use futures_test::task::{new_count_waker, noop_context};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -424,6 +424,149 @@ fn stress_poll_ready() {
     stress(16);
 }
 
+#[test]
+fn poll_ready_reserves_slot_per_sender() {
+    let mut cx = noop_context();
+    let (mut tx1, mut rx) = mpsc::channel(0);
+    let mut tx2 = tx1.clone();
+
+    // Both senders are ready even though the shared buffer is empty, and
+    // neither send can take the slot reserved by the other.
+    assert_eq!(tx1.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(tx2.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    tx2.start_send(2).unwrap();
+    tx1.start_send(1).unwrap();
+
+    assert!(tx1.poll_ready(&mut cx).is_pending());
+    assert!(tx2.poll_ready(&mut cx).is_pending());
+    assert!(tx1.start_send(3).unwrap_err().is_full());
+
+    assert_eq!(block_on(rx.next()), Some(2));
+    assert_eq!(block_on(rx.next()), Some(1));
+    assert_eq!(tx1.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(tx2.poll_ready(&mut cx), Poll::Ready(Ok(())));
+}
+
+#[test]
+fn reserve_permit() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (mut tx, mut rx) = mpsc::channel(0);
+    block_on(tx.reserve()).unwrap().send(1).unwrap();
+
+    {
+        let mut reserve = tx.reserve();
+        assert!(reserve.poll_unpin(&mut cx).is_pending());
+        assert_eq!(block_on(rx.next()), Some(1));
+        assert_eq!(counter, 1);
+
+        let permit = match reserve.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(permit)) => permit,
+            _ => panic!("expected a permit"),
+        };
+        permit.send(2).unwrap();
+    }
+    assert_eq!(block_on(rx.next()), Some(2));
+
+    let permit = block_on(tx.reserve()).unwrap();
+    drop(rx);
+    assert!(permit.send(3).unwrap_err().is_disconnected());
+    assert!(block_on(tx.reserve()).unwrap_err().is_disconnected());
+}
+
+#[test]
+fn drain() {
+    let (mut tx, mut rx) = mpsc::channel(4);
+    assert_eq!(rx.drain().next(), None);
+    assert!(!rx.is_terminated());
+
+    for i in 0..3 {
+        tx.try_send(i).unwrap();
+    }
+    assert_eq!(rx.drain().collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert!(!rx.is_terminated());
+
+    tx.try_send(3).unwrap();
+    drop(tx);
+    assert_eq!(rx.drain().collect::<Vec<_>>(), vec![3]);
+    assert!(rx.is_terminated());
+}
+
+#[test]
+fn drain_unbounded() {
+    let (tx, mut rx) = mpsc::unbounded();
+    tx.unbounded_send(0).unwrap();
+    tx.unbounded_send(1).unwrap();
+    assert_eq!(rx.drain().collect::<Vec<_>>(), vec![0, 1]);
+    assert!(!rx.is_terminated());
+
+    drop(tx);
+    assert_eq!(rx.drain().next(), None);
+    assert!(rx.is_terminated());
+}
+
+#[test]
+fn recv_many() {
+    let mut cx = noop_context();
+    let (mut tx, mut rx) = mpsc::channel(8);
+    let mut buf = Vec::new();
+
+    assert_eq!(rx.poll_recv_many(&mut cx, &mut buf, 4), Poll::Pending);
+    for i in 0..6 {
+        tx.try_send(i).unwrap();
+    }
+    assert_eq!(block_on(rx.recv_many(&mut buf, 4)), 4);
+    assert_eq!(buf, vec![0, 1, 2, 3]);
+    assert_eq!(block_on(rx.recv_many(&mut buf, 0)), 0);
+    assert_eq!(block_on(rx.recv_many(&mut buf, 4)), 2);
+    assert_eq!(buf, vec![0, 1, 2, 3, 4, 5]);
+
+    drop(tx);
+    assert_eq!(block_on(rx.recv_many(&mut buf, 4)), 0);
+    assert!(rx.is_terminated());
+}
+
+#[test]
+fn recv_many_unparks_senders() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (tx, mut rx) = mpsc::channel(0);
+    let mut senders = vec![tx.clone(), tx.clone(), tx];
+
+    // Each sender is parked once it has used its guaranteed slot.
+    for (i, tx) in senders.iter_mut().enumerate() {
+        tx.try_send(i).unwrap();
+        assert_eq!(tx.poll_ready(&mut cx), Poll::Pending);
+    }
+
+    let mut buf = Vec::new();
+    assert_eq!(block_on(rx.recv_many(&mut buf, 2)), 2);
+    assert_eq!(counter, 2);
+    assert_eq!(block_on(rx.recv_many(&mut buf, 2)), 1);
+    assert_eq!(counter, 3);
+    assert_eq!(buf, vec![0, 1, 2]);
+    for tx in &mut senders {
+        assert_eq!(tx.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    }
+}
+
+#[test]
+fn recv_many_unbounded() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let t = thread::spawn(move || {
+        for i in 0..100 {
+            tx.unbounded_send(i).unwrap();
+        }
+    });
+
+    let mut buf = Vec::new();
+    while block_on(rx.recv_many(&mut buf, 16)) > 0 {}
+    assert_eq!(buf, (0..100).collect::<Vec<_>>());
+
+    t.join().unwrap();
+}
+
 #[test]
 fn try_send_1() {
     const N: usize = 3000;
@@ -628,3 +771,93 @@ fn send_backpressure_multi_senders() {
     let item = block_on(rx.next()).unwrap();
     assert_eq!(item, 2);
 }
+
+#[test]
+fn weak_sender() {
+    let (tx, mut rx) = mpsc::channel(1);
+    let weak = tx.downgrade();
+    let weak2 = weak.clone();
+
+    let mut upgraded = weak.upgrade().unwrap();
+    assert!(upgraded.same_receiver(&tx));
+    drop(tx);
+
+    // The upgraded sender keeps the channel open.
+    block_on(upgraded.send(1)).unwrap();
+    assert_eq!(block_on(rx.next()), Some(1));
+    drop(upgraded);
+
+    // Weak senders alone don't.
+    assert_eq!(block_on(rx.next()), None);
+    assert!(weak.upgrade().is_none());
+    assert!(weak2.upgrade().is_none());
+}
+
+#[test]
+fn weak_unbounded_sender() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let weak = tx.downgrade();
+
+    weak.upgrade().unwrap().unbounded_send(1).unwrap();
+    assert_eq!(block_on(rx.next()), Some(1));
+
+    drop(tx);
+    assert_eq!(block_on(rx.next()), None);
+    assert!(weak.upgrade().is_none());
+
+    let mut tx = mpsc::unbounded::<i32>().0;
+    tx.disconnect();
+    assert!(tx.downgrade().upgrade().is_none());
+}
+
+#[test]
+fn capacity_accessors() {
+    let (mut tx, mut rx) = mpsc::channel::<i32>(2);
+    let tx2 = tx.clone();
+    assert_eq!(tx.max_capacity(), 4);
+    assert_eq!(rx.max_capacity(), 4);
+    assert!(rx.is_empty());
+
+    tx.try_send(1).unwrap();
+    tx.try_send(2).unwrap();
+    assert_eq!(rx.len(), 2);
+    assert_eq!(tx2.capacity(), 2);
+
+    drop(tx2);
+    assert_eq!(tx.max_capacity(), 3);
+
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    assert_eq!(tx.len(), 1);
+    assert_eq!(tx.capacity(), 2);
+
+    tx.disconnect();
+    assert_eq!(tx.max_capacity(), 0);
+}
+
+#[test]
+fn set_buffer() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (mut tx, mut rx) = mpsc::channel::<i32>(0);
+    tx.try_send(1).unwrap();
+    assert_eq!(tx.poll_ready(&mut cx), Poll::Pending);
+
+    // Growing the buffer wakes up the parked sender.
+    rx.set_buffer(2);
+    assert_eq!(counter, 1);
+    assert_eq!(tx.max_capacity(), 3);
+    assert_eq!(tx.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    tx.try_send(2).unwrap();
+    tx.try_send(3).unwrap();
+    assert!(tx.try_send(4).unwrap_err().is_full());
+
+    // Shrinking it keeps the buffered messages.
+    rx.set_buffer(0);
+    assert_eq!(rx.len(), 3);
+    assert_eq!(rx.capacity(), 0);
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    tx.try_send(4).unwrap();
+    assert!(tx.try_send(5).unwrap_err().is_full());
+    assert_eq!(rx.drain().collect::<Vec<_>>(), vec![2, 3, 4]);
+}
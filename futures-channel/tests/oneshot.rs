@@ -2,7 +2,7 @@ use futures::channel::oneshot::{self, Sender};
 use futures::executor::block_on;
 use futures::future::{poll_fn, FutureExt};
 use futures::task::{Context, Poll};
-use futures_test::task::panic_waker_ref;
+use futures_test::task::{new_count_waker, panic_waker_ref};
 use std::sync::mpsc;
 use std::thread;
 
@@ -33,6 +33,23 @@ fn cancel_notifies() {
     t.join().unwrap();
 }
 
+#[test]
+fn cancellation_wakes_on_receiver_drop() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (mut tx, rx) = oneshot::channel::<u32>();
+
+    {
+        let mut cancellation = tx.cancellation();
+        assert_eq!(cancellation.poll_unpin(&mut cx), Poll::Pending);
+        drop(rx);
+        assert_eq!(counter, 1);
+        assert_eq!(cancellation.poll_unpin(&mut cx), Poll::Ready(()));
+    }
+    assert!(tx.is_canceled());
+    assert_eq!(tx.send(1), Err(1));
+}
+
 #[test]
 fn cancel_lots() {
     let (tx, rx) = mpsc::channel::<(Sender<_>, mpsc::Sender<_>)>();
@@ -99,6 +116,30 @@ fn is_canceled() {
     assert!(tx.is_canceled());
 }
 
+#[test]
+fn try_recv() {
+    let (tx, mut rx) = oneshot::channel::<u32>();
+    assert_eq!(rx.try_recv(), Ok(None));
+    tx.send(1).unwrap();
+    assert_eq!(rx.try_recv(), Ok(Some(1)));
+
+    let (tx, mut rx) = oneshot::channel::<u32>();
+    drop(tx);
+    assert_eq!(rx.try_recv(), Err(oneshot::Canceled));
+
+    // A value sent before the receiver is closed can still be received.
+    let (tx, mut rx) = oneshot::channel::<u32>();
+    let t = thread::spawn(move || tx.send(2).unwrap());
+    t.join().unwrap();
+    rx.close();
+    assert_eq!(rx.try_recv(), Ok(Some(2)));
+
+    let (tx, mut rx) = oneshot::channel::<u32>();
+    rx.close();
+    assert_eq!(rx.try_recv(), Err(oneshot::Canceled));
+    assert_eq!(tx.send(3), Err(3));
+}
+
 #[test]
 fn cancel_sends() {
     let (tx, rx) = mpsc::channel::<Sender<_>>();
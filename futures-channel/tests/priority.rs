@@ -0,0 +1,77 @@
+use futures::channel::priority;
+use futures::executor::block_on;
+use futures::future::FutureExt;
+use futures::sink::SinkExt;
+use futures::stream::{FusedStream, StreamExt};
+use futures_test::task::{new_count_waker, noop_context};
+use std::task::{Context, Poll};
+
+#[test]
+fn highest_priority_first() {
+    let (mut lane0, mut rx) = priority::channel(3, 4);
+    let mut lane1 = lane0.for_lane(1);
+    let mut lane2 = lane0.for_lane(2);
+    assert_eq!(lane2.lane(), 2);
+
+    lane2.try_send(20).unwrap();
+    lane1.try_send(10).unwrap();
+    lane2.try_send(21).unwrap();
+    lane0.try_send(0).unwrap();
+    assert_eq!(rx.try_next(), Ok(Some(0)));
+
+    lane1.try_send(11).unwrap();
+    drop((lane0, lane1, lane2));
+    assert_eq!(block_on(rx.collect::<Vec<_>>()), vec![10, 11, 20, 21]);
+}
+
+#[test]
+fn per_lane_capacity() {
+    let mut cx = noop_context();
+    let (mut lane0, mut rx) = priority::channel(2, 1);
+    let mut lane1 = lane0.for_lane(1);
+
+    lane1.try_send(10).unwrap();
+    assert!(lane1.try_send(11).unwrap_err().is_full());
+
+    // A full lane doesn't hold back the other ones.
+    assert_eq!(lane0.poll_ready(&mut cx), Poll::Ready(Ok(())));
+    lane0.start_send(0).unwrap();
+
+    let mut send = lane1.send(11);
+    assert!(send.poll_unpin(&mut cx).is_pending());
+    assert_eq!(rx.try_next(), Ok(Some(0)));
+    assert!(send.poll_unpin(&mut cx).is_pending());
+    assert_eq!(rx.try_next(), Ok(Some(10)));
+    assert_eq!(send.poll_unpin(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(rx.try_next(), Ok(Some(11)));
+}
+
+#[test]
+fn send_wakes_receiver() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (mut tx, mut rx) = priority::channel(2, 1);
+
+    assert_eq!(rx.poll_next_unpin(&mut cx), Poll::Pending);
+    tx.try_send(1).unwrap();
+    assert_eq!(counter, 1);
+    assert_eq!(rx.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+
+    assert_eq!(rx.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx);
+    assert_eq!(counter, 2);
+    assert_eq!(rx.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(rx.is_terminated());
+}
+
+#[test]
+fn close_receiver() {
+    let (mut tx, mut rx) = priority::channel(1, 2);
+    tx.try_send(1).unwrap();
+    rx.close();
+
+    assert!(tx.is_closed());
+    assert!(tx.try_send(2).unwrap_err().is_disconnected());
+    assert_eq!(rx.try_next(), Ok(Some(1)));
+    assert_eq!(rx.try_next(), Ok(None));
+}
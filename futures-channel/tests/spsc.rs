@@ -0,0 +1,134 @@
+use futures::channel::spsc;
+use futures::executor::block_on;
+use futures::future::FutureExt;
+use futures::sink::SinkExt;
+use futures::stream::{FusedStream, StreamExt};
+use futures_test::task::{new_count_waker, noop_context};
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::thread;
+
+#[test]
+fn send_recv_in_order() {
+    const AMT: usize = 10_000;
+
+    let (mut tx, rx) = spsc::channel(8);
+    let t = thread::spawn(move || {
+        block_on(async {
+            for i in 0..AMT {
+                tx.send(i).await.unwrap();
+            }
+        })
+    });
+
+    let received = block_on(rx.collect::<Vec<_>>());
+    t.join().unwrap();
+    assert_eq!(received, (0..AMT).collect::<Vec<_>>());
+}
+
+#[test]
+fn try_send_full() {
+    let (mut tx, mut rx) = spsc::channel(2);
+    assert_eq!(tx.capacity(), 2);
+    tx.try_send(1).unwrap();
+    tx.try_send(2).unwrap();
+
+    let err = tx.try_send(3).unwrap_err();
+    assert!(err.is_full());
+    assert_eq!(err.into_inner(), 3);
+    assert_eq!(tx.len(), 2);
+
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    tx.try_send(3).unwrap();
+    assert_eq!(rx.try_next().unwrap(), Some(2));
+    assert_eq!(rx.try_next().unwrap(), Some(3));
+    assert!(rx.try_next().is_err());
+    assert!(rx.is_empty());
+}
+
+#[test]
+fn poll_ready_wakes_sender() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (mut tx, mut rx) = spsc::channel(1);
+    tx.try_send(1).unwrap();
+    assert_eq!(tx.poll_ready(&mut cx), Poll::Pending);
+
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    assert_eq!(counter, 1);
+    assert_eq!(tx.poll_ready(&mut cx), Poll::Ready(Ok(())));
+}
+
+#[test]
+fn receiver_woken_on_send() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (mut tx, mut rx) = spsc::channel(1);
+    assert_eq!(rx.poll_next_unpin(&mut cx), Poll::Pending);
+
+    tx.try_send(1).unwrap();
+    assert_eq!(counter, 1);
+    assert_eq!(rx.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+}
+
+#[test]
+fn drop_sender_ends_stream() {
+    let (mut tx, mut rx) = spsc::channel(2);
+    tx.try_send(1).unwrap();
+    drop(tx);
+
+    assert_eq!(block_on(rx.next()), Some(1));
+    assert_eq!(block_on(rx.next()), None);
+    assert!(rx.is_terminated());
+}
+
+#[test]
+fn drop_receiver_disconnects() {
+    let (mut tx, rx) = spsc::channel(2);
+    tx.try_send(1).unwrap();
+    drop(rx);
+
+    assert!(tx.is_closed());
+    assert!(tx.try_send(2).unwrap_err().is_disconnected());
+    assert!(block_on(tx.send(3)).unwrap_err().is_disconnected());
+}
+
+#[test]
+fn close_receiver() {
+    let (mut tx, mut rx) = spsc::channel(2);
+    tx.try_send(1).unwrap();
+    rx.close();
+
+    assert!(tx.try_send(2).unwrap_err().is_disconnected());
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    assert_eq!(rx.try_next().unwrap(), None);
+}
+
+#[test]
+fn drops_unreceived_messages() {
+    let (mut tx, rx) = spsc::channel(4);
+    let msg = Rc::new(());
+
+    tx.try_send(msg.clone()).unwrap();
+    tx.try_send(msg.clone()).unwrap();
+    assert_eq!(Rc::strong_count(&msg), 3);
+
+    drop(tx);
+    drop(rx);
+    assert_eq!(Rc::strong_count(&msg), 1);
+}
+
+#[test]
+fn send_future_waits_for_room() {
+    let (mut tx, mut rx) = spsc::channel(1);
+    tx.try_send(1).unwrap();
+
+    let mut send = tx.send(2);
+    assert!(send.poll_unpin(&mut noop_context()).is_pending());
+    assert_eq!(rx.try_next().unwrap(), Some(1));
+    assert!(send.poll_unpin(&mut noop_context()).is_ready());
+    drop(send);
+    assert_eq!(rx.try_next().unwrap(), Some(2));
+}
@@ -0,0 +1,92 @@
+use futures::channel::watch::{self, RecvError};
+use futures::executor::block_on;
+use futures::future::FutureExt;
+use futures_test::task::new_count_waker;
+use std::task::{Context, Poll};
+use std::thread;
+
+#[test]
+fn receivers_see_latest_value() {
+    let (tx, mut rx1) = watch::channel(0);
+    let mut rx2 = tx.subscribe();
+
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    assert_eq!(rx1.has_changed(), Ok(true));
+    assert_eq!(*rx1.borrow(), 2);
+
+    block_on(rx1.changed()).unwrap();
+    assert_eq!(rx1.has_changed(), Ok(false));
+    assert_eq!(*rx2.borrow_and_update(), 2);
+    assert_eq!(rx2.has_changed(), Ok(false));
+    assert_eq!(*tx.borrow(), 2);
+}
+
+#[test]
+fn changed_waits_for_send() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (tx, mut rx) = watch::channel("a");
+    {
+        let mut changed = rx.changed();
+        assert_eq!(changed.poll_unpin(&mut cx), Poll::Pending);
+        tx.send("b").unwrap();
+        assert_eq!(counter, 1);
+        assert_eq!(changed.poll_unpin(&mut cx), Poll::Ready(Ok(())));
+    }
+    assert_eq!(*rx.borrow(), "b");
+
+    assert_eq!(rx.changed().poll_unpin(&mut cx), Poll::Pending);
+    drop(tx);
+    assert_eq!(counter, 2);
+    assert_eq!(rx.changed().poll_unpin(&mut cx), Poll::Ready(Err(RecvError)));
+}
+
+#[test]
+fn sender_dropped_after_send() {
+    let (tx, mut rx) = watch::channel(0);
+    let t = thread::spawn(move || {
+        tx.send(1).unwrap();
+    });
+    t.join().unwrap();
+
+    assert_eq!(rx.has_changed(), Err(RecvError));
+    assert_eq!(block_on(rx.changed()), Ok(()));
+    assert_eq!(*rx.borrow(), 1);
+    assert_eq!(block_on(rx.changed()), Err(RecvError));
+}
+
+#[test]
+fn send_without_receivers() {
+    let (tx, rx) = watch::channel(0);
+    let rx2 = rx.clone();
+    assert_eq!(tx.receiver_count(), 2);
+    drop(rx);
+    drop(rx2);
+
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(1).unwrap_err().0, 1);
+    assert_eq!(*tx.borrow(), 0);
+}
+
+#[test]
+fn borrow_while_sending() {
+    // A receiver checking for changes while holding a `Ref` doesn't deadlock
+    // with a concurrent send.
+    let (tx, rx) = watch::channel(0);
+    let t = thread::spawn(move || {
+        for i in 1..=100_000 {
+            tx.send(i).unwrap();
+        }
+    });
+
+    loop {
+        let r = rx.borrow();
+        let _ = rx.has_changed();
+        if *r == 100_000 {
+            break;
+        }
+    }
+    t.join().unwrap();
+}
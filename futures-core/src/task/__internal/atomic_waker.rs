@@ -354,6 +354,43 @@ impl AtomicWaker {
         }
     }
 
+    /// Calls `wake_by_ref` on the last `Waker` passed to `register`, leaving
+    /// it registered.
+    ///
+    /// Unlike with [`wake`](AtomicWaker::wake), later calls to `wake` or
+    /// `wake_by_ref` wake the same task again, until `register` replaces the
+    /// waker or [`take`](AtomicWaker::take) removes it. This suits events
+    /// which may happen several times before the task gets to run.
+    ///
+    /// As with `wake`, if `register` is called concurrently, the task calling
+    /// it is woken right away, and its waker isn't kept.
+    ///
+    /// If `register` has not been called yet, then this does nothing.
+    pub fn wake_by_ref(&self) {
+        match self.state.fetch_or(WAKING, AcqRel) {
+            WAITING => {
+                // The waking lock has been acquired. Wake a clone of the
+                // waker once the lock is released, so that a panicking waker
+                // can't leave it locked.
+                let waker = unsafe { (*self.waker.get()).clone() };
+
+                // Release the lock
+                self.state.fetch_and(!WAKING, Release);
+
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+            state => {
+                // There is a concurrent thread currently updating the
+                // associated task, which will wake it, or waking it.
+                debug_assert!(
+                    state == REGISTERING || state == REGISTERING | WAKING || state == WAKING
+                );
+            }
+        }
+    }
+
     /// Returns the last `Waker` passed to `register`, so that the user can wake it.
     ///
     ///
@@ -0,0 +1,83 @@
+use core::task::{Context, Poll};
+
+#[cfg(feature = "std")]
+mod imp {
+    use core::cell::Cell;
+
+    // The number of budgeted operations a task can perform each time it is
+    // polled by an executor.
+    const INITIAL_BUDGET: usize = 128;
+
+    std::thread_local! {
+        // The budget left to the task being polled on this thread, or `None`
+        // if the task isn't polled by an executor tracking budgets.
+        static BUDGET: Cell<Option<usize>> = Cell::new(None);
+    }
+
+    pub(super) fn with_budget<R>(f: impl FnOnce() -> R) -> R {
+        struct Reset(Option<usize>);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                let _ = BUDGET.try_with(|budget| budget.set(self.0));
+            }
+        }
+
+        let _reset = Reset(BUDGET.with(|budget| budget.replace(Some(INITIAL_BUDGET))));
+        f()
+    }
+
+    pub(super) fn consume() -> bool {
+        BUDGET
+            .try_with(|budget| match budget.get() {
+                Some(0) => false,
+                Some(n) => {
+                    budget.set(Some(n - 1));
+                    true
+                }
+                None => true,
+            })
+            .unwrap_or(true)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod imp {
+    pub(super) fn with_budget<R>(f: impl FnOnce() -> R) -> R {
+        f()
+    }
+
+    pub(super) fn consume() -> bool {
+        true
+    }
+}
+
+/// Runs `f`, which polls a task, with a fresh cooperative scheduling budget.
+///
+/// Executors call this each time they poll a task, so that a task performing
+/// many budgeted operations in a row, such as receiving the messages of a
+/// channel which is never empty, eventually yields to the other tasks instead
+/// of monopolizing its thread. Operations performed outside of this function
+/// aren't limited.
+///
+/// The budget is only tracked with the `std` feature; without it, this just
+/// calls `f`.
+pub fn with_budget<R>(f: impl FnOnce() -> R) -> R {
+    imp::with_budget(f)
+}
+
+/// Consumes a unit of the cooperative scheduling budget of the current task.
+///
+/// Once the budget is exhausted, this wakes up the task and returns
+/// `Poll::Pending`, so that the operation returns `Poll::Pending` too and the
+/// task gets polled again, with a fresh budget, after the executor has run
+/// the other tasks. Operations which may complete over and over without ever
+/// returning `Poll::Pending` call this before each completion.
+pub fn poll_budget(cx: &mut Context<'_>) -> Poll<()> {
+    if imp::consume() {
+        Poll::Ready(())
+    } else {
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
@@ -2,3 +2,6 @@
 mod atomic_waker;
 #[cfg(not(futures_no_atomic_cas))]
 pub use self::atomic_waker::AtomicWaker;
+
+mod budget;
+pub use self::budget::{poll_budget, with_budget};
@@ -1,6 +1,26 @@
 /// Extracts the successful type of a `Poll<T>`.
 ///
 /// This macro bakes in propagation of `Pending` signals by returning early.
+///
+/// # Examples
+///
+/// ```
+/// use futures::ready;
+/// use futures::stream::Stream;
+/// use futures::task::{Context, Poll};
+/// use std::pin::Pin;
+///
+/// fn poll_sum<S>(mut stream: Pin<&mut S>, cx: &mut Context<'_>) -> Poll<u32>
+/// where
+///     S: Stream<Item = u32>,
+/// {
+///     let mut sum = 0;
+///     while let Some(n) = ready!(stream.as_mut().poll_next(cx)) {
+///         sum += n;
+///     }
+///     Poll::Ready(sum)
+/// }
+/// ```
 #[macro_export]
 macro_rules! ready {
     ($e:expr $(,)?) => {
@@ -10,3 +30,53 @@ macro_rules! ready {
         }
     };
 }
+
+/// Extracts the successful type of a `Poll<Result<T, E>>`.
+///
+/// This macro bakes in propagation of both `Pending` signals and errors by
+/// returning early, converting the error with [`From`] like the `?`
+/// operator. It must be used in a function returning
+/// `Poll<Result<U, F>>`, where `F: From<E>`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::io::AsyncRead;
+/// use futures::task::{Context, Poll};
+/// use futures::try_ready;
+/// use std::io;
+/// use std::pin::Pin;
+///
+/// fn poll_read_all<R>(
+///     mut reader: Pin<&mut R>,
+///     cx: &mut Context<'_>,
+///     buf: &mut [u8],
+///     filled: &mut usize,
+/// ) -> Poll<io::Result<()>>
+/// where
+///     R: AsyncRead,
+/// {
+///     while *filled < buf.len() {
+///         let n = try_ready!(reader.as_mut().poll_read(cx, &mut buf[*filled..]));
+///         if n == 0 {
+///             return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+///         }
+///         *filled += n;
+///     }
+///     Poll::Ready(Ok(()))
+/// }
+/// ```
+#[macro_export]
+macro_rules! try_ready {
+    ($e:expr $(,)?) => {
+        match $e {
+            $crate::task::Poll::Ready(::core::result::Result::Ok(t)) => t,
+            $crate::task::Poll::Ready(::core::result::Result::Err(e)) => {
+                return $crate::task::Poll::Ready(::core::result::Result::Err(
+                    ::core::convert::From::from(e),
+                ))
+            }
+            $crate::task::Poll::Pending => return $crate::task::Poll::Pending,
+        }
+    };
+}
@@ -0,0 +1,33 @@
+#![warn(rust_2018_idioms, single_use_lifetimes)]
+
+use std::env;
+use std::process::Command;
+use std::str;
+
+// The rustc-cfg strings below are *not* public API. Please let us know by
+// opening a GitHub issue if your build environment requires some way to
+// enable these cfgs other than by executing our build script.
+//
+// - `futures_no_track_caller`
+//      The compiler doesn't support `#[track_caller]` and
+//      `Location::caller`, which are stable since Rust 1.46, so the pools
+//      don't record where their tasks are spawned from.
+fn main() {
+    // Note that this is `no_*`, not `has_*`. This allows treating the
+    // features as available when the build script doesn't run or the version
+    // of the compiler can't be found.
+    if let Some(minor) = rustc_minor_version() {
+        if minor < 46 {
+            println!("cargo:rustc-cfg=futures_no_track_caller");
+        }
+    }
+}
+
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = str::from_utf8(&output.stdout).ok()?;
+    // The output is like "rustc 1.45.2 (d3fb005a3 2020-07-31)".
+    let mut parts = version.strip_prefix("rustc 1.")?.split('.');
+    parts.next()?.parse().ok()
+}
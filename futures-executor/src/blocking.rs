@@ -0,0 +1,357 @@
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll, Waker};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::thread;
+use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+#[cfg(not(feature = "phala-sgx"))]
+use std::thread;
+use std::time::Duration;
+
+// The maximum number of threads of the blocking pool.
+const MAX_THREADS: usize = 512;
+
+// Inside an enclave, the threads of the blocking pool are also bounded by the
+// TCSs left.
+#[cfg(not(feature = "phala-sgx"))]
+fn max_threads() -> usize {
+    MAX_THREADS
+}
+
+#[cfg(feature = "phala-sgx")]
+fn max_threads() -> usize {
+    std::cmp::min(MAX_THREADS, crate::sgx::thread_limit())
+}
+
+// How long a thread of the blocking pool waits for work before exiting.
+const KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+/// Runs a blocking function on a dedicated thread, returning a future
+/// resolving to its output.
+///
+/// Executors run many tasks on few threads, so a task calling a function
+/// which blocks its thread for a long time, such as a synchronous I/O
+/// operation through [`AllowStdIo`] or an expensive computation, stalls the
+/// other tasks of the same thread. With
+/// `spawn_blocking`, the function runs on a separate pool of threads instead,
+/// while the calling task waits for its output without blocking.
+///
+/// The pool is shared by the whole process. Its threads are started when
+/// needed, up to 512 of them, beyond which the functions are queued until a
+/// thread is available, and exit after being idle for 10 seconds. A function
+/// is also queued if no thread can be started, until one of the threads is
+/// free, or runs on the calling thread if the pool has no thread at all.
+/// With the `phala-sgx` feature, the pool shares the TCSs of the enclave with
+/// the workers of the thread pools, which always leave one of them to the
+/// pool.
+///
+/// If the function panics, the panic is propagated to the task awaiting the
+/// returned future. Dropping the future doesn't cancel the function, which
+/// still runs to completion.
+///
+/// This function is only available when the `thread-pool` feature of this
+/// library is activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, spawn_blocking};
+///
+/// let sum = block_on(async {
+///     spawn_blocking(|| (1..=100).sum::<u32>()).await
+/// });
+/// assert_eq!(sum, 5050);
+/// ```
+///
+/// [`AllowStdIo`]: https://docs.rs/futures/0.3/futures/io/struct.AllowStdIo.html
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+pub fn spawn_blocking<F, T>(f: F) -> SpawnBlocking<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let inner = Arc::new(Inner { state: Mutex::new(State::Running(None)) });
+    let inner2 = inner.clone();
+    BlockingPool::get().execute(Box::new(move || {
+        let res = panic::catch_unwind(AssertUnwindSafe(f));
+        let waker = match mem::replace(&mut *inner2.state.lock().unwrap(), State::Done(res)) {
+            State::Running(waker) => waker,
+            State::Done(_) | State::Taken => unreachable!(),
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }));
+    SpawnBlocking { inner }
+}
+
+/// Future for the [`spawn_blocking`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+pub struct SpawnBlocking<T> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+}
+
+enum State<T> {
+    // The function hasn't returned yet, with the waker of the awaiting task.
+    Running(Option<Waker>),
+    Done(thread::Result<T>),
+    Taken,
+}
+
+impl<T> Future for SpawnBlocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.inner.state.lock().unwrap();
+        match &mut *state {
+            State::Running(waker) => {
+                match waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    _ => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+            State::Done(_) => match mem::replace(&mut *state, State::Taken) {
+                State::Done(Ok(output)) => Poll::Ready(output),
+                State::Done(Err(payload)) => {
+                    drop(state);
+                    panic::resume_unwind(payload)
+                }
+                State::Running(_) | State::Taken => unreachable!(),
+            },
+            State::Taken => panic!("`SpawnBlocking` polled after completion"),
+        }
+    }
+}
+
+impl<T> FusedFuture for SpawnBlocking<T> {
+    fn is_terminated(&self) -> bool {
+        matches!(*self.inner.state.lock().unwrap(), State::Taken)
+    }
+}
+
+impl<T> fmt::Debug for SpawnBlocking<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpawnBlocking").finish()
+    }
+}
+
+/// Turns a blocking iterator into a stream, calling its `next` method on the
+/// thread pool of [`spawn_blocking`].
+///
+/// This is the reverse of [`block_on_stream`](crate::block_on_stream): the
+/// iterator may block, for example on a synchronous I/O operation or on a
+/// channel fed by a foreign thread, without blocking the task polling the
+/// stream. Each item is fetched by its own [`spawn_blocking`] call, once the
+/// previous one has been returned, so the iterator isn't advanced before the
+/// stream is polled.
+///
+/// If the iterator panics, the panic is propagated to the task polling the
+/// stream.
+///
+/// This function is only available when the `thread-pool` feature of this
+/// library is activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, from_blocking_iter};
+/// use futures::stream::StreamExt;
+///
+/// let lines = from_blocking_iter(vec!["a", "b"].into_iter().map(str::to_uppercase));
+/// assert_eq!(block_on(lines.collect::<Vec<_>>()), ["A", "B"]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+pub fn from_blocking_iter<I>(iter: I) -> FromBlockingIter<I>
+where
+    I: Iterator + Send + 'static,
+    I::Item: Send + 'static,
+{
+    FromBlockingIter { iter: Some(iter), next: None }
+}
+
+/// Stream for the [`from_blocking_iter`] function.
+#[must_use = "streams do nothing unless polled"]
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+pub struct FromBlockingIter<I: Iterator> {
+    // The iterator, when no call to `next` is running. `None` once it is
+    // exhausted.
+    iter: Option<I>,
+    next: Option<SpawnBlocking<(I, Option<I::Item>)>>,
+}
+
+impl<I: Iterator> Unpin for FromBlockingIter<I> {}
+
+impl<I> Stream for FromBlockingIter<I>
+where
+    I: Iterator + Send + 'static,
+    I::Item: Send + 'static,
+{
+    type Item = I::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+        let this = &mut *self;
+        if this.next.is_none() {
+            let mut iter = match this.iter.take() {
+                Some(iter) => iter,
+                None => return Poll::Ready(None),
+            };
+            this.next = Some(spawn_blocking(move || {
+                let item = iter.next();
+                (iter, item)
+            }));
+        }
+
+        let (iter, item) = ready!(Pin::new(this.next.as_mut().unwrap()).poll(cx));
+        this.next = None;
+        if item.is_some() {
+            this.iter = Some(iter);
+        }
+        Poll::Ready(item)
+    }
+}
+
+impl<I> FusedStream for FromBlockingIter<I>
+where
+    I: Iterator + Send + 'static,
+    I::Item: Send + 'static,
+{
+    fn is_terminated(&self) -> bool {
+        self.iter.is_none() && self.next.is_none()
+    }
+}
+
+impl<I: Iterator> fmt::Debug for FromBlockingIter<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromBlockingIter")
+            .field("is_running", &self.next.is_some())
+            .field("is_terminated", &(self.iter.is_none() && self.next.is_none()))
+            .finish()
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct BlockingPool {
+    state: Mutex<PoolState>,
+    condvar: Condvar,
+}
+
+struct PoolState {
+    queue: VecDeque<Job>,
+    // The number of threads, including the idle ones.
+    threads: usize,
+    // The number of threads waiting for work and not notified yet.
+    idle: usize,
+    // The number of notifications not consumed by the idle threads yet.
+    notified: usize,
+}
+
+impl BlockingPool {
+    fn get() -> &'static Self {
+        static POOL: AtomicPtr<BlockingPool> = AtomicPtr::new(ptr::null_mut());
+
+        let mut pool = POOL.load(Ordering::Acquire);
+        if pool.is_null() {
+            let new = Box::into_raw(Box::new(Self {
+                state: Mutex::new(PoolState {
+                    queue: VecDeque::new(),
+                    threads: 0,
+                    idle: 0,
+                    notified: 0,
+                }),
+                condvar: Condvar::new(),
+            }));
+            match POOL.compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => pool = new,
+                Err(current) => {
+                    // Another thread created the pool first.
+                    drop(unsafe { Box::from_raw(new) });
+                    pool = current;
+                }
+            }
+        }
+        // Safety: the pool is never freed once stored.
+        unsafe { &*pool }
+    }
+
+    fn execute(&'static self, job: Job) {
+        let mut state = self.state.lock().unwrap();
+        state.queue.push_back(job);
+        if state.idle > 0 {
+            state.idle -= 1;
+            state.notified += 1;
+            self.condvar.notify_one();
+            return;
+        }
+        if state.threads >= max_threads() {
+            // The job runs once a thread is done with its current one.
+            return;
+        }
+        state.threads += 1;
+        drop(state);
+
+        let builder = thread::Builder::new().name("futures-blocking".to_string());
+        #[cfg(not(feature = "phala-sgx"))]
+        let res = builder.spawn(move || self.work());
+        // Inside an enclave, the threads take their TCSs from the budget
+        // shared with the thread pools.
+        #[cfg(feature = "phala-sgx")]
+        let res = crate::sgx::spawn(builder, crate::sgx::thread_limit(), move || self.work());
+        if res.is_err() {
+            let mut state = self.state.lock().unwrap();
+            state.threads -= 1;
+            // With other threads, the job stays queued until one of them is
+            // free. Without any, nothing would ever run it, so it runs on
+            // this thread instead.
+            if state.threads == 0 {
+                let jobs: Vec<Job> = state.queue.drain(..).collect();
+                drop(state);
+                for job in jobs {
+                    job();
+                }
+            }
+        }
+    }
+
+    fn work(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(job) = state.queue.pop_front() {
+                drop(state);
+                job();
+                state = self.state.lock().unwrap();
+                continue;
+            }
+
+            state.idle += 1;
+            loop {
+                let (guard, timeout) = self.condvar.wait_timeout(state, KEEP_ALIVE).unwrap();
+                state = guard;
+                if state.notified > 0 {
+                    // The thread was taken off the idle count when notified.
+                    state.notified -= 1;
+                    break;
+                }
+                if timeout.timed_out() {
+                    state.idle -= 1;
+                    state.threads -= 1;
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::blocking::{spawn_blocking, SpawnBlocking};
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncSeek, AsyncWrite};
+use std::cmp;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::pin::Pin;
+
+// The maximum number of bytes read or written by a single blocking call.
+const MAX_BUF: usize = 16 * 1024;
+
+/// An adapter running the synchronous I/O operations of an object on the
+/// thread pool of [`spawn_blocking`], to use it as an
+/// [`AsyncRead`], [`AsyncWrite`] or [`AsyncSeek`] object.
+///
+/// Unlike [`AllowStdIo`], which calls the object directly when polled and so
+/// blocks the thread of the executor, `BlockingIo` never blocks: the calls to
+/// [`Read::read`], [`Write::write_all`], [`Write::flush`] and [`Seek::seek`]
+/// are run on a blocking thread, while the task awaits them.
+///
+/// Data is copied through internal buffers of up to 16 KiB. A write returns
+/// as soon as its data is buffered, and its error, if any, is returned by the
+/// next operation. Operations are run one at a time, so a read which hasn't
+/// completed delays the writes, and the other way around. Seeking discards
+/// the data read ahead of the position of the object.
+///
+/// This type is only available when the `thread-pool` feature of this
+/// library is activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, BlockingIo};
+/// use futures::io::AsyncReadExt;
+///
+/// let mut reader = BlockingIo::new(&b"hello"[..]);
+/// let mut output = String::new();
+/// block_on(reader.read_to_string(&mut output)).unwrap();
+/// assert_eq!(output, "hello");
+/// ```
+///
+/// [`AllowStdIo`]: https://docs.rs/futures/0.3/futures/io/struct.AllowStdIo.html
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+pub struct BlockingIo<T> {
+    // The object, when no operation is running.
+    inner: Option<T>,
+    op: Option<SpawnBlocking<(T, Done)>>,
+    // Data read by the last read and not returned yet.
+    read_buf: Vec<u8>,
+    read_pos: usize,
+    // The outcome of the last read, if it hasn't been returned yet.
+    read_ready: Option<io::Result<()>>,
+    // The outcome of the last seek, if it hasn't been returned yet.
+    seek_ready: Option<io::Result<u64>>,
+    write_buf: Vec<u8>,
+    // Whether data was written since the last flush.
+    need_flush: bool,
+}
+
+impl<T> Unpin for BlockingIo<T> {}
+
+enum Done {
+    Read(io::Result<()>, Vec<u8>),
+    Write(io::Result<()>, Vec<u8>),
+    Flush(io::Result<()>),
+    Seek(io::Result<u64>),
+}
+
+impl<T> BlockingIo<T> {
+    /// Creates a new `BlockingIo` wrapping the given object.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: Some(inner),
+            op: None,
+            read_buf: Vec::new(),
+            read_pos: 0,
+            read_ready: None,
+            seek_ready: None,
+            write_buf: Vec::new(),
+            need_flush: false,
+        }
+    }
+
+    // Waits for the running operation, if any, and returns the error of a
+    // write or a flush. The outcome of a read is kept in `read_ready`, and
+    // the one of a seek in `seek_ready`.
+    fn poll_complete(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let op = match &mut self.op {
+            Some(op) => op,
+            None => return Poll::Ready(Ok(())),
+        };
+        let (inner, done) = ready!(Pin::new(op).poll(cx));
+        self.op = None;
+        self.inner = Some(inner);
+        Poll::Ready(match done {
+            Done::Read(res, buf) => {
+                self.read_buf = buf;
+                self.read_pos = 0;
+                self.read_ready = Some(res);
+                Ok(())
+            }
+            Done::Write(res, buf) => {
+                self.write_buf = buf;
+                res
+            }
+            Done::Flush(res) => {
+                if res.is_ok() {
+                    self.need_flush = false;
+                }
+                res
+            }
+            Done::Seek(res) => {
+                self.seek_ready = Some(res);
+                Ok(())
+            }
+        })
+    }
+
+    fn take_inner(&mut self) -> T {
+        self.inner.take().expect("`BlockingIo` used after an operation panicked")
+    }
+}
+
+impl<T: Read + Send + 'static> AsyncRead for BlockingIo<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        loop {
+            if let Some(res) = this.read_ready.take() {
+                res?;
+                let data = &this.read_buf[this.read_pos..];
+                let n = cmp::min(buf.len(), data.len());
+                buf[..n].copy_from_slice(&data[..n]);
+                this.read_pos += n;
+                if this.read_pos < this.read_buf.len() {
+                    this.read_ready = Some(Ok(()));
+                }
+                return Poll::Ready(Ok(n));
+            }
+
+            ready!(this.poll_complete(cx))?;
+            if this.read_ready.is_some() {
+                continue;
+            }
+
+            let mut inner = this.take_inner();
+            let mut read_buf = mem::replace(&mut this.read_buf, Vec::new());
+            read_buf.resize(cmp::min(buf.len(), MAX_BUF), 0);
+            this.op = Some(spawn_blocking(move || {
+                let res = inner.read(&mut read_buf).map(|n| read_buf.truncate(n));
+                if res.is_err() {
+                    read_buf.clear();
+                }
+                (inner, Done::Read(res, read_buf))
+            }));
+        }
+    }
+}
+
+impl<T: Write + Send + 'static> AsyncWrite for BlockingIo<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_complete(cx))?;
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let mut inner = this.take_inner();
+        let mut write_buf = mem::replace(&mut this.write_buf, Vec::new());
+        let n = cmp::min(buf.len(), MAX_BUF);
+        write_buf.extend_from_slice(&buf[..n]);
+        this.need_flush = true;
+        this.op = Some(spawn_blocking(move || {
+            let res = inner.write_all(&write_buf);
+            write_buf.clear();
+            (inner, Done::Write(res, write_buf))
+        }));
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_complete(cx))?;
+            if !this.need_flush {
+                return Poll::Ready(Ok(()));
+            }
+
+            let mut inner = this.take_inner();
+            this.op = Some(spawn_blocking(move || {
+                let res = inner.flush();
+                (inner, Done::Flush(res))
+            }));
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<T: Seek + Send + 'static> AsyncSeek for BlockingIo<T> {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(res) = this.seek_ready.take() {
+                return Poll::Ready(res);
+            }
+
+            ready!(this.poll_complete(cx))?;
+            if this.seek_ready.is_some() {
+                continue;
+            }
+
+            // The object is past the data read ahead, which is discarded.
+            let pos = match pos {
+                SeekFrom::Current(offset) => {
+                    let unread = (this.read_buf.len() - this.read_pos) as i64;
+                    SeekFrom::Current(offset - unread)
+                }
+                pos => pos,
+            };
+            this.read_buf.clear();
+            this.read_pos = 0;
+            this.read_ready = None;
+
+            let mut inner = this.take_inner();
+            this.op = Some(spawn_blocking(move || {
+                let res = inner.seek(pos);
+                (inner, Done::Seek(res))
+            }));
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BlockingIo<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingIo")
+            .field("inner", &self.inner)
+            .field("busy", &self.op.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,111 @@
+use futures_core::future::Future;
+use futures_task::{Spawn, SpawnError};
+use futures_util::task::{JoinHandle, SpawnExt};
+use std::any::Any;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+// The spawner set with `set_default_spawner`, which can be taken back as its
+// own type if it is rejected.
+trait DefaultSpawn: Spawn + Send + Sync {
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<S: Spawn + Send + Sync + 'static> DefaultSpawn for S {
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+static DEFAULT_SPAWNER: AtomicPtr<Box<dyn DefaultSpawn>> = AtomicPtr::new(ptr::null_mut());
+
+/// Sets the executor on which [`spawn`] spawns tasks, for the whole process.
+///
+/// This lets a program, or a runtime it is embedded in, choose where the
+/// tasks of libraries spawning with [`spawn`] run. The default spawner can
+/// only be set once, so this should be done early, before any task is spawned
+/// with [`spawn`].
+///
+/// # Errors
+///
+/// Returns the given spawner back if a default spawner has already been set,
+/// including by the first call to [`spawn`] creating one.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{self, ThreadPool};
+///
+/// let pool = ThreadPool::builder().name_prefix("app-").create().unwrap();
+/// executor::set_default_spawner(pool).unwrap();
+///
+/// let handle = executor::spawn(async { 1 }).unwrap();
+/// assert_eq!(executor::block_on(handle), Ok(1));
+/// ```
+pub fn set_default_spawner<S>(spawner: S) -> Result<(), S>
+where
+    S: Spawn + Send + Sync + 'static,
+{
+    if !DEFAULT_SPAWNER.load(Ordering::Acquire).is_null() {
+        return Err(spawner);
+    }
+    let spawner: Box<dyn DefaultSpawn> = Box::new(spawner);
+    let new = Box::into_raw(Box::new(spawner));
+    match DEFAULT_SPAWNER.compare_exchange(
+        ptr::null_mut(),
+        new,
+        Ordering::AcqRel,
+        Ordering::Acquire,
+    ) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            // Another thread set the default spawner first.
+            let spawner = *unsafe { Box::from_raw(new) };
+            Err(*spawner.into_any().downcast().unwrap())
+        }
+    }
+}
+
+/// Spawns a task on the default spawner of the process.
+///
+/// The default spawner is the one set with [`set_default_spawner`]. If none
+/// has been set, the first call creates a [`ThreadPool`](crate::ThreadPool)
+/// with the default configuration and sets it as the default spawner, when
+/// the `thread-pool` feature of this library is activated.
+///
+/// This returns a [`JoinHandle`] to the task, or an error if the spawner
+/// can't spawn it.
+///
+/// # Panics
+///
+/// Panics if no default spawner has been set and the `thread-pool` feature is
+/// disabled, or if the default thread pool can't be created.
+pub fn spawn<Fut>(future: Fut) -> Result<JoinHandle<Fut::Output>, SpawnError>
+where
+    Fut: Future + Send + 'static,
+    Fut::Output: Send,
+{
+    default_spawner().spawn(future)
+}
+
+fn default_spawner() -> &'static dyn DefaultSpawn {
+    let mut spawner = DEFAULT_SPAWNER.load(Ordering::Acquire);
+    if spawner.is_null() {
+        init_default_spawner();
+        spawner = DEFAULT_SPAWNER.load(Ordering::Acquire);
+    }
+    // Safety: the default spawner is never freed once set.
+    unsafe { &**spawner }
+}
+
+#[cfg(feature = "thread-pool")]
+fn init_default_spawner() {
+    let pool = crate::ThreadPool::new().expect("failed to create the default thread pool");
+    // The pool is dropped if another thread set a default spawner first.
+    let _ = set_default_spawner(pool);
+}
+
+#[cfg(not(feature = "thread-pool"))]
+fn init_default_spawner() {
+    panic!("no default spawner is set, see `set_default_spawner`");
+}
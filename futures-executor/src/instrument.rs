@@ -0,0 +1,147 @@
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll, Waker};
+use futures_task::{waker, ArcWake, LocalFutureObj};
+use futures_util::future::FutureExt;
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::untrusted::time::InstantEx;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Callbacks invoked by an executor as it runs its tasks, to find out which
+/// of them are slow to poll or woken up too often.
+///
+/// An instrument is set on a [`ThreadPool`](crate::ThreadPool) with
+/// [`ThreadPoolBuilder::instrument`](crate::ThreadPoolBuilder::instrument), and
+/// on a [`LocalPool`](crate::LocalPool) with
+/// [`LocalPool::set_instrument`](crate::LocalPool::set_instrument).
+///
+/// The callbacks do nothing by default. They run on the threads polling and
+/// waking up the tasks, so they should return quickly.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{Instrument, LocalPool, TaskId};
+/// use std::time::Duration;
+///
+/// struct SlowPolls;
+///
+/// impl Instrument for SlowPolls {
+///     fn on_poll_end(&self, task: TaskId, duration: Duration) {
+///         if duration > Duration::from_millis(1) {
+///             eprintln!("task {} blocked its thread for {:?}", task, duration);
+///         }
+///     }
+/// }
+///
+/// let mut pool = LocalPool::new();
+/// pool.set_instrument(SlowPolls);
+/// ```
+pub trait Instrument: Send + Sync {
+    /// Called right before a task is polled.
+    fn on_poll_start(&self, _task: TaskId) {}
+
+    /// Called right after a task has been polled, with the time the poll took.
+    fn on_poll_end(&self, _task: TaskId, _duration: Duration) {}
+
+    /// Called each time a task is woken up.
+    fn on_wake(&self, _task: TaskId) {}
+}
+
+/// An identifier of a task spawned on an executor, unique within the process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(usize);
+
+impl TaskId {
+    pub(crate) fn next() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Returns the identifier as a number.
+    pub fn as_usize(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+// Calls `poll` between the `on_poll_start` and `on_poll_end` callbacks of
+// the instrument.
+pub(crate) fn poll_instrumented<T>(
+    instrument: &dyn Instrument,
+    task: TaskId,
+    poll: impl FnOnce() -> T,
+) -> T {
+    instrument.on_poll_start(task);
+    let start = Instant::now();
+    let res = poll();
+    instrument.on_poll_end(task, start.elapsed());
+    res
+}
+
+// Wraps a task of a `LocalPool` to invoke the callbacks of the instrument.
+pub(crate) fn instrument_local(
+    task: LocalFutureObj<'static, ()>,
+    instrument: &Arc<dyn Instrument>,
+) -> LocalFutureObj<'static, ()> {
+    LocalFutureObj::new(Box::new(Instrumented {
+        task,
+        id: TaskId::next(),
+        instrument: instrument.clone(),
+        waker: None,
+    }))
+}
+
+struct Instrumented {
+    task: LocalFutureObj<'static, ()>,
+    id: TaskId,
+    instrument: Arc<dyn Instrument>,
+    // The waker passed to the task, and the waker of the pool it wraps.
+    waker: Option<(Waker, Arc<WakeHook>)>,
+}
+
+// A waker invoking `on_wake` before waking up the task.
+struct WakeHook {
+    inner: Waker,
+    id: TaskId,
+    instrument: Arc<dyn Instrument>,
+}
+
+impl ArcWake for WakeHook {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.instrument.on_wake(arc_self.id);
+        arc_self.inner.wake_by_ref();
+    }
+}
+
+impl Future for Instrumented {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+        let up_to_date = match &this.waker {
+            Some((_, hook)) => hook.inner.will_wake(cx.waker()),
+            None => false,
+        };
+        if !up_to_date {
+            let hook = Arc::new(WakeHook {
+                inner: cx.waker().clone(),
+                id: this.id,
+                instrument: this.instrument.clone(),
+            });
+            this.waker = Some((waker(hook.clone()), hook));
+        }
+
+        let mut cx = Context::from_waker(&this.waker.as_ref().unwrap().0);
+        let task = &mut this.task;
+        poll_instrumented(&*this.instrument, this.id, || task.poll_unpin(&mut cx))
+    }
+}
@@ -0,0 +1,149 @@
+use futures_core::future::Future;
+use futures_core::stream::Stream;
+use futures_core::task::{Context, Poll};
+use futures_task::{LocalSpawn, Spawn, SpawnError};
+use futures_util::stream::FuturesUnordered;
+use futures_util::task::{JoinError, JoinHandle, LocalSpawnExt, SpawnExt};
+use std::fmt;
+use std::pin::Pin;
+
+/// A collection of tasks spawned on executors, which can be awaited in the
+/// order in which they complete.
+///
+/// Tasks are added with [`spawn`](JoinSet::spawn) or
+/// [`spawn_local`](JoinSet::spawn_local), and their outputs are taken with
+/// [`join_next`](JoinSet::join_next). All the tasks still in the set are
+/// aborted when it is dropped, or with [`abort_all`](JoinSet::abort_all).
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, JoinSet, ThreadPool};
+///
+/// let pool = ThreadPool::new().unwrap();
+/// let mut set = JoinSet::new();
+/// for i in 0..3 {
+///     set.spawn(&pool, async move { i * 2 }).unwrap();
+/// }
+///
+/// let mut sum = 0;
+/// block_on(async {
+///     while let Some(res) = set.join_next().await {
+///         sum += res.unwrap();
+///     }
+/// });
+/// assert_eq!(sum, 6);
+/// ```
+pub struct JoinSet<T> {
+    handles: FuturesUnordered<JoinHandle<T>>,
+}
+
+impl<T> JoinSet<T> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self { handles: FuturesUnordered::new() }
+    }
+
+    /// Spawns a task on the given executor, and adds it to the set.
+    ///
+    /// Returns an error if the executor can't spawn the task, in which case
+    /// the set is left unchanged.
+    pub fn spawn<S, Fut>(&mut self, spawner: &S, future: Fut) -> Result<(), SpawnError>
+    where
+        S: Spawn + ?Sized,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.handles.push(spawner.spawn(future)?);
+        Ok(())
+    }
+
+    /// Spawns a `!Send` task on the given executor, and adds it to the set.
+    ///
+    /// Returns an error if the executor can't spawn the task, in which case
+    /// the set is left unchanged.
+    pub fn spawn_local<S, Fut>(&mut self, spawner: &S, future: Fut) -> Result<(), SpawnError>
+    where
+        S: LocalSpawn + ?Sized,
+        Fut: Future<Output = T> + 'static,
+        T: 'static,
+    {
+        self.handles.push(spawner.spawn_local(future)?);
+        Ok(())
+    }
+
+    /// Waits for one of the tasks of the set to complete, and removes it from
+    /// the set.
+    ///
+    /// Resolves to the output of the task, or to an error if it was aborted
+    /// or dropped before completing, or to `None` if the set is empty.
+    pub fn join_next(&mut self) -> JoinNext<'_, T> {
+        JoinNext { set: self }
+    }
+
+    /// Polls for one of the tasks of the set to complete, and removes it from
+    /// the set.
+    ///
+    /// This is the poll-based version of [`join_next`](JoinSet::join_next).
+    pub fn poll_join_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<T, JoinError>>> {
+        Pin::new(&mut self.handles).poll_next(cx)
+    }
+
+    /// Aborts all the tasks of the set.
+    ///
+    /// The tasks stay in the set, and [`join_next`](JoinSet::join_next)
+    /// resolves to an error for those which hadn't completed yet.
+    pub fn abort_all(&mut self) {
+        for handle in self.handles.iter() {
+            handle.abort();
+        }
+    }
+
+    /// Returns the number of tasks in the set.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Returns `true` if the set contains no tasks.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+}
+
+impl<T> Default for JoinSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for JoinSet<T> {
+    fn drop(&mut self) {
+        self.abort_all();
+    }
+}
+
+impl<T> fmt::Debug for JoinSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinSet").field("len", &self.len()).finish()
+    }
+}
+
+/// Future for the [`join_next`](JoinSet::join_next) method.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct JoinNext<'a, T> {
+    set: &'a mut JoinSet<T>,
+}
+
+impl<T> Future for JoinNext<'_, T> {
+    type Output = Option<Result<T, JoinError>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.set.poll_join_next(cx)
+    }
+}
+
+impl<T> fmt::Debug for JoinNext<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinNext").field("set", &self.set).finish()
+    }
+}
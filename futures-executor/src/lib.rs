@@ -15,12 +15,24 @@
 //! with the [`spawn_ok`](ThreadPool::spawn_ok) function will run ambiently on
 //! the created threads.
 //!
+//! Functions which block their thread, such as synchronous I/O operations,
+//! should be run with [`spawn_blocking`] instead, so that they don't stall
+//! the other tasks of the pool. A synchronous I/O object can be wrapped in a
+//! [`BlockingIo`] to run all its operations this way.
+//!
 //! # Spawning additional tasks
 //!
 //! Tasks can be spawned onto a spawner by calling its [`spawn_obj`] method
 //! directly. In the case of `!Send` futures, [`spawn_local_obj`] can be used
 //! instead.
 //!
+//! Libraries which don't take a spawner from their callers can spawn their
+//! tasks with [`spawn`], on the default spawner of the process, which is set
+//! with [`set_default_spawner`].
+//!
+//! A group of spawned tasks can be kept in a [`JoinSet`], to await their
+//! outputs in the order in which they complete, or abort them all.
+//!
 //! # Single-threaded execution
 //!
 //! In addition to thread pools, it's possible to run a task (and the tasks
@@ -33,6 +45,21 @@
 //! There is also a convenience function [`block_on`] for simply running a
 //! future to completion on the current thread.
 //!
+//! For tests, the [`TestPool`] executor runs tasks in a reproducible order,
+//! with a virtual clock.
+//!
+//! # Timers
+//!
+//! The [`time`] module provides timers whose deadlines are kept in a timer
+//! wheel and measured with a pluggable [`Clock`](time::Clock), including one
+//! which can be used inside an SGX enclave, where the time can't be trusted.
+//!
+//! # Scoped tasks
+//!
+//! Tasks which borrow from the enclosing stack frame, instead of being
+//! `'static`, can be spawned within a [`scope`], which runs them to
+//! completion before returning.
+//!
 //! [`spawn_obj`]: https://docs.rs/futures/0.3/futures/task/trait.Spawn.html#tymethod.spawn_obj
 //! [`spawn_local_obj`]: https://docs.rs/futures/0.3/futures/task/trait.LocalSpawn.html#tymethod.spawn_local_obj
 
@@ -56,7 +83,41 @@
 #[cfg(feature = "std")]
 mod local_pool;
 #[cfg(feature = "std")]
-pub use crate::local_pool::{block_on, block_on_stream, BlockingStream, LocalPool, LocalSpawner};
+pub use crate::local_pool::{
+    block_on, block_on_read, block_on_sink, block_on_stream, block_on_write, BlockingRead,
+    BlockingSink, BlockingStream, BlockingWrite, LocalPool, LocalSpawner,
+};
+#[cfg(feature = "std")]
+#[cfg(not(feature = "phala-sgx"))]
+pub use crate::local_pool::{block_on_deadline, block_on_timeout, Elapsed};
+
+#[cfg(feature = "std")]
+pub mod time;
+
+#[cfg(feature = "std")]
+mod park;
+#[cfg(feature = "std")]
+pub use crate::park::{Park, Unpark};
+
+#[cfg(feature = "std")]
+mod test_pool;
+#[cfg(feature = "std")]
+pub use crate::test_pool::{Sleep, TestClock, TestPool, TestSpawner};
+
+#[cfg(feature = "std")]
+mod scope;
+#[cfg(feature = "std")]
+pub use crate::scope::{scope, Scope};
+
+#[cfg(feature = "std")]
+mod default_spawner;
+#[cfg(feature = "std")]
+pub use crate::default_spawner::{set_default_spawner, spawn};
+
+#[cfg(feature = "std")]
+mod join_set;
+#[cfg(feature = "std")]
+pub use crate::join_set::{JoinNext, JoinSet};
 
 #[cfg(feature = "thread-pool")]
 #[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
@@ -66,11 +127,49 @@ mod thread_pool;
 #[cfg(feature = "std")]
 mod unpark_mutex;
 #[cfg(feature = "thread-pool")]
+#[cfg(feature = "phala-sgx")]
+pub use crate::thread_pool::SgxThreadPool;
+#[cfg(feature = "thread-pool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+#[cfg(feature = "std")]
+pub use crate::thread_pool::{IdleStrategy, Priority, ThreadPool, ThreadPoolBuilder};
+#[cfg(feature = "thread-pool")]
+#[cfg(feature = "std")]
+mod task_dump;
+#[cfg(feature = "thread-pool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+#[cfg(feature = "std")]
+pub use crate::task_dump::{TaskInfo, TaskState};
+
+#[cfg(feature = "thread-pool")]
+#[cfg(feature = "std")]
+mod blocking;
+#[cfg(feature = "thread-pool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+#[cfg(feature = "std")]
+pub use crate::blocking::{from_blocking_iter, spawn_blocking, FromBlockingIter, SpawnBlocking};
+#[cfg(feature = "thread-pool")]
+#[cfg(feature = "std")]
+mod blocking_io;
+#[cfg(feature = "thread-pool")]
 #[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
 #[cfg(feature = "std")]
-pub use crate::thread_pool::{ThreadPool, ThreadPoolBuilder};
+pub use crate::blocking_io::BlockingIo;
+
+#[cfg(feature = "thread-pool")]
+#[cfg(feature = "phala-sgx")]
+mod sgx;
+#[cfg(feature = "thread-pool")]
+#[cfg(feature = "phala-sgx")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "thread-pool", feature = "phala-sgx"))))]
+pub mod sgx_fs;
 
 #[cfg(feature = "std")]
 mod enter;
 #[cfg(feature = "std")]
 pub use crate::enter::{enter, Enter, EnterError};
+
+#[cfg(feature = "std")]
+mod instrument;
+#[cfg(feature = "std")]
+pub use crate::instrument::{Instrument, TaskId};
@@ -1,14 +1,23 @@
 use crate::enter;
+use crate::instrument::{instrument_local, Instrument};
+use crate::park::{Park, Parker};
 use futures_core::future::Future;
 use futures_core::stream::Stream;
+use futures_core::task::__internal::with_budget;
 use futures_core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+use futures_sink::Sink;
 use futures_task::{waker_ref, ArcWake};
 use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError};
+use futures_util::future::poll_fn;
 use futures_util::pin_mut;
 use futures_util::stream::FuturesUnordered;
 use futures_util::stream::StreamExt;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io;
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 use std::rc::{Rc, Weak};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
@@ -18,6 +27,8 @@ use std::sync::{
 use sgx_tstd::thread::{self, SgxThread as Thread};
 #[cfg(not(feature = "phala-sgx"))]
 use std::thread::{self, Thread};
+#[cfg(not(feature = "phala-sgx"))]
+use std::time::{Duration, Instant};
 
 /// A single-threaded task pool for polling futures to completion.
 ///
@@ -30,10 +41,19 @@ use std::thread::{self, Thread};
 /// [`spawner()`](LocalPool::spawner) method. Because the executor is
 /// single-threaded, it supports a special form of task spawning for non-`Send`
 /// futures, via [`spawn_local_obj`](futures_task::LocalSpawn::spawn_local_obj).
-#[derive(Debug)]
+///
+/// Besides [`run`](LocalPool::run) and [`run_until`](LocalPool::run_until),
+/// which block the thread until they are done, the pool can be driven
+/// incrementally with [`run_until_stalled`](LocalPool::run_until_stalled) and
+/// [`try_run_one`](LocalPool::try_run_one), which return as soon as no task
+/// can make progress without a wakeup from outside the pool. This allows
+/// interleaving the pool with a mock clock in tests, or with an external
+/// event loop.
 pub struct LocalPool {
-    pool: FuturesUnordered<LocalFutureObj<'static, ()>>,
+    pool: FuturesUnordered<Budgeted>,
     incoming: Rc<Incoming>,
+    instrument: Option<Arc<dyn Instrument>>,
+    parker: Option<Rc<Parker>>,
 }
 
 /// A handle to a [`LocalPool`](LocalPool) that implements
@@ -56,6 +76,30 @@ pub(crate) struct ThreadNotify {
     unparked: AtomicBool,
 }
 
+thread_local! {
+    // The incoming queue of the `LocalPool` running on this thread, if any.
+    static CURRENT_INCOMING: Cell<Option<Weak<Incoming>>> = Cell::new(None);
+}
+
+// Makes the given pool the current one of this thread until dropped,
+// restoring the previous one.
+struct CurrentGuard {
+    prev: Option<Weak<Incoming>>,
+}
+
+impl CurrentGuard {
+    fn new(incoming: &Rc<Incoming>) -> Self {
+        let prev = CURRENT_INCOMING.with(|current| current.replace(Some(Rc::downgrade(incoming))));
+        Self { prev }
+    }
+}
+
+impl Drop for CurrentGuard {
+    fn drop(&mut self) {
+        CURRENT_INCOMING.with(|current| current.set(self.prev.take()));
+    }
+}
+
 thread_local! {
     static CURRENT_THREAD_NOTIFY: Arc<ThreadNotify> = Arc::new(ThreadNotify {
         thread: thread::current(),
@@ -80,7 +124,55 @@ impl ArcWake for ThreadNotify {
 
 // Set up and run a basic single-threaded spawner loop, invoking `f` on each
 // turn.
-fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(mut f: F) -> T {
+fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(f: F) -> T {
+    CURRENT_THREAD_NOTIFY
+        .with(|thread_notify| run_parked(thread_notify, &thread_notify.unparked, thread::park, f))
+}
+
+// Like `run_executor`, but waking up the tasks with `notify`, which sets
+// `unparked`, and blocking with `park`.
+fn run_parked<W, T, F>(
+    notify: &Arc<W>,
+    unparked: &AtomicBool,
+    mut park: impl FnMut(),
+    mut f: F,
+) -> T
+where
+    W: ArcWake,
+    F: FnMut(&mut Context<'_>) -> Poll<T>,
+{
+    let _enter = enter().expect(
+        "cannot execute `LocalPool` executor from within \
+         another executor",
+    );
+
+    let waker = waker_ref(notify);
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(t) = with_budget(|| f(&mut cx)) {
+            return t;
+        }
+        // Consume the wakeup that occurred while executing `f`, if any.
+        if !unparked.swap(false, Ordering::Acquire) {
+            // No wakeup occurred. It may occur now, right before parking,
+            // but in that case the token made available by `unpark()`
+            // is guaranteed to still be available and `park()` is a no-op.
+            park();
+            // When the thread is unparked, `unparked` will have been set
+            // and needs to be unset before the next call to `f` to avoid
+            // a redundant loop iteration.
+            unparked.store(false, Ordering::Release);
+        }
+    }
+}
+
+// Like `run_executor`, but gives up once the deadline is reached, returning
+// `None`.
+#[cfg(not(feature = "phala-sgx"))]
+fn run_executor_until<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(
+    deadline: Instant,
+    mut f: F,
+) -> Option<T> {
     let _enter = enter().expect(
         "cannot execute `LocalPool` executor from within \
          another executor",
@@ -90,19 +182,20 @@ fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(mut f: F) -> T {
         let waker = waker_ref(thread_notify);
         let mut cx = Context::from_waker(&waker);
         loop {
-            if let Poll::Ready(t) = f(&mut cx) {
-                return t;
+            if let Poll::Ready(t) = with_budget(|| f(&mut cx)) {
+                return Some(t);
+            }
+            // Check the deadline on each turn, in case `f` keeps waking
+            // itself up.
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
             }
             // Consume the wakeup that occurred while executing `f`, if any.
             let unparked = thread_notify.unparked.swap(false, Ordering::Acquire);
             if !unparked {
-                // No wakeup occurred. It may occur now, right before parking,
-                // but in that case the token made available by `unpark()`
-                // is guaranteed to still be available and `park()` is a no-op.
-                thread::park();
-                // When the thread is unparked, `unparked` will have been set
-                // and needs to be unset before the next call to `f` to avoid
-                // a redundant loop iteration.
+                // See `run_executor`, parking at most until the deadline.
+                thread::park_timeout(deadline - now);
                 thread_notify.unparked.store(false, Ordering::Release);
             }
         }
@@ -122,10 +215,59 @@ fn poll_executor<T, F: FnMut(&mut Context<'_>) -> T>(mut f: F) -> T {
     })
 }
 
+// Like `poll_executor`, but waking up the tasks with `notify`.
+fn poll_parked<W: ArcWake, T, F: FnMut(&mut Context<'_>) -> T>(notify: &Arc<W>, mut f: F) -> T {
+    let _enter = enter().expect(
+        "cannot execute `LocalPool` executor from within \
+         another executor",
+    );
+
+    let waker = waker_ref(notify);
+    let mut cx = Context::from_waker(&waker);
+    f(&mut cx)
+}
+
+impl fmt::Debug for LocalPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalPool")
+            .field("pool", &self.pool)
+            .field("incoming", &self.incoming)
+            .field("instrumented", &self.instrument.is_some())
+            .field("custom_park", &self.parker.is_some())
+            .finish()
+    }
+}
+
 impl LocalPool {
     /// Create a new, empty pool of tasks.
     pub fn new() -> Self {
-        Self { pool: FuturesUnordered::new(), incoming: Default::default() }
+        Self {
+            pool: FuturesUnordered::new(),
+            incoming: Default::default(),
+            instrument: None,
+            parker: None,
+        }
+    }
+
+    /// Create a new, empty pool of tasks, which blocks with the given
+    /// [`Park`] while none of its tasks can make progress.
+    ///
+    /// The tasks of the pool are woken up with the [`Unpark`](crate::Unpark)
+    /// handle of `park`, so that an external event source, such as an I/O
+    /// reactor, can drive the pool without it busy-looping or parking its
+    /// thread. [`block_on`] and the other free functions of this module still
+    /// park the thread.
+    pub fn new_with_park<P: Park + 'static>(park: P) -> Self {
+        Self { parker: Some(Rc::new(Parker::new(park))), ..Self::new() }
+    }
+
+    /// Set the [`Instrument`] invoked as the pool polls and wakes up its
+    /// tasks.
+    ///
+    /// The tasks which the pool has already started running aren't
+    /// instrumented.
+    pub fn set_instrument<I: Instrument + 'static>(&mut self, instrument: I) {
+        self.instrument = Some(Arc::new(instrument));
     }
 
     /// Get a clonable handle to the pool as a [`Spawn`].
@@ -133,6 +275,18 @@ impl LocalPool {
         LocalSpawner { incoming: Rc::downgrade(&self.incoming) }
     }
 
+    /// Returns the number of tasks spawned on the pool which haven't
+    /// completed yet.
+    pub fn alive_tasks(&self) -> usize {
+        self.pool.len() + self.incoming.borrow().len()
+    }
+
+    /// Returns the number of newly spawned tasks waiting for the pool to pick
+    /// them up the next time it runs.
+    pub fn queued_tasks(&self) -> usize {
+        self.incoming.borrow().len()
+    }
+
     /// Run all tasks in the pool to completion.
     ///
     /// ```
@@ -149,7 +303,7 @@ impl LocalPool {
     /// The function will block the calling thread until *all* tasks in the pool
     /// are complete, including any spawned while running existing tasks.
     pub fn run(&mut self) {
-        run_executor(|cx| self.poll_pool(cx))
+        self.run_executor(|pool, cx| pool.poll_pool(cx))
     }
 
     /// Runs all the tasks in the pool until the given future completes.
@@ -172,7 +326,7 @@ impl LocalPool {
     pub fn run_until<F: Future>(&mut self, future: F) -> F::Output {
         pin_mut!(future);
 
-        run_executor(|cx| {
+        self.run_executor(|pool, cx| {
             {
                 // if our main task is done, so are we
                 let result = future.as_mut().poll(cx);
@@ -181,7 +335,7 @@ impl LocalPool {
                 }
             }
 
-            let _ = self.poll_pool(cx);
+            let _ = pool.poll_pool(cx);
             Poll::Pending
         })
     }
@@ -215,9 +369,9 @@ impl LocalPool {
     /// further use of one of the pool's run or poll methods.
     /// Though only one task will be completed, progress may be made on multiple tasks.
     pub fn try_run_one(&mut self) -> bool {
-        poll_executor(|ctx| {
+        self.poll_executor(|pool, ctx| {
             loop {
-                let ret = self.poll_pool_once(ctx);
+                let ret = pool.poll_pool_once(ctx);
 
                 // return if we have executed a future
                 if let Poll::Ready(Some(_)) = ret {
@@ -227,7 +381,7 @@ impl LocalPool {
                 // if there are no new incoming futures
                 // then there is no feature that can make progress
                 // and we can return without having completed a single future
-                if self.incoming.borrow().is_empty() {
+                if pool.incoming.borrow().is_empty() {
                     return false;
                 }
             }
@@ -260,11 +414,35 @@ impl LocalPool {
     /// of the pool's run or poll methods. While the function is running, all tasks
     /// in the pool will try to make progress.
     pub fn run_until_stalled(&mut self) {
-        poll_executor(|ctx| {
-            let _ = self.poll_pool(ctx);
+        self.poll_executor(|pool, ctx| {
+            let _ = pool.poll_pool(ctx);
         });
     }
 
+    // Runs the executor loop on the current thread with the pool as the
+    // current one, blocking with its `Park` if it has one.
+    fn run_executor<T>(&mut self, mut f: impl FnMut(&mut Self, &mut Context<'_>) -> Poll<T>) -> T {
+        let _current = CurrentGuard::new(&self.incoming);
+        match self.parker.clone() {
+            Some(parker) => run_parked(
+                &parker.notify,
+                &parker.notify.unparked,
+                || parker.park(),
+                |cx| f(self, cx),
+            ),
+            None => run_executor(|cx| f(self, cx)),
+        }
+    }
+
+    // Like `run_executor`, but calling `f` once without blocking.
+    fn poll_executor<T>(&mut self, mut f: impl FnMut(&mut Self, &mut Context<'_>) -> T) -> T {
+        let _current = CurrentGuard::new(&self.incoming);
+        match self.parker.clone() {
+            Some(parker) => poll_parked(&parker.notify, |cx| f(self, cx)),
+            None => poll_executor(|cx| f(self, cx)),
+        }
+    }
+
     // Make maximal progress on the entire pool of spawned task, returning `Ready`
     // if the pool is empty and `Pending` if no further progress can be made.
     fn poll_pool(&mut self, cx: &mut Context<'_>) -> Poll<()> {
@@ -292,7 +470,11 @@ impl LocalPool {
         {
             let mut incoming = self.incoming.borrow_mut();
             for task in incoming.drain(..) {
-                self.pool.push(task)
+                let task = match &self.instrument {
+                    Some(instrument) => instrument_local(task, instrument),
+                    None => task,
+                };
+                self.pool.push(Budgeted(task));
             }
         }
 
@@ -301,6 +483,17 @@ impl LocalPool {
     }
 }
 
+// A task of a `LocalPool`, polled with its own cooperative scheduling budget.
+struct Budgeted(LocalFutureObj<'static, ()>);
+
+impl Future for Budgeted {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        with_budget(|| Pin::new(&mut self.0).poll(cx))
+    }
+}
+
 impl Default for LocalPool {
     fn default() -> Self {
         Self::new()
@@ -318,6 +511,83 @@ pub fn block_on<F: Future>(f: F) -> F::Output {
     run_executor(|cx| f.as_mut().poll(cx))
 }
 
+/// Run a future to completion on the current thread, giving up after the
+/// given timeout.
+///
+/// This function will block the caller until the given future has completed,
+/// or until `timeout` has elapsed. In the latter case, the unfinished future
+/// is returned in the error, so that it can be polled again or dropped.
+///
+/// ```
+/// use futures::executor::block_on_timeout;
+/// use futures::future;
+/// use std::time::Duration;
+///
+/// let output = block_on_timeout(future::ready(1), Duration::from_secs(1)).unwrap();
+/// assert_eq!(output, 1);
+///
+/// let elapsed = block_on_timeout(future::pending::<()>(), Duration::from_millis(10)).unwrap_err();
+/// let _unfinished = elapsed.into_inner();
+/// ```
+///
+/// Futures which aren't [`Unpin`] can be pinned with
+/// [`Box::pin`](std::boxed::Box::pin) to be passed to this function.
+#[cfg(not(feature = "phala-sgx"))]
+pub fn block_on_timeout<F: Future + Unpin>(
+    f: F,
+    timeout: Duration,
+) -> Result<F::Output, Elapsed<F>> {
+    block_on_deadline(f, Instant::now() + timeout)
+}
+
+/// Run a future to completion on the current thread, giving up once the given
+/// deadline is reached.
+///
+/// This is like [`block_on_timeout`], with the time the future has to
+/// complete given as an [`Instant`].
+#[cfg(not(feature = "phala-sgx"))]
+pub fn block_on_deadline<F: Future + Unpin>(
+    mut f: F,
+    deadline: Instant,
+) -> Result<F::Output, Elapsed<F>> {
+    match run_executor_until(deadline, |cx| Pin::new(&mut f).poll(cx)) {
+        Some(output) => Ok(output),
+        None => Err(Elapsed { future: f }),
+    }
+}
+
+/// The error returned by [`block_on_timeout`] and [`block_on_deadline`] when
+/// the future didn't complete in time, containing the unfinished future.
+#[cfg(not(feature = "phala-sgx"))]
+pub struct Elapsed<F> {
+    future: F,
+}
+
+#[cfg(not(feature = "phala-sgx"))]
+impl<F> Elapsed<F> {
+    /// Returns the unfinished future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+#[cfg(not(feature = "phala-sgx"))]
+impl<F> fmt::Debug for Elapsed<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Elapsed").finish()
+    }
+}
+
+#[cfg(not(feature = "phala-sgx"))]
+impl<F> fmt::Display for Elapsed<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "deadline has elapsed")
+    }
+}
+
+#[cfg(not(feature = "phala-sgx"))]
+impl<F: core::any::Any> std::error::Error for Elapsed<F> {}
+
 /// Turn a stream into a blocking iterator.
 ///
 /// When `next` is called on the resulting `BlockingStream`, the caller
@@ -364,6 +634,264 @@ impl<S: Stream + Unpin> Iterator for BlockingStream<S> {
     }
 }
 
+/// Turn a sink into a blocking handle.
+///
+/// The methods of the resulting `BlockingSink` block the caller until the
+/// `Sink` has accepted, flushed or closed the items sent to it, so that
+/// synchronous code can feed a sink.
+///
+/// ```
+/// use futures::channel::mpsc;
+/// use futures::executor::{block_on_sink, block_on_stream};
+///
+/// let (tx, rx) = mpsc::channel(1);
+/// let mut sink = block_on_sink(tx);
+/// sink.send(1).unwrap();
+/// sink.close().unwrap();
+///
+/// assert_eq!(block_on_stream(rx).collect::<Vec<_>>(), [1]);
+/// ```
+pub fn block_on_sink<Si: Unpin>(sink: Si) -> BlockingSink<Si> {
+    BlockingSink { sink }
+}
+
+/// A handle which blocks on a sink until it has accepted, flushed or closed
+/// the items sent to it.
+#[derive(Debug)]
+pub struct BlockingSink<Si: Unpin> {
+    sink: Si,
+}
+
+impl<Si: Unpin> Deref for BlockingSink<Si> {
+    type Target = Si;
+    fn deref(&self) -> &Self::Target {
+        &self.sink
+    }
+}
+
+impl<Si: Unpin> DerefMut for BlockingSink<Si> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.sink
+    }
+}
+
+impl<Si: Unpin> BlockingSink<Si> {
+    /// Sends an item to the sink, and flushes it.
+    ///
+    /// This blocks until the sink is ready to accept the item, then until
+    /// it has been flushed.
+    pub fn send<Item>(&mut self, item: Item) -> Result<(), Si::Error>
+    where
+        Si: Sink<Item>,
+    {
+        self.feed(item)?;
+        self.flush()
+    }
+
+    /// Sends an item to the sink, without flushing it.
+    ///
+    /// This blocks until the sink is ready to accept the item. Feeding
+    /// several items before a [`flush`](BlockingSink::flush) lets the sink
+    /// send them in a batch.
+    pub fn feed<Item>(&mut self, item: Item) -> Result<(), Si::Error>
+    where
+        Si: Sink<Item>,
+    {
+        let sink = &mut self.sink;
+        block_on(poll_fn(|cx| Pin::new(&mut *sink).poll_ready(cx)))?;
+        Pin::new(sink).start_send(item)
+    }
+
+    /// Blocks until all the items sent to the sink have been flushed.
+    pub fn flush<Item>(&mut self) -> Result<(), Si::Error>
+    where
+        Si: Sink<Item>,
+    {
+        let sink = &mut self.sink;
+        block_on(poll_fn(|cx| Pin::new(&mut *sink).poll_flush(cx)))
+    }
+
+    /// Blocks until the sink has been flushed and closed.
+    pub fn close<Item>(&mut self) -> Result<(), Si::Error>
+    where
+        Si: Sink<Item>,
+    {
+        let sink = &mut self.sink;
+        block_on(poll_fn(|cx| Pin::new(&mut *sink).poll_close(cx)))
+    }
+
+    /// Convert this `BlockingSink` into the inner `Sink` type.
+    pub fn into_inner(self) -> Si {
+        self.sink
+    }
+}
+
+/// Turn an asynchronous reader into a blocking one.
+///
+/// The resulting `BlockingRead` implements [`std::io::Read`], blocking the
+/// caller until the [`AsyncRead`] object has read some data, so that
+/// synchronous code, such as a callback of a foreign library, can read from
+/// it.
+///
+/// ```
+/// use futures::executor::block_on_read;
+/// use futures::io::Cursor;
+/// use std::io::Read;
+///
+/// let mut reader = block_on_read(Cursor::new(b"hello".to_vec()));
+/// let mut output = String::new();
+/// reader.read_to_string(&mut output).unwrap();
+/// assert_eq!(output, "hello");
+/// ```
+pub fn block_on_read<R: AsyncRead + Unpin>(reader: R) -> BlockingRead<R> {
+    BlockingRead { reader }
+}
+
+/// A [`std::io::Read`] object which blocks on an [`AsyncRead`] object until
+/// it has read some data.
+#[derive(Debug)]
+pub struct BlockingRead<R: AsyncRead + Unpin> {
+    reader: R,
+}
+
+impl<R: AsyncRead + Unpin> Deref for BlockingRead<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> DerefMut for BlockingRead<R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> BlockingRead<R> {
+    /// Convert this `BlockingRead` into the inner `AsyncRead` type.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> io::Read for BlockingRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let reader = &mut self.reader;
+        block_on(poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, buf)))
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let reader = &mut self.reader;
+        block_on(poll_fn(|cx| Pin::new(&mut *reader).poll_read_vectored(cx, bufs)))
+    }
+}
+
+/// Turn an asynchronous writer into a blocking one.
+///
+/// The resulting `BlockingWrite` implements [`std::io::Write`], blocking the
+/// caller until the [`AsyncWrite`] object has written or flushed the data,
+/// so that synchronous code, such as a callback of a foreign library, can
+/// write to it.
+///
+/// ```
+/// use futures::executor::block_on_write;
+/// use futures::io::Cursor;
+/// use std::io::Write;
+///
+/// let mut writer = block_on_write(Cursor::new(Vec::new()));
+/// writer.write_all(b"hello").unwrap();
+/// writer.close().unwrap();
+/// assert_eq!(writer.into_inner().into_inner(), b"hello");
+/// ```
+pub fn block_on_write<W: AsyncWrite + Unpin>(writer: W) -> BlockingWrite<W> {
+    BlockingWrite { writer }
+}
+
+/// A [`std::io::Write`] object which blocks on an [`AsyncWrite`] object until
+/// it has written or flushed the data.
+#[derive(Debug)]
+pub struct BlockingWrite<W: AsyncWrite + Unpin> {
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin> Deref for BlockingWrite<W> {
+    type Target = W;
+    fn deref(&self) -> &Self::Target {
+        &self.writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> DerefMut for BlockingWrite<W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> BlockingWrite<W> {
+    /// Blocks until the writer has been flushed and closed.
+    pub fn close(&mut self) -> io::Result<()> {
+        let writer = &mut self.writer;
+        block_on(poll_fn(|cx| Pin::new(&mut *writer).poll_close(cx)))
+    }
+
+    /// Convert this `BlockingWrite` into the inner `AsyncWrite` type.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> io::Write for BlockingWrite<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let writer = &mut self.writer;
+        block_on(poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, buf)))
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let writer = &mut self.writer;
+        block_on(poll_fn(|cx| Pin::new(&mut *writer).poll_write_vectored(cx, bufs)))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let writer = &mut self.writer;
+        block_on(poll_fn(|cx| Pin::new(&mut *writer).poll_flush(cx)))
+    }
+}
+
+impl LocalSpawner {
+    /// Returns a handle to the [`LocalPool`] running on the current thread.
+    ///
+    /// This lets tasks running on a pool spawn further tasks, including
+    /// non-`Send` ones, onto the same pool without a spawner being passed to
+    /// them. It returns `None` when called outside of the run and poll
+    /// methods of a pool.
+    ///
+    /// ```
+    /// use futures::executor::{LocalPool, LocalSpawner};
+    /// use futures::task::LocalSpawnExt;
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let ran = Rc::new(Cell::new(false));
+    /// let ran2 = ran.clone();
+    ///
+    /// pool.run_until(async move {
+    ///     let spawner = LocalSpawner::current().unwrap();
+    ///     spawner.spawn_local(async move { ran2.set(true) }).unwrap();
+    /// });
+    /// pool.run();
+    /// assert!(ran.get());
+    /// assert!(LocalSpawner::current().is_none());
+    /// ```
+    pub fn current() -> Option<Self> {
+        CURRENT_INCOMING.with(|current| {
+            let incoming = current.take();
+            current.set(incoming.clone());
+            incoming.map(|incoming| Self { incoming })
+        })
+    }
+}
+
 impl Spawn for LocalSpawner {
     fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
         if let Some(incoming) = self.incoming.upgrade() {
@@ -0,0 +1,120 @@
+use futures_task::ArcWake;
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::thread::SgxThread as Thread;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+#[cfg(not(feature = "phala-sgx"))]
+use std::thread::Thread;
+
+/// Blocks the thread running a [`LocalPool`](crate::LocalPool) while none of
+/// its tasks can make progress.
+///
+/// By default, a `LocalPool` parks its thread until one of its tasks is woken
+/// up. A pool created with
+/// [`LocalPool::new_with_park`](crate::LocalPool::new_with_park) blocks with
+/// the given `Park` instead, such as an I/O reactor waiting for events, which
+/// wakes up the tasks registered for them before returning.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{LocalPool, Park};
+/// use std::thread::{self, Thread};
+///
+/// struct Reactor {
+///     thread: Thread,
+/// }
+///
+/// impl Park for Reactor {
+///     type Unpark = Thread;
+///
+///     fn unpark(&self) -> Thread {
+///         self.thread.clone()
+///     }
+///
+///     fn park(&mut self) {
+///         // Wait for and dispatch events here.
+///         thread::park();
+///     }
+/// }
+///
+/// let mut pool = LocalPool::new_with_park(Reactor { thread: thread::current() });
+/// pool.run_until(async { /* ... */ });
+/// ```
+pub trait Park {
+    /// The handle to unblock [`park`](Park::park), from any thread.
+    type Unpark: Unpark;
+
+    /// Returns a handle to unblock [`park`](Park::park).
+    ///
+    /// This is called once, when the pool is created.
+    fn unpark(&self) -> Self::Unpark;
+
+    /// Blocks the current thread until the handle returned by
+    /// [`unpark`](Park::unpark) is used.
+    ///
+    /// If the handle was used since the last call, this must return right
+    /// away. It may also return spuriously, without the handle having been
+    /// used.
+    fn park(&mut self);
+}
+
+/// A handle to unblock a [`Park`], from any thread.
+pub trait Unpark: Send + Sync + 'static {
+    /// Unblocks the [`Park`], or makes its next call to
+    /// [`park`](Park::park) return right away if it isn't blocked.
+    fn unpark(&self);
+}
+
+impl Unpark for Thread {
+    fn unpark(&self) {
+        Thread::unpark(self)
+    }
+}
+
+impl<U: Unpark + ?Sized> Unpark for Arc<U> {
+    fn unpark(&self) {
+        (**self).unpark()
+    }
+}
+
+impl<U: Unpark + ?Sized> Unpark for Box<U> {
+    fn unpark(&self) {
+        (**self).unpark()
+    }
+}
+
+// The `Park` of a `LocalPool`, with the waker of its tasks.
+pub(crate) struct Parker {
+    park: RefCell<Box<dyn FnMut()>>,
+    pub(crate) notify: Arc<UnparkNotify>,
+}
+
+impl Parker {
+    pub(crate) fn new<P: Park + 'static>(mut park: P) -> Self {
+        let notify = Arc::new(UnparkNotify {
+            unpark: Box::new(park.unpark()),
+            unparked: AtomicBool::new(false),
+        });
+        Self { park: RefCell::new(Box::new(move || park.park())), notify }
+    }
+
+    pub(crate) fn park(&self) {
+        (self.park.borrow_mut())()
+    }
+}
+
+pub(crate) struct UnparkNotify {
+    unpark: Box<dyn Unpark>,
+    // Like `ThreadNotify::unparked`, which see.
+    pub(crate) unparked: AtomicBool,
+}
+
+impl ArcWake for UnparkNotify {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        if !arc_self.unparked.swap(true, Ordering::Relaxed) {
+            arc_self.unpark.unpark();
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::local_pool::{LocalPool, LocalSpawner};
+use futures_core::future::Future;
+use futures_task::LocalSpawn;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+
+/// Creates a scope for spawning tasks which borrow from the enclosing stack
+/// frame.
+///
+/// The function `f` is called with a [`Scope`], on which tasks can be
+/// [spawned](Scope::spawn). Unlike tasks spawned on an executor, they don't
+/// need to be `'static`: they can borrow anything which outlives the call to
+/// `scope`, as well as the scope itself to spawn more tasks.
+///
+/// Once `f` returns, the tasks are run to completion on the current thread,
+/// and `scope` returns the output of `f` after all of them, including those
+/// spawned while running, have completed.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor;
+///
+/// let words = vec!["hello", "world"];
+/// let mut lengths = vec![0; words.len()];
+///
+/// executor::scope(|s| {
+///     for (word, length) in words.iter().zip(&mut lengths) {
+///         s.spawn(async move { *length = word.len() });
+///     }
+/// });
+///
+/// assert_eq!(lengths, [5, 5]);
+/// ```
+///
+/// # Panics
+///
+/// This function panics if called from within an executor, since it runs the
+/// tasks itself.
+///
+/// If `f` or one of the tasks panics, the remaining tasks are dropped and the
+/// panic is propagated once they have been.
+pub fn scope<'env, F, T>(f: F) -> T
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+{
+    let pool = LocalPool::new();
+    let scope = Scope {
+        spawner: pool.spawner(),
+        pool: RefCell::new(pool),
+        closed: Cell::new(false),
+        scope: PhantomData,
+        env: PhantomData,
+    };
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        let output = f(&scope);
+        scope.pool.borrow_mut().run();
+        output
+    }));
+    match res {
+        Ok(output) => output,
+        Err(payload) => {
+            // The tasks may borrow from the scope, so they must be dropped
+            // while it's still alive.
+            scope.closed.set(true);
+            drop(scope.pool.replace(LocalPool::new()));
+            panic::resume_unwind(payload)
+        }
+    }
+}
+
+/// A scope for spawning tasks which borrow from the enclosing stack frame.
+///
+/// This value is created by the [`scope`] function.
+pub struct Scope<'scope, 'env: 'scope> {
+    spawner: LocalSpawner,
+    pool: RefCell<LocalPool>,
+    // Set once the tasks are being dropped after a panic, so that the
+    // destructors of the tasks can't spawn new ones.
+    closed: Cell<bool>,
+    // Invariant lifetimes, as in `std::thread::Scope`.
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'scope> Scope<'scope, '_> {
+    /// Spawns a task in the scope.
+    ///
+    /// The task is run after the function passed to [`scope`] returns, or
+    /// right away if it is spawned by another task of the scope. Results can
+    /// be returned by writing them to variables borrowed by the task.
+    pub fn spawn<Fut>(&'scope self, future: Fut)
+    where
+        Fut: Future<Output = ()> + 'scope,
+    {
+        if self.closed.get() {
+            return;
+        }
+
+        let future: Pin<Box<dyn Future<Output = ()> + 'scope>> = Box::pin(future);
+        // Safety: `scope` doesn't return before the task has completed or has
+        // been dropped, so it doesn't outlive `'scope`.
+        let future: Pin<Box<dyn Future<Output = ()> + 'static>> = unsafe { mem::transmute(future) };
+        // The pool is alive as long as the scope, so this can't fail.
+        self.spawner.spawn_local_obj(future.into()).unwrap();
+    }
+}
+
+impl fmt::Debug for Scope<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scope").finish()
+    }
+}
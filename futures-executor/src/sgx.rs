@@ -0,0 +1,73 @@
+//! Limits of the threads running inside an SGX enclave.
+
+use sgx_trts::enclave::SgxGlobalData;
+use sgx_tstd::thread;
+use std::cmp;
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// The TCSs taken by the threads the executors started, whichever pool they
+// belong to.
+static TAKEN: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns how many threads the executors may start inside the enclave.
+///
+/// Each thread running in an enclave occupies one of its TCSs (Thread Control
+/// Structures), whose number is fixed by the enclave's configuration. Once
+/// they are all taken, starting another thread fails. One TCS is left to the
+/// thread which entered the enclave and runs the executor.
+pub(crate) fn thread_limit() -> usize {
+    let tcs_max = SgxGlobalData::new().get_tcs_max_num() as usize;
+    cmp::max(1, tcs_max.saturating_sub(1))
+}
+
+/// Returns how many threads the workers of the thread pools may take.
+///
+/// One TCS is always left to the blocking pool, so that the functions passed
+/// to `spawn_blocking` are run even when the thread pools have all the
+/// workers they can have.
+pub(crate) fn worker_limit() -> usize {
+    thread_limit() - 1
+}
+
+/// A TCS taken from the budget shared by the executors of the enclave, given
+/// back when dropped.
+struct Tcs(());
+
+impl Tcs {
+    fn take(limit: usize) -> Option<Self> {
+        let mut taken = TAKEN.load(Ordering::Relaxed);
+        loop {
+            if taken >= limit {
+                return None;
+            }
+            match TAKEN.compare_exchange_weak(taken, taken + 1, Ordering::AcqRel, Ordering::Relaxed)
+            {
+                Ok(_) => return Some(Self(())),
+                Err(actual) => taken = actual,
+            }
+        }
+    }
+}
+
+impl Drop for Tcs {
+    fn drop(&mut self) {
+        TAKEN.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Starts a thread running `f`, if fewer than `limit` TCSs are taken by the
+/// threads of the executors. The TCS is given back once `f` returns.
+pub(crate) fn spawn<F>(builder: thread::Builder, limit: usize, f: F) -> io::Result<()>
+where
+    F: FnOnce() + Send + 'static,
+{
+    let tcs = Tcs::take(limit).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::Other, "no TCS of the enclave left for another thread")
+    })?;
+    builder.spawn(move || {
+        let _tcs = tcs;
+        f()
+    })?;
+    Ok(())
+}
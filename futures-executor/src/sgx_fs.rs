@@ -0,0 +1,190 @@
+//! Asynchronous access to the protected files of an SGX enclave.
+//!
+//! The protected file system of the SGX SDK encrypts and authenticates the
+//! files an enclave stores on the untrusted host. Its operations are
+//! synchronous, and each of them makes OCALLs to the file system of the host.
+//! This module runs them on the thread pool of
+//! [`spawn_blocking`](crate::spawn_blocking), so that reading and writing
+//! sealed data doesn't block the tasks of the executor.
+//!
+//! A [`File`] implements [`AsyncRead`], [`AsyncWrite`] and [`AsyncSeek`],
+//! and so works with the I/O combinators of `futures`, such as `BufReader`
+//! and `BufWriter`.
+//!
+//! This module is only available when the `thread-pool` and `phala-sgx`
+//! features of this library are activated. The `futures` crate exposes it as
+//! `futures::io::sgx_fs`, with its `phala-sgx` feature.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use futures::io::sgx_fs::File;
+//! use futures::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+//! use futures::stream::TryStreamExt;
+//!
+//! let mut writer = BufWriter::new(File::create("log.sealed").await?);
+//! writer.write_all(b"first\nsecond\n").await?;
+//! writer.close().await?;
+//!
+//! let lines: Vec<String> = BufReader::new(File::open("log.sealed").await?)
+//!     .lines()
+//!     .try_collect()
+//!     .await?;
+//! assert_eq!(lines, ["first", "second"]);
+//! ```
+
+use crate::blocking::{spawn_blocking, SpawnBlocking};
+use crate::blocking_io::BlockingIo;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncSeek, AsyncWrite, SeekFrom};
+use sgx_tstd::sgxfs::SgxFile;
+use std::fmt;
+use std::io::{self, Read, Seek, Write};
+use std::path::Path;
+use std::pin::Pin;
+
+/// A 128-bit key with which a protected file is encrypted.
+pub type Key = [u8; 16];
+
+/// A protected file of the enclave, performing its I/O on the thread pool of
+/// [`spawn_blocking`](crate::spawn_blocking).
+///
+/// The operations are run like those of a [`BlockingIo`](crate::BlockingIo):
+/// a write returns once its data is buffered, and its error, if any, is
+/// returned by the next operation. The data written is only guaranteed to be
+/// stored once the file is [flushed](futures_io::AsyncWrite::poll_flush).
+pub struct File {
+    inner: BlockingIo<SendFile>,
+}
+
+impl File {
+    /// Opens a protected file for reading, with a key derived from the
+    /// enclave's sealing key.
+    pub fn open<P: AsRef<Path>>(path: P) -> SpawnBlocking<io::Result<Self>> {
+        let path = path.as_ref().to_owned();
+        spawn_blocking(move || SgxFile::open(&path).map(Self::from_std))
+    }
+
+    /// Creates a protected file for writing, with a key derived from the
+    /// enclave's sealing key, truncating it if it exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> SpawnBlocking<io::Result<Self>> {
+        let path = path.as_ref().to_owned();
+        spawn_blocking(move || SgxFile::create(&path).map(Self::from_std))
+    }
+
+    /// Opens a protected file for reading, encrypted with the given key.
+    pub fn open_ex<P: AsRef<Path>>(path: P, key: &Key) -> SpawnBlocking<io::Result<Self>> {
+        let (path, key) = (path.as_ref().to_owned(), *key);
+        spawn_blocking(move || SgxFile::open_ex(&path, &key).map(Self::from_std))
+    }
+
+    /// Creates a protected file for writing, encrypted with the given key,
+    /// truncating it if it exists.
+    pub fn create_ex<P: AsRef<Path>>(path: P, key: &Key) -> SpawnBlocking<io::Result<Self>> {
+        let (path, key) = (path.as_ref().to_owned(), *key);
+        spawn_blocking(move || SgxFile::create_ex(&path, &key).map(Self::from_std))
+    }
+
+    /// Wraps a protected file opened synchronously.
+    pub fn from_std(file: SgxFile) -> Self {
+        Self { inner: BlockingIo::new(SendFile(file)) }
+    }
+}
+
+impl AsyncRead for File {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for File {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+impl AsyncSeek for File {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_seek(cx, pos)
+    }
+}
+
+impl fmt::Debug for File {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("File").finish()
+    }
+}
+
+/// Reads the whole contents of a protected file, with a key derived from
+/// the enclave's sealing key.
+pub fn read<P: AsRef<Path>>(path: P) -> SpawnBlocking<io::Result<Vec<u8>>> {
+    let path = path.as_ref().to_owned();
+    spawn_blocking(move || {
+        let mut contents = Vec::new();
+        SgxFile::open(&path)?.read_to_end(&mut contents)?;
+        Ok(contents)
+    })
+}
+
+/// Writes `contents` as the whole contents of a protected file, with a key
+/// derived from the enclave's sealing key, creating it if it doesn't exist.
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(
+    path: P,
+    contents: C,
+) -> SpawnBlocking<io::Result<()>> {
+    let (path, contents) = (path.as_ref().to_owned(), contents.as_ref().to_vec());
+    spawn_blocking(move || {
+        let mut file = SgxFile::create(&path)?;
+        file.write_all(&contents)?;
+        file.flush()
+    })
+}
+
+// A protected file, moved to the threads running its operations.
+struct SendFile(SgxFile);
+
+// Safety: The protected file system guards each file with a lock of its own,
+// so a file can be used from any thread of the enclave.
+unsafe impl Send for SendFile {}
+
+impl Read for SendFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for SendFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Seek for SendFile {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
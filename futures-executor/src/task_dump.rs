@@ -0,0 +1,146 @@
+use crate::instrument::TaskId;
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::untrusted::time::InstantEx;
+use std::panic::Location;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A snapshot of a task of a [`ThreadPool`](crate::ThreadPool), returned by
+/// [`ThreadPool::dump_tasks`](crate::ThreadPool::dump_tasks).
+///
+/// The [`Debug`](std::fmt::Debug) output of a list of these, with `{:#?}`,
+/// is meant to be read when looking for the tasks a pool is stuck on.
+#[derive(Clone, Debug)]
+pub struct TaskInfo {
+    id: TaskId,
+    spawned_at: Option<&'static Location<'static>>,
+    future: Option<&'static str>,
+    state: TaskState,
+    polls: usize,
+    since_last_poll: Option<Duration>,
+}
+
+impl TaskInfo {
+    /// Returns the identifier of the task.
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Returns where the task was spawned, if it was spawned with one of the
+    /// inherent methods of the pool, such as
+    /// [`spawn_ok`](crate::ThreadPool::spawn_ok).
+    ///
+    /// Tasks spawned through the [`Spawn`](futures_task::Spawn) trait have no
+    /// known spawn site, nor do any tasks when this library is built with a
+    /// compiler older than Rust 1.46.
+    pub fn spawned_at(&self) -> Option<&'static Location<'static>> {
+        self.spawned_at
+    }
+
+    /// Returns the type name of the future of the task, which spells out the
+    /// chain of combinators it is made of, if it was spawned with
+    /// [`spawn_ok`](crate::ThreadPool::spawn_ok) or
+    /// [`spawn_with_priority`](crate::ThreadPool::spawn_with_priority).
+    ///
+    /// The name is the one given by [`std::any::type_name`], so its exact
+    /// contents aren't guaranteed.
+    pub fn future_type(&self) -> Option<&'static str> {
+        self.future
+    }
+
+    /// Returns what the task was doing when the snapshot was taken.
+    pub fn state(&self) -> TaskState {
+        self.state
+    }
+
+    /// Returns the number of times the task has been polled.
+    pub fn polls(&self) -> usize {
+        self.polls
+    }
+
+    /// Returns the time since the task was last polled, or `None` if it
+    /// hasn't been polled yet.
+    pub fn since_last_poll(&self) -> Option<Duration> {
+        self.since_last_poll
+    }
+}
+
+/// What a task was doing when a [`TaskInfo`] snapshot of it was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskState {
+    /// The task is waiting to be woken up.
+    Idle,
+    /// The task has been woken up, and is waiting in a queue to be polled.
+    Scheduled,
+    /// The task is being polled by the worker thread with the given index.
+    Running(usize),
+}
+
+// Where a task was spawned from, recorded when the pool keeps a registry of
+// its tasks.
+#[derive(Clone, Copy)]
+pub(crate) struct Origin {
+    pub(crate) spawned_at: Option<&'static Location<'static>>,
+    pub(crate) future: Option<&'static str>,
+}
+
+impl Origin {
+    pub(crate) const UNKNOWN: Self = Self { spawned_at: None, future: None };
+
+    #[cfg(not(futures_no_track_caller))]
+    #[track_caller]
+    pub(crate) fn caller(future: Option<&'static str>) -> Self {
+        Self { spawned_at: Some(Location::caller()), future }
+    }
+
+    #[cfg(futures_no_track_caller)]
+    pub(crate) fn caller(future: Option<&'static str>) -> Self {
+        Self { spawned_at: None, future }
+    }
+}
+
+// The entry of a task in the registry of a pool.
+pub(crate) struct TaskRecord {
+    id: TaskId,
+    origin: Origin,
+    status: Mutex<Status>,
+}
+
+struct Status {
+    state: TaskState,
+    polls: usize,
+    last_poll: Option<Instant>,
+}
+
+impl TaskRecord {
+    pub(crate) fn new(id: TaskId, origin: Origin) -> Self {
+        Self {
+            id,
+            origin,
+            status: Mutex::new(Status { state: TaskState::Scheduled, polls: 0, last_poll: None }),
+        }
+    }
+
+    pub(crate) fn set_state(&self, state: TaskState) {
+        self.status.lock().unwrap().state = state;
+    }
+
+    pub(crate) fn start_poll(&self, worker: usize) {
+        let mut status = self.status.lock().unwrap();
+        status.state = TaskState::Running(worker);
+        status.polls += 1;
+        status.last_poll = Some(Instant::now());
+    }
+
+    pub(crate) fn info(&self) -> TaskInfo {
+        let status = self.status.lock().unwrap();
+        TaskInfo {
+            id: self.id,
+            spawned_at: self.origin.spawned_at,
+            future: self.origin.future,
+            state: status.state,
+            polls: status.polls,
+            since_last_poll: status.last_poll.map(|last_poll| last_poll.elapsed()),
+        }
+    }
+}
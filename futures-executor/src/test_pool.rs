@@ -0,0 +1,458 @@
+use crate::enter;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::__internal::with_budget;
+use futures_core::task::{Context, Poll, Waker};
+use futures_task::{waker, ArcWake};
+use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError};
+use futures_util::pin_mut;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A single-threaded task pool for tests, with a virtual clock and a
+/// reproducible scheduling order.
+///
+/// Time only passes on the [`TestClock`] of the pool when the pool is told to
+/// [`advance`](TestPool::advance) it, or when the tasks can't make progress
+/// otherwise while the pool [runs](TestPool::run) them. The [`Sleep`] futures
+/// of the clock then complete in the order of their deadlines, without
+/// waiting for real time to pass, so that timeouts and other timer-driven
+/// code can be tested quickly and deterministically.
+///
+/// The tasks woken up at the same time are polled in an order shuffled with
+/// a pseudo-random generator, which is [seeded](TestPool::with_seed) so that
+/// a failing order can be reproduced. Running a test with several seeds
+/// exercises several interleavings of its tasks.
+///
+/// The pool never blocks the thread: a task waiting for a wakeup from another
+/// thread is only polled again if it has been woken up when the pool checks.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::TestPool;
+/// use futures::future::{self, Either};
+/// use std::time::Duration;
+///
+/// let mut pool = TestPool::new();
+/// let clock = pool.clock();
+///
+/// let timeout = clock.sleep(Duration::from_secs(60));
+/// let output = pool.run_until(future::select(future::pending::<()>(), timeout));
+/// assert!(matches!(output, Either::Right(_)));
+/// assert_eq!(clock.now(), Duration::from_secs(60));
+/// ```
+pub struct TestPool {
+    tasks: Vec<Option<TestTask>>,
+    // The indices of the free slots in `tasks`.
+    free: Vec<usize>,
+    incoming: Rc<Incoming>,
+    ready: Arc<Mutex<Vec<usize>>>,
+    clock: TestClock,
+    rng: Rng,
+}
+
+/// A handle to a [`TestPool`] that implements [`Spawn`](futures_task::Spawn)
+/// and [`LocalSpawn`](futures_task::LocalSpawn).
+#[derive(Clone, Debug)]
+pub struct TestSpawner {
+    incoming: Weak<Incoming>,
+}
+
+/// The virtual clock of a [`TestPool`].
+///
+/// The clock measures the virtual time elapsed since the pool was created.
+/// It can be cloned and sent to other threads, but its time only advances
+/// as the pool runs.
+#[derive(Clone)]
+pub struct TestClock {
+    inner: Arc<Mutex<ClockState>>,
+}
+
+/// Future for the [`sleep`](TestClock::sleep) and
+/// [`sleep_until`](TestClock::sleep_until) methods.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    clock: TestClock,
+    deadline: Duration,
+    // The key of the timer of this future, `None` once it has completed.
+    key: Option<(Duration, u64)>,
+}
+
+type Incoming = RefCell<Vec<LocalFutureObj<'static, ()>>>;
+
+struct TestTask {
+    future: LocalFutureObj<'static, ()>,
+    waker: Waker,
+}
+
+struct TaskWaker {
+    idx: usize,
+    ready: Arc<Mutex<Vec<usize>>>,
+}
+
+impl ArcWake for TaskWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.ready.lock().unwrap().push(arc_self.idx);
+    }
+}
+
+struct MainWaker {
+    woken: AtomicBool,
+}
+
+impl ArcWake for MainWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::SeqCst);
+    }
+}
+
+struct ClockState {
+    now: Duration,
+    // The wakers of the pending `Sleep` futures, by deadline and creation
+    // order.
+    timers: BTreeMap<(Duration, u64), Option<Waker>>,
+    next_timer: u64,
+}
+
+impl TestPool {
+    /// Creates a new test pool, with the seed `0`.
+    pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    /// Creates a new test pool, shuffling the order in which tasks are polled
+    /// with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            tasks: Vec::new(),
+            free: Vec::new(),
+            incoming: Default::default(),
+            ready: Default::default(),
+            clock: TestClock {
+                inner: Arc::new(Mutex::new(ClockState {
+                    now: Duration::from_secs(0),
+                    timers: BTreeMap::new(),
+                    next_timer: 0,
+                })),
+            },
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Get a clonable handle to the pool as a [`Spawn`].
+    pub fn spawner(&self) -> TestSpawner {
+        TestSpawner { incoming: Rc::downgrade(&self.incoming) }
+    }
+
+    /// Returns the virtual clock of the pool.
+    pub fn clock(&self) -> TestClock {
+        self.clock.clone()
+    }
+
+    /// Returns the number of tasks spawned on the pool which haven't
+    /// completed yet.
+    pub fn alive_tasks(&self) -> usize {
+        self.tasks.len() - self.free.len() + self.incoming.borrow().len()
+    }
+
+    /// Runs the tasks until none of them can make progress without time
+    /// passing or a wakeup from outside the pool.
+    pub fn run_until_stalled(&mut self) {
+        let _enter = enter().expect(
+            "cannot execute `TestPool` executor from within \
+             another executor",
+        );
+        while self.poll_ready() {}
+    }
+
+    /// Advances the virtual clock by the given duration, running the tasks
+    /// as the timers of the clock fire.
+    ///
+    /// The timers fire in the order of their deadlines, and the tasks are
+    /// run until they are stalled before the clock moves to the next
+    /// deadline, so that the tasks observe the time at which each timer
+    /// fires.
+    pub fn advance(&mut self, duration: Duration) {
+        let _enter = enter().expect(
+            "cannot execute `TestPool` executor from within \
+             another executor",
+        );
+        let target = self.clock.now() + duration;
+        loop {
+            while self.poll_ready() {}
+            if !self.clock.fire_next(Some(target)) {
+                break;
+            }
+        }
+        self.clock.inner.lock().unwrap().now = target;
+    }
+
+    /// Runs the tasks until the given future completes, advancing the clock
+    /// to the next deadline whenever they are stalled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the future and the tasks are stalled with no timer pending,
+    /// since the future could then never complete.
+    pub fn run_until<F: Future>(&mut self, future: F) -> F::Output {
+        let _enter = enter().expect(
+            "cannot execute `TestPool` executor from within \
+             another executor",
+        );
+        pin_mut!(future);
+        let main = Arc::new(MainWaker { woken: AtomicBool::new(true) });
+        let waker = waker(main.clone());
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if main.woken.swap(false, Ordering::SeqCst) {
+                if let Poll::Ready(output) = with_budget(|| future.as_mut().poll(&mut cx)) {
+                    return output;
+                }
+            }
+            if self.poll_ready() || main.woken.load(Ordering::SeqCst) {
+                continue;
+            }
+            if !self.clock.fire_next(None) {
+                panic!("`TestPool::run_until` stalled with no timer pending");
+            }
+        }
+    }
+
+    /// Runs all the tasks to completion, advancing the clock to the next
+    /// deadline whenever they are stalled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if tasks are stalled with no timer pending, since they could
+    /// then never complete.
+    pub fn run(&mut self) {
+        let _enter = enter().expect(
+            "cannot execute `TestPool` executor from within \
+             another executor",
+        );
+        loop {
+            while self.poll_ready() {}
+            if self.alive_tasks() == 0 {
+                return;
+            }
+            if !self.clock.fire_next(None) {
+                panic!("`TestPool::run` stalled with no timer pending");
+            }
+        }
+    }
+
+    // Polls the tasks spawned or woken up since the last call, in a shuffled
+    // order. Returns `false` if there were none.
+    fn poll_ready(&mut self) -> bool {
+        let mut ready = std::mem::take(&mut *self.ready.lock().unwrap());
+        let incoming = std::mem::take(&mut *self.incoming.borrow_mut());
+        for future in incoming {
+            let idx = self.free.pop().unwrap_or_else(|| {
+                self.tasks.push(None);
+                self.tasks.len() - 1
+            });
+            let waker = waker(Arc::new(TaskWaker { idx, ready: self.ready.clone() }));
+            self.tasks[idx] = Some(TestTask { future, waker });
+            ready.push(idx);
+        }
+        if ready.is_empty() {
+            return false;
+        }
+
+        // Sort first so that the order only depends on the seed, and not on
+        // the order of the wakeups.
+        ready.sort_unstable();
+        ready.dedup();
+        for i in (1..ready.len()).rev() {
+            ready.swap(i, self.rng.next(i + 1));
+        }
+
+        for idx in ready {
+            // The task may have completed since it was woken up.
+            let task = match &mut self.tasks[idx] {
+                Some(task) => task,
+                None => continue,
+            };
+            let mut cx = Context::from_waker(&task.waker);
+            let future = &mut task.future;
+            if with_budget(|| Pin::new(future).poll(&mut cx)).is_ready() {
+                self.tasks[idx] = None;
+                self.free.push(idx);
+            }
+        }
+        true
+    }
+}
+
+impl Default for TestPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for TestPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestPool")
+            .field("alive_tasks", &self.alive_tasks())
+            .field("now", &self.clock.now())
+            .finish()
+    }
+}
+
+impl TestClock {
+    /// Returns the virtual time elapsed since the pool was created.
+    pub fn now(&self) -> Duration {
+        self.inner.lock().unwrap().now
+    }
+
+    /// Returns a future completing once the clock has advanced by the given
+    /// duration.
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        self.sleep_until(self.now() + duration)
+    }
+
+    /// Returns a future completing once the clock has reached the given
+    /// time.
+    pub fn sleep_until(&self, deadline: Duration) -> Sleep {
+        let mut state = self.inner.lock().unwrap();
+        let key = if deadline > state.now {
+            let key = (deadline, state.next_timer);
+            state.next_timer += 1;
+            state.timers.insert(key, None);
+            Some(key)
+        } else {
+            None
+        };
+        Sleep { clock: self.clone(), deadline, key }
+    }
+
+    // Moves the clock to the earliest deadline, unless it is after `limit`,
+    // and wakes the timers of that deadline up. Returns `false` if no timer
+    // was fired.
+    fn fire_next(&self, limit: Option<Duration>) -> bool {
+        let mut state = self.inner.lock().unwrap();
+        let deadline = match state.timers.keys().next() {
+            Some(&(deadline, _)) if limit.map_or(true, |limit| deadline <= limit) => deadline,
+            _ => return false,
+        };
+        state.now = deadline;
+        let later = state.timers.split_off(&(deadline, u64::max_value()));
+        let fired = std::mem::replace(&mut state.timers, later);
+        drop(state);
+
+        for waker in fired.into_iter().filter_map(|(_, waker)| waker) {
+            waker.wake();
+        }
+        true
+    }
+}
+
+impl fmt::Debug for TestClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestClock").field("now", &self.now()).finish()
+    }
+}
+
+impl Sleep {
+    /// Returns the time at which the future completes.
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let key = match self.key {
+            Some(key) => key,
+            None => return Poll::Ready(()),
+        };
+        let mut state = self.clock.inner.lock().unwrap();
+        match state.timers.get_mut(&key) {
+            // The timer hasn't fired yet.
+            Some(waker) => {
+                *waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            None => {
+                drop(state);
+                self.key = None;
+                Poll::Ready(())
+            }
+        }
+    }
+}
+
+impl FusedFuture for Sleep {
+    fn is_terminated(&self) -> bool {
+        self.key.is_none()
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            self.clock.inner.lock().unwrap().timers.remove(&key);
+        }
+    }
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep").field("deadline", &self.deadline).finish()
+    }
+}
+
+impl Spawn for TestSpawner {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_local_obj(future.into())
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        self.status_local()
+    }
+}
+
+impl LocalSpawn for TestSpawner {
+    fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+        if let Some(incoming) = self.incoming.upgrade() {
+            incoming.borrow_mut().push(future);
+            Ok(())
+        } else {
+            Err(SpawnError::shutdown())
+        }
+    }
+
+    fn status_local(&self) -> Result<(), SpawnError> {
+        if self.incoming.upgrade().is_some() {
+            Ok(())
+        } else {
+            Err(SpawnError::shutdown())
+        }
+    }
+}
+
+// A xorshift generator, shuffling the order of the tasks.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // The state of a xorshift generator must not be zero.
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    // Returns a number in `0..n`.
+    fn next(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
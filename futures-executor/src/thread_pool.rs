@@ -1,23 +1,55 @@
 use crate::enter;
+use crate::instrument::{poll_instrumented, Instrument, TaskId};
+use crate::park::{Park, Unpark};
+use crate::task_dump::{Origin, TaskInfo, TaskRecord, TaskState};
 use crate::unpark_mutex::UnparkMutex;
 use futures_core::future::Future;
+use futures_core::task::__internal::with_budget;
 use futures_core::task::{Context, Poll};
 use futures_task::{waker_ref, ArcWake};
 use futures_task::{FutureObj, Spawn, SpawnError};
 use futures_util::future::FutureExt;
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::thread;
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::untrusted::time::InstantEx;
+use std::any::{self, Any};
+use std::cell::{Cell, UnsafeCell};
 use std::cmp;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt;
 use std::io;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::mpsc;
-use std::sync::{Arc, Mutex};
+use std::mem::{self, MaybeUninit};
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+#[cfg(not(feature = "phala-sgx"))]
 use std::thread;
+use std::time::{Duration, Instant};
 
 /// A general-purpose thread pool for scheduling tasks that poll futures to
 /// completion.
 ///
-/// The thread pool multiplexes any number of tasks onto a fixed number of
-/// worker threads.
+/// The thread pool multiplexes any number of tasks onto a number of worker
+/// threads, which can be [changed](ThreadPool::resize) while the pool runs.
+///
+/// Each worker thread has its own run queue, onto which go the tasks spawned
+/// or woken up from that thread. The tasks spawned or woken up from other
+/// threads go to a queue shared by the workers. A worker whose queues are
+/// empty steals half of the tasks of another worker, picked at random, so
+/// that the workers stay busy without contending on a single queue.
+///
+/// Tasks can be given a [`Priority`], to run latency-critical tasks ahead of
+/// bulk work on the same pool. See
+/// [`spawn_with_priority`](ThreadPool::spawn_with_priority) for details.
+///
+/// The futures of at most 128 bytes passed to
+/// [`spawn_ok`](ThreadPool::spawn_ok) and
+/// [`spawn_with_priority`](ThreadPool::spawn_with_priority) are stored
+/// together with the rest of their task, so that spawning them takes a
+/// single allocation, instead of being boxed.
 ///
 /// This type is a clonable handle to the threadpool itself.
 /// Cloning it will only create a new reference, not a new threadpool.
@@ -29,6 +61,20 @@ pub struct ThreadPool {
     state: Arc<PoolState>,
 }
 
+/// A [`ThreadPool`] running inside an SGX enclave.
+///
+/// With the `phala-sgx` feature, or its `mesalock_sgx` alias, the worker
+/// threads of a `ThreadPool` are started with `sgx_tstd`, and take their TCSs
+/// (Thread Control Structures) from a budget shared by all the pools and the
+/// threads of [`spawn_blocking`](crate::spawn_blocking). See
+/// [`ThreadPoolBuilder::pool_size`] for how the pool is sized.
+///
+/// This type is only available when the `thread-pool` and `phala-sgx`
+/// features of this library are activated.
+#[cfg(feature = "phala-sgx")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "thread-pool", feature = "phala-sgx"))))]
+pub type SgxThreadPool = ThreadPool;
+
 /// Thread pool configuration object.
 ///
 /// This type is only available when the `thread-pool` feature of this
@@ -36,25 +82,296 @@ pub struct ThreadPool {
 #[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
 pub struct ThreadPoolBuilder {
     pool_size: usize,
+    min_size: Option<usize>,
+    max_size: Option<usize>,
+    priority_levels: usize,
     stack_size: usize,
     name_prefix: Option<String>,
     after_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
     before_stop: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    on_park: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    on_unpark: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    keep_alive: Option<Duration>,
+    idle_strategy: IdleStrategy,
+    park: Option<Arc<MakePark>>,
+    on_spawn: Option<Arc<dyn Fn() + Send + Sync>>,
+    on_complete: Option<Arc<dyn Fn() + Send + Sync>>,
+    instrument: Option<Arc<dyn Instrument>>,
+    panic_handler: Option<Arc<PanicHandler>>,
+    task_dump: bool,
 }
 
+type PanicHandler = dyn Fn(TaskId, Box<dyn Any + Send>) + Send + Sync;
+
+// Creates the `Park` of a worker thread, on that thread, returning a function
+// parking the thread with it and the handle to unpark it.
+type MakePark = dyn Fn(usize) -> (Box<dyn FnMut()>, Arc<dyn Unpark>) + Send + Sync;
+
 trait AssertSendSync: Send + Sync {}
 impl AssertSendSync for ThreadPool {}
 
 struct PoolState {
-    tx: Mutex<mpsc::Sender<Message>>,
-    rx: Mutex<mpsc::Receiver<Message>>,
+    // The queue of the tasks spawned or woken up outside of the workers.
+    injector: Mutex<RunQueue>,
+    // A slot for each worker the pool can have, running or not.
+    workers: Vec<Worker>,
+    // Set once the workers should stop, after running out of tasks.
+    closed: AtomicBool,
+    // The number of workers waiting for tasks on `sleep_changed`.
+    sleepers: AtomicUsize,
+    sleep: Mutex<()>,
+    sleep_changed: Condvar,
+    // Whether the workers sleep with a `Park` of their own rather than on
+    // `sleep_changed`.
+    custom_park: bool,
+    // The number of workers checking the queues for tasks again before going
+    // to sleep, as set by their `IdleStrategy`.
+    spinners: AtomicUsize,
+    idle_strategy: IdleStrategy,
     cnt: AtomicUsize,
-    size: usize,
+    // The number of workers the pool should have. The workers whose index
+    // isn't less than this retire once they finish their current task.
+    size: AtomicUsize,
+    // The size the pool was created or resized to, which is more than `size`
+    // while workers are stopped after being idle for `keep_alive`.
+    target_size: AtomicUsize,
+    min_size: usize,
+    keep_alive: Option<Duration>,
+    // The configuration of the worker threads, kept if the pool can be
+    // resized.
+    config: Option<WorkerConfig>,
+    priority_levels: usize,
+    live: Mutex<Live>,
+    live_changed: Condvar,
+    // The number of tasks in the queues.
+    queued: AtomicUsize,
+    polls: AtomicUsize,
+    on_spawn: Option<Arc<dyn Fn() + Send + Sync>>,
+    on_complete: Option<Arc<dyn Fn() + Send + Sync>>,
+    instrument: Option<Arc<dyn Instrument>>,
+    panic_handler: Option<Arc<PanicHandler>>,
+    // The live tasks, if the pool keeps track of them for `dump_tasks`.
+    registry: Option<Mutex<BTreeMap<TaskId, Arc<TaskRecord>>>>,
+}
+
+struct Worker {
+    // The run queue of the tasks spawned or woken up from the worker thread.
+    queue: Mutex<RunQueue>,
+    // The time spent running tasks.
+    busy: Mutex<Duration>,
+    // The handle waking the worker up if it sleeps with a `Park` of its own,
+    // and whether it sleeps.
+    unpark: Mutex<Option<Arc<dyn Unpark>>>,
+    parked: AtomicBool,
+}
+
+impl Worker {
+    fn unpark(&self) {
+        if let Some(unpark) = &*self.unpark.lock().unwrap() {
+            unpark.unpark();
+        }
+    }
+}
+
+#[derive(Clone)]
+struct WorkerConfig {
+    // Unused inside an enclave, whose configuration fixes the stack size.
+    #[cfg_attr(feature = "phala-sgx", allow(dead_code))]
+    stack_size: usize,
+    name_prefix: Option<String>,
+    after_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    before_stop: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    on_park: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    on_unpark: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    park: Option<Arc<MakePark>>,
+}
+
+/// The priority of a task spawned on a [`ThreadPool`].
+///
+/// Priorities are levels counted from `0`, the lowest priority, which is the
+/// one of the tasks spawned without a priority. The number of levels of a
+/// pool is set with
+/// [`ThreadPoolBuilder::priority_levels`](ThreadPoolBuilder::priority_levels),
+/// and the levels above the highest one of the pool are treated as its highest
+/// one.
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Priority(usize);
+
+impl Priority {
+    /// The lowest priority, given to the tasks spawned without a priority.
+    pub const LOWEST: Self = Self(0);
+
+    /// Creates the priority of the given level.
+    pub const fn new(level: usize) -> Self {
+        Self(level)
+    }
+
+    /// Returns the level of the priority.
+    pub const fn level(self) -> usize {
+        self.0
+    }
+}
+
+/// How the worker threads of a [`ThreadPool`] wait for new tasks.
+///
+/// A worker which runs out of tasks first checks the queues of the pool
+/// again a number of times in a row, which is called spinning. It then
+/// yields its thread to the operating system between further checks, and
+/// finally parks, going to sleep until a task is spawned or woken up.
+///
+/// Waking up a parked worker is much slower than having a spinning worker
+/// pick up the task, especially inside an SGX enclave, where parking and
+/// unparking a thread are OCALLs, each leaving and re-entering the enclave.
+/// Spinning trades CPU time for lower latency under bursty loads. By
+/// default, the workers park right away.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{IdleStrategy, ThreadPool};
+///
+/// let pool = ThreadPool::builder()
+///     .idle_strategy(IdleStrategy::new(1000, 10))
+///     .create()
+///     .unwrap();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "thread-pool")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IdleStrategy {
+    spins: u32,
+    yields: u32,
+}
+
+impl IdleStrategy {
+    /// Parks the worker as soon as it runs out of tasks.
+    pub const PARK: Self = Self { spins: 0, yields: 0 };
+
+    /// Checks the queues `spins` times, then `yields` more times, yielding
+    /// the thread before each of those checks, before parking the worker.
+    pub const fn new(spins: u32, yields: u32) -> Self {
+        Self { spins, yields }
+    }
+
+    /// Returns how many times the queues are checked before yielding.
+    pub const fn spins(self) -> u32 {
+        self.spins
+    }
+
+    /// Returns how many times the thread is yielded before parking.
+    pub const fn yields(self) -> u32 {
+        self.yields
+    }
+}
+
+impl Default for IdleStrategy {
+    fn default() -> Self {
+        Self::PARK
+    }
+}
+
+// The number of tasks taken in a row from a priority level while lower
+// levels have tasks waiting, after which one of the lower levels is served.
+const STARVATION_LIMIT: usize = 16;
+
+// A run queue of tasks, with a FIFO queue for each priority level.
+struct RunQueue {
+    levels: Vec<VecDeque<Task>>,
+    // The number of tasks taken from each level in a row while lower levels
+    // had tasks waiting.
+    served: Vec<usize>,
+}
+
+impl RunQueue {
+    fn new(levels: usize) -> Self {
+        Self { levels: (0..levels).map(|_| VecDeque::new()).collect(), served: vec![0; levels] }
+    }
+
+    fn push(&mut self, task: Task) {
+        self.levels[task.level].push_back(task);
+    }
+
+    // Takes the task to run next: the oldest task of the highest level, unless
+    // that level has been served `STARVATION_LIMIT` times in a row, in which
+    // case it lets the lower levels run a task.
+    fn pop(&mut self) -> Option<Task> {
+        for level in (0..self.levels.len()).rev() {
+            if self.levels[level].is_empty() {
+                continue;
+            }
+            let lower_waiting = self.levels[..level].iter().any(|queue| !queue.is_empty());
+            if !lower_waiting {
+                self.served[level] = 0;
+            } else if self.served[level] < STARVATION_LIMIT {
+                self.served[level] += 1;
+            } else {
+                self.served[level] = 0;
+                continue;
+            }
+            return self.levels[level].pop_front();
+        }
+        None
+    }
+
+    // Returns the highest level with tasks waiting.
+    fn top_level(&self) -> Option<usize> {
+        self.levels.iter().rposition(|queue| !queue.is_empty())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.levels.iter().all(VecDeque::is_empty)
+    }
+
+    // Takes half of the tasks of each level.
+    fn steal_half(&mut self) -> Vec<Task> {
+        let mut stolen = Vec::new();
+        for queue in &mut self.levels {
+            let len = queue.len();
+            stolen.extend(queue.drain(len / 2..));
+        }
+        stolen
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = Task> + '_ {
+        self.levels.iter_mut().flat_map(|queue| queue.drain(..))
+    }
+}
+
+thread_local! {
+    // The pool and index of the worker running on this thread, if any.
+    static CURRENT_WORKER: Cell<Option<(*const PoolState, usize)>> = Cell::new(None);
+}
+
+// The tasks and worker threads which are still alive, tracked to shut the
+// pool down gracefully.
+struct Live {
+    phase: Phase,
+    tasks: usize,
+    workers: usize,
+    // Whether the thread of each worker slot is running.
+    running: Vec<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Running,
+    ShuttingDown,
+    ShutDown,
+}
+
+impl Phase {
+    fn spawn_error(self) -> Option<SpawnError> {
+        match self {
+            Phase::Running => None,
+            Phase::ShuttingDown => Some(SpawnError::shutting_down()),
+            Phase::ShutDown => Some(SpawnError::shutdown()),
+        }
+    }
 }
 
 impl fmt::Debug for ThreadPool {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ThreadPool").field("size", &self.state.size).finish()
+        f.debug_struct("ThreadPool").field("size", &self.size()).finish()
     }
 }
 
@@ -62,16 +379,15 @@ impl fmt::Debug for ThreadPoolBuilder {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ThreadPoolBuilder")
             .field("pool_size", &self.pool_size)
+            .field("min_size", &self.min_size)
+            .field("max_size", &self.max_size)
             .field("name_prefix", &self.name_prefix)
+            .field("keep_alive", &self.keep_alive)
+            .field("idle_strategy", &self.idle_strategy)
             .finish()
     }
 }
 
-enum Message {
-    Run(Task),
-    Close,
-}
-
 impl ThreadPool {
     /// Creates a new thread pool with the default configuration.
     ///
@@ -94,14 +410,141 @@ impl ThreadPool {
     /// Spawns a future that will be run to completion.
     ///
     /// > **Note**: This method is similar to `Spawn::spawn_obj`, except that
-    /// >           it is guaranteed to always succeed.
+    /// >           it is guaranteed to always succeed. Once the pool
+    /// >           [shuts down](ThreadPool::shutdown), the future is dropped
+    /// >           without being run.
+    #[cfg_attr(not(futures_no_track_caller), track_caller)]
     pub fn spawn_obj_ok(&self, future: FutureObj<'static, ()>) {
+        self.spawn_ok_from(future, Origin::caller(None));
+    }
+
+    fn spawn_ok_from<Fut>(&self, future: Fut, origin: Origin)
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        {
+            let mut live = self.state.live.lock().unwrap();
+            if live.phase.spawn_error().is_some() {
+                drop(live);
+                // Count the future as a task dropped before completing, as
+                // it would never be run.
+                if let Some(on_spawn) = &self.state.on_spawn {
+                    on_spawn();
+                }
+                drop(future);
+                if let Some(on_complete) = &self.state.on_complete {
+                    on_complete();
+                }
+                return;
+            }
+            live.tasks += 1;
+        }
+        self.spawn_task(future, Priority::LOWEST, origin);
+    }
+
+    /// Spawns a task that polls the given future with output `()` to
+    /// completion, with the given priority.
+    ///
+    /// The worker threads run the tasks of the highest priority they have
+    /// first. To keep lower priorities from starving, a worker which has run
+    /// several tasks of a priority in a row while tasks of lower priorities
+    /// were waiting runs one of those next. The priority is kept by the task
+    /// each time it is woken up.
+    ///
+    /// ```
+    /// use futures::executor::{Priority, ThreadPool};
+    ///
+    /// let pool = ThreadPool::builder().priority_levels(2).create().unwrap();
+    ///
+    /// let heartbeat = async { /* ... */ };
+    /// pool.spawn_with_priority(heartbeat, Priority::new(1)).unwrap();
+    /// ```
+    ///
+    /// This method returns an error like
+    /// [`Spawn::spawn_obj`](futures_task::Spawn::spawn_obj) once the pool
+    /// [shuts down](ThreadPool::shutdown).
+    #[cfg_attr(not(futures_no_track_caller), track_caller)]
+    pub fn spawn_with_priority<Fut>(
+        &self,
+        future: Fut,
+        priority: Priority,
+    ) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let origin = Origin::caller(Some(any::type_name::<Fut>()));
+        self.spawn_with_priority_from(future, priority, origin)
+    }
+
+    /// Spawns a future that will be run to completion, with the given
+    /// priority.
+    ///
+    /// See [`spawn_with_priority`](ThreadPool::spawn_with_priority) for
+    /// details.
+    #[cfg_attr(not(futures_no_track_caller), track_caller)]
+    pub fn spawn_obj_with_priority(
+        &self,
+        future: FutureObj<'static, ()>,
+        priority: Priority,
+    ) -> Result<(), SpawnError> {
+        self.spawn_with_priority_from(future, priority, Origin::caller(None))
+    }
+
+    fn spawn_with_priority_from<Fut>(
+        &self,
+        future: Fut,
+        priority: Priority,
+        origin: Origin,
+    ) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        {
+            let mut live = self.state.live.lock().unwrap();
+            if let Some(err) = live.phase.spawn_error() {
+                return Err(err);
+            }
+            live.tasks += 1;
+        }
+        self.spawn_task(future, priority, origin);
+        Ok(())
+    }
+
+    fn spawn_task<Fut>(&self, future: Fut, priority: Priority, origin: Origin)
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let id = TaskId::next();
+        let record = self.state.registry.as_ref().map(|registry| {
+            let record = Arc::new(TaskRecord::new(id, origin));
+            registry.lock().unwrap().insert(id, record.clone());
+            record
+        });
+        let wake_handle = Arc::new(WakeHandle {
+            id,
+            record,
+            exec: self.clone(),
+            mutex: UnparkMutex::new(),
+            storage: UnsafeCell::new(InlineStorage { _bytes: MaybeUninit::uninit() }),
+        });
+        let future = if mem::size_of::<Fut>() <= INLINE_FUTURE_SIZE
+            && mem::align_of::<Fut>() <= mem::align_of::<InlineStorage>()
+        {
+            // Safety: The future fits in the storage, which isn't used yet.
+            FutureStorage::Inline(unsafe { InlineFuture::new(future, wake_handle.clone()) })
+        } else {
+            FutureStorage::Boxed(FutureObj::new(Box::new(future)))
+        };
         let task = Task {
-            future,
-            wake_handle: Arc::new(WakeHandle { exec: self.clone(), mutex: UnparkMutex::new() }),
+            future: TaskFuture { inner: future, id, state: self.state.clone() },
+            level: cmp::min(priority.level(), self.state.priority_levels - 1),
+            wake_handle,
             exec: self.clone(),
         };
-        self.state.send(Message::Run(task));
+        self.state.push(task);
+        if let Some(on_spawn) = &self.state.on_spawn {
+            on_spawn();
+        }
     }
 
     /// Spawns a task that polls the given future with output `()` to
@@ -117,47 +560,549 @@ impl ThreadPool {
     /// ```
     ///
     /// > **Note**: This method is similar to `SpawnExt::spawn`, except that
-    /// >           it is guaranteed to always succeed.
+    /// >           it is guaranteed to always succeed. Once the pool
+    /// >           [shuts down](ThreadPool::shutdown), the future is dropped
+    /// >           without being run.
+    #[cfg_attr(not(futures_no_track_caller), track_caller)]
     pub fn spawn_ok<Fut>(&self, future: Fut)
     where
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.spawn_obj_ok(FutureObj::new(Box::new(future)))
+        let origin = Origin::caller(Some(any::type_name::<Fut>()));
+        self.spawn_ok_from(future, origin)
+    }
+
+    /// Returns the number of worker threads of the pool.
+    ///
+    /// After the pool is [resized](ThreadPool::resize) down, this is the new
+    /// size, while the retiring workers may still be finishing their current
+    /// task. Workers stopped after being idle for the
+    /// [`keep_alive`](ThreadPoolBuilder::keep_alive) timeout aren't counted.
+    pub fn size(&self) -> usize {
+        self.state.size.load(Ordering::SeqCst)
+    }
+
+    /// Changes the number of worker threads of the pool.
+    ///
+    /// The size is clamped to the bounds set with
+    /// [`min_size`](ThreadPoolBuilder::min_size) and
+    /// [`max_size`](ThreadPoolBuilder::max_size), which are both the initial
+    /// size of the pool unless set. Growing the pool starts new worker
+    /// threads, which run the [`after_start`](ThreadPoolBuilder::after_start)
+    /// hook. Shrinking it retires the workers with the highest indices once
+    /// they finish the task they are running, if any, after which their
+    /// queued tasks are run by the remaining workers. This has no effect once
+    /// the pool [shuts down](ThreadPool::shutdown).
+    ///
+    /// Returns the new size of the pool.
+    ///
+    /// ```
+    /// use futures::executor::ThreadPool;
+    ///
+    /// let pool = ThreadPool::builder().pool_size(2).min_size(1).max_size(4).create().unwrap();
+    /// assert_eq!(pool.resize(4).unwrap(), 4);
+    /// assert_eq!(pool.resize(8).unwrap(), 4);
+    /// assert_eq!(pool.resize(0).unwrap(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a worker thread can't be started, in which case
+    /// the pool keeps the workers started so far.
+    pub fn resize(&self, size: usize) -> Result<usize, io::Error> {
+        let state = &self.state;
+        let config = match &state.config {
+            Some(config) => config,
+            None => return Ok(self.size()),
+        };
+        let size = cmp::min(cmp::max(size, state.min_size), state.workers.len());
+
+        let mut live = state.live.lock().unwrap();
+        if live.phase != Phase::Running {
+            return Ok(self.size());
+        }
+        state.target_size.store(size, Ordering::SeqCst);
+        let old_size = state.size.swap(size, Ordering::SeqCst);
+        if size < old_size {
+            drop(live);
+            // Wake the sleeping workers up, so that the retiring ones stop.
+            state.notify_all();
+            return Ok(size);
+        }
+
+        for idx in old_size..size {
+            // A retiring worker which hasn't stopped yet keeps running.
+            if live.running[idx] {
+                continue;
+            }
+            if let Err(err) = state.spawn_worker(idx, config) {
+                state.size.store(idx, Ordering::SeqCst);
+                return Err(err);
+            }
+            live.running[idx] = true;
+            live.workers += 1;
+        }
+        Ok(size)
+    }
+
+    /// Returns the number of tasks spawned on the pool which haven't
+    /// completed yet.
+    pub fn alive_tasks(&self) -> usize {
+        self.state.live.lock().unwrap().tasks
+    }
+
+    /// Returns a snapshot of the tasks of the pool which haven't completed
+    /// yet, in the order in which they were spawned.
+    ///
+    /// This is intended for finding out what the tasks of a pool which stopped
+    /// making progress are waiting on. The tasks are only tracked if enabled
+    /// with [`ThreadPoolBuilder::task_dump`], otherwise this returns an empty
+    /// list.
+    ///
+    /// ```
+    /// use futures::executor::{TaskState, ThreadPool};
+    /// use futures::future;
+    ///
+    /// let pool = ThreadPool::builder().task_dump(true).create().unwrap();
+    /// pool.spawn_ok(future::pending());
+    ///
+    /// for task in pool.dump_tasks() {
+    ///     if task.state() == TaskState::Idle {
+    ///         println!("{:?} is waiting, spawned at {:?}", task.future_type(), task.spawned_at());
+    ///     }
+    /// }
+    /// ```
+    pub fn dump_tasks(&self) -> Vec<TaskInfo> {
+        match &self.state.registry {
+            Some(registry) => {
+                registry.lock().unwrap().values().map(|record| record.info()).collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the number of tasks waiting in the queues of the pool for a
+    /// worker thread to poll them.
+    pub fn queued_tasks(&self) -> usize {
+        self.state.queued.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of times the tasks of the pool have been
+    /// polled.
+    pub fn total_polls(&self) -> usize {
+        self.state.polls.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total time the given worker thread has spent running
+    /// tasks.
+    ///
+    /// Workers are indexed from `0` to [`size`](ThreadPool::size), like in the
+    /// [`after_start`](ThreadPoolBuilder::after_start) hook. The time of a
+    /// retired worker is kept, and added to by the next worker with its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `worker` is not less than the
+    /// [maximum size](ThreadPoolBuilder::max_size) of the pool.
+    pub fn busy_time(&self, worker: usize) -> Duration {
+        *self.state.workers[worker].busy.lock().unwrap()
+    }
+
+    /// Shuts the pool down gracefully, waiting at most `timeout` for the
+    /// tasks it is running to complete.
+    ///
+    /// Once this is called, the pool rejects the tasks spawned with
+    /// [`spawn_obj`](Spawn::spawn_obj) with an error for which
+    /// [`is_shutting_down`](SpawnError::is_shutting_down) returns `true`, and
+    /// for which [`is_shutdown`](SpawnError::is_shutdown) returns `true` once
+    /// the shutdown is over, and drops the futures passed to
+    /// [`spawn_ok`](ThreadPool::spawn_ok) without running them. The tasks
+    /// already spawned keep running until they
+    /// complete or the deadline is reached, after which the worker threads are
+    /// stopped and joined, within what remains of the timeout.
+    ///
+    /// Returns the number of tasks abandoned because they didn't complete in
+    /// time. These tasks are never polled again once the worker threads have
+    /// stopped.
+    ///
+    /// ```
+    /// use futures::executor::ThreadPool;
+    /// use std::time::Duration;
+    ///
+    /// let pool = ThreadPool::new().unwrap();
+    /// pool.spawn_ok(async { /* ... */ });
+    ///
+    /// let abandoned = pool.shutdown(Duration::from_secs(1));
+    /// assert_eq!(abandoned, 0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called from within an executor, such as from a
+    /// task running on the pool.
+    pub fn shutdown(&self, timeout: Duration) -> usize {
+        let _enter = enter().expect(
+            "cannot shut down a `ThreadPool` from within \
+             another executor",
+        );
+        let deadline = Instant::now() + timeout;
+
+        let mut live = self.state.live.lock().unwrap();
+        if live.phase == Phase::ShutDown {
+            return 0;
+        }
+        live.phase = Phase::ShuttingDown;
+        live = self.state.wait_live(live, deadline, |live| live.tasks == 0);
+        let abandoned = live.tasks;
+        drop(live);
+
+        self.state.close();
+        let mut live = self.state.live.lock().unwrap();
+        live = self.state.wait_live(live, deadline, |live| live.workers == 0);
+        live.phase = Phase::ShutDown;
+        let stopped = live.workers == 0;
+        drop(live);
+
+        if stopped {
+            // No worker is left to run the tasks still queued, drop them.
+            let mut queued: Vec<_> = self.state.injector.lock().unwrap().drain().collect();
+            for worker in &self.state.workers {
+                queued.extend(worker.queue.lock().unwrap().drain());
+            }
+            self.state.queued.fetch_sub(queued.len(), Ordering::Relaxed);
+        }
+        abandoned
     }
 }
 
 impl Spawn for ThreadPool {
     fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
-        self.spawn_obj_ok(future);
-        Ok(())
+        self.spawn_with_priority_from(future, Priority::LOWEST, Origin::UNKNOWN)
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        match self.state.live.lock().unwrap().phase.spawn_error() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
     }
 }
 
 impl PoolState {
-    fn send(&self, msg: Message) {
-        self.tx.lock().unwrap().send(msg).unwrap();
+    fn push(self: &Arc<Self>, task: Task) {
+        if let Some(record) = &task.wake_handle.record {
+            record.set_state(TaskState::Scheduled);
+        }
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        let worker = CURRENT_WORKER.with(Cell::get);
+        match worker {
+            Some((pool, idx)) if pool == &**self as *const Self => {
+                self.workers[idx].queue.lock().unwrap().push(task)
+            }
+            _ => self.injector.lock().unwrap().push(task),
+        }
+
+        // A worker going to sleep registers itself in `sleepers` before
+        // checking the queues for tasks, so either it finds this task or we
+        // find it here. The same goes for the workers done spinning, which
+        // leave `spinners` before, so there is no need to wake a worker up
+        // if there are enough spinning ones to take the queued tasks.
+        let sleepers = self.sleepers.load(Ordering::SeqCst);
+        if sleepers > 0
+            && self.queued.load(Ordering::Relaxed) > self.spinners.load(Ordering::SeqCst)
+        {
+            self.notify_one();
+        }
+        // Start a worker stopped after being idle again if there are more
+        // tasks than workers to take them.
+        if self.queued.load(Ordering::Relaxed) > sleepers
+            && self.size.load(Ordering::SeqCst) < self.target_size.load(Ordering::SeqCst)
+        {
+            self.grow();
+        }
     }
 
-    fn work(
-        &self,
-        idx: usize,
-        after_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
-        before_stop: Option<Arc<dyn Fn(usize) + Send + Sync>>,
-    ) {
+    fn grow(self: &Arc<Self>) {
+        let config = match &self.config {
+            Some(config) => config,
+            None => return,
+        };
+        let mut live = self.live.lock().unwrap();
+        let idx = self.size.load(Ordering::SeqCst);
+        if live.phase != Phase::Running || idx >= self.target_size.load(Ordering::SeqCst) {
+            return;
+        }
+        self.size.store(idx + 1, Ordering::SeqCst);
+        // A worker which hasn't stopped yet keeps running.
+        if live.running[idx] {
+            return;
+        }
+        if self.spawn_worker(idx, config).is_err() {
+            self.size.store(idx, Ordering::SeqCst);
+            return;
+        }
+        live.running[idx] = true;
+        live.workers += 1;
+    }
+
+    // Shrinks the pool after the given worker has been idle for the
+    // keep-alive timeout, if it is the last worker above the minimum size.
+    fn shrink(&self, idx: usize) {
+        let _live = self.live.lock().unwrap();
+        if idx >= self.min_size && self.size.load(Ordering::SeqCst) == idx + 1 {
+            self.size.store(idx, Ordering::SeqCst);
+        }
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.notify_all();
+    }
+
+    // Wakes up a sleeping worker.
+    fn notify_one(&self) {
+        let _sleep = self.sleep.lock().unwrap();
+        if !self.custom_park {
+            self.sleep_changed.notify_one();
+            return;
+        }
+        if let Some(worker) =
+            self.workers.iter().find(|worker| worker.parked.swap(false, Ordering::SeqCst))
+        {
+            worker.unpark();
+        }
+    }
+
+    // Wakes up all the sleeping workers.
+    fn notify_all(&self) {
+        let _sleep = self.sleep.lock().unwrap();
+        if !self.custom_park {
+            self.sleep_changed.notify_all();
+            return;
+        }
+        for worker in &self.workers {
+            if worker.parked.swap(false, Ordering::SeqCst) {
+                worker.unpark();
+            }
+        }
+    }
+
+    // Takes the next task to run on the given worker: from its own queue or
+    // from the shared queue, whichever has the highest priority task, then
+    // stolen from another worker.
+    fn next_task(&self, idx: usize, rng: &mut Rng) -> Option<Task> {
+        let local = &self.workers[idx].queue;
+        let injector_level = self.injector.lock().unwrap().top_level();
+        {
+            let mut local = local.lock().unwrap();
+            if local.top_level() >= injector_level {
+                if let Some(task) = local.pop() {
+                    return Some(task);
+                }
+            }
+        }
+        if let Some(task) = self.injector.lock().unwrap().pop() {
+            return Some(task);
+        }
+        if let Some(task) = local.lock().unwrap().pop() {
+            return Some(task);
+        }
+
+        let slots = self.workers.len();
+        let start = rng.next(slots);
+        for i in 0..slots {
+            let victim = (start + i) % slots;
+            if victim == idx {
+                continue;
+            }
+            let stolen = self.workers[victim].queue.lock().unwrap().steal_half();
+            if !stolen.is_empty() {
+                let mut local = local.lock().unwrap();
+                for task in stolen {
+                    local.push(task);
+                }
+                return local.pop();
+            }
+        }
+        None
+    }
+
+    fn has_tasks(&self) -> bool {
+        !self.injector.lock().unwrap().is_empty()
+            || self.workers.iter().any(|worker| !worker.queue.lock().unwrap().is_empty())
+    }
+
+    fn spawn_worker(self: &Arc<Self>, idx: usize, config: &WorkerConfig) -> io::Result<()> {
+        let state = self.clone();
+        let config = config.clone();
+        let mut thread_builder = thread::Builder::new();
+        if let Some(ref name_prefix) = config.name_prefix {
+            thread_builder = thread_builder.name(format!("{}{}", name_prefix, idx));
+        }
+        // Inside an enclave, the stack size is fixed by its configuration.
+        #[cfg(not(feature = "phala-sgx"))]
+        {
+            if config.stack_size > 0 {
+                thread_builder = thread_builder.stack_size(config.stack_size);
+            }
+        }
+        #[cfg(not(feature = "phala-sgx"))]
+        thread_builder.spawn(move || state.work(idx, config))?;
+        // Inside an enclave, the workers take their TCSs from the budget
+        // shared with the other pools.
+        #[cfg(feature = "phala-sgx")]
+        crate::sgx::spawn(thread_builder, crate::sgx::worker_limit(), move || {
+            state.work(idx, config)
+        })?;
+        Ok(())
+    }
+
+    // Returns `true` if the worker should stop because the pool was resized
+    // down, after handing its queued tasks over to the other workers.
+    fn retire(&self, idx: usize) -> bool {
+        if idx < self.size.load(Ordering::SeqCst) {
+            return false;
+        }
+        let mut live = self.live.lock().unwrap();
+        // The pool may have been resized up again in the meantime.
+        if idx < self.size.load(Ordering::SeqCst) {
+            return false;
+        }
+        live.running[idx] = false;
+        drop(live);
+
+        let queued: Vec<_> = self.workers[idx].queue.lock().unwrap().drain().collect();
+        if !queued.is_empty() {
+            let mut injector = self.injector.lock().unwrap();
+            for task in queued {
+                injector.push(task);
+            }
+        }
+        // This worker may have been woken up for a task instead of a worker
+        // which is still sleeping.
+        if self.has_tasks() {
+            self.notify_all();
+        }
+        true
+    }
+
+    // Checks the queues for a task again before the worker goes to sleep, as
+    // set by the idle strategy of the pool.
+    fn spin(&self, idx: usize, rng: &mut Rng) -> Option<Task> {
+        let IdleStrategy { spins, yields } = self.idle_strategy;
+        let budget = spins.saturating_add(yields);
+        if budget == 0 {
+            return None;
+        }
+        self.spinners.fetch_add(1, Ordering::SeqCst);
+        let mut task = None;
+        for i in 0..budget {
+            if i >= spins {
+                thread::yield_now();
+            }
+            if self.closed.load(Ordering::SeqCst) || idx >= self.size.load(Ordering::SeqCst) {
+                break;
+            }
+            task = self.next_task(idx, rng);
+            if task.is_some() {
+                break;
+            }
+        }
+        self.spinners.fetch_sub(1, Ordering::SeqCst);
+        task
+    }
+
+    fn work(&self, idx: usize, config: WorkerConfig) {
         let _scope = enter().unwrap();
-        if let Some(after_start) = after_start {
+        CURRENT_WORKER.with(|current| current.set(Some((self as *const Self, idx))));
+        if let Some(after_start) = &config.after_start {
             after_start(idx);
         }
+        let mut park = config.park.as_ref().map(|make_park| {
+            let (park, unpark) = make_park(idx);
+            *self.workers[idx].unpark.lock().unwrap() = Some(unpark);
+            park
+        });
+        let mut rng = Rng::new(idx);
         loop {
-            let msg = self.rx.lock().unwrap().recv().unwrap();
-            match msg {
-                Message::Run(task) => task.run(),
-                Message::Close => break,
+            if self.retire(idx) {
+                break;
+            }
+            if let Some(task) = self.next_task(idx, &mut rng).or_else(|| self.spin(idx, &mut rng)) {
+                self.queued.fetch_sub(1, Ordering::Relaxed);
+                let start = Instant::now();
+                task.run(idx);
+                *self.workers[idx].busy.lock().unwrap() += start.elapsed();
+                continue;
+            }
+
+            if let Some(on_park) = &config.on_park {
+                on_park(idx);
+            }
+            let sleep = self.sleep.lock().unwrap();
+            self.sleepers.fetch_add(1, Ordering::SeqCst);
+            let closed = self.closed.load(Ordering::SeqCst);
+            let retiring = idx >= self.size.load(Ordering::SeqCst);
+            let mut timed_out = false;
+            if !closed && !retiring && !self.has_tasks() {
+                match (&mut park, self.keep_alive) {
+                    (Some(park), _) => {
+                        self.workers[idx].parked.store(true, Ordering::SeqCst);
+                        drop(sleep);
+                        park();
+                        self.workers[idx].parked.store(false, Ordering::SeqCst);
+                    }
+                    // The workers within the minimum size never stop.
+                    (None, Some(keep_alive)) if idx >= self.min_size => {
+                        let (sleep, res) =
+                            self.sleep_changed.wait_timeout(sleep, keep_alive).unwrap();
+                        drop(sleep);
+                        timed_out = res.timed_out();
+                    }
+                    _ => drop(self.sleep_changed.wait(sleep).unwrap()),
+                }
+            }
+            self.sleepers.fetch_sub(1, Ordering::SeqCst);
+            if let Some(on_unpark) = &config.on_unpark {
+                on_unpark(idx);
+            }
+            if closed {
+                break;
+            }
+            if timed_out && !self.has_tasks() {
+                self.shrink(idx);
             }
         }
-        if let Some(before_stop) = before_stop {
+        if let Some(before_stop) = &config.before_stop {
             before_stop(idx);
         }
+        CURRENT_WORKER.with(|current| current.set(None));
+
+        let mut live = self.live.lock().unwrap();
+        live.workers -= 1;
+        if self.closed.load(Ordering::SeqCst) {
+            live.running[idx] = false;
+        }
+        drop(live);
+        self.live_changed.notify_all();
+    }
+
+    // Waits until `done` returns `true` for the live tasks and workers, or
+    // until the deadline.
+    fn wait_live<'a>(
+        &self,
+        mut live: MutexGuard<'a, Live>,
+        deadline: Instant,
+        done: impl Fn(&Live) -> bool,
+    ) -> MutexGuard<'a, Live> {
+        while !done(&live) {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            live = self.live_changed.wait_timeout(live, deadline - now).unwrap().0;
+        }
+        live
     }
 }
 
@@ -171,24 +1116,63 @@ impl Clone for ThreadPool {
 impl Drop for ThreadPool {
     fn drop(&mut self) {
         if self.state.cnt.fetch_sub(1, Ordering::Relaxed) == 1 {
-            for _ in 0..self.state.size {
-                self.state.send(Message::Close);
-            }
+            self.state.close();
         }
     }
 }
 
+// A xorshift generator, picking the workers to steal tasks from.
+struct Rng(u32);
+
+impl Rng {
+    fn new(seed: usize) -> Self {
+        // The state of a xorshift generator must not be zero.
+        Self((seed as u32).wrapping_mul(0x9E37_79B9) | 1)
+    }
+
+    // Returns a number in `0..n`.
+    fn next(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as usize % n
+    }
+}
+
+#[cfg(not(feature = "phala-sgx"))]
+fn default_pool_size() -> usize {
+    cmp::max(1, num_cpus::get())
+}
+
+#[cfg(feature = "phala-sgx")]
+fn default_pool_size() -> usize {
+    cmp::max(1, crate::sgx::worker_limit())
+}
+
 impl ThreadPoolBuilder {
     /// Create a default thread pool configuration.
     ///
     /// See the other methods on this type for details on the defaults.
     pub fn new() -> Self {
         Self {
-            pool_size: cmp::max(1, num_cpus::get()),
+            pool_size: default_pool_size(),
+            min_size: None,
+            max_size: None,
+            priority_levels: 1,
             stack_size: 0,
             name_prefix: None,
             after_start: None,
             before_stop: None,
+            on_park: None,
+            on_unpark: None,
+            keep_alive: None,
+            idle_strategy: IdleStrategy::PARK,
+            park: None,
+            on_spawn: None,
+            on_complete: None,
+            instrument: None,
+            panic_handler: None,
+            task_dump: false,
         }
     }
 
@@ -197,6 +1181,20 @@ impl ThreadPoolBuilder {
     /// The size of a thread pool is the number of worker threads spawned. By
     /// default, this is equal to the number of CPU cores.
     ///
+    /// With the `phala-sgx` or `mesalock_sgx` feature, the pool runs inside
+    /// an SGX enclave, where each thread takes one of the TCSs (Thread Control
+    /// Structures) set by the enclave's configuration. The workers of all the
+    /// pools and the threads of [`spawn_blocking`](crate::spawn_blocking)
+    /// share the TCSs, minus one for the thread which entered the enclave. By
+    /// default, the pool then has as many workers as there are TCSs left,
+    /// minus one always kept for `spawn_blocking`, and
+    /// [`create`](ThreadPoolBuilder::create) lowers the size,
+    /// [minimum size](ThreadPoolBuilder::min_size) and
+    /// [maximum size](ThreadPoolBuilder::max_size) of the pool to that number.
+    /// Creating the pool fails if no TCS is left for its workers, such as in
+    /// an enclave with only two TCSs, or if the other pools already took the
+    /// TCSs its workers need, while growing it is skipped.
+    ///
     /// # Panics
     ///
     /// Panics if `pool_size == 0`.
@@ -206,9 +1204,64 @@ impl ThreadPoolBuilder {
         self
     }
 
+    /// Set the minimum size a future ThreadPool can be
+    /// [resized](ThreadPool::resize) down to.
+    ///
+    /// By default, this is the initial size set with
+    /// [`pool_size`](ThreadPoolBuilder::pool_size), which is raised to the
+    /// minimum size if it is lower.
+    ///
+    /// A pool which can be resized keeps the
+    /// [`after_start`](ThreadPoolBuilder::after_start) and
+    /// [`before_stop`](ThreadPoolBuilder::before_stop) hooks, to run them on
+    /// the workers it starts and retires.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size == 0`.
+    pub fn min_size(&mut self, size: usize) -> &mut Self {
+        assert!(size > 0);
+        self.min_size = Some(size);
+        self
+    }
+
+    /// Set the maximum size a future ThreadPool can be
+    /// [resized](ThreadPool::resize) up to.
+    ///
+    /// By default, this is the initial size set with
+    /// [`pool_size`](ThreadPoolBuilder::pool_size), which is lowered to the
+    /// maximum size if it is higher.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size == 0`.
+    pub fn max_size(&mut self, size: usize) -> &mut Self {
+        assert!(size > 0);
+        self.max_size = Some(size);
+        self
+    }
+
+    /// Set the number of priority levels of the tasks of a future ThreadPool.
+    ///
+    /// Tasks spawned with a [`Priority`] above the highest level are given
+    /// the highest level. By default, there is a single level, and all tasks
+    /// are scheduled in the order in which they are spawned or woken up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `levels == 0`.
+    pub fn priority_levels(&mut self, levels: usize) -> &mut Self {
+        assert!(levels > 0);
+        self.priority_levels = levels;
+        self
+    }
+
     /// Set stack size of threads in the pool, in bytes.
     ///
     /// By default, worker threads use Rust's standard stack size.
+    ///
+    /// With the `phala-sgx` feature, this setting is ignored: the stack size
+    /// of the threads of an enclave is set by the enclave's configuration.
     pub fn stack_size(&mut self, stack_size: usize) -> &mut Self {
         self.stack_size = stack_size;
         self
@@ -258,30 +1311,295 @@ impl ThreadPoolBuilder {
         self
     }
 
+    /// Execute closure `f` each time a worker thread runs out of tasks, before
+    /// it waits for new ones.
+    ///
+    /// This hook is intended for bookkeeping and monitoring.
+    /// The closure `f` runs on the worker thread, so it should return quickly.
+    ///
+    /// The closure provided will receive an index corresponding to the worker
+    /// thread it's running on.
+    pub fn on_park<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(usize) + Send + Sync + 'static,
+    {
+        self.on_park = Some(Arc::new(f));
+        self
+    }
+
+    /// Execute closure `f` each time a worker thread is done waiting for new
+    /// tasks, after the [`on_park`](ThreadPoolBuilder::on_park) hook.
+    ///
+    /// This hook is intended for bookkeeping and monitoring.
+    /// The closure `f` runs on the worker thread, so it should return quickly.
+    ///
+    /// The closure provided will receive an index corresponding to the worker
+    /// thread it's running on.
+    pub fn on_unpark<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(usize) + Send + Sync + 'static,
+    {
+        self.on_unpark = Some(Arc::new(f));
+        self
+    }
+
+    /// Set how long the worker threads of a future ThreadPool wait for new
+    /// tasks before stopping.
+    ///
+    /// A worker idle for this long stops, running the
+    /// [`before_stop`](ThreadPoolBuilder::before_stop) hook, as long as the
+    /// pool keeps its [minimum size](ThreadPoolBuilder::min_size). The
+    /// workers are started again, up to the size the pool was created or
+    /// [resized](ThreadPool::resize) to, once there are more tasks than idle
+    /// workers to run them.
+    ///
+    /// By default, the workers wait for tasks until the pool shuts down.
+    ///
+    /// ```
+    /// use futures::executor::ThreadPool;
+    /// use std::time::Duration;
+    ///
+    /// let pool = ThreadPool::builder()
+    ///     .pool_size(4)
+    ///     .min_size(1)
+    ///     .keep_alive(Duration::from_secs(10))
+    ///     .create()
+    ///     .unwrap();
+    /// ```
+    pub fn keep_alive(&mut self, timeout: Duration) -> &mut Self {
+        self.keep_alive = Some(timeout);
+        self
+    }
+
+    /// Set how the worker threads of a future ThreadPool wait for new tasks
+    /// once they run out of them.
+    ///
+    /// The [`on_park`](ThreadPoolBuilder::on_park) hook runs once the workers
+    /// are done spinning, before they park. By default, the workers park
+    /// right away.
+    pub fn idle_strategy(&mut self, strategy: IdleStrategy) -> &mut Self {
+        self.idle_strategy = strategy;
+        self
+    }
+
+    /// Park the worker threads of a future ThreadPool with the [`Park`]
+    /// returned by the closure `f`, instead of waiting on a condition
+    /// variable.
+    ///
+    /// The closure runs on each worker thread when it starts, and receives
+    /// an index corresponding to the worker thread it's running on. The
+    /// handle returned by [`Park::unpark`] is used to wake the worker up
+    /// once there are new tasks for it.
+    ///
+    /// Inside an SGX enclave, waiting on a condition variable and notifying
+    /// it are OCALLs. This lets the workers sleep on a notification
+    /// mechanism which doesn't leave the enclave instead, such as the
+    /// switchless calls of the SGX SDK, where the host threads serving the
+    /// calls wait for the workers to be unparked.
+    ///
+    /// A worker parked this way doesn't stop after the
+    /// [`keep_alive`](ThreadPoolBuilder::keep_alive) timeout.
+    ///
+    /// ```
+    /// use futures::executor::{Park, ThreadPool};
+    /// use std::thread::{self, Thread};
+    ///
+    /// struct ThreadPark(Thread);
+    ///
+    /// impl Park for ThreadPark {
+    ///     type Unpark = Thread;
+    ///
+    ///     fn unpark(&self) -> Thread {
+    ///         self.0.clone()
+    ///     }
+    ///
+    ///     fn park(&mut self) {
+    ///         thread::park();
+    ///     }
+    /// }
+    ///
+    /// let pool = ThreadPool::builder()
+    ///     .park_with(|_worker| ThreadPark(thread::current()))
+    ///     .create()
+    ///     .unwrap();
+    /// ```
+    pub fn park_with<F, P>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(usize) -> P + Send + Sync + 'static,
+        P: Park + 'static,
+    {
+        self.park = Some(Arc::new(move |idx| {
+            let mut park = f(idx);
+            let unpark: Arc<dyn Unpark> = Arc::new(park.unpark());
+            (Box::new(move || park.park()) as Box<dyn FnMut()>, unpark)
+        }));
+        self
+    }
+
+    /// Execute closure `f` each time a task is spawned on the pool.
+    ///
+    /// This hook is intended for bookkeeping and monitoring.
+    /// The closure `f` runs on the thread spawning the task, so it should
+    /// return quickly.
+    pub fn on_spawn<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_spawn = Some(Arc::new(f));
+        self
+    }
+
+    /// Execute closure `f` each time a task of the pool completes, or is
+    /// dropped before completing.
+    ///
+    /// This hook is intended for bookkeeping and monitoring.
+    /// The closure `f` usually runs on the worker thread which ran the task,
+    /// so it should return quickly.
+    pub fn on_complete<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_complete = Some(Arc::new(f));
+        self
+    }
+
+    /// Set the [`Instrument`] invoked as the pool polls and wakes up its
+    /// tasks.
+    ///
+    /// This is intended for finding the tasks which block the worker threads,
+    /// or are woken up too often.
+    pub fn instrument<I>(&mut self, instrument: I) -> &mut Self
+    where
+        I: Instrument + 'static,
+    {
+        self.instrument = Some(Arc::new(instrument));
+        self
+    }
+
+    /// Set a handler for the panics of the tasks of a future ThreadPool.
+    ///
+    /// When a handler is set, a task panicking while it is polled is dropped,
+    /// and the handler is called with the [`TaskId`] of the task and the
+    /// payload of the panic, after which the worker thread keeps running the
+    /// other tasks. Without a handler, the panic unwinds the worker thread,
+    /// which stops.
+    ///
+    /// ```
+    /// use futures::executor::ThreadPool;
+    ///
+    /// let pool = ThreadPool::builder()
+    ///     .panic_handler(|task, _payload| eprintln!("task {} panicked", task))
+    ///     .create()
+    ///     .unwrap();
+    /// ```
+    pub fn panic_handler<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(TaskId, Box<dyn Any + Send>) + Send + Sync + 'static,
+    {
+        self.panic_handler = Some(Arc::new(f));
+        self
+    }
+
+    /// Set whether a future ThreadPool keeps track of its tasks, to list them
+    /// with [`ThreadPool::dump_tasks`].
+    ///
+    /// This records where each task was spawned from, and updates its state
+    /// each time it is polled or woken up, which has a cost. By default, the
+    /// tasks aren't tracked.
+    pub fn task_dump(&mut self, enabled: bool) -> &mut Self {
+        self.task_dump = enabled;
+        self
+    }
+
     /// Create a [`ThreadPool`](ThreadPool) with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [minimum size](ThreadPoolBuilder::min_size) is greater
+    /// than the [maximum size](ThreadPoolBuilder::max_size).
     pub fn create(&mut self) -> Result<ThreadPool, io::Error> {
-        let (tx, rx) = mpsc::channel();
+        let mut size = self.pool_size;
+        if let Some(min_size) = self.min_size {
+            size = cmp::max(size, min_size);
+        }
+        if let Some(max_size) = self.max_size {
+            size = cmp::min(size, max_size);
+        }
+        let min_size = self.min_size.unwrap_or(size);
+        let max_size = self.max_size.unwrap_or(size);
+        assert!(
+            min_size <= max_size,
+            "the minimum size of the pool is greater than its maximum size"
+        );
+        #[cfg(feature = "phala-sgx")]
+        let (size, min_size, max_size) = {
+            let limit = crate::sgx::worker_limit();
+            if limit == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "the enclave has no TCS left for the workers of a pool",
+                ));
+            }
+            (cmp::min(size, limit), cmp::min(min_size, limit), cmp::min(max_size, limit))
+        };
+
+        let config = WorkerConfig {
+            stack_size: self.stack_size,
+            name_prefix: self.name_prefix.clone(),
+            after_start: self.after_start.clone(),
+            before_stop: self.before_stop.clone(),
+            on_park: self.on_park.clone(),
+            on_unpark: self.on_unpark.clone(),
+            park: self.park.clone(),
+        };
         let pool = ThreadPool {
             state: Arc::new(PoolState {
-                tx: Mutex::new(tx),
-                rx: Mutex::new(rx),
+                injector: Mutex::new(RunQueue::new(self.priority_levels)),
+                workers: (0..max_size)
+                    .map(|_| Worker {
+                        queue: Mutex::new(RunQueue::new(self.priority_levels)),
+                        busy: Mutex::new(Duration::from_secs(0)),
+                        unpark: Mutex::new(None),
+                        parked: AtomicBool::new(false),
+                    })
+                    .collect(),
+                closed: AtomicBool::new(false),
+                sleepers: AtomicUsize::new(0),
+                sleep: Mutex::new(()),
+                sleep_changed: Condvar::new(),
+                custom_park: self.park.is_some(),
+                spinners: AtomicUsize::new(0),
+                idle_strategy: self.idle_strategy,
                 cnt: AtomicUsize::new(1),
-                size: self.pool_size,
+                size: AtomicUsize::new(size),
+                target_size: AtomicUsize::new(size),
+                min_size,
+                keep_alive: self.keep_alive,
+                config: if min_size < max_size { Some(config.clone()) } else { None },
+                priority_levels: self.priority_levels,
+                live: Mutex::new(Live {
+                    phase: Phase::Running,
+                    tasks: 0,
+                    workers: 0,
+                    running: vec![false; max_size],
+                }),
+                live_changed: Condvar::new(),
+                queued: AtomicUsize::new(0),
+                polls: AtomicUsize::new(0),
+                on_spawn: self.on_spawn.clone(),
+                on_complete: self.on_complete.clone(),
+                instrument: self.instrument.clone(),
+                panic_handler: self.panic_handler.clone(),
+                registry: if self.task_dump { Some(Mutex::new(BTreeMap::new())) } else { None },
             }),
         };
 
-        for counter in 0..self.pool_size {
-            let state = pool.state.clone();
-            let after_start = self.after_start.clone();
-            let before_stop = self.before_stop.clone();
-            let mut thread_builder = thread::Builder::new();
-            if let Some(ref name_prefix) = self.name_prefix {
-                thread_builder = thread_builder.name(format!("{}{}", name_prefix, counter));
-            }
-            if self.stack_size > 0 {
-                thread_builder = thread_builder.stack_size(self.stack_size);
-            }
-            thread_builder.spawn(move || state.work(counter, after_start, before_stop))?;
+        for idx in 0..size {
+            let mut live = pool.state.live.lock().unwrap();
+            live.workers += 1;
+            live.running[idx] = true;
+            drop(live);
+            pool.state.spawn_worker(idx, &config)?;
         }
         Ok(pool)
     }
@@ -295,21 +1613,122 @@ impl Default for ThreadPoolBuilder {
 
 /// A task responsible for polling a future to completion.
 struct Task {
-    future: FutureObj<'static, ()>,
+    future: TaskFuture,
+    // The priority level of the task, within the levels of the pool.
+    level: usize,
     exec: ThreadPool,
     wake_handle: Arc<WakeHandle>,
 }
 
+/// The future of a task, which counts as alive until it is dropped.
+struct TaskFuture {
+    inner: FutureStorage,
+    id: TaskId,
+    state: Arc<PoolState>,
+}
+
+impl Drop for TaskFuture {
+    fn drop(&mut self) {
+        if let Some(registry) = &self.state.registry {
+            registry.lock().unwrap().remove(&self.id);
+        }
+        {
+            let mut live = self.state.live.lock().unwrap();
+            live.tasks -= 1;
+            if live.tasks == 0 {
+                self.state.live_changed.notify_all();
+            }
+        }
+        if let Some(on_complete) = &self.state.on_complete {
+            on_complete();
+        }
+    }
+}
+
+// The futures of at most this many bytes, aligned to at most 16 bytes, are
+// stored in the wake handle of their task rather than boxed, which saves an
+// allocation for each task.
+const INLINE_FUTURE_SIZE: usize = 128;
+
+#[repr(align(16))]
+struct InlineStorage {
+    _bytes: MaybeUninit<[u8; INLINE_FUTURE_SIZE]>,
+}
+
+enum FutureStorage {
+    Inline(InlineFuture),
+    Boxed(FutureObj<'static, ()>),
+}
+
+impl Future for FutureStorage {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match self.get_mut() {
+            FutureStorage::Inline(future) => Pin::new(future).poll(cx),
+            FutureStorage::Boxed(future) => Pin::new(future).poll(cx),
+        }
+    }
+}
+
+/// A future stored in the wake handle of its task, which it keeps alive.
+struct InlineFuture {
+    future: *mut (dyn Future<Output = ()> + Send),
+    _wake_handle: Arc<WakeHandle>,
+}
+
+// Safety: The future is `Send`, and only accessed through this value.
+unsafe impl Send for InlineFuture {}
+
+impl InlineFuture {
+    /// Moves the future into the storage of the wake handle.
+    ///
+    /// # Safety
+    ///
+    /// The future must fit in the storage, which must not hold a future yet.
+    unsafe fn new<Fut>(future: Fut, wake_handle: Arc<WakeHandle>) -> Self
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let ptr = wake_handle.storage.get() as *mut Fut;
+        ptr.write(future);
+        Self { future: ptr, _wake_handle: wake_handle }
+    }
+}
+
+impl Future for InlineFuture {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // Safety: The future never moves out of the wake handle, which is
+        // kept alive until the future is dropped.
+        unsafe { Pin::new_unchecked(&mut *self.future).poll(cx) }
+    }
+}
+
+impl Drop for InlineFuture {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.future) }
+    }
+}
+
 struct WakeHandle {
+    id: TaskId,
+    record: Option<Arc<TaskRecord>>,
     mutex: UnparkMutex<Task>,
     exec: ThreadPool,
+    // The future of the task, if stored inline.
+    storage: UnsafeCell<InlineStorage>,
 }
 
+// Safety: The storage is only accessed by the `InlineFuture` of the task.
+unsafe impl Sync for WakeHandle {}
+
 impl Task {
     /// Actually run the task (invoking `poll` on the future) on the current
-    /// thread.
-    fn run(self) {
-        let Self { mut future, wake_handle, mut exec } = self;
+    /// thread, which is the worker with the given index.
+    fn run(self, worker: usize) {
+        let Self { mut future, level, wake_handle, mut exec } = self;
         let waker = waker_ref(&wake_handle);
         let mut cx = Context::from_waker(&waker);
 
@@ -319,12 +1738,37 @@ impl Task {
             wake_handle.mutex.start_poll();
 
             loop {
-                let res = future.poll_unpin(&mut cx);
+                exec.state.polls.fetch_add(1, Ordering::Relaxed);
+                if let Some(record) = &wake_handle.record {
+                    record.start_poll(worker);
+                }
+                let mut poll = || match &exec.state.instrument {
+                    Some(instrument) => poll_instrumented(&**instrument, wake_handle.id, || {
+                        with_budget(|| future.inner.poll_unpin(&mut cx))
+                    }),
+                    None => with_budget(|| future.inner.poll_unpin(&mut cx)),
+                };
+                let res = match &exec.state.panic_handler {
+                    Some(panic_handler) => match panic::catch_unwind(AssertUnwindSafe(poll)) {
+                        Ok(res) => res,
+                        Err(payload) => {
+                            panic_handler(wake_handle.id, payload);
+                            // Drop the task as if it had completed.
+                            Poll::Ready(())
+                        }
+                    },
+                    None => poll(),
+                };
                 match res {
                     Poll::Pending => {}
                     Poll::Ready(()) => return wake_handle.mutex.complete(),
                 }
-                let task = Self { future, wake_handle: wake_handle.clone(), exec };
+                // Set before waiting, since the task can be woken up and
+                // scheduled again right after.
+                if let Some(record) = &wake_handle.record {
+                    record.set_state(TaskState::Idle);
+                }
+                let task = Self { future, level, wake_handle: wake_handle.clone(), exec };
                 match wake_handle.mutex.wait(task) {
                     Ok(()) => return, // we've waited
                     Err(task) => {
@@ -346,8 +1790,11 @@ impl fmt::Debug for Task {
 
 impl ArcWake for WakeHandle {
     fn wake_by_ref(arc_self: &Arc<Self>) {
+        if let Some(instrument) = &arc_self.exec.state.instrument {
+            instrument.on_wake(arc_self.id);
+        }
         match arc_self.mutex.notify() {
-            Ok(task) => arc_self.exec.state.send(Message::Run(task)),
+            Ok(task) => arc_self.exec.state.push(task),
             Err(()) => {}
         }
     }
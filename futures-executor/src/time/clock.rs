@@ -0,0 +1,190 @@
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::untrusted::time::InstantEx;
+use std::cmp;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A source of time for a [`Timer`](super::Timer).
+///
+/// A clock measures the time elapsed since an arbitrary starting point, such
+/// as its creation. The time it returns must never go backwards.
+pub trait Clock: Send + Sync + 'static {
+    /// Returns the time elapsed since the starting point of the clock.
+    fn now(&self) -> Duration;
+}
+
+/// A [`Clock`] reading the monotonic time of the operating system, from
+/// its creation.
+///
+/// With the `phala-sgx` feature, the time is read from the untrusted host
+/// with an OCALL, and can't be relied on by itself: see [`SgxClock`].
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    /// Creates a clock starting now.
+    pub fn new() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        Instant::now().saturating_duration_since(self.start)
+    }
+}
+
+/// A [`Clock`] following another clock which isn't trusted, without ever
+/// going backwards, jumping ahead or standing still.
+///
+/// Each time it's read, the clock advances by as much as the wrapped clock
+/// did since the previous reading, but by at least a minimum step and at most
+/// a maximum step. If the wrapped clock stands still or goes backwards, this
+/// clock advances by the minimum step, and keeps following it from its new
+/// time.
+///
+/// As a result, whoever controls the wrapped clock can't make the timers of
+/// a [`Timer`](super::Timer) fire before they have been read at least
+/// `duration / max_step` times, nor hold them back once they have been read
+/// `duration / min_step` times, nor make them fire out of order. The clock
+/// must therefore be read at a steady pace, such as by turning the timer
+/// regularly, with a minimum step no larger and a maximum step no smaller
+/// than the time between two readings, or the clock runs ahead or falls
+/// behind.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::time::{BoundedClock, Clock};
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::time::Duration;
+///
+/// // A clock controlled by someone else, in seconds.
+/// struct Untrusted(AtomicU64);
+///
+/// impl Clock for Untrusted {
+///     fn now(&self) -> Duration {
+///         Duration::from_secs(self.0.load(Ordering::SeqCst))
+///     }
+/// }
+///
+/// let clock = BoundedClock::new(
+///     Untrusted(AtomicU64::new(100)),
+///     Duration::from_millis(100),
+///     Duration::from_secs(1),
+/// );
+///
+/// // Jumping ahead by an hour only moves the clock by a second.
+/// clock.inner().0.store(3700, Ordering::SeqCst);
+/// assert_eq!(clock.now(), Duration::from_secs(1));
+///
+/// // Going backwards or standing still moves the clock by the minimum step.
+/// clock.inner().0.store(0, Ordering::SeqCst);
+/// assert_eq!(clock.now(), Duration::from_millis(1100));
+/// assert_eq!(clock.now(), Duration::from_millis(1200));
+/// ```
+pub struct BoundedClock<C> {
+    clock: C,
+    min_step: Duration,
+    max_step: Duration,
+    state: Mutex<BoundedState>,
+}
+
+struct BoundedState {
+    // The last time read from the wrapped clock.
+    last: Duration,
+    now: Duration,
+}
+
+impl<C: Clock> BoundedClock<C> {
+    /// Wraps a clock, advancing by at least `min_step` and at most `max_step`
+    /// each time it's read.
+    ///
+    /// The new clock starts at zero. A `min_step` of zero lets the wrapped
+    /// clock stop this one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_step` is greater than `max_step`.
+    pub fn new(clock: C, min_step: Duration, max_step: Duration) -> Self {
+        assert!(min_step <= max_step, "the minimum step is greater than the maximum step");
+        let last = clock.now();
+        Self {
+            clock,
+            min_step,
+            max_step,
+            state: Mutex::new(BoundedState { last, now: Duration::from_secs(0) }),
+        }
+    }
+}
+
+impl<C> BoundedClock<C> {
+    /// Returns a reference to the wrapped clock.
+    pub fn inner(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the least this clock advances each time it's read.
+    pub fn min_step(&self) -> Duration {
+        self.min_step
+    }
+
+    /// Returns the most this clock advances each time it's read.
+    pub fn max_step(&self) -> Duration {
+        self.max_step
+    }
+}
+
+impl<C: Clock> Clock for BoundedClock<C> {
+    fn now(&self) -> Duration {
+        let reading = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let step = reading.checked_sub(state.last).unwrap_or_default();
+        state.now += cmp::min(cmp::max(step, self.min_step), self.max_step);
+        state.last = reading;
+        state.now
+    }
+}
+
+impl<C: fmt::Debug> fmt::Debug for BoundedClock<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedClock")
+            .field("clock", &self.clock)
+            .field("max_step", &self.max_step)
+            .field("now", &self.state.lock().unwrap().now)
+            .finish()
+    }
+}
+
+/// The clock of the timers of an SGX enclave.
+///
+/// The time of the system is read from the untrusted host, which could move
+/// it as it likes to fire or hold back the timeouts of the enclave. This
+/// clock never goes backwards, and advances by at least `min_step` and at
+/// most `max_step` each time it's read.
+///
+/// This type is only available when the `phala-sgx` feature of this library
+/// is activated.
+///
+/// # Examples
+///
+/// ```ignore
+/// use futures::executor::time::{SgxClock, SystemClock, Timer};
+/// use std::time::Duration;
+///
+/// // The enclave turns the timer every 10ms.
+/// let clock =
+///     SgxClock::new(SystemClock::new(), Duration::from_millis(5), Duration::from_millis(20));
+/// let timer = Timer::new(clock);
+/// ```
+#[cfg(feature = "phala-sgx")]
+pub type SgxClock = BoundedClock<SystemClock>;
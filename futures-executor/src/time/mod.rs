@@ -0,0 +1,25 @@
+//! Timers, driven by a pluggable clock.
+//!
+//! A [`Timer`] keeps track of the deadlines of its [`Sleep`] and [`Timeout`]
+//! futures, and wakes them up as the time of its [`Clock`] passes. The clock
+//! is usually a [`SystemClock`], but can be any source of time, such as one
+//! driven by a test or read from another machine.
+//!
+//! # Inside an SGX enclave
+//!
+//! The time of an enclave comes from the untrusted host, which could fire or
+//! hold back timeouts as it likes by lying about it. With the `phala-sgx`
+//! feature, the [`SgxClock`] only lets the time read from the host go
+//! forward, by a step between two bounds each time it's read. Since the
+//! threads of an enclave can't wait for a given time, its timers are turned
+//! by calling [`Timer::turn`] regularly.
+
+mod clock;
+#[cfg(feature = "phala-sgx")]
+pub use self::clock::SgxClock;
+pub use self::clock::{BoundedClock, Clock, SystemClock};
+
+mod timer;
+pub use self::timer::{Elapsed, Sleep, Timeout, Timer};
+
+mod wheel;
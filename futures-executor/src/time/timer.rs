@@ -0,0 +1,344 @@
+use super::wheel::Wheel;
+use super::Clock;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll};
+use std::error::Error;
+use std::fmt;
+#[cfg(not(feature = "phala-sgx"))]
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+#[cfg(not(feature = "phala-sgx"))]
+use std::thread;
+use std::time::Duration;
+
+/// A set of timers, firing as the time of a [`Clock`] passes.
+///
+/// The timers are kept in a hierarchical timer wheel with a resolution of a
+/// millisecond: a timer never fires before its deadline, and fires on the
+/// first [`turn`](Timer::turn) of the timer at least a millisecond after it.
+///
+/// The timer is turned either by a thread dedicated to it, started with
+/// [`spawn_driver`](Timer::spawn_driver), or by calling
+/// [`turn`](Timer::turn) regularly, for example from an event loop which
+/// already wakes up periodically.
+///
+/// This type is a clonable handle to the timer itself. Cloning it will only
+/// create a new reference, not a new timer.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::executor::time::{SystemClock, Timer};
+/// use std::time::Duration;
+///
+/// let timer = Timer::new(SystemClock::new());
+/// timer.spawn_driver().unwrap();
+///
+/// block_on(timer.sleep(Duration::from_millis(10)));
+/// assert!(timer.now() >= Duration::from_millis(10));
+///
+/// let never = futures::future::pending::<()>();
+/// assert!(block_on(timer.timeout(Duration::from_millis(10), never)).is_err());
+/// ```
+pub struct Timer {
+    inner: Arc<Inner>,
+}
+
+/// Future for the [`sleep`](Timer::sleep) and
+/// [`sleep_until`](Timer::sleep_until) methods.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    inner: Arc<Inner>,
+    deadline: Duration,
+    // The key of the timer of this future, `None` once it has completed.
+    key: Option<usize>,
+}
+
+/// Future for the [`timeout`](Timer::timeout) method.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Timeout<F> {
+    future: F,
+    sleep: Sleep,
+}
+
+/// The error returned by [`Timeout`] when the deadline has elapsed before
+/// the future completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed(());
+
+struct Inner {
+    clock: Box<dyn Clock>,
+    state: Mutex<State>,
+    // Notified when the driver has to recompute how long to wait.
+    changed: Condvar,
+}
+
+struct State {
+    wheel: Wheel,
+    // The number of `Timer` handles.
+    handles: usize,
+    // Whether a timer was inserted since the driver last turned the wheel.
+    is_changed: bool,
+    has_driver: bool,
+}
+
+// The wheel ticks every millisecond.
+fn to_tick(time: Duration, round_up: bool) -> u64 {
+    let tick = time.as_secs() * 1000 + u64::from(time.subsec_millis());
+    if round_up && time.subsec_nanos() % 1_000_000 != 0 {
+        tick + 1
+    } else {
+        tick
+    }
+}
+
+impl Timer {
+    /// Creates a timer following the given clock.
+    ///
+    /// No thread turns the timer until [`spawn_driver`](Timer::spawn_driver)
+    /// is called.
+    pub fn new<C: Clock>(clock: C) -> Self {
+        let now = to_tick(clock.now(), false);
+        Self {
+            inner: Arc::new(Inner {
+                clock: Box::new(clock),
+                state: Mutex::new(State {
+                    wheel: Wheel::new(now),
+                    handles: 1,
+                    is_changed: false,
+                    has_driver: false,
+                }),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Starts a thread turning the timer as its timers are due.
+    ///
+    /// The thread exits once all the handles to the timer and all its
+    /// [`Sleep`] futures have been dropped. Calling this again while the
+    /// thread runs does nothing.
+    ///
+    /// This method isn't available with the `phala-sgx` feature, since the
+    /// threads of an enclave can't wait for a given time. The timer must
+    /// then be turned with [`turn`](Timer::turn).
+    #[cfg(not(feature = "phala-sgx"))]
+    pub fn spawn_driver(&self) -> io::Result<()> {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.has_driver {
+            return Ok(());
+        }
+        let inner = self.inner.clone();
+        thread::Builder::new().name("futures-timer".to_string()).spawn(move || inner.drive())?;
+        state.has_driver = true;
+        Ok(())
+    }
+
+    /// Returns the current time of the clock of the timer.
+    pub fn now(&self) -> Duration {
+        self.inner.clock.now()
+    }
+
+    /// Reads the clock, and fires the timers whose deadline has passed.
+    ///
+    /// Returns how long until the next timer is due, or `None` if there is
+    /// no timer left.
+    pub fn turn(&self) -> Option<Duration> {
+        self.inner.turn()
+    }
+
+    /// Returns a future completing once the clock has advanced by the given
+    /// duration.
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        self.sleep_until(self.now() + duration)
+    }
+
+    /// Returns a future completing once the clock has reached the given
+    /// time.
+    pub fn sleep_until(&self, deadline: Duration) -> Sleep {
+        let mut state = self.inner.state.lock().unwrap();
+        let key = state.wheel.insert(to_tick(deadline, true));
+        state.is_changed = true;
+        if state.has_driver {
+            self.inner.changed.notify_one();
+        }
+        Sleep { inner: self.inner.clone(), deadline, key: Some(key) }
+    }
+
+    /// Returns a future resolving to the output of `future`, or to an
+    /// [`Elapsed`] error if it hasn't completed once the clock has advanced
+    /// by the given duration.
+    ///
+    /// The future is dropped when the returned future is.
+    pub fn timeout<F: Future>(&self, duration: Duration, future: F) -> Timeout<F> {
+        Timeout { future, sleep: self.sleep(duration) }
+    }
+}
+
+impl Clone for Timer {
+    fn clone(&self) -> Self {
+        self.inner.state.lock().unwrap().handles += 1;
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.handles -= 1;
+        if state.handles == 0 && state.has_driver {
+            self.inner.changed.notify_one();
+        }
+    }
+}
+
+impl fmt::Debug for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.inner.state.lock().unwrap();
+        f.debug_struct("Timer")
+            .field("timers", &state.wheel.len())
+            .field("has_driver", &state.has_driver)
+            .finish()
+    }
+}
+
+impl Inner {
+    fn turn(&self) -> Option<Duration> {
+        let now = self.clock.now();
+        let mut wakers = Vec::new();
+        let mut state = self.state.lock().unwrap();
+        state.is_changed = false;
+        state.wheel.advance(to_tick(now, false), &mut wakers);
+        let next = state.wheel.next_expiration();
+        drop(state);
+
+        for waker in wakers {
+            waker.wake();
+        }
+        next.map(|tick| Duration::from_millis(tick).checked_sub(now).unwrap_or_default())
+    }
+
+    #[cfg(not(feature = "phala-sgx"))]
+    fn drive(&self) {
+        loop {
+            let next = self.turn();
+            let mut state = self.state.lock().unwrap();
+            if state.handles == 0 && state.wheel.len() == 0 {
+                state.has_driver = false;
+                return;
+            }
+            // A timer inserted after the wheel was turned may be due first.
+            if !state.is_changed {
+                match next {
+                    Some(timeout) => drop(self.changed.wait_timeout(state, timeout).unwrap()),
+                    None => drop(self.changed.wait(state).unwrap()),
+                }
+            }
+        }
+    }
+}
+
+impl Sleep {
+    /// Returns the time at which the future completes.
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let key = match self.key {
+            Some(key) => key,
+            None => return Poll::Ready(()),
+        };
+        let mut state = self.inner.state.lock().unwrap();
+        match state.wheel.poll(key, cx.waker()) {
+            Poll::Ready(()) => {
+                drop(state);
+                self.key = None;
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl FusedFuture for Sleep {
+    fn is_terminated(&self) -> bool {
+        self.key.is_none()
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            let mut state = self.inner.state.lock().unwrap();
+            state.wheel.remove(key);
+            // Let the driver exit if this was the last reason to run.
+            if state.handles == 0 && state.wheel.len() == 0 && state.has_driver {
+                self.inner.changed.notify_one();
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep").field("deadline", &self.deadline).finish()
+    }
+}
+
+impl<F> Timeout<F> {
+    /// Returns a reference to the wrapped future.
+    pub fn get_ref(&self) -> &F {
+        &self.future
+    }
+
+    /// Returns a mutable reference to the wrapped future.
+    pub fn get_mut(&mut self) -> &mut F {
+        &mut self.future
+    }
+
+    /// Consumes this combinator, returning the wrapped future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `future` is pinned along with `self`, and `sleep` is
+        // `Unpin`.
+        let this = unsafe { self.get_unchecked_mut() };
+        if let Poll::Ready(output) = unsafe { Pin::new_unchecked(&mut this.future) }.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        match Pin::new(&mut this.sleep).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(Elapsed(()))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<F: fmt::Debug> fmt::Debug for Timeout<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timeout")
+            .field("future", &self.future)
+            .field("deadline", &self.sleep.deadline)
+            .finish()
+    }
+}
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "deadline has elapsed")
+    }
+}
+
+impl Error for Elapsed {}
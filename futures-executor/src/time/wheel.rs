@@ -0,0 +1,232 @@
+use futures_core::task::{Poll, Waker};
+use std::mem;
+
+// The wheel has `LEVELS` levels of `SLOTS` slots each. A slot of level 0
+// spans one tick, and a slot of each next level spans a whole turn of the
+// level below it, so the levels together span `SLOTS.pow(LEVELS)` ticks, or
+// a bit more than two years with ticks of a millisecond. Timers further away
+// are kept in an overflow list until the wheel gets closer to them.
+const LEVELS: usize = 6;
+const SLOT_BITS: usize = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+
+/// A hierarchical timer wheel, counting time in ticks.
+///
+/// A timer is stored in the lowest level whose slots distinguish its
+/// deadline from the current tick. When the wheel reaches the slot of a
+/// timer at a higher level, the timer is moved down to a lower level, until
+/// it fires from level 0. Inserting and removing a timer take constant time.
+pub(super) struct Wheel {
+    // The tick up to which the timers have been fired.
+    elapsed: u64,
+    levels: Vec<Level>,
+    overflow: Vec<usize>,
+    entries: Vec<Entry>,
+    // The keys of the vacant entries, to be reused.
+    vacant: Vec<usize>,
+    len: usize,
+}
+
+struct Level {
+    // A bit is set for each slot holding timers.
+    occupied: u64,
+    slots: Vec<Vec<usize>>,
+}
+
+struct Entry {
+    deadline: u64,
+    state: EntryState,
+}
+
+enum EntryState {
+    Vacant,
+    Pending { location: Location, waker: Option<Waker> },
+    Fired,
+}
+
+#[derive(Clone, Copy)]
+enum Location {
+    Slot { level: usize, slot: usize },
+    Overflow,
+}
+
+impl Wheel {
+    pub(super) fn new(now: u64) -> Self {
+        Self {
+            elapsed: now,
+            levels: (0..LEVELS)
+                .map(|_| Level { occupied: 0, slots: (0..SLOTS).map(|_| Vec::new()).collect() })
+                .collect(),
+            overflow: Vec::new(),
+            entries: Vec::new(),
+            vacant: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of timers in the wheel, fired or not.
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Adds a timer firing at the given tick, returning its key. The timer
+    /// fires right away if its deadline has been reached already.
+    pub(super) fn insert(&mut self, deadline: u64) -> usize {
+        let entry = Entry { deadline, state: EntryState::Fired };
+        let key = match self.vacant.pop() {
+            Some(key) => {
+                self.entries[key] = entry;
+                key
+            }
+            None => {
+                self.entries.push(entry);
+                self.entries.len() - 1
+            }
+        };
+        self.len += 1;
+        if deadline > self.elapsed {
+            self.place(key, None);
+        }
+        key
+    }
+
+    /// Checks whether the timer identified by `key` has fired, removing it
+    /// if it has. Otherwise, `waker` is stored to be woken once it fires.
+    pub(super) fn poll(&mut self, key: usize, waker: &Waker) -> Poll<()> {
+        match &mut self.entries[key].state {
+            EntryState::Pending { waker: stored, .. } => {
+                if !stored.as_ref().map_or(false, |stored| stored.will_wake(waker)) {
+                    *stored = Some(waker.clone());
+                }
+                Poll::Pending
+            }
+            EntryState::Fired => {
+                self.release(key);
+                Poll::Ready(())
+            }
+            EntryState::Vacant => unreachable!(),
+        }
+    }
+
+    /// Removes the timer identified by `key`, whether it has fired or not.
+    pub(super) fn remove(&mut self, key: usize) {
+        if let EntryState::Pending { location, .. } = self.entries[key].state {
+            let list = self.list_mut(location);
+            list.retain(|&k| k != key);
+            if list.is_empty() {
+                if let Location::Slot { level, slot } = location {
+                    self.levels[level].occupied &= !(1 << slot);
+                }
+            }
+        }
+        self.release(key);
+    }
+
+    /// Returns the tick of the next timer to fire, or of the next time the
+    /// wheel has to move timers between its levels, if that's earlier.
+    pub(super) fn next_expiration(&self) -> Option<u64> {
+        for (level, slots) in self.levels.iter().enumerate() {
+            if slots.occupied == 0 {
+                continue;
+            }
+            let shift = level * SLOT_BITS;
+            let level_range = 1u64 << (shift + SLOT_BITS);
+            let level_start = self.elapsed & !(level_range - 1);
+            // The slots before the current one are empty, since the wheel
+            // has gone past them.
+            let slot = slots.occupied.trailing_zeros() as u64;
+            return Some(level_start + (slot << shift));
+        }
+        if self.overflow.is_empty() {
+            None
+        } else {
+            // The start of the next turn of the top level.
+            Some((self.elapsed | (Self::top_range() - 1)) + 1)
+        }
+    }
+
+    /// Advances the wheel to the given tick, pushing the wakers of the
+    /// timers which fire onto `wakers`.
+    pub(super) fn advance(&mut self, now: u64, wakers: &mut Vec<Waker>) {
+        while let Some(expiration) = self.next_expiration() {
+            if expiration > now {
+                break;
+            }
+            self.elapsed = expiration;
+            let keys = match self.find_expired() {
+                Some(Location::Slot { level, slot }) => {
+                    self.levels[level].occupied &= !(1 << slot);
+                    mem::replace(&mut self.levels[level].slots[slot], Vec::new())
+                }
+                Some(Location::Overflow) => mem::replace(&mut self.overflow, Vec::new()),
+                None => break,
+            };
+            for key in keys {
+                if self.entries[key].deadline <= self.elapsed {
+                    let state = mem::replace(&mut self.entries[key].state, EntryState::Fired);
+                    if let EntryState::Pending { waker: Some(waker), .. } = state {
+                        wakers.push(waker);
+                    }
+                } else {
+                    let waker = match &mut self.entries[key].state {
+                        EntryState::Pending { waker, .. } => waker.take(),
+                        _ => None,
+                    };
+                    self.place(key, waker);
+                }
+            }
+        }
+        if now > self.elapsed {
+            self.elapsed = now;
+        }
+    }
+
+    // Returns the location of the timers expiring at `self.elapsed`.
+    fn find_expired(&self) -> Option<Location> {
+        for (level, slots) in self.levels.iter().enumerate() {
+            if slots.occupied != 0 {
+                let slot = slots.occupied.trailing_zeros() as usize;
+                return Some(Location::Slot { level, slot });
+            }
+        }
+        if self.overflow.is_empty() {
+            None
+        } else {
+            Some(Location::Overflow)
+        }
+    }
+
+    fn place(&mut self, key: usize, waker: Option<Waker>) {
+        let deadline = self.entries[key].deadline;
+        // The highest bit in which the deadline differs from the current
+        // tick decides the level.
+        let masked = (self.elapsed ^ deadline) | (SLOTS as u64 - 1);
+        let level = (63 - masked.leading_zeros() as usize) / SLOT_BITS;
+        let location = if level < LEVELS {
+            let slot = ((deadline >> (level * SLOT_BITS)) as usize) & (SLOTS - 1);
+            self.levels[level].occupied |= 1 << slot;
+            Location::Slot { level, slot }
+        } else {
+            Location::Overflow
+        };
+        self.list_mut(location).push(key);
+        self.entries[key].state = EntryState::Pending { location, waker };
+    }
+
+    fn list_mut(&mut self, location: Location) -> &mut Vec<usize> {
+        match location {
+            Location::Slot { level, slot } => &mut self.levels[level].slots[slot],
+            Location::Overflow => &mut self.overflow,
+        }
+    }
+
+    fn release(&mut self, key: usize) {
+        self.entries[key].state = EntryState::Vacant;
+        self.vacant.push(key);
+        self.len -= 1;
+    }
+
+    fn top_range() -> u64 {
+        1 << (LEVELS * SLOT_BITS)
+    }
+}
@@ -0,0 +1,30 @@
+use futures::executor::{self, block_on};
+use futures::task::{FutureObj, Spawn, SpawnError};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct QueueSpawner {
+    tasks: Arc<Mutex<Vec<FutureObj<'static, ()>>>>,
+}
+
+impl Spawn for QueueSpawner {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.tasks.lock().unwrap().push(future);
+        Ok(())
+    }
+}
+
+#[test]
+fn set_default_spawner() {
+    let spawner = QueueSpawner::default();
+    assert!(executor::set_default_spawner(spawner.clone()).is_ok());
+    assert!(executor::set_default_spawner(QueueSpawner::default()).is_err());
+
+    let handle = executor::spawn(async { 1 }).unwrap();
+    let tasks: Vec<_> = spawner.tasks.lock().unwrap().drain(..).collect();
+    assert_eq!(tasks.len(), 1);
+    for task in tasks {
+        block_on(task);
+    }
+    assert_eq!(block_on(handle), Ok(1));
+}
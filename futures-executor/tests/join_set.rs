@@ -0,0 +1,61 @@
+use futures::channel::oneshot;
+use futures::executor::{JoinSet, LocalPool};
+use futures::future;
+
+#[test]
+fn join_in_completion_order() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let mut set = JoinSet::new();
+    let (tx1, rx1) = oneshot::channel::<i32>();
+    let (tx2, rx2) = oneshot::channel::<i32>();
+    set.spawn_local(&spawner, async { rx1.await.unwrap() }).unwrap();
+    set.spawn_local(&spawner, async { rx2.await.unwrap() }).unwrap();
+    assert_eq!(set.len(), 2);
+
+    tx2.send(2).unwrap();
+    assert_eq!(pool.run_until(set.join_next()), Some(Ok(2)));
+    tx1.send(1).unwrap();
+    assert_eq!(pool.run_until(set.join_next()), Some(Ok(1)));
+    assert_eq!(pool.run_until(set.join_next()), None);
+    assert!(set.is_empty());
+}
+
+#[test]
+fn abort_all() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let mut set = JoinSet::new();
+    set.spawn(&spawner, future::ready(1)).unwrap();
+    pool.run_until_stalled();
+    for _ in 0..2 {
+        set.spawn(&spawner, future::pending::<i32>()).unwrap();
+    }
+
+    set.abort_all();
+    assert_eq!(set.len(), 3);
+    let mut results = Vec::new();
+    while let Some(res) = pool.run_until(set.join_next()) {
+        results.push(res.map_err(|e| e.is_aborted()));
+    }
+    results.sort();
+    assert_eq!(results, [Ok(1), Err(true), Err(true)]);
+}
+
+#[test]
+fn drop_aborts_tasks() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let mut set = JoinSet::new();
+    let (tx, rx) = oneshot::channel::<()>();
+    set.spawn(&spawner, async move {
+        let _tx = tx;
+        future::pending::<()>().await
+    })
+    .unwrap();
+    pool.run_until_stalled();
+
+    drop(set);
+    pool.run_until_stalled();
+    assert_eq!(pool.run_until(rx), Err(oneshot::Canceled));
+}
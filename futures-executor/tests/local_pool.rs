@@ -1,14 +1,18 @@
-use futures::channel::oneshot;
-use futures::executor::LocalPool;
+use futures::channel::{mpsc as async_mpsc, oneshot};
+use futures::executor::{
+    block_on, block_on_deadline, block_on_read, block_on_sink, block_on_stream, block_on_timeout,
+    block_on_write, Instrument, LocalPool, LocalSpawner, Park, TaskId, Unpark,
+};
 use futures::future::{self, lazy, poll_fn, Future};
-use futures::task::{Context, LocalSpawn, Poll, Spawn, Waker};
+use futures::task::{Context, LocalSpawn, LocalSpawnExt, Poll, Spawn, Waker};
 use std::cell::{Cell, RefCell};
+use std::io::{Read, Write};
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 struct Pending(Rc<()>);
 
@@ -319,6 +323,111 @@ fn run_until_stalled_executes_all_ready() {
     }
 }
 
+#[test]
+fn run_until_stalled_interleaves_with_external_wakeups() {
+    let mut pool = LocalPool::new();
+    let spawn = pool.spawner();
+    let (tx, rx) = oneshot::channel();
+    let received = Rc::new(Cell::new(None));
+
+    let received2 = received.clone();
+    spawn
+        .spawn_local_obj(Box::pin(async move { received2.set(Some(rx.await.unwrap())) }).into())
+        .unwrap();
+
+    pool.run_until_stalled();
+    assert_eq!(received.get(), None);
+    assert_eq!(pool.alive_tasks(), 1);
+
+    tx.send(1).unwrap();
+    assert!(pool.try_run_one());
+    assert_eq!(received.get(), Some(1));
+    assert_eq!(pool.alive_tasks(), 0);
+}
+
+#[test]
+fn spawn_from_current() {
+    fn spawn_countdown(cnt: Rc<Cell<u32>>) {
+        let spawn = LocalSpawner::current().unwrap();
+        spawn
+            .spawn_local_obj(
+                Box::pin(lazy(move |_| {
+                    cnt.set(cnt.get() - 1);
+                    if cnt.get() > 0 {
+                        spawn_countdown(cnt);
+                    }
+                }))
+                .into(),
+            )
+            .unwrap();
+    }
+
+    assert!(LocalSpawner::current().is_none());
+
+    let cnt = Rc::new(Cell::new(10));
+    let mut pool = LocalPool::new();
+    let cnt2 = cnt.clone();
+    pool.run_until(lazy(move |_| spawn_countdown(cnt2)));
+    assert_eq!(cnt.get(), 10);
+
+    pool.run();
+    assert_eq!(cnt.get(), 0);
+    assert!(LocalSpawner::current().is_none());
+}
+
+#[test]
+fn metrics() {
+    let mut pool = LocalPool::new();
+    let spawn = pool.spawner();
+    assert_eq!(pool.alive_tasks(), 0);
+
+    spawn.spawn_local_obj(Box::pin(lazy(|_| ())).into()).unwrap();
+    spawn.spawn_local_obj(Box::pin(pending()).into()).unwrap();
+    assert_eq!(pool.alive_tasks(), 2);
+    assert_eq!(pool.queued_tasks(), 2);
+
+    pool.run_until_stalled();
+    assert_eq!(pool.alive_tasks(), 1);
+    assert_eq!(pool.queued_tasks(), 0);
+}
+
+#[test]
+fn instrument() {
+    struct Recorder(Arc<Mutex<Vec<(&'static str, TaskId)>>>);
+
+    impl Instrument for Recorder {
+        fn on_poll_start(&self, task: TaskId) {
+            self.0.lock().unwrap().push(("start", task));
+        }
+
+        fn on_poll_end(&self, task: TaskId, _duration: Duration) {
+            self.0.lock().unwrap().push(("end", task));
+        }
+
+        fn on_wake(&self, task: TaskId) {
+            self.0.lock().unwrap().push(("wake", task));
+        }
+    }
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut pool = LocalPool::new();
+    pool.set_instrument(Recorder(events.clone()));
+    let spawn = pool.spawner();
+    let (tx, rx) = oneshot::channel();
+    spawn.spawn_local_obj(Box::pin(async move { rx.await.unwrap() }).into()).unwrap();
+
+    pool.run_until_stalled();
+    tx.send(()).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(pool.alive_tasks(), 0);
+
+    let events = events.lock().unwrap();
+    let task = events[0].1;
+    assert!(events.iter().all(|&(_, id)| id == task));
+    let names: Vec<_> = events.iter().map(|&(name, _)| name).collect();
+    assert_eq!(names, ["start", "end", "wake", "start", "end"]);
+}
+
 #[test]
 #[should_panic]
 fn nesting_run() {
@@ -432,3 +541,133 @@ fn park_unpark_independence() {
 
     futures::executor::block_on(future)
 }
+
+#[test]
+fn block_on_timeout_completes() {
+    assert_eq!(block_on_timeout(future::ready(1), Duration::from_secs(10)).unwrap(), 1);
+
+    let (tx, rx) = oneshot::channel();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(2).unwrap();
+    });
+    assert_eq!(block_on_timeout(rx, Duration::from_secs(10)).unwrap(), Ok(2));
+}
+
+#[test]
+fn block_on_timeout_elapses() {
+    let (tx, rx) = oneshot::channel();
+    let elapsed = block_on_timeout(rx, Duration::from_millis(10)).unwrap_err();
+
+    // The unfinished future can be completed afterwards.
+    tx.send(3).unwrap();
+    assert_eq!(block_on(elapsed.into_inner()), Ok(3));
+}
+
+#[test]
+fn block_on_deadline_elapses_while_woken() {
+    let future = poll_fn(|cx| {
+        cx.waker().wake_by_ref();
+        Poll::<()>::Pending
+    });
+    assert!(block_on_deadline(future, Instant::now() + Duration::from_millis(10)).is_err());
+}
+
+#[test]
+fn join_handle() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+
+    let handle = spawner.spawn_local(async { Rc::new(1) }).unwrap();
+    assert_eq!(pool.run_until(handle).unwrap(), Rc::new(1));
+
+    let handle = spawner.spawn_local(future::pending::<()>()).unwrap();
+    pool.run_until_stalled();
+    assert!(!handle.is_finished());
+    handle.abort();
+    pool.run_until_stalled();
+    assert!(handle.is_finished());
+    assert!(pool.run_until(handle).unwrap_err().is_aborted());
+
+    let handle = spawner.spawn_local(future::pending::<()>()).unwrap();
+    drop(pool);
+    assert!(!block_on(handle).unwrap_err().is_aborted());
+}
+
+#[test]
+fn custom_park() {
+    struct Reactor {
+        events: mpsc::Receiver<()>,
+        unpark: mpsc::SyncSender<()>,
+        parks: Rc<Cell<usize>>,
+    }
+
+    struct ReactorUnpark(mpsc::SyncSender<()>);
+
+    impl Park for Reactor {
+        type Unpark = ReactorUnpark;
+
+        fn unpark(&self) -> ReactorUnpark {
+            ReactorUnpark(self.unpark.clone())
+        }
+
+        fn park(&mut self) {
+            self.parks.set(self.parks.get() + 1);
+            self.events.recv().unwrap();
+        }
+    }
+
+    impl Unpark for ReactorUnpark {
+        fn unpark(&self) {
+            let _ = self.0.try_send(());
+        }
+    }
+
+    let (unpark, events) = mpsc::sync_channel(1);
+    let parks = Rc::new(Cell::new(0));
+    let mut pool = LocalPool::new_with_park(Reactor { events, unpark, parks: parks.clone() });
+    let spawner = pool.spawner();
+
+    let (tx, rx) = oneshot::channel();
+    let handle = spawner.spawn_local(rx).unwrap();
+    pool.run_until_stalled();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(1).unwrap();
+    });
+    assert_eq!(pool.run_until(handle), Ok(Ok(1)));
+    assert!(parks.get() > 0);
+}
+
+#[test]
+fn block_on_sink_sends_items() {
+    let (tx, rx) = async_mpsc::channel(0);
+    let reader = thread::spawn(move || block_on_stream(rx).collect::<Vec<_>>());
+
+    let mut sink = block_on_sink(tx);
+    for i in 0..3 {
+        sink.send(i).unwrap();
+    }
+    sink.feed(3).unwrap();
+    sink.flush().unwrap();
+    sink.close().unwrap();
+    assert!(sink.is_closed());
+
+    assert_eq!(reader.join().unwrap(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn block_on_read_write_bridge_io() {
+    let (reader, writer) = futures::io::duplex(4);
+    let producer = thread::spawn(move || {
+        let mut writer = block_on_write(writer);
+        writer.write_all(b"hello world").unwrap();
+        writer.flush().unwrap();
+        writer.close().unwrap();
+    });
+
+    let mut output = String::new();
+    block_on_read(reader).read_to_string(&mut output).unwrap();
+    assert_eq!(output, "hello world");
+    producer.join().unwrap();
+}
@@ -0,0 +1,73 @@
+use futures::channel::oneshot;
+use futures::executor::{block_on, scope};
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+
+#[test]
+fn tasks_borrow_from_stack() {
+    let values = vec![1, 2, 3];
+    let sum = Cell::new(0);
+
+    let output = scope(|s| {
+        for value in &values {
+            let sum = &sum;
+            s.spawn(async move { sum.set(sum.get() + value) });
+        }
+        "done"
+    });
+
+    assert_eq!(output, "done");
+    assert_eq!(sum.get(), 6);
+}
+
+#[test]
+fn tasks_spawn_tasks() {
+    let count = Cell::new(0);
+
+    scope(|s| {
+        let count = &count;
+        s.spawn(async move {
+            let (tx, rx) = oneshot::channel();
+            s.spawn(async move {
+                count.set(count.get() + 1);
+                tx.send(()).unwrap();
+            });
+            rx.await.unwrap();
+            count.set(count.get() + 1);
+        });
+    });
+
+    assert_eq!(count.get(), 2);
+}
+
+#[test]
+fn panic_drops_tasks() {
+    struct SetOnDrop<'a>(&'a Cell<bool>);
+
+    impl Drop for SetOnDrop<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let dropped = Cell::new(false);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        scope(|s| {
+            let guard = SetOnDrop(&dropped);
+            s.spawn(async move {
+                let _guard = guard;
+                futures::future::pending::<()>().await;
+            });
+            s.spawn(async { panic!("task panicked") });
+        })
+    }));
+
+    assert!(res.is_err());
+    assert!(dropped.get());
+}
+
+#[test]
+#[should_panic(expected = "cannot execute `LocalPool` executor from within another executor")]
+fn nested_in_executor() {
+    block_on(async { scope(|_| {}) });
+}
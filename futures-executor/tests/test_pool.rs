@@ -0,0 +1,86 @@
+use futures::executor::TestPool;
+use futures::future;
+use futures::task::LocalSpawnExt;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+#[test]
+fn timers_fire_in_order() {
+    let mut pool = TestPool::new();
+    let spawner = pool.spawner();
+    let clock = pool.clock();
+    let fired = Rc::new(RefCell::new(Vec::new()));
+
+    for secs in &[3, 1, 2] {
+        let clock = clock.clone();
+        let fired = fired.clone();
+        let secs = *secs;
+        spawner
+            .spawn_local(async move {
+                clock.sleep(Duration::from_secs(secs)).await;
+                fired.borrow_mut().push((secs, clock.now()));
+            })
+            .unwrap();
+    }
+
+    pool.run();
+    assert_eq!(
+        *fired.borrow(),
+        [1, 2, 3].iter().map(|&secs| (secs, Duration::from_secs(secs))).collect::<Vec<_>>()
+    );
+    assert_eq!(clock.now(), Duration::from_secs(3));
+}
+
+#[test]
+fn advance() {
+    let mut pool = TestPool::new();
+    let clock = pool.clock();
+    let done = Rc::new(Cell::new(false));
+
+    let done2 = done.clone();
+    let sleep = clock.sleep(Duration::from_secs(10));
+    pool.spawner()
+        .spawn_local(async move {
+            sleep.await;
+            done2.set(true);
+        })
+        .unwrap();
+
+    pool.advance(Duration::from_secs(5));
+    assert!(!done.get());
+    assert_eq!(clock.now(), Duration::from_secs(5));
+    assert_eq!(pool.alive_tasks(), 1);
+
+    pool.advance(Duration::from_secs(5));
+    assert!(done.get());
+    assert_eq!(pool.alive_tasks(), 0);
+
+    // A deadline in the past completes right away.
+    pool.run_until(clock.sleep_until(Duration::from_secs(1)));
+    assert_eq!(clock.now(), Duration::from_secs(10));
+}
+
+#[test]
+fn seeded_order() {
+    fn run(seed: u64) -> Vec<usize> {
+        let mut pool = TestPool::with_seed(seed);
+        let order = Rc::new(RefCell::new(Vec::new()));
+        for i in 0..8 {
+            let order = order.clone();
+            pool.spawner().spawn_local(async move { order.borrow_mut().push(i) }).unwrap();
+        }
+        pool.run_until_stalled();
+        Rc::try_unwrap(order).unwrap().into_inner()
+    }
+
+    assert_eq!(run(1), run(1));
+    let orders: Vec<_> = (0..10).map(run).collect();
+    assert!(orders.iter().any(|order| *order != orders[0]));
+}
+
+#[test]
+#[should_panic(expected = "`TestPool::run_until` stalled with no timer pending")]
+fn stalled() {
+    TestPool::new().run_until(future::pending::<()>());
+}
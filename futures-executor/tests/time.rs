@@ -0,0 +1,148 @@
+use futures::executor::block_on;
+use futures::executor::time::{BoundedClock, Clock, SystemClock, Timer};
+use futures::future::{self, FutureExt};
+use futures::task::{noop_waker_ref, Context, Poll};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// A clock moved by hand, in milliseconds.
+#[derive(Clone, Default)]
+struct ManualClock(Arc<AtomicU64>);
+
+impl ManualClock {
+    fn set(&self, ms: u64) {
+        self.0.store(ms, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.0.load(Ordering::SeqCst))
+    }
+}
+
+#[test]
+fn timers_fire_in_order() {
+    let clock = ManualClock::default();
+    let timer = Timer::new(clock.clone());
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    // Deadlines spread over the levels of the wheel, and beyond them.
+    let deadlines = [5, 1, 63, 64, 65, 4095, 4096, 300_000, 3_000_000_000_000];
+    let mut sleeps: Vec<_> =
+        deadlines.iter().map(|&ms| (ms, timer.sleep_until(Duration::from_millis(ms)))).collect();
+    sleeps.sort_by_key(|&(ms, _)| ms);
+
+    for (ms, sleep) in &mut sleeps {
+        clock.set(*ms - 1);
+        timer.turn();
+        assert_eq!(sleep.poll_unpin(&mut cx), Poll::Pending, "fired before {}ms", ms);
+        clock.set(*ms);
+        timer.turn();
+        assert_eq!(sleep.poll_unpin(&mut cx), Poll::Ready(()), "didn't fire at {}ms", ms);
+    }
+    assert_eq!(timer.turn(), None);
+}
+
+#[test]
+fn turn_returns_next_deadline() {
+    let clock = ManualClock::default();
+    let timer = Timer::new(clock.clone());
+    assert_eq!(timer.turn(), None);
+
+    let sleep = timer.sleep(Duration::from_millis(10));
+    assert_eq!(timer.turn(), Some(Duration::from_millis(10)));
+    clock.set(4);
+    assert_eq!(timer.turn(), Some(Duration::from_millis(6)));
+
+    // A dropped timer doesn't fire.
+    drop(sleep);
+    assert_eq!(timer.turn(), None);
+
+    // A deadline in the past completes right away.
+    clock.set(100);
+    timer.turn();
+    assert_eq!(timer.sleep_until(Duration::from_millis(50)).now_or_never(), Some(()));
+}
+
+#[test]
+fn timeout() {
+    let clock = ManualClock::default();
+    let timer = Timer::new(clock.clone());
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    assert_eq!(
+        timer.timeout(Duration::from_millis(10), future::ready(1)).now_or_never(),
+        Some(Ok(1))
+    );
+
+    let mut timeout = timer.timeout(Duration::from_millis(10), future::pending::<()>());
+    assert_eq!(timeout.poll_unpin(&mut cx), Poll::Pending);
+    clock.set(10);
+    timer.turn();
+    assert!(matches!(timeout.poll_unpin(&mut cx), Poll::Ready(Err(_))));
+}
+
+#[test]
+fn driver_thread() {
+    let timer = Timer::new(SystemClock::new());
+    timer.spawn_driver().unwrap();
+
+    let start = timer.now();
+    block_on(timer.sleep(Duration::from_millis(20)));
+    assert!(timer.now() - start >= Duration::from_millis(20));
+
+    // Timers inserted while the driver waits for a later one still fire in
+    // time.
+    let later = timer.sleep(Duration::from_secs(60));
+    assert_eq!(
+        block_on(timer.timeout(Duration::from_secs(30), timer.sleep(Duration::from_millis(10)))),
+        Ok(())
+    );
+    drop(later);
+}
+
+#[test]
+fn bounded_clock_resists_jumps() {
+    let host = ManualClock::default();
+    let clock = BoundedClock::new(host.clone(), Duration::from_secs(0), Duration::from_millis(10));
+    let timer = Timer::new(clock);
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    let mut sleep = timer.sleep(Duration::from_secs(1));
+
+    // The host jumps ahead by an hour: the clock only moves by 10ms.
+    host.set(3_600_000);
+    timer.turn();
+    assert_eq!(timer.now(), Duration::from_millis(10));
+    assert_eq!(sleep.poll_unpin(&mut cx), Poll::Pending);
+
+    // The host goes backwards: the clock stands still.
+    host.set(0);
+    assert_eq!(timer.now(), Duration::from_millis(10));
+
+    // The time moves normally again.
+    for ms in 1..=100 {
+        host.set(ms * 10);
+        timer.turn();
+    }
+    assert_eq!(sleep.poll_unpin(&mut cx), Poll::Ready(()));
+}
+
+#[test]
+fn bounded_clock_resists_standing_still() {
+    let host = ManualClock::default();
+    let clock = BoundedClock::new(host, Duration::from_millis(10), Duration::from_millis(20));
+    let timer = Timer::new(clock);
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    // The host never moves its clock: each turn still moves it by 10ms.
+    let mut sleep = timer.sleep(Duration::from_millis(100));
+    for _ in 0..9 {
+        timer.turn();
+    }
+    assert_eq!(sleep.poll_unpin(&mut cx), Poll::Pending);
+    timer.turn();
+    assert_eq!(sleep.poll_unpin(&mut cx), Poll::Ready(()));
+}
@@ -25,8 +25,12 @@
 #[cfg(all(feature = "read-initializer", not(feature = "unstable")))]
 compile_error!("The `read-initializer` feature requires the `unstable` feature as an explicit opt-in to unstable features");
 
+#[cfg(feature = "std")]
+mod read_buf;
+
 #[cfg(feature = "std")]
 mod if_std {
+    use std::cmp;
     use std::io;
     use std::ops::DerefMut;
     use std::pin::Pin;
@@ -43,6 +47,8 @@ mod if_std {
     #[doc(no_inline)]
     pub use io::{Error, ErrorKind, IoSlice, IoSliceMut, Result, SeekFrom};
 
+    pub use crate::read_buf::ReadBuf;
+
     /// Read bytes asynchronously.
     ///
     /// This trait is analogous to the `std::io::Read` trait, but integrates
@@ -128,6 +134,45 @@ mod if_std {
 
             self.poll_read(cx, &mut [])
         }
+
+        /// Attempt to read from the `AsyncRead` into `buf`, which may be
+        /// partly uninitialized.
+        ///
+        /// On success, returns `Poll::Ready(Ok(()))` and the bytes read are
+        /// appended to the filled part of `buf`. If no bytes were read, the
+        /// reader has reached EOF, unless `buf` had no room left.
+        ///
+        /// If no data is available for reading, the method returns
+        /// `Poll::Pending` and arranges for the current task (via
+        /// `cx.waker().wake_by_ref()`) to receive a notification when the object becomes
+        /// readable or is closed.
+        ///
+        /// By default, this method zeroes the part of `buf` which isn't
+        /// initialized yet, and delegates to `poll_read`. Objects which can
+        /// read into uninitialized memory should override this method, so
+        /// that buffers reused across reads are only ever zeroed once, if at
+        /// all.
+        ///
+        /// # Implementation
+        ///
+        /// This function may not return errors of kind `WouldBlock` or
+        /// `Interrupted`.  Implementations must convert `WouldBlock` into
+        /// `Poll::Pending` and either internally retry or convert
+        /// `Interrupted` into another error kind.
+        fn poll_read_buf(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<Result<()>> {
+            match self.poll_read(cx, buf.initialize_unfilled()) {
+                Poll::Ready(Ok(n)) => {
+                    buf.advance(n);
+                    Poll::Ready(Ok(()))
+                }
+                Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                Poll::Pending => Poll::Pending,
+            }
+        }
     }
 
     /// Write bytes asynchronously.
@@ -349,6 +394,14 @@ mod if_std {
             ) -> Poll<Result<usize>> {
                 Pin::new(&mut **self).poll_read_vectored(cx, bufs)
             }
+
+            fn poll_read_buf(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut ReadBuf<'_>,
+            ) -> Poll<Result<()>> {
+                Pin::new(&mut **self).poll_read_buf(cx, buf)
+            }
         };
     }
 
@@ -385,6 +438,14 @@ mod if_std {
         ) -> Poll<Result<usize>> {
             self.get_mut().as_mut().poll_read_vectored(cx, bufs)
         }
+
+        fn poll_read_buf(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<Result<()>> {
+            self.get_mut().as_mut().poll_read_buf(cx, buf)
+        }
     }
 
     macro_rules! delegate_async_read_to_stdio {
@@ -414,6 +475,18 @@ mod if_std {
 
     impl AsyncRead for &[u8] {
         delegate_async_read_to_stdio!();
+
+        fn poll_read_buf(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<Result<()>> {
+            let n = cmp::min(self.len(), buf.remaining());
+            let (a, b) = self.split_at(n);
+            buf.put_slice(a);
+            *self = b;
+            Poll::Ready(Ok(()))
+        }
     }
 
     macro_rules! deref_async_write {
@@ -0,0 +1,201 @@
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::slice;
+
+/// A buffer to read bytes into, which may be partly uninitialized.
+///
+/// The buffer is made of three regions: the bytes filled by reads, then the
+/// unfilled bytes which are nonetheless initialized, then uninitialized
+/// bytes. Keeping track of the initialized region allows the buffer to be
+/// reused by [`poll_read_buf`](crate::AsyncRead::poll_read_buf) without
+/// zeroing it again before each read.
+///
+/// ```text
+/// [             capacity              ]
+/// [ filled |         unfilled         ]
+/// [    initialized    | uninitialized ]
+/// ```
+pub struct ReadBuf<'a> {
+    buf: &'a mut [MaybeUninit<u8>],
+    filled: usize,
+    initialized: usize,
+}
+
+impl<'a> ReadBuf<'a> {
+    /// Creates a new `ReadBuf` from a fully initialized buffer.
+    #[inline]
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        let initialized = buf.len();
+        let buf = unsafe { slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut _, initialized) };
+        Self { buf, filled: 0, initialized }
+    }
+
+    /// Creates a new `ReadBuf` from a fully uninitialized buffer.
+    ///
+    /// Use [`assume_init`](ReadBuf::assume_init) if part of the buffer is
+    /// known to be initialized already.
+    #[inline]
+    pub fn uninit(buf: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self { buf, filled: 0, initialized: 0 }
+    }
+
+    /// Returns the total capacity of the buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the filled part of the buffer.
+    #[inline]
+    pub fn filled(&self) -> &[u8] {
+        // Safety: the filled part is always initialized.
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const u8, self.filled) }
+    }
+
+    /// Returns a mutable reference to the filled part of the buffer.
+    #[inline]
+    pub fn filled_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut u8, self.filled) }
+    }
+
+    /// Returns the initialized part of the buffer, which includes the filled
+    /// part.
+    #[inline]
+    pub fn initialized(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const u8, self.initialized) }
+    }
+
+    /// Returns a mutable reference to the unfilled part of the buffer, which
+    /// may be uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller must not write uninitialized bytes to the initialized part
+    /// of the buffer.
+    #[inline]
+    pub unsafe fn unfilled_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        &mut self.buf[self.filled..]
+    }
+
+    /// Returns a mutable reference to the unfilled part of the buffer,
+    /// zeroing the part of it which isn't initialized yet.
+    ///
+    /// Only the bytes which have never been initialized are zeroed, so this
+    /// is cheap to call repeatedly.
+    #[inline]
+    pub fn initialize_unfilled(&mut self) -> &mut [u8] {
+        self.initialize_unfilled_to(self.remaining())
+    }
+
+    /// Returns a mutable reference to the first `n` bytes of the unfilled
+    /// part of the buffer, zeroing the part of them which isn't initialized
+    /// yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `n` bytes of the buffer are unfilled.
+    #[inline]
+    pub fn initialize_unfilled_to(&mut self, n: usize) -> &mut [u8] {
+        assert!(self.remaining() >= n, "n overflows remaining");
+        let end = self.filled + n;
+        if self.initialized < end {
+            unsafe {
+                ptr::write_bytes(
+                    self.buf.as_mut_ptr().add(self.initialized),
+                    0,
+                    end - self.initialized,
+                );
+            }
+            self.initialized = end;
+        }
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr().add(self.filled) as *mut u8, n) }
+    }
+
+    /// Returns the number of bytes of the buffer which are unfilled.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.filled
+    }
+
+    /// Clears the buffer, leaving its initialized part initialized.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.filled = 0;
+    }
+
+    /// Marks `n` more bytes of the buffer as filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this would fill bytes which aren't initialized.
+    #[inline]
+    pub fn advance(&mut self, n: usize) {
+        let filled = self.filled.checked_add(n).expect("filled overflow");
+        self.set_filled(filled);
+    }
+
+    /// Sets the length of the filled part of the buffer.
+    ///
+    /// This can shrink the filled part, but the initialized part never
+    /// shrinks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this would fill bytes which aren't initialized.
+    #[inline]
+    pub fn set_filled(&mut self, n: usize) {
+        assert!(n <= self.initialized, "filled must not become larger than initialized");
+        self.filled = n;
+    }
+
+    /// Asserts that the first `n` bytes of the unfilled part of the buffer
+    /// are initialized.
+    ///
+    /// This is used once the unfilled part has been written to through
+    /// [`unfilled_mut`](ReadBuf::unfilled_mut), before calling
+    /// [`advance`](ReadBuf::advance).
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that these bytes are initialized.
+    #[inline]
+    pub unsafe fn assume_init(&mut self, n: usize) {
+        let end = self.filled + n;
+        if end > self.initialized {
+            self.initialized = end;
+        }
+    }
+
+    /// Appends the given bytes to the filled part of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `buf.len()` bytes of the buffer are unfilled.
+    #[inline]
+    pub fn put_slice(&mut self, buf: &[u8]) {
+        assert!(self.remaining() >= buf.len(), "buf.len() must fit in remaining()");
+        let end = self.filled + buf.len();
+        unsafe {
+            ptr::copy_nonoverlapping(
+                buf.as_ptr(),
+                self.buf.as_mut_ptr().add(self.filled) as *mut u8,
+                buf.len(),
+            );
+        }
+        if self.initialized < end {
+            self.initialized = end;
+        }
+        self.filled = end;
+    }
+}
+
+impl fmt::Debug for ReadBuf<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadBuf")
+            .field("filled", &self.filled)
+            .field("initialized", &self.initialized)
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -19,6 +19,7 @@ use proc_macro::TokenStream;
 mod executor;
 mod join;
 mod select;
+mod stream;
 mod stream_select;
 
 /// The `join!` macro.
@@ -59,3 +60,15 @@ pub fn stream_select_internal(input: TokenStream) -> TokenStream {
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
+
+/// The `stream!` macro.
+#[proc_macro]
+pub fn stream_internal(input: TokenStream) -> TokenStream {
+    crate::stream::stream(input.into()).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// The `try_stream!` macro.
+#[proc_macro]
+pub fn try_stream_internal(input: TokenStream) -> TokenStream {
+    crate::stream::try_stream(input.into()).unwrap_or_else(syn::Error::into_compile_error).into()
+}
@@ -0,0 +1,88 @@
+//! The futures-rs `stream!` and `try_stream!` macro implementation.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::Parser;
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_quote, Block, Expr, Ident, Macro};
+
+/// Rewrites every `yield` expression of the body into a send through the
+/// yielder, without descending into nested closures, async blocks or macros
+/// whose own `yield`s (if any) don't belong to this stream.
+struct Yields {
+    yielder: Ident,
+    is_try: bool,
+}
+
+impl VisitMut for Yields {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Yield(yield_expr) => {
+                let yielder = &self.yielder;
+                let value = match yield_expr.expr.take() {
+                    Some(value) => {
+                        let mut value = *value;
+                        self.visit_expr_mut(&mut value);
+                        quote!(#value)
+                    }
+                    None => quote!(()),
+                };
+                *expr = if self.is_try {
+                    parse_quote!(#yielder.send(__futures_crate::Ok(#value)).await)
+                } else {
+                    parse_quote!(#yielder.send(#value).await)
+                };
+            }
+            Expr::Closure(_) | Expr::Async(_) => {}
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_macro_mut(&mut self, _: &mut Macro) {}
+}
+
+fn generate(input: TokenStream, is_try: bool) -> Result<TokenStream, syn::Error> {
+    let mut stmts = Block::parse_within.parse2(input)?;
+
+    let yielder = Ident::new("__yielder", Span::call_site());
+    let mut yields = Yields { yielder: yielder.clone(), is_try };
+    for stmt in &mut stmts {
+        yields.visit_stmt_mut(stmt);
+    }
+
+    let body = if is_try {
+        // Run the body in its own async block so that `?` bails out of it,
+        // then yield the error (if any) as the last item of the stream.
+        quote! {
+            let __result: __futures_crate::Result<(), _> = async {
+                #(#stmts)*
+                #[allow(unreachable_code)]
+                return __futures_crate::Ok(());
+            }.await;
+            if let __futures_crate::Err(e) = __result {
+                #yielder.send(__futures_crate::Err(e)).await;
+            }
+        }
+    } else {
+        quote! {
+            #(#stmts)*
+        }
+    };
+
+    Ok(quote! {{
+        let (#yielder, __receiver) = __futures_crate::async_await::__stream_channel();
+        __futures_crate::async_await::AsyncStream::new(__receiver, async move {
+            #body
+        })
+    }})
+}
+
+/// The `stream!` macro.
+pub(crate) fn stream(input: TokenStream) -> Result<TokenStream, syn::Error> {
+    generate(input, false)
+}
+
+/// The `try_stream!` macro.
+pub(crate) fn try_stream(input: TokenStream) -> Result<TokenStream, syn::Error> {
+    generate(input, true)
+}
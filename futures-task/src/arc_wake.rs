@@ -13,8 +13,46 @@ use alloc::sync::Arc;
 /// * [`waker_ref`](super::waker_ref()) converts `&Arc<impl ArcWake>` into [`WakerRef`] that
 ///   provides access to a [`&Waker`][`Waker`].
 ///
+/// Neither requires any unsafe code.
+///
 /// [`Waker`]: std::task::Waker
 /// [`WakerRef`]: super::WakerRef
+///
+/// # Examples
+///
+/// A minimal executor, running a future on the current thread and parking
+/// the thread until the future is woken:
+///
+/// ```
+/// use futures::task::{waker_ref, ArcWake, Context, Poll};
+/// use std::future::Future;
+/// use std::sync::Arc;
+/// use std::thread::{self, Thread};
+///
+/// struct ThreadWaker(Thread);
+///
+/// impl ArcWake for ThreadWaker {
+///     fn wake_by_ref(arc_self: &Arc<Self>) {
+///         arc_self.0.unpark();
+///     }
+/// }
+///
+/// fn block_on<F: Future>(future: F) -> F::Output {
+///     let mut future = Box::pin(future);
+///     let thread_waker = Arc::new(ThreadWaker(thread::current()));
+///     // Borrowing the `Arc` avoids touching its reference count on each poll.
+///     let waker = waker_ref(&thread_waker);
+///     let mut cx = Context::from_waker(&waker);
+///     loop {
+///         match future.as_mut().poll(&mut cx) {
+///             Poll::Ready(output) => return output,
+///             Poll::Pending => thread::park(),
+///         }
+///     }
+/// }
+///
+/// assert_eq!(block_on(async { 1 + 2 }), 3);
+/// ```
 // Note: Send + Sync required because `Arc<T>` doesn't automatically imply
 // those bounds, but `Waker` implements them.
 pub trait ArcWake: Send + Sync {
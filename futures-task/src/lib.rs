@@ -46,5 +46,8 @@ mod noop_waker;
 pub use crate::noop_waker::noop_waker;
 pub use crate::noop_waker::noop_waker_ref;
 
+mod panic_waker;
+pub use crate::panic_waker::{panic_waker, panic_waker_ref};
+
 #[doc(no_inline)]
 pub use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
@@ -1,5 +1,7 @@
+//! Utilities for creating wakers that panic when woken.
+
 use core::ptr::null;
-use futures_core::task::{RawWaker, RawWakerVTable, Waker};
+use core::task::{RawWaker, RawWakerVTable, Waker};
 
 unsafe fn clone_panic_waker(_data: *const ()) -> RawWaker {
     raw_panic_waker()
@@ -8,9 +10,14 @@ unsafe fn clone_panic_waker(_data: *const ()) -> RawWaker {
 unsafe fn noop(_data: *const ()) {}
 
 unsafe fn wake_panic(_data: *const ()) {
-    if !std::thread::panicking() {
-        panic!("should not be woken");
+    // Don't panic while panicking, which would abort.
+    #[cfg(feature = "std")]
+    {
+        if std::thread::panicking() {
+            return;
+        }
     }
+    panic!("should not be woken");
 }
 
 const PANIC_WAKER_VTABLE: RawWakerVTable =
@@ -20,35 +27,38 @@ const fn raw_panic_waker() -> RawWaker {
     RawWaker::new(null(), &PANIC_WAKER_VTABLE)
 }
 
-/// Create a new [`Waker`](futures_core::task::Waker) which will
-/// panic when `wake()` is called on it. The [`Waker`] can be converted
-/// into a [`Waker`] which will behave the same way.
+/// Create a new [`Waker`] which will
+/// panic when `wake()` is called on it.
+///
+/// This is useful to check that a future which is expected to be ready
+/// doesn't register its waker.
 ///
 /// # Examples
 ///
 /// ```should_panic
-/// use futures_test::task::panic_waker;
+/// use futures::task::panic_waker;
 ///
 /// let waker = panic_waker();
 /// waker.wake(); // Will panic
 /// ```
+#[inline]
 pub fn panic_waker() -> Waker {
     // FIXME: Since 1.46.0 we can use transmute in consts, allowing this function to be const.
     unsafe { Waker::from_raw(raw_panic_waker()) }
 }
 
-/// Get a global reference to a
-/// [`Waker`](futures_core::task::Waker) referencing a singleton
-/// instance of a [`Waker`] which panics when woken.
+/// Get a static reference to a [`Waker`] which
+/// panics when woken.
 ///
 /// # Examples
 ///
 /// ```should_panic
-/// use futures_test::task::panic_waker_ref;
+/// use futures::task::panic_waker_ref;
 ///
 /// let waker = panic_waker_ref();
 /// waker.wake_by_ref(); // Will panic
 /// ```
+#[inline]
 pub fn panic_waker_ref() -> &'static Waker {
     struct SyncRawWaker(RawWaker);
     unsafe impl Sync for SyncRawWaker {}
@@ -62,6 +72,7 @@ pub fn panic_waker_ref() -> &'static Waker {
 #[cfg(test)]
 mod tests {
     #[test]
+    #[cfg(feature = "std")]
     #[should_panic(expected = "should not be woken")]
     fn issue_2091_cross_thread_segfault() {
         let waker = std::thread::spawn(super::panic_waker_ref).join().unwrap();
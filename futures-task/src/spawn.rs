@@ -53,18 +53,31 @@ pub trait LocalSpawn {
 
 /// An error that occurred during spawning.
 pub struct SpawnError {
-    _priv: (),
+    kind: SpawnErrorKind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SpawnErrorKind {
+    Shutdown,
+    ShuttingDown,
 }
 
 impl fmt::Debug for SpawnError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("SpawnError").field(&"shutdown").finish()
+        let kind = match self.kind {
+            SpawnErrorKind::Shutdown => "shutdown",
+            SpawnErrorKind::ShuttingDown => "shutting down",
+        };
+        f.debug_tuple("SpawnError").field(&kind).finish()
     }
 }
 
 impl fmt::Display for SpawnError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Executor is shutdown")
+        match self.kind {
+            SpawnErrorKind::Shutdown => write!(f, "Executor is shutdown"),
+            SpawnErrorKind::ShuttingDown => write!(f, "Executor is shutting down"),
+        }
     }
 }
 
@@ -74,12 +87,23 @@ impl std::error::Error for SpawnError {}
 impl SpawnError {
     /// Spawning failed because the executor has been shut down.
     pub fn shutdown() -> Self {
-        Self { _priv: () }
+        Self { kind: SpawnErrorKind::Shutdown }
+    }
+
+    /// Spawning failed because the executor is shutting down, and no longer
+    /// accepts new tasks while it finishes the ones it is running.
+    pub fn shutting_down() -> Self {
+        Self { kind: SpawnErrorKind::ShuttingDown }
     }
 
     /// Check whether spawning failed to the executor being shut down.
     pub fn is_shutdown(&self) -> bool {
-        true
+        self.kind == SpawnErrorKind::Shutdown
+    }
+
+    /// Check whether spawning failed to the executor shutting down.
+    pub fn is_shutting_down(&self) -> bool {
+        self.kind == SpawnErrorKind::ShuttingDown
     }
 }
 
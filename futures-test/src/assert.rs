@@ -1,8 +1,12 @@
+use futures_core::future::Future;
 use futures_core::stream::Stream;
 
 #[doc(hidden)]
 pub fn assert_is_unpin_stream<S: Stream + Unpin>(_: &mut S) {}
 
+#[doc(hidden)]
+pub fn assert_is_unpin_future<F: Future + Unpin>(_: &mut F) {}
+
 /// Assert that the next poll to the provided stream will return
 /// [`Poll::Pending`](futures_core::task::Poll::Pending).
 ///
@@ -119,3 +123,87 @@ macro_rules! assert_stream_done {
         }
     }};
 }
+
+/// Assert that the next poll to the provided future will return
+/// [`Poll::Pending`](futures_core::task::Poll::Pending).
+///
+/// # Examples
+///
+/// ```
+/// use futures_test::future::FutureTestExt;
+/// use futures_test::{assert_pending, assert_ready_eq};
+/// use futures::pin_mut;
+///
+/// let future = (async { 5 }).pending_once();
+/// pin_mut!(future);
+///
+/// assert_pending!(future);
+/// assert_ready_eq!(future, 5);
+/// ```
+#[macro_export]
+macro_rules! assert_pending {
+    ($future:expr) => {{
+        let mut future = &mut $future;
+        $crate::__private::assert::assert_is_unpin_future(future);
+        let future = $crate::__private::Pin::new(future);
+        let mut cx = $crate::task::noop_context();
+        let poll = $crate::__private::future::Future::poll(future, &mut cx);
+        if poll.is_ready() {
+            panic!("assertion failed: future is not pending");
+        }
+    }};
+}
+
+/// Assert that the next poll to the provided future will return
+/// [`Poll::Ready`](futures_core::task::Poll::Ready), evaluating to its
+/// output.
+///
+/// # Examples
+///
+/// ```
+/// use futures_test::assert_ready;
+/// use futures::future;
+///
+/// let mut future = future::ready(vec![5]);
+/// let output = assert_ready!(future);
+/// assert_eq!(output, [5]);
+/// ```
+#[macro_export]
+macro_rules! assert_ready {
+    ($future:expr) => {{
+        let mut future = &mut $future;
+        $crate::__private::assert::assert_is_unpin_future(future);
+        let future = $crate::__private::Pin::new(future);
+        let mut cx = $crate::task::noop_context();
+        match $crate::__private::future::Future::poll(future, &mut cx) {
+            $crate::__private::task::Poll::Ready(output) => output,
+            $crate::__private::task::Poll::Pending => {
+                panic!("assertion failed: expected future to be ready but it was pending");
+            }
+        }
+    }};
+}
+
+/// Assert that the next poll to the provided future will return
+/// [`Poll::Ready`](futures_core::task::Poll::Ready) with the provided output.
+///
+/// # Examples
+///
+/// ```
+/// use futures_test::future::FutureTestExt;
+/// use futures_test::{assert_pending, assert_ready_eq};
+/// use futures::pin_mut;
+///
+/// let future = (async { 5 }).pending_once();
+/// pin_mut!(future);
+///
+/// assert_pending!(future);
+/// assert_ready_eq!(future, 5);
+/// ```
+#[macro_export]
+macro_rules! assert_ready_eq {
+    ($future:expr, $output:expr) => {{
+        let output = $crate::assert_ready!($future);
+        assert_eq!(output, $output);
+    }};
+}
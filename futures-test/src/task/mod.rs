@@ -45,14 +45,11 @@ pub use self::context::{noop_context, panic_context};
 mod noop_spawner;
 pub use self::noop_spawner::{noop_spawner_mut, NoopSpawner};
 
-pub use futures_util::task::{noop_waker, noop_waker_ref};
+pub use futures_util::task::{noop_waker, noop_waker_ref, panic_waker, panic_waker_ref};
 
 mod panic_spawner;
 pub use self::panic_spawner::{panic_spawner_mut, PanicSpawner};
 
-mod panic_waker;
-pub use self::panic_waker::{panic_waker, panic_waker_ref};
-
 mod record_spawner;
 pub use self::record_spawner::RecordSpawner;
 
@@ -0,0 +1,234 @@
+//! The `stream` and `try_stream` macros.
+
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::sync::{Arc, Mutex};
+
+#[allow(unreachable_pub)]
+#[doc(hidden)]
+pub use futures_macro::stream_internal;
+
+#[allow(unreachable_pub)]
+#[doc(hidden)]
+pub use futures_macro::try_stream_internal;
+
+/// Creates a stream from imperative code, using `yield` to produce items.
+///
+/// The body of the macro is run as an `async` block: it may `.await` other
+/// futures, and every `yield value` expression hands `value` out as the next
+/// item of the stream. The body is suspended at each `yield` until the
+/// consumer asks for another item, and the stream ends once the body
+/// completes. Like an `async move` block, the body takes ownership of the
+/// variables it uses.
+///
+/// `yield` is only recognized in the body itself, not within nested closures
+/// or `async` blocks.
+///
+/// This macro is gated behind the `async-await` and `std` features of this
+/// library, which are activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::StreamExt;
+/// // Brings both the `stream` module and the `stream!` macro into scope.
+/// use futures::stream;
+///
+/// let mut words = stream::iter(vec!["hello", "world"]);
+/// let letters = stream! {
+///     while let Some(word) = words.next().await {
+///         for c in word.chars() {
+///             yield c;
+///         }
+///         yield ' ';
+///     }
+/// };
+///
+/// let s: String = letters.collect().await;
+/// assert_eq!(s, "hello world ");
+/// # });
+/// ```
+#[macro_export]
+macro_rules! stream {
+    ($($tokens:tt)*) => {{
+        use $crate::__private as __futures_crate;
+        $crate::stream_internal! {
+            $( $tokens )*
+        }
+    }}
+}
+
+/// Creates a stream of `Result`s from imperative code, using `yield` to
+/// produce items and `?` to bail out with an error.
+///
+/// This is the fallible variant of [`stream!`]: every `yield value` produces
+/// an `Ok(value)` item, and if the body returns early with an error through
+/// the `?` operator, that error is produced as the last item of the stream.
+///
+/// As with `?` in functions, the error is converted with `From::from`, so the
+/// error type of the stream usually needs to be determined by the context it
+/// is used in, for instance the return type of the enclosing function.
+///
+/// This macro is gated behind the `async-await` and `std` features of this
+/// library, which are activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{Stream, TryStreamExt};
+/// use futures::try_stream;
+/// use std::num::ParseIntError;
+///
+/// fn parse<'a>(input: &'a str) -> impl Stream<Item = Result<u32, ParseIntError>> + 'a {
+///     try_stream! {
+///         for part in input.split(',') {
+///             yield part.parse::<u32>()?;
+///         }
+///     }
+/// }
+///
+/// assert_eq!(parse("1,2,3").try_collect::<Vec<_>>().await, Ok(vec![1, 2, 3]));
+/// assert!(parse("1,x,3").try_collect::<Vec<_>>().await.is_err());
+/// # });
+/// ```
+#[macro_export]
+macro_rules! try_stream {
+    ($($tokens:tt)*) => {{
+        use $crate::__private as __futures_crate;
+        $crate::try_stream_internal! {
+            $( $tokens )*
+        }
+    }}
+}
+
+#[doc(hidden)]
+pub fn __stream_channel<T>() -> (Yielder<T>, Receiver<T>) {
+    let slot = Arc::new(Mutex::new(None));
+    (Yielder { slot: slot.clone() }, Receiver { slot })
+}
+
+/// The sending half used by the body of a [`stream!`] to hand out items.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Yielder<T> {
+    slot: Arc<Mutex<Option<T>>>,
+}
+
+impl<T> Yielder<T> {
+    #[doc(hidden)]
+    pub fn send(&self, value: T) -> YieldFuture<'_, T> {
+        YieldFuture { yielder: self, value: Some(value) }
+    }
+}
+
+/// The receiving half polled by an [`AsyncStream`].
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Receiver<T> {
+    slot: Arc<Mutex<Option<T>>>,
+}
+
+/// Future which stores an item for the stream and suspends the body once.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct YieldFuture<'a, T> {
+    yielder: &'a Yielder<T>,
+    value: Option<T>,
+}
+
+impl<T> Unpin for YieldFuture<'_, T> {}
+
+impl<T> Future for YieldFuture<'_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.value.take() {
+            Some(value) => {
+                let mut slot = this.yielder.slot.lock().unwrap();
+                if slot.is_some() {
+                    // Another item is still waiting to be handed out, which
+                    // can only happen if several yields are polled
+                    // concurrently. Retry once the stream has taken it.
+                    this.value = Some(value);
+                } else {
+                    *slot = Some(value);
+                }
+                // No need to register a waker: the stream returns the stored
+                // item right away, and polls the body again on the next call
+                // to `poll_next`.
+                Poll::Pending
+            }
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+pin_project! {
+    /// Stream returned by the [`stream!`] and [`try_stream!`] macros.
+    #[doc(hidden)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct AsyncStream<T, Fut> {
+        receiver: Receiver<T>,
+        done: bool,
+        #[pin]
+        future: Fut,
+    }
+}
+
+impl<T, Fut> fmt::Debug for AsyncStream<T, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncStream").field("done", &self.done).finish()
+    }
+}
+
+impl<T, Fut> AsyncStream<T, Fut>
+where
+    Fut: Future<Output = ()>,
+{
+    #[doc(hidden)]
+    pub fn new(receiver: Receiver<T>, future: Fut) -> Self {
+        Self { receiver, done: false, future }
+    }
+}
+
+impl<T, Fut> FusedStream for AsyncStream<T, Fut>
+where
+    Fut: Future<Output = ()>,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl<T, Fut> Stream for AsyncStream<T, Fut>
+where
+    Fut: Future<Output = ()>,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        let res = this.future.poll(cx);
+        if let Some(item) = this.receiver.slot.lock().unwrap().take() {
+            return Poll::Ready(Some(item));
+        }
+
+        if res.is_ready() {
+            *this.done = true;
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -3,7 +3,9 @@ use crate::{
     future::{FutureExt, TryFutureExt, UnitError},
     task::SpawnExt,
 };
-use futures_01::future::{ExecuteError as ExecuteError01, Executor as Executor01};
+use futures_01::future::{
+    ExecuteError as ExecuteError01, ExecuteErrorKind as ExecuteErrorKind01, Executor as Executor01,
+};
 use futures_01::Future as Future01;
 use futures_task::{FutureObj, Spawn as Spawn03, SpawnError as SpawnError03};
 
@@ -77,6 +79,11 @@ where
     Fut: Future01<Item = (), Error = ()> + Send + 'static,
 {
     fn execute(&self, future: Fut) -> Result<(), ExecuteError01<Fut>> {
+        // Check first, so that the future can be handed back if the executor
+        // has been shut down.
+        if (&self.inner).status().is_err() {
+            return Err(ExecuteError01::new(ExecuteErrorKind01::Shutdown, future));
+        }
         (&self.inner)
             .spawn(future.compat().map(|_| ()))
             .expect("unable to spawn future from Compat executor");
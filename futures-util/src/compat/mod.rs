@@ -1,5 +1,29 @@
 //! Interop between `futures` 0.1 and 0.3.
 //!
+//! Each 0.1 trait has a counterpart in 0.3, and the adapters of this module
+//! convert between them in both directions:
+//!
+//! | futures 0.1 | futures 0.3 | 0.1 to 0.3 | 0.3 to 0.1 |
+//! |-------------|-------------|------------|------------|
+//! | `Future<Item = T, Error = E>` | `Future<Output = Result<T, E>>` | [`Future01CompatExt::compat`] | [`TryFutureExt::compat`](crate::future::TryFutureExt::compat) |
+//! | `Stream<Item = T, Error = E>` | `Stream<Item = Result<T, E>>` | [`Stream01CompatExt::compat`] | [`TryStreamExt::compat`](crate::stream::TryStreamExt::compat) |
+//! | `Sink<SinkItem = T, SinkError = E>` | `Sink<T, Error = E>` | `Sink01CompatExt::sink_compat` | `SinkExt::compat` |
+//! | `Executor` | `Spawn` | [`Executor01CompatExt::compat`] | [`SpawnExt::compat`](crate::task::SpawnExt::compat) |
+//! | `tokio_io::AsyncRead` / `AsyncWrite` | `AsyncRead` / `AsyncWrite` | `AsyncRead01CompatExt::compat` / `AsyncWrite01CompatExt::compat` | `AsyncReadExt::compat` / `AsyncWriteExt::compat_write` |
+//!
+//! 0.3 futures and streams which don't return a `Result` can be converted
+//! after [`FutureExt::unit_error`](crate::future::FutureExt::unit_error) or
+//! [`FutureExt::never_error`](crate::future::FutureExt::never_error), and
+//! those which aren't `Unpin` after being boxed:
+//!
+//! ```
+//! use futures::future::{FutureExt, TryFutureExt};
+//! use futures_util::compat::Future01CompatExt;
+//!
+//! let future01 = async { 1 }.unit_error().boxed().compat();
+//! assert_eq!(futures::executor::block_on(future01.compat()), Ok(1));
+//! ```
+//!
 //! This module is only available when the `compat` feature of this
 //! library is activated.
 
@@ -0,0 +1,103 @@
+//! Conversions between futures and the types which turn into one.
+//!
+//! The [`Future`] trait of this crate is the one of the standard library, so
+//! [`from_std`] and [`into_std`] don't need to change the future they are
+//! given. They spell out the conversion for code which is written against
+//! both traits, such as code moving off an older definition of futures.
+//!
+//! [`IntoFuture`] is the trait of the types which turn into a future. Every
+//! future implements it, so a function accepting an `impl IntoFuture` accepts
+//! any future as well as the types of other libraries which implement it.
+//! It lives in this module rather than in [`future`](super) because the
+//! future returned by [`TryFutureExt::into_future`](super::TryFutureExt::into_future)
+//! is named [`IntoFuture`](super::IntoFuture) there.
+
+use core::future::Future;
+
+/// Conversion into a [`Future`].
+///
+/// By implementing this trait, a type defines how it turns into a future, so
+/// that it can be passed to the functions which accept an `impl IntoFuture`.
+/// It is implemented for every future, which turns into itself.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future::{self, convert::IntoFuture};
+///
+/// struct Answer;
+///
+/// impl IntoFuture for Answer {
+///     type Output = u32;
+///     type IntoFuture = future::Ready<u32>;
+///
+///     fn into_future(self) -> Self::IntoFuture {
+///         future::ready(42)
+///     }
+/// }
+///
+/// async fn twice<F: IntoFuture<Output = u32>>(value: F) -> u32 {
+///     value.into_future().await * 2
+/// }
+///
+/// assert_eq!(twice(Answer).await, 84);
+/// assert_eq!(twice(async { 1 }).await, 2);
+/// # });
+/// ```
+pub trait IntoFuture {
+    /// The output of the future.
+    type Output;
+
+    /// The future this value turns into.
+    type IntoFuture: Future<Output = Self::Output>;
+
+    /// Turns this value into a future.
+    fn into_future(self) -> Self::IntoFuture;
+}
+
+impl<F: Future> IntoFuture for F {
+    type Output = F::Output;
+    type IntoFuture = F;
+
+    fn into_future(self) -> F {
+        self
+    }
+}
+
+/// Turns a future of the standard library into a future of this crate.
+///
+/// Both traits are the same, so the future is returned as is.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future::{self, FutureExt};
+///
+/// let future = future::from_std(std::future::ready(1)).map(|x| x + 1);
+/// assert_eq!(future.await, 2);
+/// # });
+/// ```
+pub fn from_std<F: Future>(future: F) -> F {
+    future
+}
+
+/// Turns a future of this crate into a future of the standard library.
+///
+/// Both traits are the same, so the future is returned as is.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future;
+///
+/// let future: std::pin::Pin<Box<dyn std::future::Future<Output = i32>>> =
+///     Box::pin(future::into_std(future::ready(1)));
+/// assert_eq!(future.await, 1);
+/// # });
+/// ```
+pub fn into_std<F: Future>(future: F) -> F {
+    future
+}
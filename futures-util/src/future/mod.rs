@@ -8,6 +8,35 @@
 //! - Top-level future combinators like [`lazy`](lazy()) which creates a future
 //!   from a closure that defines its return value, and [`ready`](ready()),
 //!   which constructs a future with an immediate defined value.
+//!
+//! [`Future`] is the trait of the standard library, so futures from any
+//! source, such as `async` blocks and functions or the types of other
+//! libraries, can be used with the combinators of this module without any
+//! conversion. Closures passed to combinators such as
+//! [`then`](FutureExt::then) or [`and_then`](TryFutureExt::and_then) can
+//! return any future, and a closure which computes its result right away can
+//! return [`ready`](ready()):
+//!
+//! ```
+//! # futures::executor::block_on(async {
+//! use futures::future::{self, TryFutureExt};
+//!
+//! async fn fetch(id: u32) -> Result<u32, String> {
+//!     Ok(id * 2)
+//! }
+//!
+//! assert_eq!(fetch(1).and_then(fetch).await, Ok(4));
+//!
+//! let checked = fetch(3).and_then(|n| {
+//!     future::ready(if n < 5 { Ok(n) } else { Err(format!("{} is too large", n)) })
+//! });
+//! assert_eq!(checked.await, Err("6 is too large".to_string()));
+//! # });
+//! ```
+//!
+//! The [`from_std`] and [`into_std`] functions spell out the conversion
+//! between both traits where needed, and the [`convert::IntoFuture`] trait is
+//! implemented by the types which turn into a future.
 
 #[doc(no_inline)]
 pub use core::future::Future;
@@ -50,6 +79,9 @@ pub use self::try_future::FlattenSink;
 
 // Primitive futures
 
+pub mod convert;
+pub use self::convert::{from_std, into_std};
+
 mod lazy;
 pub use self::lazy::{lazy, Lazy};
 
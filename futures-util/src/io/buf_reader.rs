@@ -4,9 +4,10 @@ use futures_core::ready;
 use futures_core::task::{Context, Poll};
 #[cfg(feature = "read-initializer")]
 use futures_io::Initializer;
-use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, IoSliceMut, SeekFrom};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, IoSliceMut, ReadBuf, SeekFrom};
 use pin_project_lite::pin_project;
 use std::io::{self, Read};
+use std::mem::MaybeUninit;
 use std::pin::Pin;
 use std::{cmp, fmt};
 
@@ -29,13 +30,28 @@ pin_project! {
     ///
     /// [`AsyncRead`]: futures_io::AsyncRead
     ///
-    // TODO: Examples
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncBufReadExt, BufReader, Cursor};
+    ///
+    /// let mut reader = BufReader::with_capacity(16, Cursor::new(&b"a line\nanother"[..]));
+    /// let mut line = String::new();
+    /// reader.read_line(&mut line).await?;
+    ///
+    /// assert_eq!(line, "a line\n");
+    /// assert_eq!(reader.buffer(), b"another");
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+    /// ```
     pub struct BufReader<R> {
         #[pin]
         inner: R,
-        buffer: Box<[u8]>,
+        buffer: Box<[MaybeUninit<u8>]>,
         pos: usize,
         cap: usize,
+        // The length of the start of the buffer which has been initialized.
+        initialized: usize,
     }
 }
 
@@ -48,12 +64,10 @@ impl<R: AsyncRead> BufReader<R> {
 
     /// Creates a new `BufReader` with the specified buffer capacity.
     pub fn with_capacity(capacity: usize, inner: R) -> Self {
-        unsafe {
-            let mut buffer = Vec::with_capacity(capacity);
-            buffer.set_len(capacity);
-            super::initialize(&inner, &mut buffer);
-            Self { inner, buffer: buffer.into_boxed_slice(), pos: 0, cap: 0 }
-        }
+        let mut buffer = Vec::with_capacity(capacity);
+        // Safety: the buffer is only read once it has been filled.
+        unsafe { buffer.set_len(capacity) };
+        Self { inner, buffer: buffer.into_boxed_slice(), pos: 0, cap: 0, initialized: 0 }
     }
 
     delegate_access_inner!(inner, R, ());
@@ -62,7 +76,12 @@ impl<R: AsyncRead> BufReader<R> {
     ///
     /// Unlike `fill_buf`, this will not attempt to fill the buffer if it is empty.
     pub fn buffer(&self) -> &[u8] {
-        &self.buffer[self.pos..self.cap]
+        unsafe { filled(&self.buffer[self.pos..self.cap]) }
+    }
+
+    /// Returns the number of bytes the internal buffer can hold at once.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
     }
 
     /// Invalidates all data in the internal buffer.
@@ -145,6 +164,23 @@ impl<R: AsyncRead> AsyncRead for BufReader<R> {
         Poll::Ready(Ok(nread))
     }
 
+    fn poll_read_buf(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.pos == self.cap && buf.remaining() >= self.buffer.len() {
+            let res = ready!(self.as_mut().project().inner.poll_read_buf(cx, buf));
+            self.discard_buffer();
+            return Poll::Ready(res);
+        }
+        let rem = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let nread = cmp::min(rem.len(), buf.remaining());
+        buf.put_slice(&rem[..nread]);
+        self.consume(nread);
+        Poll::Ready(Ok(()))
+    }
+
     // we can't skip unconditionally because of the large buffer case in read.
     #[cfg(feature = "read-initializer")]
     unsafe fn initializer(&self) -> Initializer {
@@ -162,10 +198,18 @@ impl<R: AsyncRead> AsyncBufRead for BufReader<R> {
         // to tell the compiler that the pos..cap slice is always valid.
         if *this.pos >= *this.cap {
             debug_assert!(*this.pos == *this.cap);
-            *this.cap = ready!(this.inner.poll_read(cx, this.buffer))?;
+            let mut buf = ReadBuf::uninit(this.buffer);
+            // Safety: the buffer is only written to with initialized bytes.
+            unsafe { buf.assume_init(*this.initialized) };
+            let ptr = buf.filled().as_ptr();
+            ready!(this.inner.poll_read_buf(cx, &mut buf))?;
+            // The reader must not have swapped the buffer for another one.
+            assert_eq!(buf.filled().as_ptr(), ptr);
+            *this.cap = buf.filled().len();
+            *this.initialized = buf.initialized().len();
             *this.pos = 0;
         }
-        Poll::Ready(Ok(&this.buffer[*this.pos..*this.cap]))
+        Poll::Ready(Ok(unsafe { filled(&this.buffer[*this.pos..*this.cap]) }))
     }
 
     fn consume(self: Pin<&mut Self>, amt: usize) {
@@ -173,6 +217,13 @@ impl<R: AsyncRead> AsyncBufRead for BufReader<R> {
     }
 }
 
+// Returns the filled part of the buffer, from which bytes are consumed.
+//
+// Safety: the bytes must be initialized.
+unsafe fn filled(buf: &[MaybeUninit<u8>]) -> &[u8] {
+    &*(buf as *const [MaybeUninit<u8>] as *const [u8])
+}
+
 impl<R: AsyncWrite> AsyncWrite for BufReader<R> {
     delegate_async_write!(inner);
 }
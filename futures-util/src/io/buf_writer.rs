@@ -24,12 +24,29 @@ pin_project! {
     /// When the `BufWriter` is dropped, the contents of its buffer will be
     /// discarded. Creating multiple instances of a `BufWriter` on the same
     /// stream can cause data loss. If you need to write out the contents of its
-    /// buffer, you must manually call flush before the writer is dropped.
+    /// buffer, you must manually call [`flush`] or [`close`], which flushes the
+    /// buffer before closing the underlying writer, before the writer is
+    /// dropped.
     ///
     /// [`AsyncWrite`]: futures_io::AsyncWrite
     /// [`flush`]: super::AsyncWriteExt::flush
+    /// [`close`]: super::AsyncWriteExt::close
     ///
-    // TODO: Examples
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncWriteExt, BufWriter};
+    ///
+    /// let mut writer = BufWriter::with_capacity(16, Vec::new());
+    /// writer.write_all(b"hello").await?;
+    /// assert_eq!(writer.buffer(), b"hello");
+    /// assert!(writer.get_ref().is_empty());
+    ///
+    /// writer.close().await?;
+    /// assert_eq!(writer.get_ref(), b"hello");
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+    /// ```
     pub struct BufWriter<W> {
         #[pin]
         inner: W,
@@ -85,8 +102,9 @@ impl<W: AsyncWrite> BufWriter<W> {
         &self.buf
     }
 
-    /// Capacity of `buf`. how many chars can be held in buffer
-    pub(super) fn capacity(&self) -> usize {
+    /// Returns the number of bytes the internal buffer can hold without
+    /// flushing it.
+    pub fn capacity(&self) -> usize {
         self.buf.capacity()
     }
 
@@ -0,0 +1,207 @@
+use super::Decoder;
+#[cfg(feature = "sink")]
+use super::Encoder;
+use crate::io::DEFAULT_BUF_SIZE;
+#[cfg(feature = "sink")]
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use futures_io::AsyncRead;
+#[cfg(feature = "sink")]
+use futures_io::AsyncWrite;
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+#[cfg(feature = "sink")]
+use std::io;
+use std::pin::Pin;
+
+pin_project! {
+    /// A [`Stream`] and [`Sink`] of frames, decoded from and encoded to an
+    /// I/O object with a codec.
+    ///
+    /// The stream is implemented when the object is an [`AsyncRead`] and the
+    /// codec a [`Decoder`], and the sink when the object is an
+    /// [`AsyncWrite`] and the codec an [`Encoder`], so a `Framed` can be used
+    /// with objects which are only readable or writable.
+    ///
+    /// Encoded frames are buffered, and written out when the buffer is full
+    /// or the sink is flushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::codec::{Framed, LinesCodec};
+    /// use futures::io::Cursor;
+    /// use futures::sink::SinkExt;
+    /// use futures::stream::TryStreamExt;
+    ///
+    /// let mut framed = Framed::new(Cursor::new(Vec::new()), LinesCodec::new());
+    /// framed.send("hello").await?;
+    /// framed.send("world").await?;
+    /// assert_eq!(framed.get_ref().get_ref(), b"hello\nworld\n");
+    ///
+    /// let reader = Cursor::new(&b"hello\nworld\n"[..]);
+    /// let lines: Vec<String> = Framed::new(reader, LinesCodec::new()).try_collect().await?;
+    /// assert_eq!(lines, ["hello", "world"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+    /// ```
+    ///
+    /// [`Sink`]: futures_sink::Sink
+    /// [`AsyncWrite`]: futures_io::AsyncWrite
+    /// [`Encoder`]: super::Encoder
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Framed<T, U> {
+        #[pin]
+        inner: T,
+        codec: U,
+        read_buf: Vec<u8>,
+        write_buf: Vec<u8>,
+        // Whether the buffer may contain a frame which wasn't decoded yet.
+        is_readable: bool,
+        eof: bool,
+        // Whether the stream of frames has ended.
+        done: bool,
+    }
+}
+
+impl<T, U> Framed<T, U> {
+    /// Creates a new `Framed`, decoding and encoding the frames of the given
+    /// I/O object with the given codec.
+    pub fn new(inner: T, codec: U) -> Self {
+        Self {
+            inner,
+            codec,
+            read_buf: Vec::new(),
+            write_buf: Vec::new(),
+            is_readable: false,
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Returns a reference to the codec.
+    pub fn codec(&self) -> &U {
+        &self.codec
+    }
+
+    /// Returns a mutable reference to the codec.
+    pub fn codec_mut(&mut self) -> &mut U {
+        &mut self.codec
+    }
+
+    /// Returns the bytes read from the I/O object which weren't decoded yet.
+    pub fn read_buffer(&self) -> &[u8] {
+        &self.read_buf
+    }
+
+    delegate_access_inner!(inner, T, ());
+}
+
+impl<T: AsyncRead, U: Decoder> Stream for Framed<T, U> {
+    type Item = Result<U::Item, U::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            if *this.done {
+                return Poll::Ready(None);
+            }
+
+            if *this.is_readable {
+                let res = if *this.eof {
+                    this.codec.decode_eof(this.read_buf)
+                } else {
+                    this.codec.decode(this.read_buf)
+                };
+                match res {
+                    Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame))),
+                    Ok(None) if *this.eof => *this.done = true,
+                    Ok(None) => *this.is_readable = false,
+                    Err(e) => {
+                        *this.done = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+                continue;
+            }
+
+            let len = this.read_buf.len();
+            this.read_buf.resize(len + DEFAULT_BUF_SIZE, 0);
+            let res = this.inner.as_mut().poll_read(cx, &mut this.read_buf[len..]);
+            let n = match res {
+                Poll::Ready(Ok(n)) => n,
+                Poll::Ready(Err(e)) => {
+                    this.read_buf.truncate(len);
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+                Poll::Pending => {
+                    this.read_buf.truncate(len);
+                    return Poll::Pending;
+                }
+            };
+            this.read_buf.truncate(len + n);
+            if n == 0 {
+                *this.eof = true;
+            }
+            *this.is_readable = true;
+        }
+    }
+}
+
+impl<T: AsyncRead, U: Decoder> FusedStream for Framed<T, U> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(feature = "sink")]
+impl<T: AsyncWrite, U> Framed<T, U> {
+    // Writes out the encoded frames, without flushing the I/O object.
+    fn poll_write_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+        while !this.write_buf.is_empty() {
+            let n = ready!(this.inner.as_mut().poll_write(cx, this.write_buf))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write frame to transport",
+                )));
+            }
+            this.write_buf.drain(..n);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "sink")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sink")))]
+impl<T: AsyncWrite, U: Encoder<I>, I> Sink<I> for Framed<T, U> {
+    type Error = U::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.write_buf.len() >= DEFAULT_BUF_SIZE {
+            ready!(self.poll_write_buf(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        let this = self.project();
+        this.codec.encode(item, this.write_buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_write_buf(cx))?;
+        ready!(self.project().inner.poll_flush(cx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_write_buf(cx))?;
+        ready!(self.project().inner.poll_close(cx))?;
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,202 @@
+use super::{Decoder, Encoder};
+use std::io;
+use std::mem;
+
+/// A codec for frames prefixed with their length.
+///
+/// Each frame is made of a header, holding the length of the payload as an
+/// unsigned integer, followed by the payload. By default, the header is 4
+/// bytes long and big-endian, and payloads can't be longer than 8 MiB; this
+/// can be changed with a [`LengthDelimitedCodecBuilder`].
+///
+/// Frames are decoded to `Vec<u8>`, and anything implementing `AsRef<[u8]>`
+/// can be encoded. A frame longer than the maximum length results in an error
+/// of the [`InvalidData`](io::ErrorKind::InvalidData) kind, both when
+/// decoding and encoding it.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::io::codec::{Framed, LengthDelimitedCodec};
+/// use futures::io::Cursor;
+/// use futures::sink::SinkExt;
+/// use futures::stream::TryStreamExt;
+///
+/// let codec = LengthDelimitedCodec::builder().length_field_length(2).create();
+/// let mut framed = Framed::new(Cursor::new(Vec::new()), codec);
+/// framed.send(b"hi").await?;
+/// assert_eq!(framed.get_ref().get_ref(), b"\x00\x02hi");
+///
+/// let reader = Cursor::new(&b"\x00\x02hi\x00\x03foo"[..]);
+/// let frames: Vec<Vec<u8>> = Framed::new(reader, codec).try_collect().await?;
+/// assert_eq!(frames, [&b"hi"[..], b"foo"]);
+/// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LengthDelimitedCodec {
+    length_field_length: usize,
+    big_endian: bool,
+    max_frame_length: usize,
+    // The length of the payload being decoded, once its header is read.
+    frame_length: Option<usize>,
+}
+
+impl LengthDelimitedCodec {
+    /// Creates a new codec with the default configuration.
+    pub fn new() -> Self {
+        Self::builder().create()
+    }
+
+    /// Creates a builder to configure a codec.
+    pub fn builder() -> LengthDelimitedCodecBuilder {
+        LengthDelimitedCodecBuilder::new()
+    }
+
+    /// Returns the maximum length of the payload of a frame.
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Sets the maximum length of the payload of a frame.
+    pub fn set_max_frame_length(&mut self, max_frame_length: usize) {
+        self.max_frame_length = max_frame_length;
+    }
+}
+
+impl Default for LengthDelimitedCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for LengthDelimitedCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        let frame_length = match self.frame_length {
+            Some(frame_length) => frame_length,
+            None => {
+                let n = self.length_field_length;
+                if src.len() < n {
+                    return Ok(None);
+                }
+                let header = &src[..n];
+                let length = if self.big_endian {
+                    header.iter().fold(0, |acc, &b| acc << 8 | u64::from(b))
+                } else {
+                    header.iter().rev().fold(0, |acc, &b| acc << 8 | u64::from(b))
+                };
+                if length > self.max_frame_length as u64 {
+                    return Err(frame_too_long());
+                }
+                src.drain(..n);
+                self.frame_length = Some(length as usize);
+                length as usize
+            }
+        };
+
+        if src.len() < frame_length {
+            src.reserve(frame_length - src.len());
+            return Ok(None);
+        }
+        self.frame_length = None;
+        let rest = src.split_off(frame_length);
+        Ok(Some(mem::replace(src, rest)))
+    }
+}
+
+impl<T: AsRef<[u8]>> Encoder<T> for LengthDelimitedCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut Vec<u8>) -> io::Result<()> {
+        let payload = item.as_ref();
+        let n = self.length_field_length;
+        if payload.len() > self.max_frame_length
+            || (n < 8 && payload.len() as u64 >= 1 << (8 * n as u64))
+        {
+            return Err(frame_too_long());
+        }
+
+        dst.reserve(n + payload.len());
+        if self.big_endian {
+            dst.extend_from_slice(&(payload.len() as u64).to_be_bytes()[8 - n..]);
+        } else {
+            dst.extend_from_slice(&(payload.len() as u64).to_le_bytes()[..n]);
+        }
+        dst.extend_from_slice(payload);
+        Ok(())
+    }
+}
+
+fn frame_too_long() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "frame exceeds the maximum length")
+}
+
+/// A builder to configure a [`LengthDelimitedCodec`].
+#[derive(Clone, Copy, Debug)]
+pub struct LengthDelimitedCodecBuilder {
+    length_field_length: usize,
+    big_endian: bool,
+    max_frame_length: usize,
+}
+
+impl LengthDelimitedCodecBuilder {
+    /// Creates a builder with the default configuration: 4 bytes long
+    /// big-endian headers, and payloads of up to 8 MiB.
+    pub fn new() -> Self {
+        Self { length_field_length: 4, big_endian: true, max_frame_length: 8 * 1024 * 1024 }
+    }
+
+    /// Sets the length of the header of the frames, in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length isn't between 1 and 8.
+    pub fn length_field_length(&mut self, length: usize) -> &mut Self {
+        assert!(
+            (1..=8).contains(&length),
+            "length field length must be between 1 and 8, got {}",
+            length
+        );
+        self.length_field_length = length;
+        self
+    }
+
+    /// Reads and writes the header of the frames in big-endian order.
+    ///
+    /// This is the default.
+    pub fn big_endian(&mut self) -> &mut Self {
+        self.big_endian = true;
+        self
+    }
+
+    /// Reads and writes the header of the frames in little-endian order.
+    pub fn little_endian(&mut self) -> &mut Self {
+        self.big_endian = false;
+        self
+    }
+
+    /// Sets the maximum length of the payload of a frame.
+    pub fn max_frame_length(&mut self, max_frame_length: usize) -> &mut Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// Creates a codec with this configuration.
+    pub fn create(&self) -> LengthDelimitedCodec {
+        LengthDelimitedCodec {
+            length_field_length: self.length_field_length,
+            big_endian: self.big_endian,
+            max_frame_length: self.max_frame_length,
+            frame_length: None,
+        }
+    }
+}
+
+impl Default for LengthDelimitedCodecBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
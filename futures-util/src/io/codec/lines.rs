@@ -0,0 +1,116 @@
+use super::{Decoder, Encoder};
+use std::io;
+use std::mem;
+
+/// A codec for lines of text.
+///
+/// Lines are decoded to `String`s without their line ending, which is either
+/// `\n` or `\r\n`, and anything implementing `AsRef<str>` can be encoded,
+/// followed by `\n`. A last line without line ending is decoded once the I/O
+/// object reaches EOF.
+///
+/// Decoding a line which isn't valid UTF-8 results in an error of the
+/// [`InvalidData`](io::ErrorKind::InvalidData) kind, as does a line longer
+/// than the maximum length given to
+/// [`new_with_max_length`](LinesCodec::new_with_max_length).
+#[derive(Clone, Debug)]
+pub struct LinesCodec {
+    // The length of the start of the buffer already searched for a newline.
+    next_index: usize,
+    max_length: usize,
+}
+
+impl LinesCodec {
+    /// Creates a new codec, decoding lines of any length.
+    pub fn new() -> Self {
+        Self { next_index: 0, max_length: usize::max_value() }
+    }
+
+    /// Creates a new codec, decoding lines of up to `max_length` bytes,
+    /// without their line ending.
+    ///
+    /// This prevents a peer sending a line without end from making the buffer
+    /// of a [`Framed`](super::Framed) grow without bounds.
+    pub fn new_with_max_length(max_length: usize) -> Self {
+        Self { next_index: 0, max_length }
+    }
+
+    /// Returns the maximum length of the decoded lines.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+impl Default for LinesCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for LinesCodec {
+    type Item = String;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut Vec<u8>) -> io::Result<Option<String>> {
+        match memchr::memchr(b'\n', &src[self.next_index..]) {
+            Some(i) => {
+                let end = self.next_index + i;
+                self.next_index = 0;
+                let rest = src.split_off(end + 1);
+                let mut line = mem::replace(src, rest);
+                line.pop();
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                if line.len() > self.max_length {
+                    return Err(line_too_long());
+                }
+                to_string(line).map(Some)
+            }
+            None => {
+                // Don't count a `\r` which may be followed by a `\n`.
+                if src.len() > self.max_length.saturating_add(1) {
+                    return Err(line_too_long());
+                }
+                self.next_index = src.len();
+                Ok(None)
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut Vec<u8>) -> io::Result<Option<String>> {
+        match self.decode(src)? {
+            Some(line) => Ok(Some(line)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                self.next_index = 0;
+                let line = mem::replace(src, Vec::new());
+                if line.len() > self.max_length {
+                    return Err(line_too_long());
+                }
+                to_string(line).map(Some)
+            }
+        }
+    }
+}
+
+impl<T: AsRef<str>> Encoder<T> for LinesCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut Vec<u8>) -> io::Result<()> {
+        let line = item.as_ref();
+        dst.reserve(line.len() + 1);
+        dst.extend_from_slice(line.as_bytes());
+        dst.push(b'\n');
+        Ok(())
+    }
+}
+
+fn to_string(line: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(line)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "line did not contain valid UTF-8"))
+}
+
+fn line_too_long() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "line exceeds the maximum length")
+}
@@ -0,0 +1,81 @@
+//! Framing of byte streams.
+//!
+//! This module contains the [`Decoder`] and [`Encoder`] traits, which turn
+//! bytes into frames of a protocol and back, and the [`Framed`] adapter,
+//! which uses them to turn an [`AsyncRead`](crate::io::AsyncRead) and
+//! [`AsyncWrite`](crate::io::AsyncWrite) object into a [`Stream`] and a
+//! [`Sink`] of frames.
+//!
+//! Two codecs are provided: [`LengthDelimitedCodec`], for frames prefixed
+//! with their length, and [`LinesCodec`], for lines of text.
+//!
+//! [`Stream`]: futures_core::stream::Stream
+//! [`Sink`]: futures_sink::Sink
+
+use std::io;
+
+mod framed;
+pub use self::framed::Framed;
+
+mod length_delimited;
+pub use self::length_delimited::{LengthDelimitedCodec, LengthDelimitedCodecBuilder};
+
+mod lines;
+pub use self::lines::LinesCodec;
+
+/// Decodes frames from a buffer of bytes.
+///
+/// This is used by [`Framed`] to turn the bytes read from an I/O object into
+/// a stream of frames.
+pub trait Decoder {
+    /// The type of the decoded frames.
+    type Item;
+
+    /// The type of the decoding errors.
+    ///
+    /// I/O errors of the object a [`Framed`] reads from are converted to this
+    /// type.
+    type Error: From<io::Error>;
+
+    /// Attempts to decode a frame from the start of the given buffer.
+    ///
+    /// If the buffer contains a whole frame, its bytes must be removed from
+    /// the buffer and the frame returned. Otherwise, `Ok(None)` must be
+    /// returned, and this is called again once more bytes are read.
+    ///
+    /// An error ends the stream of frames of a [`Framed`].
+    fn decode(&mut self, src: &mut Vec<u8>) -> Result<Option<Self::Item>, Self::Error>;
+
+    /// Attempts to decode a frame from the start of the given buffer, once
+    /// the I/O object has reached EOF.
+    ///
+    /// This is called until it returns `Ok(None)`, which ends the stream of
+    /// frames. By default, this calls [`decode`](Decoder::decode), and
+    /// returns an error if it doesn't decode a frame while bytes remain in
+    /// the buffer.
+    fn decode_eof(&mut self, src: &mut Vec<u8>) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                Err(io::Error::new(io::ErrorKind::UnexpectedEof, "bytes remaining on stream")
+                    .into())
+            }
+        }
+    }
+}
+
+/// Encodes frames into a buffer of bytes.
+///
+/// This is used by [`Framed`] to turn a sink of frames into the bytes written
+/// to an I/O object.
+pub trait Encoder<Item> {
+    /// The type of the encoding errors.
+    ///
+    /// I/O errors of the object a [`Framed`] writes to are converted to this
+    /// type.
+    type Error: From<io::Error>;
+
+    /// Encodes a frame, appending its bytes to the given buffer.
+    fn encode(&mut self, item: Item, dst: &mut Vec<u8>) -> Result<(), Self::Error>;
+}
@@ -13,7 +13,11 @@ use std::pin::Pin;
 /// EOF and all bytes have been written to and flushed from the `writer`
 /// provided.
 ///
-/// On success the number of bytes is returned.
+/// On success the number of bytes is returned. Reads and writes failing with
+/// [`ErrorKind::Interrupted`](std::io::ErrorKind::Interrupted) are retried,
+/// and the future fails with
+/// [`ErrorKind::WriteZero`](std::io::ErrorKind::WriteZero) if the `writer`
+/// accepts no bytes.
 ///
 /// # Examples
 ///
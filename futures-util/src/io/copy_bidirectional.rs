@@ -0,0 +1,180 @@
+use super::DEFAULT_BUF_SIZE;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+
+/// Creates a future which copies data between two objects in both
+/// directions, until both have reached EOF.
+///
+/// The bytes read from `a` are written to `b`, and the bytes read from `b`
+/// are written to `a`, concurrently. Once one of them reaches EOF, the other
+/// one is closed, to propagate the EOF, and the copy carries on in the other
+/// direction. Data is flushed whenever there is nothing left to read for the
+/// moment, so it doesn't linger in the buffers of the objects.
+///
+/// On success the numbers of bytes copied from `a` to `b` and from `b` to `a`
+/// are returned. Reads and writes failing with
+/// [`ErrorKind::Interrupted`](std::io::ErrorKind::Interrupted) are retried,
+/// and the future fails as soon as any other error occurs in either
+/// direction.
+///
+/// This is useful to proxy a connection to another one.
+///
+/// # Examples
+///
+/// ```
+/// use futures::io::{self, AsyncRead, AsyncWrite};
+///
+/// async fn proxy<C, S>(client: &mut C, server: &mut S) -> io::Result<()>
+/// where
+///     C: AsyncRead + AsyncWrite + Unpin,
+///     S: AsyncRead + AsyncWrite + Unpin,
+/// {
+///     let (sent, received) = io::copy_bidirectional(client, server).await?;
+///     println!("sent {} bytes, received {} bytes", sent, received);
+///     Ok(())
+/// }
+/// ```
+pub fn copy_bidirectional<'a, A, B>(a: &'a mut A, b: &'a mut B) -> CopyBidirectional<'a, A, B>
+where
+    A: AsyncRead + AsyncWrite + Unpin + ?Sized,
+    B: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    CopyBidirectional { a, b, a_to_b: Transfer::new(), b_to_a: Transfer::new() }
+}
+
+/// Future for the [`copy_bidirectional()`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CopyBidirectional<'a, A: ?Sized, B: ?Sized> {
+    a: &'a mut A,
+    b: &'a mut B,
+    a_to_b: Transfer,
+    b_to_a: Transfer,
+}
+
+struct Transfer {
+    buf: Box<[u8]>,
+    // The bytes of `buf` read and not written yet.
+    pos: usize,
+    cap: usize,
+    amt: u64,
+    need_flush: bool,
+    state: TransferState,
+}
+
+#[derive(Clone, Copy)]
+enum TransferState {
+    Running,
+    Closing,
+    Done,
+}
+
+impl Transfer {
+    fn new() -> Self {
+        Self {
+            buf: vec![0; DEFAULT_BUF_SIZE].into_boxed_slice(),
+            pos: 0,
+            cap: 0,
+            amt: 0,
+            need_flush: false,
+            state: TransferState::Running,
+        }
+    }
+
+    fn poll_transfer<R, W>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut reader: Pin<&mut R>,
+        mut writer: Pin<&mut W>,
+    ) -> Poll<io::Result<u64>>
+    where
+        R: AsyncRead + ?Sized,
+        W: AsyncWrite + ?Sized,
+    {
+        loop {
+            match self.state {
+                TransferState::Running => {
+                    if self.pos == self.cap {
+                        let n = match reader.as_mut().poll_read(cx, &mut self.buf) {
+                            Poll::Ready(Ok(n)) => n,
+                            Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {
+                                continue
+                            }
+                            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                            Poll::Pending => {
+                                if self.need_flush {
+                                    ready!(writer.as_mut().poll_flush(cx))?;
+                                    self.need_flush = false;
+                                }
+                                return Poll::Pending;
+                            }
+                        };
+                        if n == 0 {
+                            self.state = TransferState::Closing;
+                            continue;
+                        }
+                        self.pos = 0;
+                        self.cap = n;
+                    }
+
+                    while self.pos < self.cap {
+                        let i = match ready!(writer
+                            .as_mut()
+                            .poll_write(cx, &self.buf[self.pos..self.cap]))
+                        {
+                            Ok(i) => i,
+                            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                            Err(e) => return Poll::Ready(Err(e)),
+                        };
+                        if i == 0 {
+                            return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+                        }
+                        self.pos += i;
+                        self.amt += i as u64;
+                        self.need_flush = true;
+                    }
+                }
+                TransferState::Closing => {
+                    ready!(writer.as_mut().poll_close(cx))?;
+                    self.state = TransferState::Done;
+                }
+                TransferState::Done => return Poll::Ready(Ok(self.amt)),
+            }
+        }
+    }
+}
+
+impl<A, B> Future for CopyBidirectional<'_, A, B>
+where
+    A: AsyncRead + AsyncWrite + Unpin + ?Sized,
+    B: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    type Output = io::Result<(u64, u64)>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let a_to_b =
+            this.a_to_b.poll_transfer(cx, Pin::new(&mut *this.a), Pin::new(&mut *this.b))?;
+        let b_to_a =
+            this.b_to_a.poll_transfer(cx, Pin::new(&mut *this.b), Pin::new(&mut *this.a))?;
+        match (a_to_b, b_to_a) {
+            (Poll::Ready(a_to_b), Poll::Ready(b_to_a)) => Poll::Ready(Ok((a_to_b, b_to_a))),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl<A: fmt::Debug + ?Sized, B: fmt::Debug + ?Sized> fmt::Debug for CopyBidirectional<'_, A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyBidirectional")
+            .field("a", &self.a)
+            .field("b", &self.b)
+            .field("a_to_b", &self.a_to_b.amt)
+            .field("b_to_a", &self.b_to_a.amt)
+            .finish()
+    }
+}
@@ -13,7 +13,11 @@ use std::pin::Pin;
 /// EOF and all bytes have been written to and flushed from the `writer`
 /// provided.
 ///
-/// On success the number of bytes is returned.
+/// On success the number of bytes is returned. Reads and writes failing with
+/// [`ErrorKind::Interrupted`](std::io::ErrorKind::Interrupted) are retried,
+/// and the future fails with
+/// [`ErrorKind::WriteZero`](std::io::ErrorKind::WriteZero) if the `writer`
+/// accepts no bytes.
 ///
 /// # Examples
 ///
@@ -61,13 +65,22 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut this = self.project();
         loop {
-            let buffer = ready!(this.reader.as_mut().poll_fill_buf(cx))?;
+            // Interrupted reads and writes are retried, like `std::io::copy`.
+            let buffer = match ready!(this.reader.as_mut().poll_fill_buf(cx)) {
+                Ok(buffer) => buffer,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            };
             if buffer.is_empty() {
                 ready!(Pin::new(&mut this.writer).poll_flush(cx))?;
                 return Poll::Ready(Ok(*this.amt));
             }
 
-            let i = ready!(Pin::new(&mut this.writer).poll_write(cx, buffer))?;
+            let i = match ready!(Pin::new(&mut this.writer).poll_write(cx, buffer)) {
+                Ok(i) => i,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            };
             if i == 0 {
                 return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
             }
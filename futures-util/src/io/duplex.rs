@@ -0,0 +1,186 @@
+use futures_core::task::{Context, Poll, Waker};
+use futures_io::{AsyncRead, AsyncWrite, ReadBuf};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::sync::SgxMutex as Mutex;
+use std::collections::VecDeque;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(not(feature = "phala-sgx"))]
+use std::sync::Mutex;
+
+/// One end of an in-memory pipe, created by the [`duplex`] function.
+///
+/// The bytes written to one end can be read from the other one. Dropping one
+/// end closes both directions of the pipe: the other end reads EOF once it
+/// has read the bytes written before, and its writes fail with an error of
+/// the [`BrokenPipe`](io::ErrorKind::BrokenPipe) kind.
+#[derive(Debug)]
+pub struct DuplexStream {
+    read: Arc<Mutex<Pipe>>,
+    write: Arc<Mutex<Pipe>>,
+}
+
+// One direction of the pipe.
+#[derive(Debug)]
+struct Pipe {
+    buf: VecDeque<u8>,
+    max_buf_size: usize,
+    is_closed: bool,
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+}
+
+/// Creates a pair of connected in-memory I/O objects, both implementing
+/// [`AsyncRead`] and [`AsyncWrite`].
+///
+/// The bytes written to one end can be read from the other one. Each
+/// direction buffers up to `max_buf_size` bytes, after which writes wait for
+/// the other end to read.
+///
+/// This allows protocol code to be tested end-to-end without sockets, or
+/// components of a program to talk to each other over a pipe.
+///
+/// # Panics
+///
+/// Panics if `max_buf_size` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::io::{self, AsyncReadExt, AsyncWriteExt};
+///
+/// let (mut client, mut server) = io::duplex(64);
+///
+/// client.write_all(b"ping").await?;
+/// let mut buf = [0; 4];
+/// server.read_exact(&mut buf).await?;
+/// assert_eq!(&buf, b"ping");
+///
+/// server.write_all(b"pong").await?;
+/// client.read_exact(&mut buf).await?;
+/// assert_eq!(&buf, b"pong");
+/// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+/// ```
+pub fn duplex(max_buf_size: usize) -> (DuplexStream, DuplexStream) {
+    assert!(max_buf_size > 0, "max_buf_size must be greater than 0");
+    let one = Arc::new(Mutex::new(Pipe::new(max_buf_size)));
+    let two = Arc::new(Mutex::new(Pipe::new(max_buf_size)));
+    (DuplexStream { read: one.clone(), write: two.clone() }, DuplexStream { read: two, write: one })
+}
+
+impl Pipe {
+    fn new(max_buf_size: usize) -> Self {
+        Self {
+            buf: VecDeque::new(),
+            max_buf_size,
+            is_closed: false,
+            read_waker: None,
+            write_waker: None,
+        }
+    }
+
+    fn close(&mut self) {
+        self.is_closed = true;
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+        if let Some(waker) = self.write_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll_read_buf(&mut self, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<()> {
+        if self.buf.is_empty() && !self.is_closed && buf.remaining() > 0 {
+            self.read_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let n = self.buf.len().min(buf.remaining());
+        let (front, back) = self.buf.as_slices();
+        if n <= front.len() {
+            buf.put_slice(&front[..n]);
+        } else {
+            buf.put_slice(front);
+            buf.put_slice(&back[..n - front.len()]);
+        }
+        self.buf.drain(..n);
+        if n > 0 {
+            if let Some(waker) = self.write_waker.take() {
+                waker.wake();
+            }
+        }
+        Poll::Ready(())
+    }
+
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        if self.is_closed {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        let n = (self.max_buf_size - self.buf.len()).min(buf.len());
+        if n == 0 && !buf.is_empty() {
+            self.write_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        self.buf.extend(&buf[..n]);
+        if n > 0 {
+            if let Some(waker) = self.read_waker.take() {
+                waker.wake();
+            }
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncRead for DuplexStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut buf = ReadBuf::new(buf);
+        self.poll_read_buf(cx, &mut buf).map_ok(|()| buf.filled().len())
+    }
+
+    fn poll_read_buf(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.read.lock().unwrap().poll_read_buf(cx, buf).map(Ok)
+    }
+}
+
+impl AsyncWrite for DuplexStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.write.lock().unwrap().poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.write.lock().unwrap().close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for DuplexStream {
+    fn drop(&mut self) {
+        // Locking only fails if another thread panicked with the lock held,
+        // in which case there's nothing left to wake.
+        if let Ok(mut pipe) = self.read.lock() {
+            pipe.close();
+        }
+        if let Ok(mut pipe) = self.write.lock() {
+            pipe.close();
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use futures_io::AsyncRead;
+use pin_project_lite::pin_project;
+use std::io;
+use std::pin::Pin;
+
+pin_project! {
+    /// Stream for the [`into_stream`](super::AsyncReadExt::into_stream) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct IntoStream<R> {
+        #[pin]
+        reader: R,
+        buf: Vec<u8>,
+        done: bool,
+    }
+}
+
+impl<R: AsyncRead> IntoStream<R> {
+    pub(super) fn new(reader: R, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be non-zero");
+        Self { reader, buf: vec![0; chunk_size], done: false }
+    }
+
+    delegate_access_inner!(reader, R, ());
+}
+
+impl<R: AsyncRead> Stream for IntoStream<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.reader.poll_read(cx, this.buf)) {
+            Ok(0) => {
+                *this.done = true;
+                Poll::Ready(None)
+            }
+            Ok(n) => Poll::Ready(Some(Ok(this.buf[..n].to_vec()))),
+            Err(e) => Poll::Ready(Some(Err(e))),
+        }
+    }
+}
+
+impl<R: AsyncRead> FusedStream for IntoStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
@@ -0,0 +1,267 @@
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+use pin_project_lite::pin_project;
+use std::cmp;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::time::Duration;
+
+pin_project! {
+    /// I/O object for the [`limited`](super::limited) function.
+    #[must_use = "I/O objects do nothing unless polled"]
+    pub struct Limited<T, F, Fut> {
+        #[pin]
+        inner: T,
+        bytes_per_sec: usize,
+        burst: usize,
+        new_timer: F,
+        #[pin]
+        read: Bucket<Fut>,
+        #[pin]
+        write: Bucket<Fut>,
+    }
+}
+
+pin_project! {
+    // A token bucket, holding the number of bytes which can be transferred
+    // right away.
+    struct Bucket<Fut> {
+        tokens: usize,
+        // The tokens added back to the bucket once the timer fires.
+        credit: usize,
+        #[pin]
+        timer: Option<Fut>,
+    }
+}
+
+/// Wraps an I/O object so that the bytes read from and written to it are
+/// limited to a rate of `bytes_per_sec`.
+///
+/// Reads and writes are throttled separately, each one with a token bucket:
+/// up to `burst` bytes can be transferred at once, after which reads or
+/// writes are only ready again as the budget refills, at `bytes_per_sec`.
+/// Reads and writes may transfer fewer bytes than asked for, to stay within
+/// the budget. Flushing and closing aren't throttled.
+///
+/// `new_timer` is called with a duration to create a timer future which
+/// completes once it has elapsed, and is used to refill the budgets. This
+/// library doesn't provide timers, so they are left to the caller to supply.
+///
+/// This allows several tasks to share a single connection fairly.
+///
+/// # Panics
+///
+/// Panics if `bytes_per_sec` or `burst` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future;
+/// use futures::io::{self, AsyncReadExt, Cursor};
+///
+/// // A timer which has already elapsed, standing in for a real delay.
+/// let reader = Cursor::new(vec![1; 64]);
+/// let mut reader = io::limited(reader, 16, 16, |_| future::ready(()));
+///
+/// let mut buf = [0; 64];
+/// assert_eq!(reader.read(&mut buf).await?, 16);
+///
+/// let mut rest = Vec::new();
+/// reader.read_to_end(&mut rest).await?;
+/// assert_eq!(rest.len(), 48);
+/// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+/// ```
+pub fn limited<T, F, Fut>(
+    io: T,
+    bytes_per_sec: usize,
+    burst: usize,
+    new_timer: F,
+) -> Limited<T, F, Fut>
+where
+    F: FnMut(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    assert!(bytes_per_sec > 0, "bytes_per_sec must be greater than 0");
+    assert!(burst > 0, "burst must be greater than 0");
+    Limited {
+        inner: io,
+        bytes_per_sec,
+        burst,
+        new_timer,
+        read: Bucket::new(burst),
+        write: Bucket::new(burst),
+    }
+}
+
+impl<T, F, Fut> Limited<T, F, Fut> {
+    delegate_access_inner!(inner, T, ());
+}
+
+impl<Fut> Bucket<Fut>
+where
+    Fut: Future<Output = ()>,
+{
+    fn new(burst: usize) -> Self {
+        Self { tokens: burst, credit: 0, timer: None }
+    }
+
+    /// Arms the timer refilling the bucket, if it isn't full and the timer
+    /// isn't running already.
+    fn refill<F>(self: Pin<&mut Self>, bytes_per_sec: usize, burst: usize, new_timer: &mut F)
+    where
+        F: FnMut(Duration) -> Fut,
+    {
+        let mut this = self.project();
+        if this.timer.is_none() && *this.tokens < burst {
+            *this.credit = burst - *this.tokens;
+            let nanos = (*this.credit as u128 * 1_000_000_000 + bytes_per_sec as u128 - 1)
+                / bytes_per_sec as u128;
+            let delay = Duration::from_nanos(cmp::min(nanos, u64::max_value() as u128) as u64);
+            this.timer.set(Some(new_timer(delay)));
+        }
+    }
+
+    /// Returns the number of bytes which can be transferred, once it isn't
+    /// zero.
+    fn poll_tokens<F>(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bytes_per_sec: usize,
+        burst: usize,
+        new_timer: &mut F,
+    ) -> Poll<usize>
+    where
+        F: FnMut(Duration) -> Fut,
+    {
+        loop {
+            self.as_mut().refill(bytes_per_sec, burst, new_timer);
+            let mut this = self.as_mut().project();
+            let timer = match this.timer.as_mut().as_pin_mut() {
+                Some(timer) => timer,
+                None => break,
+            };
+            if timer.poll(cx).is_pending() {
+                break;
+            }
+            this.timer.set(None);
+            *this.tokens = cmp::min(burst, *this.tokens + *this.credit);
+        }
+
+        if self.tokens == 0 {
+            Poll::Pending
+        } else {
+            Poll::Ready(self.tokens)
+        }
+    }
+
+    fn consume<F>(
+        mut self: Pin<&mut Self>,
+        amt: usize,
+        bytes_per_sec: usize,
+        burst: usize,
+        new_timer: &mut F,
+    ) where
+        F: FnMut(Duration) -> Fut,
+    {
+        *self.as_mut().project().tokens -= amt;
+        // Start refilling right away, so that the time until the next
+        // transfer counts.
+        self.refill(bytes_per_sec, burst, new_timer);
+    }
+}
+
+impl<T, F, Fut> AsyncRead for Limited<T, F, Fut>
+where
+    T: AsyncRead,
+    F: FnMut(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut this = self.project();
+        if buf.is_empty() {
+            return this.inner.poll_read(cx, buf);
+        }
+
+        let (rate, burst) = (*this.bytes_per_sec, *this.burst);
+        let tokens = ready!(this.read.as_mut().poll_tokens(cx, rate, burst, this.new_timer));
+        let max = cmp::min(buf.len(), tokens);
+        let n = ready!(this.inner.poll_read(cx, &mut buf[..max]))?;
+        this.read.consume(n, rate, burst, this.new_timer);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<T, F, Fut> AsyncBufRead for Limited<T, F, Fut>
+where
+    T: AsyncBufRead,
+    F: FnMut(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.project();
+        let (rate, burst) = (*this.bytes_per_sec, *this.burst);
+        let tokens = ready!(this.read.poll_tokens(cx, rate, burst, this.new_timer));
+        let buf = ready!(this.inner.poll_fill_buf(cx))?;
+        let max = cmp::min(buf.len(), tokens);
+        Poll::Ready(Ok(&buf[..max]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        this.read.consume(amt, *this.bytes_per_sec, *this.burst, this.new_timer);
+        this.inner.consume(amt)
+    }
+}
+
+impl<T, F, Fut> AsyncWrite for Limited<T, F, Fut>
+where
+    T: AsyncWrite,
+    F: FnMut(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut this = self.project();
+        if buf.is_empty() {
+            return this.inner.poll_write(cx, buf);
+        }
+
+        let (rate, burst) = (*this.bytes_per_sec, *this.burst);
+        let tokens = ready!(this.write.as_mut().poll_tokens(cx, rate, burst, this.new_timer));
+        let max = cmp::min(buf.len(), tokens);
+        let n = ready!(this.inner.poll_write(cx, &buf[..max]))?;
+        this.write.consume(n, rate, burst, this.new_timer);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<T: fmt::Debug, F, Fut> fmt::Debug for Limited<T, F, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Limited")
+            .field("inner", &self.inner)
+            .field("bytes_per_sec", &self.bytes_per_sec)
+            .field("burst", &self.burst)
+            .field("read_tokens", &self.read.tokens)
+            .field("write_tokens", &self.write.tokens)
+            .finish()
+    }
+}
@@ -25,6 +25,8 @@ impl<R: AsyncBufRead> Lines<R> {
     pub(super) fn new(reader: R) -> Self {
         Self { reader, buf: String::new(), bytes: Vec::new(), read: 0 }
     }
+
+    delegate_access_inner!(reader, R, ());
 }
 
 impl<R: AsyncBufRead> Stream for Lines<R> {
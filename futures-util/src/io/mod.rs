@@ -11,17 +11,21 @@
 //! In addition, the [`AsyncReadExt`], [`AsyncWriteExt`], [`AsyncSeekExt`], and
 //! [`AsyncBufReadExt`] extension traits offer a variety of useful combinators
 //! for operating with asynchronous I/O objects, including ways to work with
-//! them using futures, streams and sinks.
+//! them using futures, streams and sinks. The [`codec`] module turns I/O
+//! objects into streams and sinks of the frames of a protocol.
 //!
 //! This module is only available when the `std` feature of this
-//! library is activated, and it is activated by default.
+//! library is activated, and it is activated by default. With the `phala-sgx`
+//! feature, or its `mesalock_sgx` alias, the I/O objects shared between
+//! tasks are locked with the mutexes of `sgx_tstd`, so that they can be used
+//! inside an enclave.
 
 #[cfg(feature = "io-compat")]
 #[cfg_attr(docsrs, doc(cfg(feature = "io-compat")))]
 use crate::compat::Compat;
 use crate::future::assert_future;
 use crate::stream::assert_stream;
-use std::{pin::Pin, ptr};
+use std::pin::Pin;
 
 // Re-export some types from `std::io` so that users don't have to deal
 // with conflicts when `use`ing `futures::io` and `std::io`.
@@ -32,26 +36,12 @@ pub use std::io::Initializer;
 #[doc(no_inline)]
 pub use std::io::{Error, ErrorKind, IoSlice, IoSliceMut, Result, SeekFrom};
 
-pub use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
+pub use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
 
 // used by `BufReader` and `BufWriter`
 // https://github.com/rust-lang/rust/blob/master/src/libstd/sys_common/io.rs#L1
 const DEFAULT_BUF_SIZE: usize = 8 * 1024;
 
-/// Initializes a buffer if necessary.
-///
-/// A buffer is always initialized if `read-initializer` feature is disabled.
-#[inline]
-unsafe fn initialize<R: AsyncRead>(_reader: &R, buf: &mut [u8]) {
-    #[cfg(feature = "read-initializer")]
-    {
-        if !_reader.initializer().should_initialize() {
-            return;
-        }
-    }
-    ptr::write_bytes(buf.as_mut_ptr(), 0, buf.len())
-}
-
 mod allow_std;
 pub use self::allow_std::AllowStdIo;
 
@@ -70,15 +60,23 @@ pub use self::chain::Chain;
 mod close;
 pub use self::close::Close;
 
+pub mod codec;
+
 mod copy;
 pub use self::copy::{copy, Copy};
 
+mod copy_bidirectional;
+pub use self::copy_bidirectional::{copy_bidirectional, CopyBidirectional};
+
 mod copy_buf;
 pub use self::copy_buf::{copy_buf, CopyBuf};
 
 mod cursor;
 pub use self::cursor::Cursor;
 
+mod duplex;
+pub use self::duplex::{duplex, DuplexStream};
+
 mod empty;
 pub use self::empty::{empty, Empty};
 
@@ -95,6 +93,12 @@ mod into_sink;
 #[cfg_attr(docsrs, doc(cfg(feature = "sink")))]
 pub use self::into_sink::IntoSink;
 
+mod into_stream;
+pub use self::into_stream::IntoStream;
+
+mod limited;
+pub use self::limited::{limited, Limited};
+
 mod lines;
 pub use self::lines::Lines;
 
@@ -134,6 +138,18 @@ pub use self::split::{ReadHalf, ReuniteError, WriteHalf};
 mod take;
 pub use self::take::Take;
 
+mod timeout;
+pub use self::timeout::{timeout, Timeout};
+
+#[cfg(feature = "io-tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "io-tokio")))]
+mod tokio_compat;
+#[cfg(feature = "io-tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "io-tokio")))]
+pub use self::tokio_compat::TokioCompat;
+
+pub mod untrusted;
+
 mod window;
 pub use self::window::Window;
 
@@ -301,6 +317,10 @@ pub trait AsyncReadExt: AsyncRead {
     ///
     /// On success the total number of bytes read is returned.
     ///
+    /// If the data read is not valid UTF-8, an error of the
+    /// [`InvalidData`](std::io::ErrorKind::InvalidData) kind is returned and
+    /// `buf` is left as it was before the call.
+    ///
     /// # Examples
     ///
     /// ```
@@ -386,6 +406,36 @@ pub trait AsyncReadExt: AsyncRead {
         assert_read(Take::new(self, limit))
     }
 
+    /// Converts this reader into a stream of the chunks of bytes read from
+    /// it, each of at most `chunk_size` bytes.
+    ///
+    /// The stream ends when the reader reaches EOF. Errors are yielded by the
+    /// stream, which can still be polled after them to try reading again.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `chunk_size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncReadExt, Cursor};
+    /// use futures::stream::TryStreamExt;
+    ///
+    /// let reader = Cursor::new(&b"12345678"[..]);
+    /// let chunks: Vec<Vec<u8>> = reader.into_stream(3).try_collect().await?;
+    ///
+    /// assert_eq!(chunks, [&b"123"[..], b"456", b"78"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn into_stream(self, chunk_size: usize) -> IntoStream<Self>
+    where
+        Self: Sized,
+    {
+        assert_stream::<Result<Vec<u8>>, _>(IntoStream::new(self, chunk_size))
+    }
+
     /// Wraps an [`AsyncRead`] in a compatibility wrapper that allows it to be
     /// used as a futures 0.1 / tokio-io 0.1 `AsyncRead`. If the wrapped type
     /// implements [`AsyncWrite`] as well, the result will also implement the
@@ -622,6 +672,34 @@ pub trait AsyncSeekExt: AsyncSeek {
     {
         self.seek(SeekFrom::Current(0))
     }
+
+    /// Creates a future which will seek back to the start of the stream.
+    ///
+    /// This is equivalent to `self.seek(SeekFrom::Start(0))`, and so resolves
+    /// to `0` on success.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncReadExt, AsyncSeekExt, Cursor};
+    ///
+    /// let mut reader = Cursor::new(&b"hello"[..]);
+    /// let mut output = String::new();
+    /// reader.read_to_string(&mut output).await?;
+    ///
+    /// reader.rewind().await?;
+    /// output.clear();
+    /// reader.read_to_string(&mut output).await?;
+    /// assert_eq!(output, "hello");
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn rewind(&mut self) -> Seek<'_, Self>
+    where
+        Self: Unpin,
+    {
+        self.seek(SeekFrom::Start(0))
+    }
 }
 
 impl<S: AsyncSeek + ?Sized> AsyncSeekExt for S {}
@@ -1,9 +1,11 @@
 use futures_core::future::Future;
 use futures_core::ready;
 use futures_core::task::{Context, Poll};
-use futures_io::AsyncRead;
+use futures_io::{AsyncRead, ReadBuf};
 use std::io;
+use std::mem::MaybeUninit;
 use std::pin::Pin;
+use std::slice;
 use std::vec::Vec;
 
 /// Future for the [`read_to_end`](super::AsyncReadExt::read_to_end) method.
@@ -13,6 +15,7 @@ pub struct ReadToEnd<'a, R: ?Sized> {
     reader: &'a mut R,
     buf: &'a mut Vec<u8>,
     start_len: usize,
+    initialized: usize,
 }
 
 impl<R: ?Sized + Unpin> Unpin for ReadToEnd<'_, R> {}
@@ -20,20 +23,7 @@ impl<R: ?Sized + Unpin> Unpin for ReadToEnd<'_, R> {}
 impl<'a, R: AsyncRead + ?Sized + Unpin> ReadToEnd<'a, R> {
     pub(super) fn new(reader: &'a mut R, buf: &'a mut Vec<u8>) -> Self {
         let start_len = buf.len();
-        Self { reader, buf, start_len }
-    }
-}
-
-struct Guard<'a> {
-    buf: &'a mut Vec<u8>,
-    len: usize,
-}
-
-impl Drop for Guard<'_> {
-    fn drop(&mut self) {
-        unsafe {
-            self.buf.set_len(self.len);
-        }
+        Self { reader, buf, start_len, initialized: 0 }
     }
 }
 
@@ -44,37 +34,47 @@ impl Drop for Guard<'_> {
 // time is 4,500 times (!) slower than this if the reader has a very small
 // amount of data to return.
 //
-// Because we're extending the buffer with uninitialized data for trusted
-// readers, we need to make sure to truncate that if any of this panics.
+// The spare capacity of the vector is read into with `poll_read_buf`, so that
+// it is never zeroed by readers which support uninitialized buffers, and at
+// most once by the others. `initialized` is the length of the start of the
+// spare capacity which is initialized already.
 pub(super) fn read_to_end_internal<R: AsyncRead + ?Sized>(
     mut rd: Pin<&mut R>,
     cx: &mut Context<'_>,
     buf: &mut Vec<u8>,
     start_len: usize,
+    initialized: &mut usize,
 ) -> Poll<io::Result<usize>> {
-    let mut g = Guard { len: buf.len(), buf };
     loop {
-        if g.len == g.buf.len() {
-            unsafe {
-                g.buf.reserve(32);
-                let capacity = g.buf.capacity();
-                g.buf.set_len(capacity);
-                super::initialize(&rd, &mut g.buf[g.len..]);
-            }
+        if buf.len() == buf.capacity() {
+            buf.reserve(32);
+            *initialized = 0;
         }
 
-        let buf = &mut g.buf[g.len..];
-        match ready!(rd.as_mut().poll_read(cx, buf)) {
-            Ok(0) => return Poll::Ready(Ok(g.len - start_len)),
-            Ok(n) => {
-                // We can't allow bogus values from read. If it is too large, the returned vec could have its length
-                // set past its capacity, or if it overflows the vec could be shortened which could create an invalid
-                // string if this is called via read_to_string.
-                assert!(n <= buf.len());
-                g.len += n;
-            }
-            Err(e) => return Poll::Ready(Err(e)),
+        let len = buf.len();
+        // Safety: the spare capacity of the vector is valid memory, which may
+        // be uninitialized.
+        let spare = unsafe {
+            slice::from_raw_parts_mut(
+                buf.as_mut_ptr().add(len) as *mut MaybeUninit<u8>,
+                buf.capacity() - len,
+            )
+        };
+        let mut read_buf = ReadBuf::uninit(spare);
+        // Safety: these bytes were initialized by previous reads.
+        unsafe { read_buf.assume_init(*initialized) };
+        let ptr = read_buf.filled().as_ptr();
+        ready!(rd.as_mut().poll_read_buf(cx, &mut read_buf))?;
+        // The reader must not have swapped the buffer for another one.
+        assert_eq!(read_buf.filled().as_ptr(), ptr);
+
+        let n = read_buf.filled().len();
+        if n == 0 {
+            return Poll::Ready(Ok(len - start_len));
         }
+        *initialized = read_buf.initialized().len() - n;
+        // Safety: the bytes read into the spare capacity are initialized.
+        unsafe { buf.set_len(len + n) };
     }
 }
 
@@ -86,6 +86,12 @@ where
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = &mut *self;
-        read_to_end_internal(Pin::new(&mut this.reader), cx, this.buf, this.start_len)
+        read_to_end_internal(
+            Pin::new(&mut this.reader),
+            cx,
+            this.buf,
+            this.start_len,
+            &mut this.initialized,
+        )
     }
 }
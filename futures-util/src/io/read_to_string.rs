@@ -15,6 +15,7 @@ pub struct ReadToString<'a, R: ?Sized> {
     buf: &'a mut String,
     bytes: Vec<u8>,
     start_len: usize,
+    initialized: usize,
 }
 
 impl<R: ?Sized + Unpin> Unpin for ReadToString<'_, R> {}
@@ -22,7 +23,13 @@ impl<R: ?Sized + Unpin> Unpin for ReadToString<'_, R> {}
 impl<'a, R: AsyncRead + ?Sized + Unpin> ReadToString<'a, R> {
     pub(super) fn new(reader: &'a mut R, buf: &'a mut String) -> Self {
         let start_len = buf.len();
-        Self { reader, bytes: mem::replace(buf, String::new()).into_bytes(), buf, start_len }
+        Self {
+            reader,
+            bytes: mem::replace(buf, String::new()).into_bytes(),
+            buf,
+            start_len,
+            initialized: 0,
+        }
     }
 }
 
@@ -32,9 +39,15 @@ fn read_to_string_internal<R: AsyncRead + ?Sized>(
     buf: &mut String,
     bytes: &mut Vec<u8>,
     start_len: usize,
+    initialized: &mut usize,
 ) -> Poll<io::Result<usize>> {
-    let ret = ready!(read_to_end_internal(reader, cx, bytes, start_len));
+    let ret = ready!(read_to_end_internal(reader, cx, bytes, start_len, initialized));
     if str::from_utf8(bytes).is_err() {
+        // Leave `buf` as it was before reading, like `std::io::Read::read_to_string`.
+        bytes.truncate(start_len);
+        debug_assert!(buf.is_empty());
+        // Safety: the first `start_len` bytes come from `buf`, so they are valid UTF-8.
+        mem::swap(unsafe { buf.as_mut_vec() }, bytes);
         Poll::Ready(ret.and_then(|_| {
             Err(io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8"))
         }))
@@ -53,7 +66,7 @@ where
     type Output = io::Result<usize>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let Self { reader, buf, bytes, start_len } = &mut *self;
-        read_to_string_internal(Pin::new(reader), cx, buf, bytes, *start_len)
+        let Self { reader, buf, bytes, start_len, initialized } = &mut *self;
+        read_to_string_internal(Pin::new(reader), cx, buf, bytes, *start_len, initialized)
     }
 }
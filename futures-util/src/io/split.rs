@@ -31,6 +31,22 @@ pub(super) fn split<T: AsyncRead + AsyncWrite>(t: T) -> (ReadHalf<T>, WriteHalf<
     (ReadHalf { handle: a }, WriteHalf { handle: b })
 }
 
+impl<T> ReadHalf<T> {
+    /// Returns `true` if this `ReadHalf<T>` and the given `WriteHalf<T>` come
+    /// from the same call to `AsyncReadExt::split`.
+    pub fn is_pair_of(&self, other: &WriteHalf<T>) -> bool {
+        self.handle.is_pair_of(&other.handle)
+    }
+}
+
+impl<T> WriteHalf<T> {
+    /// Returns `true` if this `WriteHalf<T>` and the given `ReadHalf<T>` come
+    /// from the same call to `AsyncReadExt::split`.
+    pub fn is_pair_of(&self, other: &ReadHalf<T>) -> bool {
+        other.is_pair_of(self)
+    }
+}
+
 impl<T: Unpin> ReadHalf<T> {
     /// Attempts to put the two "halves" of a split `AsyncRead + AsyncWrite` back
     /// together. Succeeds only if the `ReadHalf<T>` and `WriteHalf<T>` are
@@ -2,7 +2,7 @@ use futures_core::ready;
 use futures_core::task::{Context, Poll};
 #[cfg(feature = "read-initializer")]
 use futures_io::Initializer;
-use futures_io::{AsyncBufRead, AsyncRead};
+use futures_io::{AsyncBufRead, AsyncRead, ReadBuf};
 use pin_project_lite::pin_project;
 use std::pin::Pin;
 use std::{cmp, io};
@@ -101,6 +101,36 @@ impl<R: AsyncRead> AsyncRead for Take<R> {
         Poll::Ready(Ok(n))
     }
 
+    fn poll_read_buf(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.project();
+
+        if *this.limit == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        let max = cmp::min(buf.remaining() as u64, *this.limit) as usize;
+        let initialized = cmp::min(max, buf.initialized().len() - buf.filled().len());
+        let (n, initialized) = {
+            // Safety: only initialized bytes are written to `limited`.
+            let mut limited = ReadBuf::uninit(unsafe { &mut buf.unfilled_mut()[..max] });
+            unsafe { limited.assume_init(initialized) };
+            let ptr = limited.filled().as_ptr();
+            ready!(this.inner.poll_read_buf(cx, &mut limited))?;
+            // The reader must not have swapped the buffer for another one.
+            assert_eq!(limited.filled().as_ptr(), ptr);
+            (limited.filled().len(), limited.initialized().len())
+        };
+        // Safety: these bytes were initialized by the reader.
+        unsafe { buf.assume_init(initialized) };
+        buf.advance(n);
+        *this.limit -= n as u64;
+        Poll::Ready(Ok(()))
+    }
+
     #[cfg(feature = "read-initializer")]
     unsafe fn initializer(&self) -> Initializer {
         self.inner.initializer()
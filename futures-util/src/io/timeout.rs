@@ -0,0 +1,184 @@
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+use pin_project_lite::pin_project;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+
+pin_project! {
+    /// I/O object for the [`timeout`](super::timeout) function.
+    #[must_use = "I/O objects do nothing unless polled"]
+    pub struct Timeout<T, F, Fut> {
+        #[pin]
+        inner: T,
+        new_timer: F,
+        #[pin]
+        read_timer: Option<Fut>,
+        #[pin]
+        write_timer: Option<Fut>,
+    }
+}
+
+/// Wraps an I/O object so that its operations fail once they have been
+/// stalled for a while.
+///
+/// When a read, write, flush or close of the object isn't ready, `new_timer`
+/// is called to create a timer future (for instance a delay of the maximum
+/// time an operation may take). If that future completes before the
+/// operation makes progress, the operation fails with an error of the
+/// [`TimedOut`](io::ErrorKind::TimedOut) kind. Reads and writes have their
+/// own timers, which are dropped as soon as their operation is ready.
+///
+/// This defends loops serving peers against dead ones, without wrapping each
+/// call in a timeout of its own. A timed out operation can be retried.
+///
+/// This library doesn't provide timers, so they are left to the caller to
+/// supply.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future;
+/// use futures::io::{self, AsyncReadExt};
+/// use futures::stream::{self, TryStreamExt};
+///
+/// // A reader which never becomes ready, and a timer which has already
+/// // elapsed, standing in for a real delay.
+/// let stalled = stream::pending::<io::Result<Vec<u8>>>().into_async_read();
+/// let mut reader = io::timeout(stalled, || future::ready(()));
+///
+/// let mut buf = [0; 16];
+/// let err = reader.read(&mut buf).await.unwrap_err();
+/// assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+/// # });
+/// ```
+pub fn timeout<T, F, Fut>(io: T, new_timer: F) -> Timeout<T, F, Fut>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    Timeout { inner: io, new_timer, read_timer: None, write_timer: None }
+}
+
+impl<T, F, Fut> Timeout<T, F, Fut> {
+    delegate_access_inner!(inner, T, ());
+}
+
+// Polls the timer of a stalled operation, creating it if needed.
+fn poll_timer<F, Fut>(
+    mut timer: Pin<&mut Option<Fut>>,
+    new_timer: &mut F,
+    cx: &mut Context<'_>,
+) -> Poll<io::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    if timer.is_none() {
+        timer.set(Some(new_timer()));
+    }
+    ready!(timer.as_mut().as_pin_mut().unwrap().poll(cx));
+    timer.set(None);
+    Poll::Ready(io::Error::new(io::ErrorKind::TimedOut, "I/O operation timed out"))
+}
+
+macro_rules! with_timer {
+    ($this:ident . $timer:ident, $cx:ident, $op:expr) => {
+        match $op {
+            Poll::Ready(res) => {
+                $this.$timer.set(None);
+                Poll::Ready(res)
+            }
+            Poll::Pending => poll_timer($this.$timer, $this.new_timer, $cx).map(Err),
+        }
+    };
+}
+
+impl<T, F, Fut> AsyncRead for Timeout<T, F, Fut>
+where
+    T: AsyncRead,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut this = self.project();
+        with_timer!(this.read_timer, cx, this.inner.poll_read(cx, buf))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let mut this = self.project();
+        with_timer!(this.read_timer, cx, this.inner.poll_read_vectored(cx, bufs))
+    }
+}
+
+impl<T, F, Fut> AsyncBufRead for Timeout<T, F, Fut>
+where
+    T: AsyncBufRead,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let mut this = self.project();
+        with_timer!(this.read_timer, cx, this.inner.poll_fill_buf(cx))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.project().inner.consume(amt)
+    }
+}
+
+impl<T, F, Fut> AsyncWrite for Timeout<T, F, Fut>
+where
+    T: AsyncWrite,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut this = self.project();
+        with_timer!(this.write_timer, cx, this.inner.poll_write(cx, buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let mut this = self.project();
+        with_timer!(this.write_timer, cx, this.inner.poll_write_vectored(cx, bufs))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+        with_timer!(this.write_timer, cx, this.inner.poll_flush(cx))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+        with_timer!(this.write_timer, cx, this.inner.poll_close(cx))
+    }
+}
+
+impl<T: fmt::Debug, F, Fut: fmt::Debug> fmt::Debug for Timeout<T, F, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timeout")
+            .field("inner", &self.inner)
+            .field("read_timer", &self.read_timer)
+            .field("write_timer", &self.write_timer)
+            .finish()
+    }
+}
@@ -0,0 +1,210 @@
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, IoSlice, SeekFrom};
+use pin_project_lite::pin_project;
+use std::io;
+use std::pin::Pin;
+use tokio_1::io as tokio_io;
+
+pin_project! {
+    /// Adapts an I/O object between the I/O traits of tokio 1.x and those of
+    /// this crate, in either direction.
+    ///
+    /// If `T` implements tokio's [`AsyncRead`](tokio_io::AsyncRead),
+    /// [`AsyncWrite`](tokio_io::AsyncWrite),
+    /// [`AsyncBufRead`](tokio_io::AsyncBufRead) or
+    /// [`AsyncSeek`](tokio_io::AsyncSeek), `TokioCompat<T>` implements the
+    /// trait of the same name of this crate, so that tokio transports can be
+    /// used with the combinators of [`AsyncReadExt`](super::AsyncReadExt) and
+    /// [`AsyncWriteExt`](super::AsyncWriteExt), or the [`codec`](super::codec)
+    /// module. Conversely, if `T` implements the traits of this crate,
+    /// `TokioCompat<T>` implements those of tokio.
+    ///
+    /// Requires the `io-tokio` feature to enable. That feature depends on
+    /// tokio 1.x, whose minimum supported Rust version is higher than the one
+    /// of this crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncReadExt, Cursor, TokioCompat};
+    ///
+    /// // `TokioCompat<Cursor<_>>` implements tokio's `AsyncRead`, and
+    /// // wrapping it again gives back this crate's `AsyncRead`.
+    /// let tokio_reader = TokioCompat::new(Cursor::new(vec![1, 2, 3]));
+    /// let mut reader = TokioCompat::new(tokio_reader);
+    ///
+    /// let mut output = Vec::new();
+    /// reader.read_to_end(&mut output).await?;
+    /// assert_eq!(output, [1, 2, 3]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+    /// ```
+    #[derive(Debug)]
+    pub struct TokioCompat<T> {
+        #[pin]
+        inner: T,
+        // The position of a seek which has been started but not completed.
+        seek_pos: Option<SeekFrom>,
+    }
+}
+
+impl<T> TokioCompat<T> {
+    /// Wraps an I/O object implementing the I/O traits of either tokio or
+    /// this crate.
+    pub fn new(inner: T) -> Self {
+        Self { inner, seek_pos: None }
+    }
+
+    /// Returns a reference to the wrapped I/O object.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped I/O object.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns a pinned mutable reference to the wrapped I/O object.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.project().inner
+    }
+
+    /// Consumes this wrapper, returning the wrapped I/O object.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: tokio_io::AsyncRead> AsyncRead for TokioCompat<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut buf = tokio_io::ReadBuf::new(buf);
+        ready!(self.project().inner.poll_read(cx, &mut buf))?;
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+}
+
+impl<T: AsyncRead> tokio_io::AsyncRead for TokioCompat<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio_io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let n = ready!(self.project().inner.poll_read(cx, buf.initialize_unfilled()))?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: tokio_io::AsyncWrite> AsyncWrite for TokioCompat<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_write_vectored(cx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_shutdown(cx)
+    }
+}
+
+impl<T: AsyncWrite> tokio_io::AsyncWrite for TokioCompat<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_write_vectored(cx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<T: tokio_io::AsyncBufRead> AsyncBufRead for TokioCompat<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.project().inner.poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.project().inner.consume(amt)
+    }
+}
+
+impl<T: AsyncBufRead> tokio_io::AsyncBufRead for TokioCompat<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.project().inner.poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.project().inner.consume(amt)
+    }
+}
+
+impl<T: tokio_io::AsyncSeek> AsyncSeek for TokioCompat<T> {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        if self.seek_pos != Some(pos) {
+            // Wait for any seek started before, then start this one.
+            ready!(self.as_mut().project().inner.poll_complete(cx))?;
+            let this = self.as_mut().project();
+            this.inner.start_seek(pos)?;
+            *this.seek_pos = Some(pos);
+        }
+        let this = self.project();
+        let res = ready!(this.inner.poll_complete(cx));
+        *this.seek_pos = None;
+        Poll::Ready(res)
+    }
+}
+
+impl<T: AsyncSeek> tokio_io::AsyncSeek for TokioCompat<T> {
+    fn start_seek(self: Pin<&mut Self>, pos: SeekFrom) -> io::Result<()> {
+        *self.project().seek_pos = Some(pos);
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        let this = self.project();
+        // Without a started seek, this returns the current position.
+        let pos = this.seek_pos.unwrap_or(SeekFrom::Current(0));
+        let res = ready!(this.inner.poll_seek(cx, pos));
+        *this.seek_pos = None;
+        Poll::Ready(res)
+    }
+}
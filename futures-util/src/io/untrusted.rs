@@ -0,0 +1,362 @@
+//! Asynchronous I/O on the file descriptors of an untrusted host.
+//!
+//! Code running inside an SGX enclave can't make system calls: its sockets
+//! and files belong to the host, which performs I/O on its behalf when asked
+//! through OCALLs. This module turns such OCALLs into asynchronous I/O
+//! objects.
+//!
+//! The OCALLs are supplied by implementing the [`Host`] trait. Its methods
+//! never block: an operation which can't complete right away fails with
+//! [`ErrorKind::WouldBlock`](std::io::ErrorKind::WouldBlock), and the task is
+//! then woken up once the host reports the file descriptor to be ready.
+//!
+//! A [`Reactor`] keeps track of the tasks waiting for the host, and wakes
+//! them up as the host reports events in [`Reactor::turn`]. A [`HostFd`]
+//! wraps a file descriptor of the host, implementing [`AsyncRead`],
+//! [`AsyncWrite`] and [`AsyncSeek`] with the OCALLs of the reactor's host.
+//!
+//! # Running the reactor
+//!
+//! The reactor is usually turned by the executor whenever it runs out of
+//! tasks to run, so that the thread waits in the host for I/O events. For a
+//! [`LocalPool`], this is done by implementing its `Park` trait:
+//!
+//! ```ignore
+//! use futures::executor::{LocalPool, Park, Unpark};
+//! use futures::io::untrusted::Reactor;
+//!
+//! struct ReactorPark(Reactor);
+//! struct ReactorUnpark(Reactor);
+//!
+//! impl Park for ReactorPark {
+//!     type Unpark = ReactorUnpark;
+//!
+//!     fn unpark(&self) -> ReactorUnpark {
+//!         ReactorUnpark(self.0.clone())
+//!     }
+//!
+//!     fn park(&mut self) {
+//!         // Wait in the host for events, and wake up the tasks they concern.
+//!         self.0.turn(true).expect("failed to wait for host events");
+//!     }
+//! }
+//!
+//! impl Unpark for ReactorUnpark {
+//!     fn unpark(&self) {
+//!         self.0.wake();
+//!     }
+//! }
+//!
+//! let reactor = Reactor::new(OcallHost);
+//! let mut pool = LocalPool::new_with_park(ReactorPark(reactor.clone()));
+//! ```
+//!
+//! [`LocalPool`]: https://docs.rs/futures/0.3/futures/executor/struct.LocalPool.html
+
+use futures_core::task::{Context, Poll, Waker};
+use futures_io::{AsyncRead, AsyncSeek, AsyncWrite, SeekFrom};
+#[cfg(feature = "phala-sgx")]
+use sgx_tstd::sync::SgxMutex as Mutex;
+use slab::Slab;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(not(feature = "phala-sgx"))]
+use std::sync::Mutex;
+
+/// A file descriptor of the host.
+pub type RawFd = i32;
+
+/// The readiness of a file descriptor which a task waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interest {
+    /// The file descriptor can be read from.
+    Read,
+    /// The file descriptor can be written to.
+    Write,
+}
+
+/// The OCALLs performing I/O on the host for a [`Reactor`].
+///
+/// None of these methods may block, except for
+/// [`poll_events`](Host::poll_events) when asked to.
+pub trait Host: Send + Sync + 'static {
+    /// Reads from the file descriptor into `buf`, returning how many bytes
+    /// were read.
+    ///
+    /// If no data is available yet, this must fail with
+    /// [`ErrorKind::WouldBlock`](io::ErrorKind::WouldBlock).
+    fn read(&self, fd: RawFd, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Writes `buf` to the file descriptor, returning how many bytes were
+    /// written.
+    ///
+    /// If the file descriptor can't take more data yet, this must fail with
+    /// [`ErrorKind::WouldBlock`](io::ErrorKind::WouldBlock).
+    fn write(&self, fd: RawFd, buf: &[u8]) -> io::Result<usize>;
+
+    /// Flushes the data written to the file descriptor.
+    ///
+    /// By default, this does nothing.
+    fn flush(&self, fd: RawFd) -> io::Result<()> {
+        let _ = fd;
+        Ok(())
+    }
+
+    /// Moves the cursor of the file descriptor, returning its new position
+    /// from the start of the file.
+    ///
+    /// By default, this fails, as for sockets.
+    fn seek(&self, fd: RawFd, pos: SeekFrom) -> io::Result<u64> {
+        let _ = (fd, pos);
+        Err(io::Error::new(io::ErrorKind::Other, "seeking is not supported"))
+    }
+
+    /// Closes the file descriptor. This is called when a [`HostFd`] is
+    /// dropped, and errors are ignored.
+    fn close(&self, fd: RawFd) -> io::Result<()>;
+
+    /// Asks the host to report once the file descriptor is ready for the
+    /// given interest, by returning `token` from a later call to
+    /// [`poll_events`](Host::poll_events).
+    ///
+    /// If the file descriptor is ready already, the event must be reported
+    /// by the next call to `poll_events`. Each request is reported once.
+    fn watch(&self, fd: RawFd, interest: Interest, token: usize) -> io::Result<()>;
+
+    /// Pushes the tokens of the events which occurred since the last call
+    /// onto `tokens`.
+    ///
+    /// If `block` is `true` and no event occurred, this waits until an event
+    /// occurs or [`wake`](Host::wake) is called.
+    fn poll_events(&self, tokens: &mut Vec<usize>, block: bool) -> io::Result<()>;
+
+    /// Makes a blocked call to [`poll_events`](Host::poll_events) return, or
+    /// the next one return right away. This may be called from any thread.
+    fn wake(&self);
+}
+
+/// Wakes up the tasks waiting for the file descriptors of a [`Host`].
+///
+/// This type is a clonable handle to the reactor itself. Cloning it will
+/// only create a new reference, not a new reactor.
+#[derive(Clone)]
+pub struct Reactor {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    host: Box<dyn Host>,
+    watches: Mutex<Slab<Watch>>,
+}
+
+// A request to the host to report the readiness of a file descriptor.
+struct Watch {
+    waker: Waker,
+    // Whether the host has reported the event.
+    is_ready: bool,
+}
+
+impl Reactor {
+    /// Creates a reactor submitting its OCALLs to the given host.
+    pub fn new<H: Host>(host: H) -> Self {
+        Self { inner: Arc::new(Inner { host: Box::new(host), watches: Mutex::new(Slab::new()) }) }
+    }
+
+    /// Wraps a file descriptor of the host, which is closed when the
+    /// returned value is dropped.
+    pub fn open(&self, fd: RawFd) -> HostFd {
+        HostFd { reactor: self.clone(), fd, read: None, write: None }
+    }
+
+    /// Collects the events reported by the host, and wakes up the tasks
+    /// they concern, returning how many tasks were woken up.
+    ///
+    /// If `block` is `true`, this waits in the host until at least one event
+    /// occurs or [`wake`](Reactor::wake) is called.
+    pub fn turn(&self, block: bool) -> io::Result<usize> {
+        let mut tokens = Vec::new();
+        self.inner.host.poll_events(&mut tokens, block)?;
+
+        let mut wakers = Vec::with_capacity(tokens.len());
+        let mut watches = self.inner.watches.lock().unwrap();
+        for token in tokens {
+            // The watch may have been dropped since it was submitted.
+            if let Some(watch) = watches.get_mut(token) {
+                if !watch.is_ready {
+                    watch.is_ready = true;
+                    wakers.push(watch.waker.clone());
+                }
+            }
+        }
+        drop(watches);
+
+        let woken = wakers.len();
+        for waker in wakers {
+            waker.wake();
+        }
+        Ok(woken)
+    }
+
+    /// Makes a blocked call to [`turn`](Reactor::turn) return, or the next
+    /// one return right away. This may be called from any thread.
+    pub fn wake(&self) {
+        self.inner.host.wake();
+    }
+
+    // Makes the task of `cx` wait for `fd` to be ready for `interest`,
+    // reusing the watch identified by `token` if the host hasn't reported it
+    // yet.
+    fn watch(
+        &self,
+        fd: RawFd,
+        interest: Interest,
+        token: &mut Option<usize>,
+        cx: &mut Context<'_>,
+    ) -> io::Result<()> {
+        let mut watches = self.inner.watches.lock().unwrap();
+        let key = match *token {
+            Some(key) => {
+                let watch = &mut watches[key];
+                if !watch.waker.will_wake(cx.waker()) {
+                    watch.waker = cx.waker().clone();
+                }
+                if !watch.is_ready {
+                    return Ok(());
+                }
+                watch.is_ready = false;
+                key
+            }
+            None => {
+                let key = watches.insert(Watch { waker: cx.waker().clone(), is_ready: false });
+                *token = Some(key);
+                key
+            }
+        };
+        drop(watches);
+        self.inner.host.watch(fd, interest, key)
+    }
+
+    fn unwatch(&self, token: Option<usize>) {
+        if let Some(key) = token {
+            self.inner.watches.lock().unwrap().remove(key);
+        }
+    }
+}
+
+impl fmt::Debug for Reactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reactor")
+            .field("watches", &self.inner.watches.lock().unwrap().len())
+            .finish()
+    }
+}
+
+/// A file descriptor of the host, performing I/O through the OCALLs of a
+/// [`Reactor`].
+///
+/// This value is created by the [`Reactor::open`] method.
+pub struct HostFd {
+    reactor: Reactor,
+    fd: RawFd,
+    // The watches of the tasks waiting for the file descriptor to be ready.
+    read: Option<usize>,
+    write: Option<usize>,
+}
+
+impl HostFd {
+    /// Returns the file descriptor of the host.
+    pub fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+
+    /// Returns the reactor of this file descriptor.
+    pub fn reactor(&self) -> &Reactor {
+        &self.reactor
+    }
+
+    /// Returns the file descriptor of the host, without closing it.
+    pub fn into_raw_fd(mut self) -> RawFd {
+        self.reactor.unwatch(self.read.take());
+        self.reactor.unwatch(self.write.take());
+        let fd = self.fd;
+        self.fd = -1;
+        fd
+    }
+
+    fn poll_io<T>(
+        &mut self,
+        interest: Interest,
+        cx: &mut Context<'_>,
+        mut op: impl FnMut(&dyn Host, RawFd) -> io::Result<T>,
+    ) -> Poll<io::Result<T>> {
+        loop {
+            match op(&*self.reactor.inner.host, self.fd) {
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                res => return Poll::Ready(res),
+            }
+            let token = match interest {
+                Interest::Read => &mut self.read,
+                Interest::Write => &mut self.write,
+            };
+            self.reactor.watch(self.fd, interest, token, cx)?;
+            return Poll::Pending;
+        }
+    }
+}
+
+impl AsyncRead for HostFd {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_io(Interest::Read, cx, |host, fd| host.read(fd, buf))
+    }
+}
+
+impl AsyncWrite for HostFd {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_io(Interest::Write, cx, |host, fd| host.write(fd, buf))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_io(Interest::Write, cx, |host, fd| host.flush(fd))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl AsyncSeek for HostFd {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        self.poll_io(Interest::Read, cx, |host, fd| host.seek(fd, pos))
+    }
+}
+
+impl Drop for HostFd {
+    fn drop(&mut self) {
+        self.reactor.unwatch(self.read.take());
+        self.reactor.unwatch(self.write.take());
+        if self.fd >= 0 {
+            let _ = self.reactor.inner.host.close(self.fd);
+        }
+    }
+}
+
+impl fmt::Debug for HostFd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostFd").field("fd", &self.fd).finish()
+    }
+}
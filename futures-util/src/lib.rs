@@ -30,7 +30,7 @@ compile_error!("The `read-initializer` feature requires the `unstable` feature a
 extern crate alloc;
 
 // Macro re-exports
-pub use futures_core::ready;
+pub use futures_core::{ready, try_ready};
 pub use pin_utils::pin_mut;
 
 #[cfg(feature = "async-await")]
@@ -48,7 +48,7 @@ pub mod __private {
     pub use core::{
         option::Option::{self, None, Some},
         pin::Pin,
-        result::Result::{Err, Ok},
+        result::Result::{self, Err, Ok},
     };
 
     pub mod async_await {
@@ -168,6 +168,14 @@ macro_rules! delegate_async_read {
         ) -> core::task::Poll<std::io::Result<usize>> {
             self.project().$field.poll_read_vectored(cx, bufs)
         }
+
+        fn poll_read_buf(
+            self: core::pin::Pin<&mut Self>,
+            cx: &mut core::task::Context<'_>,
+            buf: &mut $crate::io::ReadBuf<'_>,
+        ) -> core::task::Poll<std::io::Result<()>> {
+            self.project().$field.poll_read_buf(cx, buf)
+        }
     };
 }
 
@@ -0,0 +1,168 @@
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll, Waker};
+use slab::Slab;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Mutex as StdMutex;
+
+/// A futures-aware barrier, which lets a number of tasks wait for each other
+/// to reach the same point.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::future;
+/// use futures::lock::Barrier;
+///
+/// let barrier = Barrier::new(3);
+/// let results = block_on(future::join_all((0..3).map(|_| barrier.wait())));
+///
+/// // Exactly one of the tasks is the leader.
+/// assert_eq!(results.iter().filter(|res| res.is_leader()).count(), 1);
+/// ```
+pub struct Barrier {
+    n: usize,
+    state: StdMutex<State>,
+}
+
+struct State {
+    // The number of tasks waiting for the current generation.
+    count: usize,
+    // Incremented each time the barrier is released.
+    generation: usize,
+    waiters: Slab<Waker>,
+}
+
+impl fmt::Debug for Barrier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Barrier").field("n", &self.n).field("waiting", &state.count).finish()
+    }
+}
+
+impl Barrier {
+    /// Creates a new barrier, which releases the tasks waiting on it once `n`
+    /// of them are waiting.
+    ///
+    /// A barrier created with an `n` of 0 behaves like one with an `n` of 1:
+    /// it never makes tasks wait.
+    pub fn new(n: usize) -> Self {
+        Self {
+            n: n.max(1),
+            state: StdMutex::new(State { count: 0, generation: 0, waiters: Slab::new() }),
+        }
+    }
+
+    /// Waits for `n` tasks to wait on this barrier.
+    ///
+    /// The returned future resolves once the last of the `n` tasks has
+    /// reached the barrier, after which the barrier can be used again. One of
+    /// the tasks is told it is the leader by the [`BarrierWaitResult`].
+    ///
+    /// A task only starts counting towards the `n` tasks once the future is
+    /// first polled, and stops counting if the future is dropped before the
+    /// barrier is released.
+    pub fn wait(&self) -> BarrierWait<'_> {
+        BarrierWait { barrier: Some(self), generation: None, wait_key: WAIT_KEY_NONE }
+    }
+}
+
+// Sentinel for when no slot in the `Slab` has been dedicated to this object.
+const WAIT_KEY_NONE: usize = usize::max_value();
+
+/// A future which resolves when enough tasks are waiting on a barrier,
+/// returned by [`Barrier::wait`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct BarrierWait<'a> {
+    // `None` indicates that the barrier has released this task.
+    barrier: Option<&'a Barrier>,
+    // The generation this task is waiting on, once polled.
+    generation: Option<usize>,
+    wait_key: usize,
+}
+
+impl fmt::Debug for BarrierWait<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BarrierWait")
+            .field("was_released", &self.barrier.is_none())
+            .field("barrier", &self.barrier)
+            .finish()
+    }
+}
+
+impl FusedFuture for BarrierWait<'_> {
+    fn is_terminated(&self) -> bool {
+        self.barrier.is_none()
+    }
+}
+
+impl Future for BarrierWait<'_> {
+    type Output = BarrierWaitResult;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let barrier = self.barrier.expect("polled BarrierWait after completion");
+        let mut state = barrier.state.lock().unwrap();
+
+        match self.generation {
+            None => {
+                state.count += 1;
+                if state.count == barrier.n {
+                    state.count = 0;
+                    state.generation = state.generation.wrapping_add(1);
+                    for waker in state.waiters.drain() {
+                        waker.wake();
+                    }
+                    drop(state);
+                    self.barrier = None;
+                    return Poll::Ready(BarrierWaitResult(true));
+                }
+
+                self.generation = Some(state.generation);
+                self.wait_key = state.waiters.insert(cx.waker().clone());
+                Poll::Pending
+            }
+            Some(generation) if generation != state.generation => {
+                // The waiters have been drained when the barrier was released.
+                drop(state);
+                self.barrier = None;
+                self.wait_key = WAIT_KEY_NONE;
+                Poll::Ready(BarrierWaitResult(false))
+            }
+            Some(_) => {
+                let waker = &mut state.waiters[self.wait_key];
+                if !waker.will_wake(cx.waker()) {
+                    *waker = cx.waker().clone();
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for BarrierWait<'_> {
+    fn drop(&mut self) {
+        if let (Some(barrier), Some(generation)) = (self.barrier, self.generation) {
+            // This future was dropped while waiting for the barrier. Unless the
+            // barrier has been released since it was last polled, it no longer
+            // counts towards the tasks the barrier is waiting for.
+            let mut state = barrier.state.lock().unwrap();
+            if generation == state.generation {
+                state.count -= 1;
+                state.waiters.remove(self.wait_key);
+            }
+        }
+    }
+}
+
+/// The result of waiting on a [`Barrier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult(bool);
+
+impl BarrierWaitResult {
+    /// Returns whether this task was the leader, which is the one that
+    /// released the barrier by being the last to reach it.
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
@@ -22,8 +22,7 @@ use futures_core::task::{Context, Poll, Waker};
 ///
 /// Note that it's possible to use this lock through a poll-style interface with
 /// the `poll_lock` method but you can also use it as a future with the `lock`
-/// method that consumes a `BiLock` and returns a future that will resolve when
-/// it's locked.
+/// method, which returns a future that will resolve when it's locked.
 ///
 /// A `BiLock` is typically used for "split" operations where data which serves
 /// two purposes wants to be split into two to be worked with separately. For
@@ -130,13 +129,11 @@ impl<T> BiLock<T> {
         }
     }
 
-    /// Perform a "blocking lock" of this lock, consuming this lock handle and
-    /// returning a future to the acquired lock.
+    /// Perform a "blocking lock" of this lock, returning a future to the
+    /// acquired lock.
     ///
-    /// This function consumes the `BiLock<T>` and returns a sentinel future,
-    /// `BiLockAcquire<T>`. The returned future will resolve to
-    /// `BiLockAcquired<T>` which represents a locked lock similarly to
-    /// `BiLockGuard<T>`.
+    /// The returned future, `BiLockAcquire<T>`, resolves to a `BiLockGuard<T>`
+    /// once the lock is acquired, like `poll_lock`.
     ///
     /// Note that the returned future will never resolve to an error.
     #[cfg(feature = "bilock")]
@@ -145,6 +142,12 @@ impl<T> BiLock<T> {
         BiLockAcquire { bilock: self }
     }
 
+    /// Returns `true` only if the other `BiLock<T>` originated from the same
+    /// call to `BiLock::new`.
+    pub fn is_pair_of(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.arc, &other.arc)
+    }
+
     /// Attempts to put the two "halves" of a `BiLock<T>` back together and
     /// recover the original value. Succeeds only if the two `BiLock<T>`s
     /// originated from the same call to `BiLock::new`.
@@ -152,7 +155,7 @@ impl<T> BiLock<T> {
     where
         T: Unpin,
     {
-        if Arc::ptr_eq(&self.arc, &other.arc) {
+        if self.is_pair_of(&other) {
             drop(other);
             let inner = Arc::try_unwrap(self.arc)
                 .ok()
@@ -0,0 +1,158 @@
+use super::{Mutex, MutexGuard, MutexLockFuture};
+use crate::task::{noop_waker_ref, WakerSet};
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Mutex as StdMutex;
+
+/// A futures-aware condition variable, to be used with a
+/// [`Mutex`](crate::lock::Mutex).
+///
+/// A condition variable lets a task wait until the data protected by a mutex
+/// satisfies some condition: [`wait`](Condvar::wait) releases the mutex while
+/// the task waits for a notification from another task, and acquires it again
+/// before returning.
+///
+/// As with [`std::sync::Condvar`], a task may be woken when the condition
+/// doesn't hold, so it should be checked again in a loop after each wait.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future;
+/// use futures::lock::{Condvar, Mutex};
+///
+/// let ready = Mutex::new(false);
+/// let condvar = Condvar::new();
+///
+/// let waiter = async {
+///     let mut guard = ready.lock().await;
+///     while !*guard {
+///         guard = condvar.wait(guard).await;
+///     }
+/// };
+/// let notifier = async {
+///     *ready.lock().await = true;
+///     condvar.notify_one();
+/// };
+/// future::join(waiter, notifier).await;
+/// # });
+/// ```
+pub struct Condvar {
+    waiters: StdMutex<WakerSet>,
+}
+
+impl fmt::Debug for Condvar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Condvar").field("waiters", &self.waiters.lock().unwrap().len()).finish()
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Condvar {
+    /// Creates a new condition variable with no waiting tasks.
+    pub fn new() -> Self {
+        Self { waiters: StdMutex::new(WakerSet::new()) }
+    }
+
+    /// Releases the mutex locked by `guard` and waits for a notification,
+    /// then locks the mutex again.
+    ///
+    /// The task starts waiting before the mutex is released, so a
+    /// notification sent by a task which locks the mutex after this call
+    /// isn't missed. The returned future resolves to a new guard of the mutex.
+    pub fn wait<'a, T: ?Sized>(&'a self, guard: MutexGuard<'a, T>) -> CondvarWait<'a, T> {
+        let mutex = MutexGuard::mutex(&guard);
+        // The waker is replaced by the task's own when the future is polled.
+        let wait_key = self.waiters.lock().unwrap().insert(noop_waker_ref());
+        drop(guard);
+        CondvarWait { condvar: Some(self), mutex, wait_key, lock: None }
+    }
+
+    /// Wakes the task which has been waiting the longest, returning `false`
+    /// if no task is waiting.
+    ///
+    /// Unlike [`Notify`](crate::lock::Notify), a notification isn't stored
+    /// if no task is waiting.
+    pub fn notify_one(&self) -> bool {
+        self.waiters.lock().unwrap().wake_one()
+    }
+
+    /// Wakes all the waiting tasks, returning how many were woken.
+    pub fn notify_all(&self) -> usize {
+        self.waiters.lock().unwrap().wake_all()
+    }
+}
+
+// Sentinel for when no key in the `WakerSet` has been dedicated to this object.
+const WAIT_KEY_NONE: usize = usize::max_value();
+
+/// Future for the [`Condvar::wait`] method.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CondvarWait<'a, T: ?Sized> {
+    // `None` indicates that the mutex was locked again.
+    condvar: Option<&'a Condvar>,
+    mutex: &'a Mutex<T>,
+    wait_key: usize,
+    // Set once the task has been notified.
+    lock: Option<MutexLockFuture<'a, T>>,
+}
+
+impl<T: ?Sized> fmt::Debug for CondvarWait<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CondvarWait")
+            .field("was_notified", &(self.wait_key == WAIT_KEY_NONE))
+            .field("condvar", &self.condvar)
+            .field("mutex", &self.mutex)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> FusedFuture for CondvarWait<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.condvar.is_none()
+    }
+}
+
+impl<'a, T: ?Sized> Future for CondvarWait<'a, T> {
+    type Output = MutexGuard<'a, T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let condvar = this.condvar.expect("polled CondvarWait after completion");
+
+        if this.wait_key != WAIT_KEY_NONE {
+            ready!(condvar.waiters.lock().unwrap().poll_woken(this.wait_key, cx));
+            this.wait_key = WAIT_KEY_NONE;
+            this.lock = Some(this.mutex.lock());
+        }
+
+        let guard = ready!(Pin::new(this.lock.as_mut().unwrap()).poll(cx));
+        this.condvar = None;
+        this.lock = None;
+        Poll::Ready(guard)
+    }
+}
+
+impl<T: ?Sized> Drop for CondvarWait<'_, T> {
+    fn drop(&mut self) {
+        if let Some(condvar) = self.condvar {
+            if self.wait_key != WAIT_KEY_NONE {
+                let mut waiters = condvar.waiters.lock().unwrap();
+                if waiters.remove(self.wait_key) {
+                    // We were notified, but then dropped before we could
+                    // lock the mutex. Pass the notification on.
+                    waiters.wake_one();
+                }
+            }
+        }
+    }
+}
@@ -3,12 +3,61 @@
 //! This module is only available when the `std` or `alloc` feature of this
 //! library is activated, and it is activated by default.
 
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+mod barrier;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub use self::barrier::{Barrier, BarrierWait, BarrierWaitResult};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+mod condvar;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub use self::condvar::{Condvar, CondvarWait};
+
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "std")]
 mod mutex;
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "std")]
-pub use self::mutex::{MappedMutexGuard, Mutex, MutexGuard, MutexLockFuture};
+pub use self::mutex::{
+    MappedMutexGuard, Mutex, MutexGuard, MutexLockFuture, OwnedMutexGuard, OwnedMutexLockFuture,
+};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+mod notify;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub use self::notify::{Notified, Notify};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+mod once_cell;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub use self::once_cell::{GetOrInit, GetOrTryInit, OnceCell};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+mod rwlock;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub use self::rwlock::{
+    OwnedRwLockReadFuture, OwnedRwLockReadGuard, OwnedRwLockWriteFuture, OwnedRwLockWriteGuard,
+    RwLock, RwLockPriority, RwLockReadFuture, RwLockReadGuard, RwLockWriteFuture, RwLockWriteGuard,
+};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+mod semaphore;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "std")]
+pub use self::semaphore::{
+    Acquire, AcquireOwned, OwnedSemaphorePermit, Semaphore, SemaphorePermit,
+};
 
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(any(feature = "bilock", feature = "sink", feature = "io"))]
@@ -1,11 +1,11 @@
+use crate::task::WakerSet;
 use futures_core::future::{FusedFuture, Future};
-use futures_core::task::{Context, Poll, Waker};
-use slab::Slab;
+use futures_core::task::{Context, Poll};
 use std::cell::UnsafeCell;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
 use std::{fmt, mem};
 
@@ -13,22 +13,48 @@ use std::{fmt, mem};
 ///
 /// # Fairness
 ///
-/// This mutex provides no fairness guarantees. Tasks may not acquire the mutex
-/// in the order that they requested the lock, and it's possible for a single task
-/// which repeatedly takes the lock to starve other tasks, which may be left waiting
-/// indefinitely.
+/// Tasks waiting for the lock acquire it in the order in which they started
+/// waiting: a lock which is released while tasks are waiting is handed to the
+/// one which has been waiting the longest, and
+/// [`try_lock`](Mutex::try_lock) never takes the lock from a waiting task.
+/// This prevents a single task which repeatedly takes the lock from starving
+/// the others.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::lock::Mutex;
+/// use std::sync::Arc;
+///
+/// let mutex = Arc::new(Mutex::new(0));
+///
+/// let mut guard = mutex.clone().lock_owned().await;
+/// *guard += 1;
+/// assert!(mutex.try_lock().is_none());
+///
+/// drop(guard);
+/// assert_eq!(*mutex.lock().await, 1);
+/// # });
+/// ```
 pub struct Mutex<T: ?Sized> {
-    state: AtomicUsize,
-    waiters: StdMutex<Slab<Waiter>>,
+    state: StdMutex<State>,
     value: UnsafeCell<T>,
 }
 
+struct State {
+    is_locked: bool,
+    // A waiter which has been woken has been handed the lock, which it takes
+    // once it is polled.
+    waiters: WakerSet,
+}
+
 impl<T: ?Sized> fmt::Debug for Mutex<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let state = self.state.load(Ordering::SeqCst);
+        let state = self.state.lock().unwrap();
         f.debug_struct("Mutex")
-            .field("is_locked", &((state & IS_LOCKED) != 0))
-            .field("has_waiters", &((state & HAS_WAITERS) != 0))
+            .field("is_locked", &state.is_locked)
+            .field("has_waiters", &!state.waiters.is_empty())
             .finish()
     }
 }
@@ -45,36 +71,11 @@ impl<T: Default> Default for Mutex<T> {
     }
 }
 
-enum Waiter {
-    Waiting(Waker),
-    Woken,
-}
-
-impl Waiter {
-    fn register(&mut self, waker: &Waker) {
-        match self {
-            Self::Waiting(w) if waker.will_wake(w) => {}
-            _ => *self = Self::Waiting(waker.clone()),
-        }
-    }
-
-    fn wake(&mut self) {
-        match mem::replace(self, Self::Woken) {
-            Self::Waiting(waker) => waker.wake(),
-            Self::Woken => {}
-        }
-    }
-}
-
-const IS_LOCKED: usize = 1 << 0;
-const HAS_WAITERS: usize = 1 << 1;
-
 impl<T> Mutex<T> {
     /// Creates a new futures-aware mutex.
     pub fn new(t: T) -> Self {
         Self {
-            state: AtomicUsize::new(0),
-            waiters: StdMutex::new(Slab::new()),
+            state: StdMutex::new(State { is_locked: false, waiters: WakerSet::new() }),
             value: UnsafeCell::new(t),
         }
     }
@@ -97,16 +98,29 @@ impl<T> Mutex<T> {
 impl<T: ?Sized> Mutex<T> {
     /// Attempt to acquire the lock immediately.
     ///
-    /// If the lock is currently held, this will return `None`.
+    /// If the lock is currently held, or if other tasks are already waiting
+    /// for it, this will return `None`.
     pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
-        let old_state = self.state.fetch_or(IS_LOCKED, Ordering::Acquire);
-        if (old_state & IS_LOCKED) == 0 {
+        if self.try_take() {
             Some(MutexGuard { mutex: self })
         } else {
             None
         }
     }
 
+    /// Attempt to acquire the lock immediately, returning a guard which owns
+    /// a handle to the mutex.
+    ///
+    /// If the lock is currently held, or if other tasks are already waiting
+    /// for it, this will return `None`.
+    pub fn try_lock_owned(self: Arc<Self>) -> Option<OwnedMutexGuard<T>> {
+        if self.try_take() {
+            Some(OwnedMutexGuard { mutex: self })
+        } else {
+            None
+        }
+    }
+
     /// Acquire the lock asynchronously.
     ///
     /// This method returns a future that will resolve once the lock has been
@@ -115,6 +129,16 @@ impl<T: ?Sized> Mutex<T> {
         MutexLockFuture { mutex: Some(self), wait_key: WAIT_KEY_NONE }
     }
 
+    /// Acquire the lock asynchronously, resolving to a guard which owns a
+    /// handle to the mutex.
+    ///
+    /// Unlike the guard returned by [`lock`](Mutex::lock), the guard isn't
+    /// tied to a borrow of the mutex, so it can be held across the await
+    /// points of a spawned task, or moved into one.
+    pub fn lock_owned(self: Arc<Self>) -> OwnedMutexLockFuture<T> {
+        OwnedMutexLockFuture { mutex: Some(self), wait_key: WAIT_KEY_NONE }
+    }
+
     /// Returns a mutable reference to the underlying data.
     ///
     /// Since this call borrows the `Mutex` mutably, no actual locking needs to
@@ -137,42 +161,60 @@ impl<T: ?Sized> Mutex<T> {
         unsafe { &mut *self.value.get() }
     }
 
-    fn remove_waker(&self, wait_key: usize, wake_another: bool) {
-        if wait_key != WAIT_KEY_NONE {
-            let mut waiters = self.waiters.lock().unwrap();
-            match waiters.remove(wait_key) {
-                Waiter::Waiting(_) => {}
-                Waiter::Woken => {
-                    // We were awoken, but then dropped before we could
-                    // wake up to acquire the lock. Wake up another
-                    // waiter.
-                    if wake_another {
-                        if let Some((_i, waiter)) = waiters.iter_mut().next() {
-                            waiter.wake();
-                        }
-                    }
-                }
+    fn try_take(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.is_locked {
+            false
+        } else {
+            state.is_locked = true;
+            true
+        }
+    }
+
+    fn poll_take(&self, wait_key: &mut usize, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if *wait_key == WAIT_KEY_NONE {
+            if !state.is_locked {
+                state.is_locked = true;
+                return Poll::Ready(());
             }
-            if waiters.is_empty() {
-                self.state.fetch_and(!HAS_WAITERS, Ordering::Relaxed); // released by mutex unlock
+
+            *wait_key = state.waiters.insert(cx.waker());
+            return Poll::Pending;
+        }
+
+        futures_core::ready!(state.waiters.poll_woken(*wait_key, cx));
+        *wait_key = WAIT_KEY_NONE;
+        Poll::Ready(())
+    }
+
+    fn cancel_take(&self, wait_key: usize) {
+        if wait_key != WAIT_KEY_NONE {
+            let mut state = self.state.lock().unwrap();
+            if state.waiters.remove(wait_key) {
+                // We were handed the lock, but then dropped before we could
+                // take it. Hand it to the next waiter.
+                state.release();
             }
         }
     }
 
-    // Unlocks the mutex. Called by MutexGuard and MappedMutexGuard when they are
-    // dropped.
+    // Unlocks the mutex. Called by the guards when they are dropped.
     fn unlock(&self) {
-        let old_state = self.state.fetch_and(!IS_LOCKED, Ordering::AcqRel);
-        if (old_state & HAS_WAITERS) != 0 {
-            let mut waiters = self.waiters.lock().unwrap();
-            if let Some((_i, waiter)) = waiters.iter_mut().next() {
-                waiter.wake();
-            }
+        self.state.lock().unwrap().release();
+    }
+}
+
+impl State {
+    fn release(&mut self) {
+        // The lock stays locked if it's handed to a waiter.
+        if !self.waiters.wake_one() {
+            self.is_locked = false;
         }
     }
 }
 
-// Sentinel for when no slot in the `Slab` has been dedicated to this object.
+// Sentinel for when no key in the `WakerSet` has been dedicated to this object.
 const WAIT_KEY_NONE: usize = usize::max_value();
 
 /// A future which resolves when the target mutex has been successfully acquired.
@@ -205,50 +247,115 @@ impl<'a, T: ?Sized> Future for MutexLockFuture<'a, T> {
     type Output = MutexGuard<'a, T>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let mutex = self.mutex.expect("polled MutexLockFuture after completion");
+        let this = &mut *self;
+        let mutex = this.mutex.expect("polled MutexLockFuture after completion");
+        futures_core::ready!(mutex.poll_take(&mut this.wait_key, cx));
+        this.mutex = None;
+        Poll::Ready(MutexGuard { mutex })
+    }
+}
 
-        if let Some(lock) = mutex.try_lock() {
-            mutex.remove_waker(self.wait_key, false);
-            self.mutex = None;
-            return Poll::Ready(lock);
+impl<T: ?Sized> Drop for MutexLockFuture<'_, T> {
+    fn drop(&mut self) {
+        if let Some(mutex) = self.mutex {
+            // This future was dropped before it acquired the mutex.
+            //
+            // Remove ourselves from the waiters, handing the lock to another
+            // waiter if it had been handed to us.
+            mutex.cancel_take(self.wait_key);
         }
+    }
+}
 
-        {
-            let mut waiters = mutex.waiters.lock().unwrap();
-            if self.wait_key == WAIT_KEY_NONE {
-                self.wait_key = waiters.insert(Waiter::Waiting(cx.waker().clone()));
-                if waiters.len() == 1 {
-                    mutex.state.fetch_or(HAS_WAITERS, Ordering::Relaxed); // released by mutex unlock
-                }
-            } else {
-                waiters[self.wait_key].register(cx.waker());
-            }
-        }
+/// A future which resolves when the target mutex has been successfully
+/// acquired, returned by [`Mutex::lock_owned`].
+pub struct OwnedMutexLockFuture<T: ?Sized> {
+    // `None` indicates that the mutex was successfully acquired.
+    mutex: Option<Arc<Mutex<T>>>,
+    wait_key: usize,
+}
 
-        // Ensure that we haven't raced `MutexGuard::drop`'s unlock path by
-        // attempting to acquire the lock again.
-        if let Some(lock) = mutex.try_lock() {
-            mutex.remove_waker(self.wait_key, false);
-            self.mutex = None;
-            return Poll::Ready(lock);
-        }
+impl<T: ?Sized> fmt::Debug for OwnedMutexLockFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedMutexLockFuture")
+            .field("was_acquired", &self.mutex.is_none())
+            .field("mutex", &self.mutex)
+            .field(
+                "wait_key",
+                &(if self.wait_key == WAIT_KEY_NONE { None } else { Some(self.wait_key) }),
+            )
+            .finish()
+    }
+}
 
-        Poll::Pending
+impl<T: ?Sized> FusedFuture for OwnedMutexLockFuture<T> {
+    fn is_terminated(&self) -> bool {
+        self.mutex.is_none()
     }
 }
 
-impl<T: ?Sized> Drop for MutexLockFuture<'_, T> {
+impl<T: ?Sized> Future for OwnedMutexLockFuture<T> {
+    type Output = OwnedMutexGuard<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mutex = this.mutex.as_ref().expect("polled OwnedMutexLockFuture after completion");
+        futures_core::ready!(mutex.poll_take(&mut this.wait_key, cx));
+        let mutex = this.mutex.take().unwrap();
+        Poll::Ready(OwnedMutexGuard { mutex })
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedMutexLockFuture<T> {
     fn drop(&mut self) {
-        if let Some(mutex) = self.mutex {
-            // This future was dropped before it acquired the mutex.
-            //
-            // Remove ourselves from the map, waking up another waiter if we
-            // had been awoken to acquire the lock.
-            mutex.remove_waker(self.wait_key, true);
+        if let Some(mutex) = &self.mutex {
+            mutex.cancel_take(self.wait_key);
         }
     }
 }
 
+/// An RAII guard returned by the `lock_owned` and `try_lock_owned` methods.
+/// When this structure is dropped (falls out of scope), the lock will be
+/// unlocked.
+pub struct OwnedMutexGuard<T: ?Sized> {
+    mutex: Arc<Mutex<T>>,
+}
+
+impl<T: ?Sized> OwnedMutexGuard<T> {
+    /// Returns the mutex this guard has locked.
+    pub fn mutex(this: &Self) -> &Arc<Mutex<T>> {
+        &this.mutex
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for OwnedMutexGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedMutexGuard")
+            .field("value", &&**self)
+            .field("mutex", &self.mutex)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedMutexGuard<T> {
+    fn drop(&mut self) {
+        self.mutex.unlock()
+    }
+}
+
+impl<T: ?Sized> Deref for OwnedMutexGuard<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for OwnedMutexGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
 /// An RAII guard returned by the `lock` and `try_lock` methods.
 /// When this structure is dropped (falls out of scope), the lock will be
 /// unlocked.
@@ -257,6 +364,11 @@ pub struct MutexGuard<'a, T: ?Sized> {
 }
 
 impl<'a, T: ?Sized> MutexGuard<'a, T> {
+    /// Returns the mutex this guard has locked.
+    pub fn mutex(this: &Self) -> &'a Mutex<T> {
+        this.mutex
+    }
+
     /// Returns a locked view over a portion of the locked data.
     ///
     /// # Example
@@ -389,10 +501,15 @@ unsafe impl<T: ?Sized + Send> Send for MutexLockFuture<'_, T> {}
 // doesn't have any interesting `&self` methods (only Debug)
 unsafe impl<T: ?Sized> Sync for MutexLockFuture<'_, T> {}
 
+unsafe impl<T: ?Sized + Send> Send for OwnedMutexLockFuture<T> {}
+unsafe impl<T: ?Sized> Sync for OwnedMutexLockFuture<T> {}
+
 // Safe to send since we don't track any thread-specific details-- the inner
-// lock is essentially spinlock-equivalent (attempt to flip an atomic bool)
+// lock is essentially spinlock-equivalent (attempt to flip a bool)
 unsafe impl<T: ?Sized + Send> Send for MutexGuard<'_, T> {}
 unsafe impl<T: ?Sized + Sync> Sync for MutexGuard<'_, T> {}
+unsafe impl<T: ?Sized + Send> Send for OwnedMutexGuard<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for OwnedMutexGuard<T> {}
 unsafe impl<T: ?Sized + Send, U: ?Sized + Send> Send for MappedMutexGuard<'_, T, U> {}
 unsafe impl<T: ?Sized + Sync, U: ?Sized + Sync> Sync for MappedMutexGuard<'_, T, U> {}
 
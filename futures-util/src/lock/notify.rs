@@ -0,0 +1,221 @@
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll, Waker};
+use slab::Slab;
+use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
+use std::pin::Pin;
+use std::sync::Mutex as StdMutex;
+
+/// A futures-aware notification primitive, letting tasks wait for a signal
+/// from other tasks.
+///
+/// Unlike [`AtomicWaker`](crate::task::AtomicWaker), any number of tasks can
+/// wait on a `Notify` at the same time. [`notify_one`](Notify::notify_one)
+/// wakes one of them, and stores a permit if none is waiting, so that the
+/// next call to [`notified`](Notify::notified) completes right away instead of
+/// losing the notification. [`notify_waiters`](Notify::notify_waiters) wakes
+/// all of them.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::lock::Notify;
+///
+/// let notify = Notify::new();
+///
+/// // The notification is stored until a task waits for it.
+/// notify.notify_one();
+/// notify.notified().await;
+/// # });
+/// ```
+pub struct Notify {
+    state: StdMutex<State>,
+}
+
+struct State {
+    // Whether a `notify_one` call is stored for the next waiter.
+    permit: bool,
+    // Incremented by each `notify_waiters` call.
+    generation: usize,
+    waiters: Slab<Waiter>,
+    // The keys of the waiters which haven't been notified yet, in the order in
+    // which they started waiting.
+    queue: VecDeque<usize>,
+}
+
+enum Waiter {
+    Waiting(Waker),
+    // The waiter has been picked by `notify_one`.
+    Notified,
+}
+
+impl State {
+    fn notify_one(&mut self) {
+        match self.queue.pop_front() {
+            Some(key) => {
+                if let Waiter::Waiting(waker) =
+                    mem::replace(&mut self.waiters[key], Waiter::Notified)
+                {
+                    waker.wake();
+                }
+            }
+            None => self.permit = true,
+        }
+    }
+}
+
+impl fmt::Debug for Notify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Notify")
+            .field("permit", &state.permit)
+            .field("waiters", &state.queue.len())
+            .finish()
+    }
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Notify {
+    /// Creates a new `Notify`, without a stored permit.
+    pub fn new() -> Self {
+        Self {
+            state: StdMutex::new(State {
+                permit: false,
+                generation: 0,
+                waiters: Slab::new(),
+                queue: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Waits for a notification.
+    ///
+    /// The returned future completes once it is picked by
+    /// [`notify_one`](Notify::notify_one), after consuming the stored permit
+    /// if there is one when it is first polled, or after any call to
+    /// [`notify_waiters`](Notify::notify_waiters) made since it was created.
+    pub fn notified(&self) -> Notified<'_> {
+        let generation = self.state.lock().unwrap().generation;
+        Notified { notify: Some(self), generation, wait_key: WAIT_KEY_NONE }
+    }
+
+    /// Notifies the task which has been waiting the longest.
+    ///
+    /// If no task is waiting, a permit is stored for the next one, making it
+    /// complete right away. At most one permit is stored, however many times
+    /// this is called.
+    pub fn notify_one(&self) {
+        self.state.lock().unwrap().notify_one();
+    }
+
+    /// Notifies all tasks which are waiting, including those which haven't
+    /// polled their [`Notified`] future yet.
+    ///
+    /// This doesn't store a permit for tasks which start waiting later.
+    pub fn notify_waiters(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.generation = state.generation.wrapping_add(1);
+
+        let state = &mut *state;
+        for key in state.queue.drain(..) {
+            if let Waiter::Waiting(waker) = &state.waiters[key] {
+                waker.wake_by_ref();
+            }
+        }
+    }
+}
+
+// Sentinel for when no slot in the `Slab` has been dedicated to this object.
+const WAIT_KEY_NONE: usize = usize::max_value();
+
+/// A future which resolves when a notification has been received, returned by
+/// [`Notify::notified`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Notified<'a> {
+    // `None` indicates that a notification was received.
+    notify: Option<&'a Notify>,
+    // The `notify_waiters` generation when this future was created.
+    generation: usize,
+    wait_key: usize,
+}
+
+impl fmt::Debug for Notified<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notified")
+            .field("was_notified", &self.notify.is_none())
+            .field("notify", &self.notify)
+            .finish()
+    }
+}
+
+impl FusedFuture for Notified<'_> {
+    fn is_terminated(&self) -> bool {
+        self.notify.is_none()
+    }
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let notify = self.notify.expect("polled Notified after completion");
+        let mut state = notify.state.lock().unwrap();
+
+        if self.wait_key == WAIT_KEY_NONE {
+            if state.generation != self.generation || mem::replace(&mut state.permit, false) {
+                drop(state);
+                self.notify = None;
+                return Poll::Ready(());
+            }
+
+            self.wait_key = state.waiters.insert(Waiter::Waiting(cx.waker().clone()));
+            state.queue.push_back(self.wait_key);
+            return Poll::Pending;
+        }
+
+        let generation_changed = state.generation != self.generation;
+        let notified = match &mut state.waiters[self.wait_key] {
+            Waiter::Notified => true,
+            Waiter::Waiting(_) if generation_changed => true,
+            Waiter::Waiting(waker) => {
+                if !waker.will_wake(cx.waker()) {
+                    *waker = cx.waker().clone();
+                }
+                false
+            }
+        };
+        if !notified {
+            return Poll::Pending;
+        }
+
+        state.waiters.remove(self.wait_key);
+        drop(state);
+        self.notify = None;
+        self.wait_key = WAIT_KEY_NONE;
+        Poll::Ready(())
+    }
+}
+
+impl Drop for Notified<'_> {
+    fn drop(&mut self) {
+        let wait_key = self.wait_key;
+        if let Some(notify) = self.notify {
+            if wait_key != WAIT_KEY_NONE {
+                let mut state = notify.state.lock().unwrap();
+                match state.waiters.remove(wait_key) {
+                    Waiter::Waiting(_) => state.queue.retain(|&key| key != wait_key),
+                    // We were picked by `notify_one`, but then dropped before
+                    // we could complete. Pass the notification on.
+                    Waiter::Notified => state.notify_one(),
+                }
+            }
+        }
+    }
+}
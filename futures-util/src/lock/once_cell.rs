@@ -0,0 +1,345 @@
+use crate::task::WakerSet;
+use futures_core::future::{Future, TryFuture};
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex as StdMutex;
+
+/// A futures-aware cell which can be written to only once.
+///
+/// The value is usually initialized with
+/// [`get_or_init`](OnceCell::get_or_init), by whichever task gets there
+/// first: its initializer runs while the other tasks wait for the value,
+/// without running their own initializers. This suits expensive
+/// asynchronous setup which must happen once, such as a handshake.
+///
+/// If the task initializing the cell gives up, because its future is dropped
+/// or because the initializer of
+/// [`get_or_try_init`](OnceCell::get_or_try_init) fails, the task which has
+/// been waiting the longest runs its own initializer instead. So the cell is
+/// initialized at most once, by at most one initializer at a time.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future;
+/// use futures::lock::OnceCell;
+///
+/// let cell = OnceCell::new();
+///
+/// let (first, second) =
+///     future::join(cell.get_or_init(|| async { 1 }), cell.get_or_init(|| async { 2 })).await;
+/// assert_eq!((*first, *second), (1, 1));
+/// assert_eq!(cell.get(), Some(&1));
+/// # });
+/// ```
+pub struct OnceCell<T> {
+    is_initialized: AtomicBool,
+    state: StdMutex<State>,
+    value: UnsafeCell<Option<T>>,
+}
+
+struct State {
+    // Whether a task is running its initializer.
+    is_initializing: bool,
+    // A waiter which has been woken before the cell is initialized has been
+    // handed the job of initializing it.
+    waiters: WakerSet,
+}
+
+impl State {
+    fn release(&mut self) {
+        if !self.waiters.wake_one() {
+            self.is_initializing = false;
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OnceCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnceCell").field("value", &self.get()).finish()
+    }
+}
+
+impl<T> Default for OnceCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for OnceCell<T> {
+    fn from(value: T) -> Self {
+        Self {
+            is_initialized: AtomicBool::new(true),
+            state: StdMutex::new(State { is_initializing: false, waiters: WakerSet::new() }),
+            value: UnsafeCell::new(Some(value)),
+        }
+    }
+}
+
+impl<T> OnceCell<T> {
+    /// Creates a new, uninitialized cell.
+    pub fn new() -> Self {
+        Self {
+            is_initialized: AtomicBool::new(false),
+            state: StdMutex::new(State { is_initializing: false, waiters: WakerSet::new() }),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Returns the value of the cell, or `None` if it isn't initialized yet.
+    pub fn get(&self) -> Option<&T> {
+        if self.is_initialized.load(Ordering::Acquire) {
+            // Safety: the value is never written to again once initialized.
+            unsafe { (*self.value.get()).as_ref() }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the value of the cell, or `None` if it
+    /// isn't initialized yet.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.value.get_mut().as_mut()
+    }
+
+    /// Initializes the cell with `value`, unless it's already initialized
+    /// or being initialized, in which case `value` is given back.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let state = self.state.lock().unwrap();
+        if state.is_initializing || self.is_initialized.load(Ordering::Acquire) {
+            return Err(value);
+        }
+        unsafe { *self.value.get() = Some(value) };
+        self.is_initialized.store(true, Ordering::Release);
+        drop(state);
+        Ok(())
+    }
+
+    /// Consumes the cell, returning its value if it's initialized.
+    pub fn into_inner(self) -> Option<T> {
+        self.value.into_inner()
+    }
+
+    /// Returns the value of the cell, initializing it with the future
+    /// returned by `init` if it isn't initialized yet.
+    ///
+    /// `init` is only called if no other task initializes the cell first.
+    pub fn get_or_init<F, Fut>(&self, init: F) -> GetOrInit<'_, T, F, Fut>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        GetOrInit { future: None, init: Some(init), initializer: Initializer::new(self) }
+    }
+
+    /// Returns the value of the cell, initializing it with the future
+    /// returned by `init` if it isn't initialized yet.
+    ///
+    /// `init` is only called if no other task initializes the cell first. If
+    /// the future returned by `init` fails, the cell is left uninitialized
+    /// and the error is returned, while another waiting task, if any, gets to
+    /// initialize the cell.
+    pub fn get_or_try_init<F, Fut>(&self, init: F) -> GetOrTryInit<'_, T, F, Fut>
+    where
+        F: FnOnce() -> Fut,
+        Fut: TryFuture<Ok = T>,
+    {
+        GetOrTryInit { future: None, init: Some(init), initializer: Initializer::new(self) }
+    }
+}
+
+// Sentinel for when no key in the `WakerSet` has been dedicated to this object.
+const WAIT_KEY_NONE: usize = usize::max_value();
+
+// The part of the futures initializing a cell which waits for the cell to be
+// initialized, or for the job of initializing it.
+struct Initializer<'a, T> {
+    cell: &'a OnceCell<T>,
+    wait_key: usize,
+    is_initializing: bool,
+}
+
+impl<'a, T> Initializer<'a, T> {
+    fn new(cell: &'a OnceCell<T>) -> Self {
+        Self { cell, wait_key: WAIT_KEY_NONE, is_initializing: false }
+    }
+
+    /// Returns the value of the cell once it's initialized, or `None` once
+    /// this task has to initialize it.
+    fn poll_value(&mut self, cx: &mut Context<'_>) -> Poll<Option<&'a T>> {
+        if let Some(value) = self.cell.get() {
+            return Poll::Ready(Some(value));
+        }
+
+        let mut state = self.cell.state.lock().unwrap();
+        if self.wait_key == WAIT_KEY_NONE {
+            if let Some(value) = self.cell.get() {
+                return Poll::Ready(Some(value));
+            }
+            if state.is_initializing {
+                self.wait_key = state.waiters.insert(cx.waker());
+                return Poll::Pending;
+            }
+            state.is_initializing = true;
+        } else {
+            ready!(state.waiters.poll_woken(self.wait_key, cx));
+            self.wait_key = WAIT_KEY_NONE;
+            if let Some(value) = self.cell.get() {
+                return Poll::Ready(Some(value));
+            }
+        }
+        self.is_initializing = true;
+        Poll::Ready(None)
+    }
+
+    fn complete(&mut self, value: T) -> &'a T {
+        let mut state = self.cell.state.lock().unwrap();
+        // Safety: no other task reads or writes the value until
+        // `is_initialized` is set, as this task is initializing it.
+        unsafe { *self.cell.value.get() = Some(value) };
+        self.cell.is_initialized.store(true, Ordering::Release);
+        self.is_initializing = false;
+        state.is_initializing = false;
+        state.waiters.wake_all();
+        drop(state);
+        self.cell.get().unwrap()
+    }
+
+    fn abort(&mut self) {
+        if self.is_initializing {
+            self.is_initializing = false;
+            self.cell.state.lock().unwrap().release();
+        }
+    }
+}
+
+impl<T> Drop for Initializer<'_, T> {
+    fn drop(&mut self) {
+        if self.is_initializing {
+            // Give the job of initializing the cell to another task.
+            self.abort();
+        } else if self.wait_key != WAIT_KEY_NONE {
+            let mut state = self.cell.state.lock().unwrap();
+            if state.waiters.remove(self.wait_key) && self.cell.get().is_none() {
+                // We were handed the job of initializing the cell, but then
+                // dropped before we could start. Hand it to the next waiter.
+                state.release();
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Future for the [`OnceCell::get_or_init`] method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct GetOrInit<'a, T, F, Fut> {
+        // Dropped before `initializer`, so that the initialization is
+        // cancelled before it's handed to another task.
+        #[pin]
+        future: Option<Fut>,
+        init: Option<F>,
+        initializer: Initializer<'a, T>,
+    }
+}
+
+impl<'a, T, F, Fut> Future for GetOrInit<'a, T, F, Fut>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = T>,
+{
+    type Output = &'a T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        loop {
+            if let Some(future) = this.future.as_mut().as_pin_mut() {
+                let value = ready!(future.poll(cx));
+                this.future.set(None);
+                return Poll::Ready(this.initializer.complete(value));
+            }
+
+            match ready!(this.initializer.poll_value(cx)) {
+                Some(value) => return Poll::Ready(value),
+                None => {
+                    let init = this.init.take().expect("polled GetOrInit after completion");
+                    this.future.set(Some(init()));
+                }
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug, F, Fut> fmt::Debug for GetOrInit<'_, T, F, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GetOrInit")
+            .field("cell", &self.initializer.cell)
+            .field("is_initializing", &self.initializer.is_initializing)
+            .finish()
+    }
+}
+
+pin_project! {
+    /// Future for the [`OnceCell::get_or_try_init`] method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct GetOrTryInit<'a, T, F, Fut> {
+        // Dropped before `initializer`, so that the initialization is
+        // cancelled before it's handed to another task.
+        #[pin]
+        future: Option<Fut>,
+        init: Option<F>,
+        initializer: Initializer<'a, T>,
+    }
+}
+
+impl<'a, T, F, Fut> Future for GetOrTryInit<'a, T, F, Fut>
+where
+    F: FnOnce() -> Fut,
+    Fut: TryFuture<Ok = T>,
+{
+    type Output = Result<&'a T, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        loop {
+            if let Some(future) = this.future.as_mut().as_pin_mut() {
+                let result = ready!(future.try_poll(cx));
+                this.future.set(None);
+                return Poll::Ready(match result {
+                    Ok(value) => Ok(this.initializer.complete(value)),
+                    Err(e) => {
+                        this.initializer.abort();
+                        Err(e)
+                    }
+                });
+            }
+
+            match ready!(this.initializer.poll_value(cx)) {
+                Some(value) => return Poll::Ready(Ok(value)),
+                None => {
+                    let init = this.init.take().expect("polled GetOrTryInit after completion");
+                    this.future.set(Some(init()));
+                }
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug, F, Fut> fmt::Debug for GetOrTryInit<'_, T, F, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GetOrTryInit")
+            .field("cell", &self.initializer.cell)
+            .field("is_initializing", &self.initializer.is_initializing)
+            .finish()
+    }
+}
+
+// The value is shared between threads once initialized, and may be
+// initialized on any thread.
+unsafe impl<T: Send> Send for OnceCell<T> {}
+unsafe impl<T: Send + Sync> Sync for OnceCell<T> {}
@@ -0,0 +1,691 @@
+use crate::task::WakerSet;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll};
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+
+/// A futures-aware reader-writer lock.
+///
+/// Any number of readers can hold the lock at the same time, while a writer
+/// has exclusive access to it. This suits shared state which is read much
+/// more often than it's modified.
+///
+/// # Fairness
+///
+/// A lock which is released while tasks are waiting is handed directly to
+/// them, so that a task which acquires it with
+/// [`try_read`](RwLock::try_read) or [`try_write`](RwLock::try_write) never
+/// takes it from a waiting task. Writers wait for each other in the order in
+/// which they started waiting, and so do readers, while the
+/// [`RwLockPriority`] of the lock decides which of the two go first:
+///
+/// - With [`RwLockPriority::Write`], the default, readers wait while a
+///   writer is waiting, and the lock is handed to the waiting writers first.
+///   This prevents a steady flow of readers from starving the writers.
+/// - With [`RwLockPriority::Read`], readers only wait while a writer holds
+///   the lock, and the lock is handed to all the waiting readers first. This
+///   gives readers the most concurrency, but writers may wait indefinitely.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::lock::RwLock;
+///
+/// let lock = RwLock::new(5);
+///
+/// {
+///     let first = lock.read().await;
+///     let second = lock.read().await;
+///     assert_eq!(*first + *second, 10);
+///     assert!(lock.try_write().is_none());
+/// }
+///
+/// *lock.write().await += 1;
+/// assert_eq!(*lock.read().await, 6);
+/// # });
+/// ```
+pub struct RwLock<T: ?Sized> {
+    state: StdMutex<State>,
+    value: UnsafeCell<T>,
+}
+
+/// Whether an [`RwLock`] hands itself to readers or to writers first.
+///
+/// See the [fairness](RwLock#fairness) section of the `RwLock` docs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RwLockPriority {
+    /// Readers go first.
+    Read,
+    /// Writers go first.
+    Write,
+}
+
+impl Default for RwLockPriority {
+    fn default() -> Self {
+        Self::Write
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Access {
+    Read,
+    Write,
+}
+
+struct State {
+    priority: RwLockPriority,
+    // The number of readers holding the lock.
+    readers: usize,
+    is_write_locked: bool,
+    // A waiter which has been woken has been handed the lock, which it takes
+    // once it is polled.
+    waiting_readers: WakerSet,
+    waiting_writers: WakerSet,
+}
+
+impl State {
+    fn waiters(&mut self, access: Access) -> &mut WakerSet {
+        match access {
+            Access::Read => &mut self.waiting_readers,
+            Access::Write => &mut self.waiting_writers,
+        }
+    }
+
+    fn try_take(&mut self, access: Access) -> bool {
+        match access {
+            Access::Read => {
+                let writers_first = self.priority == RwLockPriority::Write;
+                if self.is_write_locked || (writers_first && !self.waiting_writers.is_empty()) {
+                    return false;
+                }
+                self.readers += 1;
+            }
+            Access::Write => {
+                if self.is_write_locked
+                    || self.readers > 0
+                    || !self.waiting_writers.is_empty()
+                    || !self.waiting_readers.is_empty()
+                {
+                    return false;
+                }
+                self.is_write_locked = true;
+            }
+        }
+        true
+    }
+
+    fn release(&mut self, access: Access) {
+        match access {
+            Access::Read => self.readers -= 1,
+            Access::Write => self.is_write_locked = false,
+        }
+        if self.readers > 0 {
+            return;
+        }
+
+        // The lock is free: hand it to the waiters, if there are any.
+        match self.priority {
+            RwLockPriority::Read => {
+                if !self.wake_readers() {
+                    self.wake_writer();
+                }
+            }
+            RwLockPriority::Write => {
+                if !self.wake_writer() {
+                    self.wake_readers();
+                }
+            }
+        }
+    }
+
+    fn cancel(&mut self, access: Access, wait_key: usize) {
+        if self.waiters(access).remove(wait_key) {
+            // We were handed the lock, but then dropped before we could take
+            // it. Give it back to the other waiters.
+            self.release(access);
+        } else if let Access::Write = access {
+            // Readers may have been waiting only because of this writer.
+            if !self.is_write_locked && self.waiting_writers.is_empty() {
+                self.wake_readers();
+            }
+        }
+    }
+
+    fn wake_readers(&mut self) -> bool {
+        let n = self.waiting_readers.wake_all();
+        self.readers += n;
+        n > 0
+    }
+
+    fn wake_writer(&mut self) -> bool {
+        self.is_write_locked = self.waiting_writers.wake_one();
+        self.is_write_locked
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for RwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("RwLock")
+            .field("priority", &state.priority)
+            .field("readers", &state.readers)
+            .field("is_write_locked", &state.is_write_locked)
+            .field("waiting_readers", &state.waiting_readers.len())
+            .field("waiting_writers", &state.waiting_writers.len())
+            .finish()
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T> RwLock<T> {
+    /// Creates a new futures-aware reader-writer lock, which gives priority
+    /// to writers.
+    pub fn new(t: T) -> Self {
+        Self::with_priority(t, RwLockPriority::default())
+    }
+
+    /// Creates a new futures-aware reader-writer lock, with the given
+    /// priority.
+    pub fn with_priority(t: T, priority: RwLockPriority) -> Self {
+        Self {
+            state: StdMutex::new(State {
+                priority,
+                readers: 0,
+                is_write_locked: false,
+                waiting_readers: WakerSet::new(),
+                waiting_writers: WakerSet::new(),
+            }),
+            value: UnsafeCell::new(t),
+        }
+    }
+
+    /// Consumes this lock, returning the underlying data.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Attempt to acquire the lock for reading immediately.
+    ///
+    /// If the lock can't be acquired without waiting, this will return
+    /// `None`.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        if self.state.lock().unwrap().try_take(Access::Read) {
+            Some(RwLockReadGuard { lock: self })
+        } else {
+            None
+        }
+    }
+
+    /// Attempt to acquire the lock for writing immediately.
+    ///
+    /// If the lock can't be acquired without waiting, this will return
+    /// `None`.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        if self.state.lock().unwrap().try_take(Access::Write) {
+            Some(RwLockWriteGuard { lock: self })
+        } else {
+            None
+        }
+    }
+
+    /// Attempt to acquire the lock for reading immediately, returning a guard
+    /// which owns a handle to the lock.
+    ///
+    /// If the lock can't be acquired without waiting, this will return
+    /// `None`.
+    pub fn try_read_owned(self: Arc<Self>) -> Option<OwnedRwLockReadGuard<T>> {
+        if self.state.lock().unwrap().try_take(Access::Read) {
+            Some(OwnedRwLockReadGuard { lock: self })
+        } else {
+            None
+        }
+    }
+
+    /// Attempt to acquire the lock for writing immediately, returning a guard
+    /// which owns a handle to the lock.
+    ///
+    /// If the lock can't be acquired without waiting, this will return
+    /// `None`.
+    pub fn try_write_owned(self: Arc<Self>) -> Option<OwnedRwLockWriteGuard<T>> {
+        if self.state.lock().unwrap().try_take(Access::Write) {
+            Some(OwnedRwLockWriteGuard { lock: self })
+        } else {
+            None
+        }
+    }
+
+    /// Acquire the lock for reading asynchronously.
+    ///
+    /// This method returns a future that will resolve once the lock has been
+    /// successfully acquired.
+    pub fn read(&self) -> RwLockReadFuture<'_, T> {
+        RwLockReadFuture { lock: Some(self), wait_key: WAIT_KEY_NONE }
+    }
+
+    /// Acquire the lock for writing asynchronously.
+    ///
+    /// This method returns a future that will resolve once the lock has been
+    /// successfully acquired.
+    pub fn write(&self) -> RwLockWriteFuture<'_, T> {
+        RwLockWriteFuture { lock: Some(self), wait_key: WAIT_KEY_NONE }
+    }
+
+    /// Acquire the lock for reading asynchronously, resolving to a guard
+    /// which owns a handle to the lock.
+    ///
+    /// Unlike the guard returned by [`read`](RwLock::read), the guard isn't
+    /// tied to a borrow of the lock, so it can be moved into a spawned task.
+    pub fn read_owned(self: Arc<Self>) -> OwnedRwLockReadFuture<T> {
+        OwnedRwLockReadFuture { lock: Some(self), wait_key: WAIT_KEY_NONE }
+    }
+
+    /// Acquire the lock for writing asynchronously, resolving to a guard
+    /// which owns a handle to the lock.
+    ///
+    /// Unlike the guard returned by [`write`](RwLock::write), the guard isn't
+    /// tied to a borrow of the lock, so it can be moved into a spawned task.
+    pub fn write_owned(self: Arc<Self>) -> OwnedRwLockWriteFuture<T> {
+        OwnedRwLockWriteFuture { lock: Some(self), wait_key: WAIT_KEY_NONE }
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the `RwLock` mutably, no actual locking needs
+    /// to take place -- the mutable borrow statically guarantees no locks
+    /// exist.
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.value.get() }
+    }
+
+    fn poll_take(&self, access: Access, wait_key: &mut usize, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if *wait_key == WAIT_KEY_NONE {
+            if state.try_take(access) {
+                return Poll::Ready(());
+            }
+
+            *wait_key = state.waiters(access).insert(cx.waker());
+            return Poll::Pending;
+        }
+
+        futures_core::ready!(state.waiters(access).poll_woken(*wait_key, cx));
+        *wait_key = WAIT_KEY_NONE;
+        Poll::Ready(())
+    }
+
+    fn cancel_take(&self, access: Access, wait_key: usize) {
+        if wait_key != WAIT_KEY_NONE {
+            self.state.lock().unwrap().cancel(access, wait_key);
+        }
+    }
+
+    fn unlock(&self, access: Access) {
+        self.state.lock().unwrap().release(access);
+    }
+}
+
+// Sentinel for when no key in a `WakerSet` has been dedicated to this object.
+const WAIT_KEY_NONE: usize = usize::max_value();
+
+/// A future which resolves when the target lock has been successfully
+/// acquired for reading, returned by [`RwLock::read`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RwLockReadFuture<'a, T: ?Sized> {
+    // `None` indicates that the lock was successfully acquired.
+    lock: Option<&'a RwLock<T>>,
+    wait_key: usize,
+}
+
+impl<T: ?Sized> fmt::Debug for RwLockReadFuture<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RwLockReadFuture")
+            .field("was_acquired", &self.lock.is_none())
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> FusedFuture for RwLockReadFuture<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.lock.is_none()
+    }
+}
+
+impl<'a, T: ?Sized> Future for RwLockReadFuture<'a, T> {
+    type Output = RwLockReadGuard<'a, T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let lock = this.lock.expect("polled RwLockReadFuture after completion");
+        futures_core::ready!(lock.poll_take(Access::Read, &mut this.wait_key, cx));
+        this.lock = None;
+        Poll::Ready(RwLockReadGuard { lock })
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockReadFuture<'_, T> {
+    fn drop(&mut self) {
+        if let Some(lock) = self.lock {
+            lock.cancel_take(Access::Read, self.wait_key);
+        }
+    }
+}
+
+/// A future which resolves when the target lock has been successfully
+/// acquired for writing, returned by [`RwLock::write`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RwLockWriteFuture<'a, T: ?Sized> {
+    // `None` indicates that the lock was successfully acquired.
+    lock: Option<&'a RwLock<T>>,
+    wait_key: usize,
+}
+
+impl<T: ?Sized> fmt::Debug for RwLockWriteFuture<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RwLockWriteFuture")
+            .field("was_acquired", &self.lock.is_none())
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> FusedFuture for RwLockWriteFuture<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.lock.is_none()
+    }
+}
+
+impl<'a, T: ?Sized> Future for RwLockWriteFuture<'a, T> {
+    type Output = RwLockWriteGuard<'a, T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let lock = this.lock.expect("polled RwLockWriteFuture after completion");
+        futures_core::ready!(lock.poll_take(Access::Write, &mut this.wait_key, cx));
+        this.lock = None;
+        Poll::Ready(RwLockWriteGuard { lock })
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockWriteFuture<'_, T> {
+    fn drop(&mut self) {
+        if let Some(lock) = self.lock {
+            lock.cancel_take(Access::Write, self.wait_key);
+        }
+    }
+}
+
+/// A future which resolves when the target lock has been successfully
+/// acquired for reading, returned by [`RwLock::read_owned`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct OwnedRwLockReadFuture<T: ?Sized> {
+    // `None` indicates that the lock was successfully acquired.
+    lock: Option<Arc<RwLock<T>>>,
+    wait_key: usize,
+}
+
+impl<T: ?Sized> fmt::Debug for OwnedRwLockReadFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedRwLockReadFuture")
+            .field("was_acquired", &self.lock.is_none())
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> FusedFuture for OwnedRwLockReadFuture<T> {
+    fn is_terminated(&self) -> bool {
+        self.lock.is_none()
+    }
+}
+
+impl<T: ?Sized> Future for OwnedRwLockReadFuture<T> {
+    type Output = OwnedRwLockReadGuard<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let lock = this.lock.as_ref().expect("polled OwnedRwLockReadFuture after completion");
+        futures_core::ready!(lock.poll_take(Access::Read, &mut this.wait_key, cx));
+        let lock = this.lock.take().unwrap();
+        Poll::Ready(OwnedRwLockReadGuard { lock })
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedRwLockReadFuture<T> {
+    fn drop(&mut self) {
+        if let Some(lock) = &self.lock {
+            lock.cancel_take(Access::Read, self.wait_key);
+        }
+    }
+}
+
+/// A future which resolves when the target lock has been successfully
+/// acquired for writing, returned by [`RwLock::write_owned`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct OwnedRwLockWriteFuture<T: ?Sized> {
+    // `None` indicates that the lock was successfully acquired.
+    lock: Option<Arc<RwLock<T>>>,
+    wait_key: usize,
+}
+
+impl<T: ?Sized> fmt::Debug for OwnedRwLockWriteFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedRwLockWriteFuture")
+            .field("was_acquired", &self.lock.is_none())
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> FusedFuture for OwnedRwLockWriteFuture<T> {
+    fn is_terminated(&self) -> bool {
+        self.lock.is_none()
+    }
+}
+
+impl<T: ?Sized> Future for OwnedRwLockWriteFuture<T> {
+    type Output = OwnedRwLockWriteGuard<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let lock = this.lock.as_ref().expect("polled OwnedRwLockWriteFuture after completion");
+        futures_core::ready!(lock.poll_take(Access::Write, &mut this.wait_key, cx));
+        let lock = this.lock.take().unwrap();
+        Poll::Ready(OwnedRwLockWriteGuard { lock })
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedRwLockWriteFuture<T> {
+    fn drop(&mut self) {
+        if let Some(lock) = &self.lock {
+            lock.cancel_take(Access::Write, self.wait_key);
+        }
+    }
+}
+
+/// An RAII guard returned by the `read` and `try_read` methods.
+/// When this structure is dropped (falls out of scope), the lock will be
+/// released.
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RwLockReadGuard")
+            .field("value", &&**self)
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.unlock(Access::Read)
+    }
+}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+/// An RAII guard returned by the `write` and `try_write` methods.
+/// When this structure is dropped (falls out of scope), the lock will be
+/// released.
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RwLockWriteGuard")
+            .field("value", &&**self)
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.unlock(Access::Write)
+    }
+}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+/// An RAII guard returned by the `read_owned` and `try_read_owned` methods.
+/// When this structure is dropped (falls out of scope), the lock will be
+/// released.
+pub struct OwnedRwLockReadGuard<T: ?Sized> {
+    lock: Arc<RwLock<T>>,
+}
+
+impl<T: ?Sized> OwnedRwLockReadGuard<T> {
+    /// Returns the lock this guard has acquired.
+    pub fn lock(this: &Self) -> &Arc<RwLock<T>> {
+        &this.lock
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for OwnedRwLockReadGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedRwLockReadGuard")
+            .field("value", &&**self)
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedRwLockReadGuard<T> {
+    fn drop(&mut self) {
+        self.lock.unlock(Access::Read)
+    }
+}
+
+impl<T: ?Sized> Deref for OwnedRwLockReadGuard<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+/// An RAII guard returned by the `write_owned` and `try_write_owned`
+/// methods. When this structure is dropped (falls out of scope), the lock
+/// will be released.
+pub struct OwnedRwLockWriteGuard<T: ?Sized> {
+    lock: Arc<RwLock<T>>,
+}
+
+impl<T: ?Sized> OwnedRwLockWriteGuard<T> {
+    /// Returns the lock this guard has acquired.
+    pub fn lock(this: &Self) -> &Arc<RwLock<T>> {
+        &this.lock
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for OwnedRwLockWriteGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedRwLockWriteGuard")
+            .field("value", &&**self)
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedRwLockWriteGuard<T> {
+    fn drop(&mut self) {
+        self.lock.unlock(Access::Write)
+    }
+}
+
+impl<T: ?Sized> Deref for OwnedRwLockWriteGuard<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for OwnedRwLockWriteGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+// Locks can be moved freely between threads and acquired on any thread so
+// long as the inner value can be safely sent between threads, and shared
+// between them for reading.
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+// It's safe to switch which thread the acquire is being attempted on so long
+// as `T` can be accessed on that thread.
+unsafe impl<T: ?Sized + Send + Sync> Send for RwLockReadFuture<'_, T> {}
+unsafe impl<T: ?Sized + Send + Sync> Send for RwLockWriteFuture<'_, T> {}
+unsafe impl<T: ?Sized + Send + Sync> Send for OwnedRwLockReadFuture<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Send for OwnedRwLockWriteFuture<T> {}
+// doesn't have any interesting `&self` methods (only Debug)
+unsafe impl<T: ?Sized> Sync for RwLockReadFuture<'_, T> {}
+unsafe impl<T: ?Sized> Sync for RwLockWriteFuture<'_, T> {}
+unsafe impl<T: ?Sized> Sync for OwnedRwLockReadFuture<T> {}
+unsafe impl<T: ?Sized> Sync for OwnedRwLockWriteFuture<T> {}
+
+unsafe impl<T: ?Sized + Send + Sync> Send for RwLockReadGuard<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for RwLockReadGuard<'_, T> {}
+unsafe impl<T: ?Sized + Send + Sync> Send for RwLockWriteGuard<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for RwLockWriteGuard<'_, T> {}
+unsafe impl<T: ?Sized + Send + Sync> Send for OwnedRwLockReadGuard<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for OwnedRwLockReadGuard<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Send for OwnedRwLockWriteGuard<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for OwnedRwLockWriteGuard<T> {}
@@ -0,0 +1,325 @@
+use crate::task::WakerSet;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll};
+use std::fmt;
+use std::mem;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+
+/// A futures-aware counting semaphore.
+///
+/// A semaphore holds a number of permits, which tasks acquire to access a
+/// shared resource and give back when they are done with it, limiting how
+/// many tasks access the resource at the same time.
+///
+/// # Fairness
+///
+/// Tasks waiting for a permit are served in the order in which they started
+/// waiting: a permit which is given back while tasks are waiting goes to the
+/// one which has been waiting the longest, and
+/// [`try_acquire`](Semaphore::try_acquire) never takes a permit from a waiting
+/// task.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::lock::Semaphore;
+///
+/// let semaphore = Semaphore::new(2);
+///
+/// let first = semaphore.acquire().await;
+/// let second = semaphore.acquire().await;
+/// assert!(semaphore.try_acquire().is_none());
+///
+/// drop(first);
+/// assert!(semaphore.try_acquire().is_some());
+/// # });
+/// ```
+pub struct Semaphore {
+    state: StdMutex<State>,
+}
+
+struct State {
+    // The permits not handed out yet. This is only ever non-zero while no
+    // task is waiting.
+    permits: usize,
+    // A waiter which has been woken has been handed a permit, which it takes
+    // once it is polled.
+    waiters: WakerSet,
+}
+
+impl State {
+    fn release(&mut self, mut permits: usize) {
+        while permits > 0 && self.waiters.wake_one() {
+            permits -= 1;
+        }
+        self.permits += permits;
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Semaphore")
+            .field("permits", &state.permits)
+            .field("waiters", &state.waiters.len())
+            .finish()
+    }
+}
+
+impl Semaphore {
+    /// Creates a new semaphore holding the given number of permits.
+    pub fn new(permits: usize) -> Self {
+        Self { state: StdMutex::new(State { permits, waiters: WakerSet::new() }) }
+    }
+
+    /// Returns the number of permits which can currently be acquired without
+    /// waiting.
+    pub fn available_permits(&self) -> usize {
+        self.state.lock().unwrap().permits
+    }
+
+    /// Adds permits to the semaphore, handing them to the waiting tasks
+    /// first.
+    pub fn add_permits(&self, permits: usize) {
+        self.state.lock().unwrap().release(permits);
+    }
+
+    /// Attempts to acquire a permit immediately.
+    ///
+    /// If no permit is available, or if other tasks are already waiting for
+    /// one, this returns `None`.
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        if self.try_take() {
+            Some(SemaphorePermit { semaphore: self })
+        } else {
+            None
+        }
+    }
+
+    /// Attempts to acquire a permit immediately, returning a permit which
+    /// owns a handle to the semaphore.
+    ///
+    /// If no permit is available, or if other tasks are already waiting for
+    /// one, this returns `None`.
+    pub fn try_acquire_owned(self: Arc<Self>) -> Option<OwnedSemaphorePermit> {
+        if self.try_take() {
+            Some(OwnedSemaphorePermit { semaphore: Some(self) })
+        } else {
+            None
+        }
+    }
+
+    /// Acquires a permit asynchronously.
+    ///
+    /// This method returns a future that will resolve once a permit has been
+    /// handed to this task.
+    pub fn acquire(&self) -> Acquire<'_> {
+        Acquire { semaphore: Some(self), wait_key: WAIT_KEY_NONE }
+    }
+
+    /// Acquires a permit asynchronously, resolving to a permit which owns a
+    /// handle to the semaphore.
+    ///
+    /// Unlike the permit returned by [`acquire`](Semaphore::acquire), the
+    /// permit isn't tied to a borrow of the semaphore, so it can be moved
+    /// into a spawned task.
+    pub fn acquire_owned(self: Arc<Self>) -> AcquireOwned {
+        AcquireOwned { semaphore: Some(self), wait_key: WAIT_KEY_NONE }
+    }
+
+    fn try_take(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.permits > 0 {
+            state.permits -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn poll_take(&self, wait_key: &mut usize, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if *wait_key == WAIT_KEY_NONE {
+            if state.permits > 0 {
+                state.permits -= 1;
+                return Poll::Ready(());
+            }
+
+            *wait_key = state.waiters.insert(cx.waker());
+            return Poll::Pending;
+        }
+
+        futures_core::ready!(state.waiters.poll_woken(*wait_key, cx));
+        *wait_key = WAIT_KEY_NONE;
+        Poll::Ready(())
+    }
+
+    fn cancel_take(&self, wait_key: usize) {
+        if wait_key != WAIT_KEY_NONE {
+            let mut state = self.state.lock().unwrap();
+            if state.waiters.remove(wait_key) {
+                // We were handed a permit, but then dropped before we could
+                // take it. Hand it to the next waiter.
+                state.release(1);
+            }
+        }
+    }
+}
+
+// Sentinel for when no key in the `WakerSet` has been dedicated to this object.
+const WAIT_KEY_NONE: usize = usize::max_value();
+
+/// A future which resolves when a permit has been acquired, returned by
+/// [`Semaphore::acquire`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Acquire<'a> {
+    // `None` indicates that a permit was successfully acquired.
+    semaphore: Option<&'a Semaphore>,
+    wait_key: usize,
+}
+
+impl fmt::Debug for Acquire<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Acquire")
+            .field("was_acquired", &self.semaphore.is_none())
+            .field("semaphore", &self.semaphore)
+            .finish()
+    }
+}
+
+impl FusedFuture for Acquire<'_> {
+    fn is_terminated(&self) -> bool {
+        self.semaphore.is_none()
+    }
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = SemaphorePermit<'a>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let semaphore = this.semaphore.expect("polled Acquire after completion");
+        futures_core::ready!(semaphore.poll_take(&mut this.wait_key, cx));
+        this.semaphore = None;
+        Poll::Ready(SemaphorePermit { semaphore })
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        if let Some(semaphore) = self.semaphore {
+            semaphore.cancel_take(self.wait_key);
+        }
+    }
+}
+
+/// A future which resolves when a permit has been acquired, returned by
+/// [`Semaphore::acquire_owned`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct AcquireOwned {
+    // `None` indicates that a permit was successfully acquired.
+    semaphore: Option<Arc<Semaphore>>,
+    wait_key: usize,
+}
+
+impl fmt::Debug for AcquireOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AcquireOwned")
+            .field("was_acquired", &self.semaphore.is_none())
+            .field("semaphore", &self.semaphore)
+            .finish()
+    }
+}
+
+impl FusedFuture for AcquireOwned {
+    fn is_terminated(&self) -> bool {
+        self.semaphore.is_none()
+    }
+}
+
+impl Future for AcquireOwned {
+    type Output = OwnedSemaphorePermit;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let semaphore = this.semaphore.as_ref().expect("polled AcquireOwned after completion");
+        futures_core::ready!(semaphore.poll_take(&mut this.wait_key, cx));
+        let semaphore = this.semaphore.take().unwrap();
+        Poll::Ready(OwnedSemaphorePermit { semaphore: Some(semaphore) })
+    }
+}
+
+impl Drop for AcquireOwned {
+    fn drop(&mut self) {
+        if let Some(semaphore) = &self.semaphore {
+            semaphore.cancel_take(self.wait_key);
+        }
+    }
+}
+
+/// An RAII permit returned by the `acquire` and `try_acquire` methods.
+/// When this structure is dropped (falls out of scope), the permit is given
+/// back to the semaphore.
+#[must_use = "the permit is given back immediately if unused"]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl SemaphorePermit<'_> {
+    /// Consumes the permit without giving it back to the semaphore, which
+    /// permanently holds one permit less.
+    pub fn forget(self) {
+        mem::forget(self);
+    }
+}
+
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit").field("semaphore", &self.semaphore).finish()
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(1);
+    }
+}
+
+/// An RAII permit returned by the `acquire_owned` and `try_acquire_owned`
+/// methods. When this structure is dropped (falls out of scope), the permit is
+/// given back to the semaphore.
+#[must_use = "the permit is given back immediately if unused"]
+pub struct OwnedSemaphorePermit {
+    // `None` indicates that the permit has been forgotten.
+    semaphore: Option<Arc<Semaphore>>,
+}
+
+impl OwnedSemaphorePermit {
+    /// Returns the semaphore this permit was acquired from.
+    pub fn semaphore(&self) -> &Arc<Semaphore> {
+        self.semaphore.as_ref().unwrap()
+    }
+
+    /// Consumes the permit without giving it back to the semaphore, which
+    /// permanently holds one permit less.
+    pub fn forget(mut self) {
+        self.semaphore = None;
+    }
+}
+
+impl fmt::Debug for OwnedSemaphorePermit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedSemaphorePermit").field("semaphore", &self.semaphore).finish()
+    }
+}
+
+impl Drop for OwnedSemaphorePermit {
+    fn drop(&mut self) {
+        if let Some(semaphore) = &self.semaphore {
+            semaphore.add_permits(1);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use super::Buffer;
+use crate::task::{waker_ref, ArcWake, AtomicWaker};
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Sink for the [`buffer_timed`](super::SinkExt::buffer_timed) method.
+#[must_use = "sinks do nothing unless polled"]
+pub struct BufferTimed<Si: Sink<Item>, Item> {
+    shared: Arc<Shared<Si, Item>>,
+}
+
+pin_project! {
+    /// Future flushing the buffer of a
+    /// [`BufferTimed`](super::BufferTimed) once its timer fires, created by
+    /// the [`buffer_timed`](super::SinkExt::buffer_timed) method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Linger<Si: Sink<Item>, Item, F, Fut> {
+        shared: Arc<Shared<Si, Item>>,
+        new_timer: F,
+        #[pin]
+        timer: Option<Fut>,
+        // The number of flushes of the buffer when the timer was armed
+        batch: usize,
+        // Whether the timer fired and the flush it triggered is in progress
+        flushing: bool,
+    }
+}
+
+struct Shared<Si: Sink<Item>, Item> {
+    inner: Mutex<Inner<Si, Item>>,
+    wakers: Arc<Wakers>,
+}
+
+struct Inner<Si: Sink<Item>, Item> {
+    buffer: Pin<Box<Buffer<Si, Item>>>,
+    // The number of times the buffer was flushed, telling a timer armed for
+    // an earlier batch apart
+    flushes: usize,
+    // Whether items were buffered since the last flush
+    armed: bool,
+    // Whether the sink was closed or dropped
+    closed: bool,
+    // The error of a timed flush, returned by the next operation of the sink
+    error: Option<Si::Error>,
+}
+
+// The underlying sink only wakes the last task which polled it, so both the
+// task using the sink and the one running `Linger` are woken.
+struct Wakers {
+    sink: AtomicWaker,
+    linger: AtomicWaker,
+}
+
+impl ArcWake for Wakers {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.sink.wake();
+        arc_self.linger.wake();
+    }
+}
+
+impl<Si: Sink<Item>, Item> Inner<Si, Item> {
+    fn flushed(&mut self) {
+        self.flushes = self.flushes.wrapping_add(1);
+        self.armed = false;
+    }
+}
+
+impl<Si: Sink<Item>, Item> Shared<Si, Item> {
+    fn poll_sink<T>(
+        &self,
+        cx: &mut Context<'_>,
+        f: impl FnOnce(&mut Inner<Si, Item>, &mut Context<'_>) -> Poll<Result<T, Si::Error>>,
+    ) -> Poll<Result<T, Si::Error>> {
+        self.wakers.sink.register(cx.waker());
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(e) = inner.error.take() {
+            return Poll::Ready(Err(e));
+        }
+        let waker = waker_ref(&self.wakers);
+        f(&mut inner, &mut Context::from_waker(&waker))
+    }
+}
+
+pub(super) fn new<Si, Item, F, Fut>(
+    sink: Si,
+    capacity: usize,
+    new_timer: F,
+) -> (BufferTimed<Si, Item>, Linger<Si, Item, F, Fut>)
+where
+    Si: Sink<Item>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let shared = Arc::new(Shared {
+        inner: Mutex::new(Inner {
+            buffer: Box::pin(Buffer::new(sink, capacity)),
+            flushes: 0,
+            armed: false,
+            closed: false,
+            error: None,
+        }),
+        wakers: Arc::new(Wakers { sink: AtomicWaker::new(), linger: AtomicWaker::new() }),
+    });
+    let linger =
+        Linger { shared: shared.clone(), new_timer, timer: None, batch: 0, flushing: false };
+    (BufferTimed { shared }, linger)
+}
+
+impl<Si: Sink<Item>, Item> fmt::Debug for BufferTimed<Si, Item> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferTimed").finish()
+    }
+}
+
+impl<Si, Item, F, Fut> fmt::Debug for Linger<Si, Item, F, Fut>
+where
+    Si: Sink<Item>,
+    Fut: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Linger")
+            .field("timer", &self.timer)
+            .field("flushing", &self.flushing)
+            .finish()
+    }
+}
+
+impl<Si: Sink<Item>, Item> Drop for BufferTimed<Si, Item> {
+    fn drop(&mut self) {
+        if let Ok(mut inner) = self.shared.inner.lock() {
+            inner.closed = true;
+        }
+        self.shared.wakers.linger.wake();
+    }
+}
+
+impl<Si: Sink<Item>, Item> Sink<Item> for BufferTimed<Si, Item> {
+    type Error = Si::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.shared.poll_sink(cx, |inner, cx| inner.buffer.as_mut().poll_ready(cx))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        let mut inner = self.shared.inner.lock().unwrap();
+        inner.buffer.as_mut().start_send(item)?;
+        // Arm the timer for the first item buffered since the last flush.
+        if !inner.armed {
+            inner.armed = true;
+            self.shared.wakers.linger.wake();
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let res = ready!(self.shared.poll_sink(cx, |inner, cx| {
+            ready!(inner.buffer.as_mut().poll_flush(cx))?;
+            inner.flushed();
+            Poll::Ready(Ok(()))
+        }));
+        // Let the timer of the flushed batch be dropped.
+        self.shared.wakers.linger.wake();
+        Poll::Ready(res)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let res = ready!(self.shared.poll_sink(cx, |inner, cx| {
+            ready!(inner.buffer.as_mut().poll_close(cx))?;
+            inner.flushed();
+            inner.closed = true;
+            Poll::Ready(Ok(()))
+        }));
+        self.shared.wakers.linger.wake();
+        Poll::Ready(res)
+    }
+}
+
+impl<Si, Item, F, Fut> Future for Linger<Si, Item, F, Fut>
+where
+    Si: Sink<Item>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut this = self.project();
+        let shared = &**this.shared;
+        shared.wakers.linger.register(cx.waker());
+
+        loop {
+            let mut inner = shared.inner.lock().unwrap();
+
+            // Unless the sink flushed the batch in the meantime, flush it.
+            if *this.flushing && inner.flushes == *this.batch && inner.error.is_none() {
+                let waker = waker_ref(&shared.wakers);
+                let res =
+                    ready!(inner.buffer.as_mut().poll_flush(&mut Context::from_waker(&waker)));
+                inner.flushed();
+                inner.error = res.err();
+                shared.wakers.sink.wake();
+            }
+            *this.flushing = false;
+
+            if inner.closed {
+                return Poll::Ready(());
+            }
+            if this.timer.is_some() && inner.flushes != *this.batch {
+                this.timer.set(None);
+            }
+            if this.timer.is_none() {
+                if !inner.armed {
+                    return Poll::Pending;
+                }
+                *this.batch = inner.flushes;
+                this.timer.set(Some((this.new_timer)()));
+            }
+            drop(inner);
+
+            ready!(this.timer.as_mut().as_pin_mut().unwrap().poll(cx));
+            this.timer.set(None);
+            *this.flushing = true;
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::pin::Pin;
+use futures_core::task::{Context, Poll};
+use futures_sink::Sink;
+
+/// What a [`FanoutVec`] does when one of its sinks returns an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FanoutErrorPolicy {
+    /// Return the error from the `FanoutVec` right away, leaving all sinks in
+    /// place. This is the default.
+    FailFast,
+    /// Drop the failing sink, discarding its error, and keep going with the
+    /// remaining sinks.
+    DropFailing,
+}
+
+impl Default for FanoutErrorPolicy {
+    fn default() -> Self {
+        Self::FailFast
+    }
+}
+
+/// Sink that clones incoming items and forwards them to any number of sinks
+/// at the same time.
+///
+/// This is the dynamically sized counterpart of [`Fanout`](super::Fanout),
+/// created with the [`fanout_all`] function. Backpressure from any downstream
+/// sink propagates up, which means that this sink can only process items as
+/// fast as its _slowest_ downstream sink.
+///
+/// What happens when one of the sinks fails is configured with
+/// [`error_policy`](FanoutVec::error_policy).
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct FanoutVec<Si> {
+    sinks: Vec<Si>,
+    policy: FanoutErrorPolicy,
+}
+
+/// Creates a sink which broadcasts each item to all of the given sinks.
+///
+/// The sinks must all have the same type and be [`Unpin`]; boxing them can
+/// be used to combine sinks of different types.
+///
+/// This function is only available when the `std` or `alloc` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::sink::{self, SinkExt};
+///
+/// let mut fanout = sink::fanout_all(vec![Vec::new(), Vec::new(), Vec::new()]);
+/// fanout.send(1).await?;
+/// fanout.send(2).await?;
+///
+/// for sink in fanout.into_inner() {
+///     assert_eq!(sink, vec![1, 2]);
+/// }
+/// # Ok::<(), futures::never::Never>(()) }).unwrap();
+/// ```
+pub fn fanout_all<I>(sinks: I) -> FanoutVec<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Unpin,
+{
+    FanoutVec { sinks: sinks.into_iter().collect(), policy: FanoutErrorPolicy::default() }
+}
+
+impl<Si: Unpin> FanoutVec<Si> {
+    /// Creates a new, empty `FanoutVec`, which discards all items until
+    /// sinks are added to it with [`push`](FanoutVec::push).
+    pub fn new() -> Self {
+        Self { sinks: Vec::new(), policy: FanoutErrorPolicy::default() }
+    }
+
+    /// Sets the policy applied when one of the sinks returns an error.
+    pub fn error_policy(mut self, policy: FanoutErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Adds a sink to the set of sinks items are forwarded to.
+    ///
+    /// The new sink only receives the items sent after it has been added.
+    pub fn push(&mut self, sink: Si) {
+        self.sinks.push(sink);
+    }
+
+    /// Returns the number of sinks items are forwarded to.
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    /// Returns `true` if there are no sinks to forward items to.
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Get a shared reference to the inner sinks.
+    pub fn get_ref(&self) -> &[Si] {
+        &self.sinks
+    }
+
+    /// Get a mutable reference to the inner sinks.
+    pub fn get_mut(&mut self) -> &mut [Si] {
+        &mut self.sinks
+    }
+
+    /// Consumes this combinator, returning the underlying sinks.
+    ///
+    /// Note that this may discard intermediate state of this combinator,
+    /// so care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> Vec<Si> {
+        self.sinks
+    }
+
+    /// Applies `f` to every sink, resolving once all of them are ready.
+    fn poll_each<E>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut f: impl FnMut(Pin<&mut Si>, &mut Context<'_>) -> Poll<Result<(), E>>,
+    ) -> Poll<Result<(), E>> {
+        let mut ready = true;
+        let mut i = 0;
+        while i < self.sinks.len() {
+            match f(Pin::new(&mut self.sinks[i]), cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Pending => ready = false,
+                Poll::Ready(Err(e)) => match self.policy {
+                    FanoutErrorPolicy::FailFast => return Poll::Ready(Err(e)),
+                    FanoutErrorPolicy::DropFailing => {
+                        self.sinks.remove(i);
+                        continue;
+                    }
+                },
+            }
+            i += 1;
+        }
+
+        if ready {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<Si: Unpin> Default for FanoutVec<Si> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Si: Unpin> FromIterator<Si> for FanoutVec<Si> {
+    fn from_iter<T: IntoIterator<Item = Si>>(iter: T) -> Self {
+        fanout_all(iter)
+    }
+}
+
+impl<Si: Unpin> Extend<Si> for FanoutVec<Si> {
+    fn extend<T: IntoIterator<Item = Si>>(&mut self, iter: T) {
+        self.sinks.extend(iter)
+    }
+}
+
+impl<Si, Item> Sink<Item> for FanoutVec<Si>
+where
+    Si: Sink<Item> + Unpin,
+    Item: Clone,
+{
+    type Error = Si::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_each(cx, |sink, cx| sink.poll_ready(cx))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+
+        let mut item = Some(item);
+        let mut i = 0;
+        while i < this.sinks.len() {
+            // Move the item into the last sink instead of cloning it.
+            let value = if i + 1 == this.sinks.len() {
+                item.take().unwrap()
+            } else {
+                item.as_ref().unwrap().clone()
+            };
+            if let Err(e) = Pin::new(&mut this.sinks[i]).start_send(value) {
+                match this.policy {
+                    FanoutErrorPolicy::FailFast => return Err(e),
+                    FanoutErrorPolicy::DropFailing => {
+                        this.sinks.remove(i);
+                        continue;
+                    }
+                }
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_each(cx, |sink, cx| sink.poll_flush(cx))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_each(cx, |sink, cx| sink.poll_close(cx))
+    }
+}
@@ -34,10 +34,23 @@ impl<Si: Sink<Item> + Unpin + ?Sized, Item> Future for Feed<'_, Si, Item> {
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
-        let mut sink = Pin::new(&mut this.sink);
-        ready!(sink.as_mut().poll_ready(cx))?;
-        let item = this.item.take().expect("polled Feed after completion");
-        sink.as_mut().start_send(item)?;
-        Poll::Ready(Ok(()))
+        poll_feed(Pin::new(&mut this.sink), cx, &mut this.item)
     }
 }
+
+/// Waits for `sink` to be ready and starts sending `item` to it, without
+/// flushing. The item is left in place for a later call if the sink isn't
+/// ready yet.
+pub(super) fn poll_feed<Si, Item>(
+    mut sink: Pin<&mut Si>,
+    cx: &mut Context<'_>,
+    item: &mut Option<Item>,
+) -> Poll<Result<(), Si::Error>>
+where
+    Si: Sink<Item> + ?Sized,
+{
+    ready!(sink.as_mut().poll_ready(cx))?;
+    let item = item.take().expect("polled Feed after completion");
+    sink.start_send(item)?;
+    Poll::Ready(Ok(()))
+}
@@ -58,6 +58,16 @@ mod buffer;
 #[cfg(feature = "alloc")]
 pub use self::buffer::Buffer;
 
+#[cfg(feature = "std")]
+mod buffer_timed;
+#[cfg(feature = "std")]
+pub use self::buffer_timed::{BufferTimed, Linger};
+
+#[cfg(feature = "alloc")]
+mod fanout_vec;
+#[cfg(feature = "alloc")]
+pub use self::fanout_vec::{fanout_all, FanoutErrorPolicy, FanoutVec};
+
 impl<T: ?Sized, Item> SinkExt<Item> for T where T: Sink<Item> {}
 
 /// An extension trait for `Sink`s that provides a variety of convenient
@@ -91,7 +101,9 @@ pub trait SinkExt<Item>: Sink<Item> {
     ///
     /// To process each value, `f` produces a *stream*, of which each value
     /// is passed to the underlying sink. A new value will not be accepted until
-    /// the stream has been drained
+    /// the stream has been drained. This makes it possible to expand a single
+    /// value into several items of the underlying sink, e.g. to split one
+    /// message into multiple frames.
     ///
     /// Note that this function consumes the given sink, returning a wrapped
     /// version, much like `Iterator::flat_map`.
@@ -179,6 +191,64 @@ pub trait SinkExt<Item>: Sink<Item> {
         assert_sink::<Item, Self::Error, _>(Buffer::new(self, capacity))
     }
 
+    /// Adds a fixed-size buffer to the current sink which also flushes
+    /// itself once items have been buffered for a while.
+    ///
+    /// This behaves like [`buffer`](SinkExt::buffer), except that when an
+    /// item is buffered while no timed flush is pending, `new_timer` is
+    /// called to create a timer future (for instance a delay of the maximum
+    /// time items may linger in the buffer). Once that future completes, the
+    /// buffered items are pushed into the underlying sink and flushed, so
+    /// that a small trailing batch doesn't wait indefinitely for the buffer
+    /// to fill up. An explicit flush disarms the timer.
+    ///
+    /// This library doesn't provide timers, so they are left to the caller
+    /// to supply. They are driven by the returned [`Linger`] future, which
+    /// should be spawned as a task of its own: it runs the timers and the
+    /// flushes they trigger, so that the buffer is flushed even when the sink
+    /// isn't polled again after its last item. An error of a timed flush is
+    /// returned by the next operation of the sink. The future completes once
+    /// the sink is closed or dropped.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::channel::mpsc;
+    /// use futures::executor::LocalPool;
+    /// use futures::future;
+    /// use futures::sink::SinkExt;
+    /// use futures::task::LocalSpawnExt;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let (tx, mut rx) = mpsc::unbounded();
+    ///
+    /// // A timer which has already elapsed, standing in for a real delay.
+    /// let (mut sink, linger) = tx.buffer_timed(16, || future::ready(()));
+    /// pool.spawner().spawn_local(linger)?;
+    ///
+    /// pool.run_until(sink.feed(1))?;
+    /// pool.run_until_stalled();
+    /// assert_eq!(rx.try_next()?, Some(1));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "std")]
+    fn buffer_timed<F, Fut>(
+        self,
+        capacity: usize,
+        new_timer: F,
+    ) -> (BufferTimed<Self, Item>, Linger<Self, Item, F, Fut>)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+        Self: Sized,
+    {
+        let (sink, linger) = buffer_timed::new(self, capacity, new_timer);
+        (assert_sink::<Item, Self::Error, _>(sink), assert_future::<(), _>(linger))
+    }
+
     /// Close the sink.
     fn close(&mut self) -> Close<'_, Self, Item>
     where
@@ -191,6 +261,9 @@ pub trait SinkExt<Item>: Sink<Item> {
     ///
     /// This adapter clones each incoming item and forwards it to both this as well as
     /// the other sink at the same time.
+    ///
+    /// See [`fanout_all`] for broadcasting items to an arbitrary number of
+    /// sinks.
     fn fanout<Si>(self, other: Si) -> Fanout<Self, Si>
     where
         Self: Sized,
@@ -230,6 +303,27 @@ pub trait SinkExt<Item>: Sink<Item> {
     /// Unlike `send`, the returned future does not flush the sink.
     /// It is the caller's responsibility to ensure all pending items
     /// are processed, which can be done via `flush` or `close`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::mpsc;
+    /// use futures::sink::SinkExt;
+    /// use futures::stream::StreamExt;
+    ///
+    /// let (mut tx, rx) = mpsc::unbounded();
+    ///
+    /// // Enqueue a batch of items, then flush once.
+    /// for i in 0..3 {
+    ///     tx.feed(i).await?;
+    /// }
+    /// tx.flush().await?;
+    /// drop(tx);
+    ///
+    /// assert_eq!(rx.collect::<Vec<_>>().await, vec![0, 1, 2]);
+    /// # Ok::<(), mpsc::SendError>(()) }).unwrap();
+    /// ```
     fn feed(&mut self, item: Item) -> Feed<'_, Self, Item>
     where
         Self: Unpin,
@@ -246,13 +340,37 @@ pub trait SinkExt<Item>: Sink<Item> {
     /// been flushed. Note that the sink is **not** closed. If the stream produces
     /// an error, that error will be returned by this future without flushing the sink.
     ///
+    /// Items are handed to the sink as with [`feed`](SinkExt::feed), and the
+    /// sink is only flushed whenever the stream has no item immediately
+    /// available, or once it is exhausted.
+    ///
     /// Doing `sink.send_all(stream)` is roughly equivalent to
     /// `stream.forward(sink)`. The returned future will exhaust all items from
     /// `stream` and send them to `self`.
+    ///
+    /// Any [`TryStream`] whose error type converts into the sink's error can
+    /// be forwarded, its errors being converted with [`Into`]. Since the
+    /// stream is only borrowed, it can still be used afterwards, for instance
+    /// after forwarding only a prefix of it:
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::never::Never;
+    /// use futures::sink::SinkExt;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let mut sink = Vec::new();
+    /// let mut items = stream::iter(1..=5).map(Ok::<_, Never>);
+    ///
+    /// sink.send_all(&mut (&mut items).take(3)).await?;
+    /// assert_eq!(sink, vec![1, 2, 3]);
+    /// assert_eq!(items.next().await, Some(Ok(4)));
+    /// # Ok::<(), futures::never::Never>(()) }).unwrap();
+    /// ```
     fn send_all<'a, St>(&'a mut self, stream: &'a mut St) -> SendAll<'a, Self, St>
     where
-        St: TryStream<Ok = Item, Error = Self::Error> + Stream + Unpin + ?Sized,
-        // St: Stream<Item = Result<Item, Self::Error>> + Unpin + ?Sized,
+        St: TryStream<Ok = Item> + Stream + Unpin + ?Sized,
+        St::Error: Into<Self::Error>,
         Self: Unpin,
     {
         // TODO: type mismatch resolving `<St as Stream>::Item == std::result::Result<Item, <Self as futures_sink::Sink<Item>>::Error>`
@@ -264,7 +382,25 @@ pub trait SinkExt<Item>: Sink<Item> {
     /// of that `Either`.
     ///
     /// This can be used in combination with the `right_sink` method to write `if`
-    /// statements that evaluate to different streams in different branches.
+    /// statements that evaluate to different sinks in different branches,
+    /// without having to box them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::sink::{self, SinkExt};
+    ///
+    /// let discard = false;
+    /// let mut sink = if discard {
+    ///     sink::drain().left_sink()
+    /// } else {
+    ///     Vec::new().right_sink()
+    /// };
+    ///
+    /// sink.send(1).await?;
+    /// # Ok::<(), futures::never::Never>(()) }).unwrap();
+    /// ```
     fn left_sink<Si2>(self) -> Either<Self, Si2>
     where
         Si2: Sink<Item, Error = Self::Error>,
@@ -273,11 +409,12 @@ pub trait SinkExt<Item>: Sink<Item> {
         assert_sink::<Item, Self::Error, _>(Either::Left(self))
     }
 
-    /// Wrap this stream in an `Either` stream, making it the right-hand variant
+    /// Wrap this sink in an `Either` sink, making it the right-hand variant
     /// of that `Either`.
     ///
     /// This can be used in combination with the `left_sink` method to write `if`
-    /// statements that evaluate to different streams in different branches.
+    /// statements that evaluate to different sinks in different branches,
+    /// without having to box them.
     fn right_sink<Si1>(self) -> Either<Si1, Self>
     where
         Si1: Sink<Item, Error = Self::Error>,
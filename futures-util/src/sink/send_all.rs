@@ -1,3 +1,4 @@
+use super::feed::poll_feed;
 use crate::stream::{Fuse, StreamExt, TryStreamExt};
 use core::fmt;
 use core::pin::Pin;
@@ -43,10 +44,11 @@ where
 {
 }
 
-impl<'a, Si, St, Ok, Error> SendAll<'a, Si, St>
+impl<'a, Si, St, Ok> SendAll<'a, Si, St>
 where
-    Si: Sink<Ok, Error = Error> + Unpin + ?Sized,
-    St: TryStream<Ok = Ok, Error = Error> + Stream + Unpin + ?Sized,
+    Si: Sink<Ok> + Unpin + ?Sized,
+    St: TryStream<Ok = Ok> + Stream + Unpin + ?Sized,
+    St::Error: Into<Si::Error>,
 {
     pub(super) fn new(sink: &'a mut Si, stream: &'a mut St) -> Self {
         Self { sink, stream: stream.fuse(), buffered: None }
@@ -58,34 +60,35 @@ where
         item: St::Ok,
     ) -> Poll<Result<(), Si::Error>> {
         debug_assert!(self.buffered.is_none());
-        match Pin::new(&mut self.sink).poll_ready(cx)? {
-            Poll::Ready(()) => Poll::Ready(Pin::new(&mut self.sink).start_send(item)),
-            Poll::Pending => {
-                self.buffered = Some(item);
-                Poll::Pending
-            }
-        }
+        self.buffered = Some(item);
+        self.poll_buffered(cx)
+    }
+
+    fn poll_buffered(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Si::Error>> {
+        poll_feed(Pin::new(&mut *self.sink), cx, &mut self.buffered)
     }
 }
 
 impl<Si, St, Ok, Error> Future for SendAll<'_, Si, St>
 where
-    Si: Sink<Ok, Error = Error> + Unpin + ?Sized,
+    Si: Sink<Ok> + Unpin + ?Sized,
     St: Stream<Item = Result<Ok, Error>> + Unpin + ?Sized,
+    Error: Into<Si::Error>,
 {
-    type Output = Result<(), Error>;
+    type Output = Result<(), Si::Error>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = &mut *self;
         // If we've got an item buffered already, we need to write it to the
         // sink before we can do anything else
-        if let Some(item) = this.buffered.take() {
-            ready!(this.try_start_send(cx, item))?
+        if this.buffered.is_some() {
+            ready!(this.poll_buffered(cx))?
         }
 
         loop {
-            match this.stream.try_poll_next_unpin(cx)? {
-                Poll::Ready(Some(item)) => ready!(this.try_start_send(cx, item))?,
+            match this.stream.try_poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(item))) => ready!(this.try_start_send(cx, item))?,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Err(e.into())),
                 Poll::Ready(None) => {
                     ready!(Pin::new(&mut this.sink).poll_flush(cx))?;
                     return Poll::Ready(Ok(()));
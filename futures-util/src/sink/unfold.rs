@@ -19,6 +19,13 @@ pin_project! {
 
 /// Create a sink from a function which processes one item at a time.
 ///
+/// The sink holds a state, initially `init`. Each item passed to
+/// `start_send` is handed to `function` together with the current state, and
+/// the returned future is then driven to completion by `poll_ready`,
+/// `poll_flush` or `poll_close`, resolving to the state used for the next
+/// item. If the future resolves to an error, the error is returned from the
+/// sink and the state is lost: the sink won't accept any further items.
+///
 /// # Examples
 ///
 /// ```
@@ -73,7 +80,10 @@ where
                     this.state.set(UnfoldState::Value { value: state });
                     Ok(())
                 }
-                Err(err) => Err(err),
+                Err(err) => {
+                    this.state.set(UnfoldState::Empty);
+                    Err(err)
+                }
             }
         } else {
             Ok(())
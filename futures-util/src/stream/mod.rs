@@ -18,9 +18,10 @@ pub use futures_core::stream::{FusedStream, Stream, TryStream};
 #[allow(clippy::module_inception)]
 mod stream;
 pub use self::stream::{
-    Chain, Collect, Concat, Cycle, Enumerate, Filter, FilterMap, FlatMap, Flatten, Fold, ForEach,
-    Fuse, Inspect, Map, Next, NextIf, NextIfEq, Peek, PeekMut, Peekable, Scan, SelectNextSome,
-    Skip, SkipWhile, StreamExt, StreamFuture, Take, TakeUntil, TakeWhile, Then, Unzip, Zip,
+    Chain, Collect, CollectInto, Concat, Count, Cycle, Dedup, DedupByKey, Enumerate, Filter,
+    FilterMap, FlatMap, Flatten, Fold, ForEach, Fuse, Inspect, Map, Next, NextIf, NextIfEq, Peek,
+    PeekMut, Peekable, Scan, SelectNextSome, Skip, SkipWhile, StreamExt, StreamFuture, SwitchMap,
+    Take, TakeUntil, TakeWhile, Then, Unzip, Zip,
 };
 
 #[cfg(feature = "std")]
@@ -32,6 +33,9 @@ pub use self::stream::Chunks;
 #[cfg(feature = "alloc")]
 pub use self::stream::ReadyChunks;
 
+#[cfg(feature = "alloc")]
+pub use self::stream::Windows;
+
 #[cfg(feature = "sink")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sink")))]
 pub use self::stream::Forward;
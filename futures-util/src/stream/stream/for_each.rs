@@ -3,6 +3,7 @@ use core::pin::Pin;
 use futures_core::future::{FusedFuture, Future};
 use futures_core::ready;
 use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::__internal::poll_budget;
 use futures_core::task::{Context, Poll};
 use pin_project_lite::pin_project;
 
@@ -67,6 +68,7 @@ where
             if let Some(fut) = this.future.as_mut().as_pin_mut() {
                 ready!(fut.poll(cx));
                 this.future.set(None);
+                ready!(poll_budget(cx));
             } else if let Some(item) = ready!(this.stream.as_mut().poll_next(cx)) {
                 this.future.set(Some((this.f)(item)));
             } else {
@@ -32,6 +32,10 @@ mod collect;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::collect::Collect;
 
+mod collect_into;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::collect_into::CollectInto;
+
 mod unzip;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::unzip::Unzip;
@@ -40,10 +44,18 @@ mod concat;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::concat::Concat;
 
+mod count;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::count::Count;
+
 mod cycle;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::cycle::Cycle;
 
+mod dedup;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::dedup::{Dedup, DedupByKey};
+
 mod enumerate;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::enumerate::Enumerate;
@@ -161,6 +173,10 @@ mod zip;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::zip::Zip;
 
+mod switch_map;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::switch_map::SwitchMap;
+
 #[cfg(feature = "alloc")]
 mod chunks;
 #[cfg(feature = "alloc")]
@@ -173,6 +189,12 @@ mod ready_chunks;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::ready_chunks::ReadyChunks;
 
+#[cfg(feature = "alloc")]
+mod windows;
+#[cfg(feature = "alloc")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::windows::Windows;
+
 mod scan;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::scan::Scan;
@@ -468,6 +490,11 @@ pub trait StreamExt: Stream {
     /// Transforms a stream into a collection, returning a
     /// future representing the result of that computation.
     ///
+    /// The collection can be of any type implementing [`Default`] and
+    /// [`Extend`], such as `Vec`, `HashSet` or `BTreeMap`. Use
+    /// [`collect_into`](StreamExt::collect_into) to extend an existing
+    /// collection instead.
+    ///
     /// The returned future will be resolved when the stream terminates.
     ///
     /// # Examples
@@ -497,6 +524,36 @@ pub trait StreamExt: Stream {
         assert_future::<C, _>(Collect::new(self))
     }
 
+    /// Extends an existing collection with the items of this stream,
+    /// returning a future which resolves to a reference to that collection.
+    ///
+    /// This is useful when the collection should be reused, or when it
+    /// can't be created with [`Default`], as required by
+    /// [`collect`](StreamExt::collect).
+    ///
+    /// The returned future will be resolved when the stream terminates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    /// use std::collections::HashSet;
+    ///
+    /// let mut set = HashSet::new();
+    /// set.insert(0);
+    ///
+    /// stream::iter(vec![1, 2, 1]).collect_into(&mut set).await;
+    /// assert_eq!(set.len(), 3);
+    /// # });
+    /// ```
+    fn collect_into<C: Extend<Self::Item>>(self, collection: &mut C) -> CollectInto<'_, Self, C>
+    where
+        Self: Sized,
+    {
+        assert_future::<&mut C, _>(CollectInto::new(self, collection))
+    }
+
     /// Converts a stream of pairs into a future, which
     /// resolves to pair of containers.
     ///
@@ -606,6 +663,28 @@ pub trait StreamExt: Stream {
         assert_stream::<Self::Item, _>(Cycle::new(self))
     }
 
+    /// Drives the stream to completion, counting the number of items it
+    /// yields.
+    ///
+    /// The returned future will be resolved when the stream terminates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=10).filter(|x| futures::future::ready(x % 3 == 0));
+    /// assert_eq!(stream.count().await, 3);
+    /// # });
+    /// ```
+    fn count(self) -> Count<Self>
+    where
+        Self: Sized,
+    {
+        assert_future::<usize, _>(Count::new(self))
+    }
+
     /// Execute an accumulating asynchronous computation over a stream,
     /// collecting all the values into one final result.
     ///
@@ -752,6 +831,40 @@ pub trait StreamExt: Stream {
         assert_stream::<U::Item, _>(FlatMap::new(self, f))
     }
 
+    /// Maps each item of this stream to an inner stream, switching over to
+    /// the most recent inner stream as soon as a new item arrives.
+    ///
+    /// Unlike [`StreamExt::flat_map`], which exhausts each inner stream before
+    /// moving on to the next outer item, this combinator drops the current
+    /// inner stream whenever the outer stream yields a new item. Only items
+    /// from the latest inner stream are produced.
+    ///
+    /// The returned stream ends once the outer stream has ended and the last
+    /// inner stream has been exhausted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// // All outer items are ready immediately, so only the inner stream
+    /// // produced for the last one is ever polled.
+    /// let stream = stream::iter(1..=3);
+    /// let stream = stream.switch_map(|x| stream::iter(vec![x; x]));
+    ///
+    /// assert_eq!(vec![3, 3, 3], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn switch_map<U, F>(self, f: F) -> SwitchMap<Self, U, F>
+    where
+        F: FnMut(Self::Item) -> U,
+        U: Stream,
+        Self: Sized,
+    {
+        assert_stream::<U::Item, _>(SwitchMap::new(self, f))
+    }
+
     /// Combinator similar to [`StreamExt::fold`] that holds internal state
     /// and produces a new stream.
     ///
@@ -816,6 +929,62 @@ pub trait StreamExt: Stream {
         assert_stream::<Self::Item, _>(SkipWhile::new(self, f))
     }
 
+    /// Suppresses consecutive duplicate items of this stream.
+    ///
+    /// An item is only yielded if it differs from the item yielded right
+    /// before it, as determined by `PartialEq`. This is useful for watch-style
+    /// streams which emit long runs of identical values and where only changes
+    /// are of interest. Duplicates which are not adjacent are still yielded.
+    ///
+    /// The last yielded item is cloned and retained in order to compare it
+    /// against the following ones; see [`dedup_by_key`](StreamExt::dedup_by_key)
+    /// for a variant which only retains a key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![1, 1, 2, 2, 2, 3, 1]).dedup();
+    ///
+    /// assert_eq!(vec![1, 2, 3, 1], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn dedup(self) -> Dedup<Self>
+    where
+        Self::Item: Clone + PartialEq,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Dedup::new(self))
+    }
+
+    /// Suppresses consecutive items of this stream which map to the same key.
+    ///
+    /// The provided closure is called on each item to compute a key, and the
+    /// item is only yielded if its key differs from the key of the item
+    /// yielded right before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(vec![10, 11, 20, 25, 12]).dedup_by_key(|x| x / 10);
+    ///
+    /// assert_eq!(vec![10, 20, 12], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    fn dedup_by_key<K, F>(self, f: F) -> DedupByKey<Self, K, F>
+    where
+        F: FnMut(&Self::Item) -> K,
+        K: PartialEq,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(DedupByKey::new(self, f))
+    }
+
     /// Take elements from this stream while the provided asynchronous predicate
     /// resolves to `true`.
     ///
@@ -1378,6 +1547,45 @@ pub trait StreamExt: Stream {
         assert_stream::<Vec<Self::Item>, _>(ReadyChunks::new(self, capacity))
     }
 
+    /// An adaptor for yielding overlapping windows over the items of the
+    /// stream.
+    ///
+    /// Each time the underlying stream yields an item, this combinator yields
+    /// a vector containing the last `size` items, oldest first. This is the
+    /// overlapping counterpart of [`chunks`](StreamExt::chunks) and is useful
+    /// for moving-average style computations.
+    ///
+    /// No window is yielded until `size` items have been received, so if the
+    /// underlying stream ends with fewer items than that, the returned stream
+    /// ends without yielding anything.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=4).windows(2);
+    ///
+    /// assert_eq!(vec![vec![1, 2], vec![2, 3], vec![3, 4]], stream.collect::<Vec<_>>().await);
+    /// # });
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn windows(self, size: usize) -> Windows<Self>
+    where
+        Self::Item: Clone,
+        Self: Sized,
+    {
+        assert_stream::<Vec<Self::Item>, _>(Windows::new(self, size))
+    }
+
     /// A future that completes after the given stream has been fully processed
     /// into the sink and the sink has been flushed and closed.
     ///
@@ -15,6 +15,14 @@ pub struct SplitStream<S>(BiLock<S>);
 
 impl<S> Unpin for SplitStream<S> {}
 
+impl<S> SplitStream<S> {
+    /// Returns `true` if this `SplitStream<S>` and the given `SplitSink<S>`
+    /// come from the same call to `StreamExt::split`.
+    pub fn is_pair_of<Item>(&self, other: &SplitSink<S, Item>) -> bool {
+        other.is_pair_of(self)
+    }
+}
+
 impl<S: Unpin> SplitStream<S> {
     /// Attempts to put the two "halves" of a split `Stream + Sink` back
     /// together. Succeeds only if the `SplitStream<S>` and `SplitSink<S>` are
@@ -51,6 +59,14 @@ pub struct SplitSink<S, Item> {
 
 impl<S, Item> Unpin for SplitSink<S, Item> {}
 
+impl<S, Item> SplitSink<S, Item> {
+    /// Returns `true` if this `SplitSink<S>` and the given `SplitStream<S>`
+    /// come from the same call to `StreamExt::split`.
+    pub fn is_pair_of(&self, other: &SplitStream<S>) -> bool {
+        self.lock.is_pair_of(&other.0)
+    }
+}
+
 impl<S: Sink<Item> + Unpin, Item> SplitSink<S, Item> {
     /// Attempts to put the two "halves" of a split `Stream + Sink` back
     /// together. Succeeds only if the `SplitStream<S>` and `SplitSink<S>` are
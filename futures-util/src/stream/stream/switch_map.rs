@@ -0,0 +1,130 @@
+use crate::stream::Fuse;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+// How many outer items are drained in a single call to `poll_next`.
+const YIELD_EVERY: usize = 32;
+
+pin_project! {
+    /// Stream for the [`switch_map`](super::StreamExt::switch_map) method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct SwitchMap<St, U, F> {
+        #[pin]
+        stream: Fuse<St>,
+        #[pin]
+        inner: Option<U>,
+        f: F,
+    }
+}
+
+impl<St, U, F> fmt::Debug for SwitchMap<St, U, F>
+where
+    St: fmt::Debug,
+    U: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SwitchMap")
+            .field("stream", &self.stream)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<St, U, F> SwitchMap<St, U, F>
+where
+    St: Stream,
+    F: FnMut(St::Item) -> U,
+{
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream: super::Fuse::new(stream), inner: None, f }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St, U, F> FusedStream for SwitchMap<St, U, F>
+where
+    St: Stream,
+    U: Stream,
+    F: FnMut(St::Item) -> U,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_none() && self.stream.is_terminated()
+    }
+}
+
+impl<St, U, F> Stream for SwitchMap<St, U, F>
+where
+    St: Stream,
+    U: Stream,
+    F: FnMut(St::Item) -> U,
+{
+    type Item = U::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        // Drain the outer items that are already available, dropping the
+        // previous inner stream each time so that only the latest one is
+        // ever polled. The drain is bounded so that an outer stream that is
+        // always ready doesn't keep this call from returning.
+        let mut drained = 0;
+        while drained < YIELD_EVERY {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => this.inner.set(Some((this.f)(item))),
+                _ => break,
+            }
+            drained += 1;
+        }
+
+        if let Some(inner) = this.inner.as_mut().as_pin_mut() {
+            match inner.poll_next(cx) {
+                Poll::Ready(Some(item)) => return Poll::Ready(Some(item)),
+                Poll::Ready(None) => this.inner.set(None),
+                Poll::Pending => {
+                    // The outer stream may have more items, but hasn't
+                    // registered for a wakeup, so poll again.
+                    if drained == YIELD_EVERY {
+                        cx.waker().wake_by_ref();
+                    }
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        if drained == YIELD_EVERY {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        if this.stream.is_done() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.inner.is_none() && self.stream.is_done() {
+            (0, Some(0))
+        } else {
+            (0, None)
+        }
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, U, F, Item> Sink<Item> for SwitchMap<S, U, F>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
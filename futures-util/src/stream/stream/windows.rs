@@ -0,0 +1,95 @@
+use crate::stream::Fuse;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`windows`](super::StreamExt::windows) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Windows<St: Stream> {
+        #[pin]
+        stream: Fuse<St>,
+        items: VecDeque<St::Item>,
+        size: usize,
+    }
+}
+
+impl<St: Stream> Windows<St>
+where
+    St: Stream,
+    St::Item: Clone,
+{
+    pub(super) fn new(stream: St, size: usize) -> Self {
+        assert!(size > 0);
+
+        Self { stream: super::Fuse::new(stream), items: VecDeque::with_capacity(size), size }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream> Stream for Windows<St>
+where
+    St::Item: Clone,
+{
+    type Item = Vec<St::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                // Slide the window forward by one item, and yield it once it
+                // has been filled for the first time.
+                Some(item) => {
+                    if this.items.len() == *this.size {
+                        this.items.pop_front();
+                    }
+                    this.items.push_back(item);
+                    if this.items.len() == *this.size {
+                        return Poll::Ready(Some(this.items.iter().cloned().collect()));
+                    }
+                }
+
+                // Unlike `chunks`, a partial window is never yielded.
+                None => {
+                    this.items.clear();
+                    return Poll::Ready(None);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+        // Items still needed before the first window can be yielded.
+        let missing = self.size - 1 - self.items.len().min(self.size - 1);
+        (lower.saturating_sub(missing), upper.map(|x| x.saturating_sub(missing)))
+    }
+}
+
+impl<St: FusedStream> FusedStream for Windows<St>
+where
+    St::Item: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, Item> Sink<Item> for Windows<S>
+where
+    S: Stream + Sink<Item>,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
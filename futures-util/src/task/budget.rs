@@ -0,0 +1,46 @@
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::task::__internal::poll_budget;
+use futures_core::task::{Context, Poll};
+
+/// Consumes a unit of the cooperative scheduling budget of the current task,
+/// yielding to the other tasks of the executor if it is exhausted.
+///
+/// Executors give each task a budget whenever they poll it, which is consumed
+/// by operations such as receiving from a channel. Loops which may never
+/// return `Poll::Pending` on their own, because their operations always
+/// complete right away, can await this on each iteration so that they don't
+/// monopolize the thread.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::task::consume_budget;
+///
+/// let mut sum = 0u64;
+/// for i in 0..10_000 {
+///     sum += i;
+///     consume_budget().await;
+/// }
+/// assert_eq!(sum, 49_995_000);
+/// # });
+/// ```
+pub fn consume_budget() -> ConsumeBudget {
+    ConsumeBudget { _priv: () }
+}
+
+/// Future for the [`consume_budget`] function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ConsumeBudget {
+    _priv: (),
+}
+
+impl Future for ConsumeBudget {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        poll_budget(cx)
+    }
+}
@@ -0,0 +1,220 @@
+use crate::task::AtomicWaker;
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+// `JoinHandle::abort` has been called.
+const ABORTED: usize = 0b01;
+// The task has stored its output, or has been dropped without completing.
+const COMPLETE: usize = 0b10;
+
+struct Inner<T> {
+    state: AtomicUsize,
+    // Written by the task before setting `COMPLETE`, then taken by the handle.
+    output: UnsafeCell<Option<Result<T, JoinError>>>,
+    // The task waiting for the output.
+    join_waker: AtomicWaker,
+    // The spawned task, woken up to stop it when it's aborted.
+    task_waker: AtomicWaker,
+}
+
+unsafe impl<T: Send> Send for Inner<T> {}
+unsafe impl<T: Send> Sync for Inner<T> {}
+
+/// A handle to a task spawned with [`spawn`](super::SpawnExt::spawn) or
+/// [`spawn_local`](super::LocalSpawnExt::spawn_local).
+///
+/// Awaiting the handle waits for the task to complete, and returns its output,
+/// or an error if the task was [aborted](JoinHandle::abort) or dropped before
+/// completing, for example because it panicked or the `LocalPool` running it
+/// was dropped.
+///
+/// Dropping the handle detaches the task, which keeps running in the
+/// background.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, ThreadPool};
+/// use futures::future;
+/// use futures::task::SpawnExt;
+///
+/// let pool = ThreadPool::new().unwrap();
+///
+/// let handle = pool.spawn(async { 1 + 2 }).unwrap();
+/// assert_eq!(block_on(handle).unwrap(), 3);
+///
+/// let handle = pool.spawn(future::pending::<()>()).unwrap();
+/// handle.abort();
+/// assert!(block_on(handle).unwrap_err().is_aborted());
+/// ```
+pub struct JoinHandle<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// The error returned by a [`JoinHandle`] when its task didn't run to
+/// completion.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct JoinError {
+    kind: JoinErrorKind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum JoinErrorKind {
+    Aborted,
+    Dropped,
+}
+
+impl JoinError {
+    /// Returns `true` if the task was stopped by [`JoinHandle::abort`], and
+    /// `false` if it was dropped before completing.
+    pub fn is_aborted(&self) -> bool {
+        self.kind == JoinErrorKind::Aborted
+    }
+}
+
+impl fmt::Debug for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            JoinErrorKind::Aborted => f.debug_tuple("JoinError").field(&"aborted").finish(),
+            JoinErrorKind::Dropped => f.debug_tuple("JoinError").field(&"dropped").finish(),
+        }
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            JoinErrorKind::Aborted => write!(f, "task was aborted"),
+            JoinErrorKind::Dropped => write!(f, "task was dropped before completing"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for JoinError {}
+
+impl<T> JoinHandle<T> {
+    /// Aborts the task.
+    ///
+    /// The task is woken up, and dropped by the executor the next time it is
+    /// polled, after which the handle resolves to an error. This has no
+    /// effect if the task has already completed.
+    pub fn abort(&self) {
+        self.inner.state.fetch_or(ABORTED, Ordering::AcqRel);
+        self.inner.task_waker.wake();
+    }
+
+    /// Returns `true` if the task has completed, or has been dropped without
+    /// completing, so that awaiting the handle won't block.
+    pub fn is_finished(&self) -> bool {
+        self.inner.state.load(Ordering::Acquire) & COMPLETE != 0
+    }
+
+    /// Drops the handle, letting the task run in the background.
+    ///
+    /// This is the same as dropping the handle, but makes the intent explicit.
+    pub fn detach(self) {}
+}
+
+impl<T> Unpin for JoinHandle<T> {}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &self.inner;
+        if inner.state.load(Ordering::Acquire) & COMPLETE == 0 {
+            inner.join_waker.register(cx.waker());
+            // Check again in case the task completed before the registration.
+            if inner.state.load(Ordering::Acquire) & COMPLETE == 0 {
+                return Poll::Pending;
+            }
+        }
+
+        // Safety: the task doesn't access the output anymore once it has set
+        // `COMPLETE`, and the handle isn't shared.
+        let output = unsafe { (*inner.output.get()).take() };
+        Poll::Ready(output.expect("`JoinHandle` polled after completion"))
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle").field("finished", &self.is_finished()).finish()
+    }
+}
+
+// The side of the task, completing the handle when dropped if the task
+// hasn't completed.
+struct Completer<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Completer<T> {
+    fn is_complete(&self) -> bool {
+        self.inner.state.load(Ordering::Relaxed) & COMPLETE != 0
+    }
+
+    fn complete(&self, output: Result<T, JoinError>) {
+        // Safety: only the task writes the output, before setting `COMPLETE`.
+        unsafe { *self.inner.output.get() = Some(output) };
+        self.inner.state.fetch_or(COMPLETE, Ordering::AcqRel);
+        self.inner.join_waker.wake();
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        if !self.is_complete() {
+            self.complete(Err(JoinError { kind: JoinErrorKind::Dropped }));
+        }
+    }
+}
+
+pin_project! {
+    // A spawned future, sending its output to its `JoinHandle`.
+    pub(super) struct Joinable<Fut: Future> {
+        #[pin]
+        future: Fut,
+        completer: Completer<Fut::Output>,
+    }
+}
+
+impl<Fut: Future> Future for Joinable<Fut> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.project();
+        if this.completer.is_complete() {
+            return Poll::Ready(());
+        }
+
+        let inner = &this.completer.inner;
+        inner.task_waker.register(cx.waker());
+        if inner.state.load(Ordering::Acquire) & ABORTED != 0 {
+            this.completer.complete(Err(JoinError { kind: JoinErrorKind::Aborted }));
+            return Poll::Ready(());
+        }
+
+        let output = ready!(this.future.poll(cx));
+        this.completer.complete(Ok(output));
+        Poll::Ready(())
+    }
+}
+
+pub(super) fn joinable<Fut: Future>(future: Fut) -> (Joinable<Fut>, JoinHandle<Fut::Output>) {
+    let inner = Arc::new(Inner {
+        state: AtomicUsize::new(0),
+        output: UnsafeCell::new(None),
+        join_waker: AtomicWaker::new(),
+        task_waker: AtomicWaker::new(),
+    });
+    (Joinable { future, completer: Completer { inner: inner.clone() } }, JoinHandle { inner })
+}
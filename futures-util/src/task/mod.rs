@@ -6,6 +6,13 @@
 //! - [`Context`], a context of an asynchronous task,
 //!   including a handle for waking up the task.
 //! - [`Waker`], a handle for waking up a task.
+//! - [`consume_budget`], for yielding to the other tasks after doing a lot
+//!   of work.
+//! - [`yield_now`], for yielding to the other tasks once.
+//! - [`WakerSet`], a set of waiting tasks for building synchronization
+//!   primitives.
+//! - [`LocalKey`], a key for task-local data, declared with
+//!   [`task_local!`](crate::task_local).
 //!
 //! The remaining types and traits in the module are used for implementing
 //! executors or dealing with synchronization issues around task wakeup.
@@ -17,6 +24,7 @@ pub use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError,
 
 pub use futures_task::noop_waker;
 pub use futures_task::noop_waker_ref;
+pub use futures_task::{panic_waker, panic_waker_ref};
 
 #[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
@@ -33,5 +41,34 @@ pub use futures_task::{waker_ref, WakerRef};
 #[cfg(not(futures_no_atomic_cas))]
 pub use futures_core::task::__internal::AtomicWaker;
 
+pub use futures_core::task::__internal::{poll_budget, with_budget};
+
+mod budget;
+pub use self::budget::{consume_budget, ConsumeBudget};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+mod join_handle;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+pub use self::join_handle::{JoinError, JoinHandle};
+
 mod spawn;
 pub use self::spawn::{LocalSpawnExt, SpawnExt};
+
+mod yield_now;
+pub use self::yield_now::{yield_now, YieldNow};
+
+#[cfg(feature = "std")]
+mod waker_set;
+#[cfg(feature = "std")]
+pub use self::waker_set::WakerSet;
+
+#[cfg(feature = "std")]
+mod task_local;
+#[cfg(feature = "std")]
+pub use self::task_local::{AccessError, LocalKey, TaskLocalFuture};
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use std::thread_local as __thread_local;
@@ -13,20 +13,21 @@ use futures_core::future::Future;
 #[cfg(feature = "alloc")]
 use futures_task::{FutureObj, LocalFutureObj, SpawnError};
 
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+use super::join_handle::{joinable, JoinHandle};
+
 impl<Sp: ?Sized> SpawnExt for Sp where Sp: Spawn {}
 impl<Sp: ?Sized> LocalSpawnExt for Sp where Sp: LocalSpawn {}
 
 /// Extension trait for `Spawn`.
 pub trait SpawnExt: Spawn {
-    /// Spawns a task that polls the given future with output `()` to
-    /// completion.
-    ///
-    /// This method returns a [`Result`] that contains a [`SpawnError`] if
-    /// spawning fails.
+    /// Spawns a task that polls the given future to completion.
     ///
-    /// You can use [`spawn_with_handle`](SpawnExt::spawn_with_handle) if
-    /// you want to spawn a future with output other than `()` or if you want
-    /// to be able to await its completion.
+    /// This method returns a [`Result`] that contains a [`JoinHandle`] to the
+    /// task, or, if spawning fails, a [`SpawnError`]. The handle can be
+    /// awaited for the output of the future, or used to abort the task.
+    /// Dropping it lets the task run in the background.
     ///
     /// Note this method will eventually be replaced with the upcoming
     /// `Spawn::spawn` method which will take a `dyn Future` as input.
@@ -34,14 +35,37 @@ pub trait SpawnExt: Spawn {
     /// today. Feel free to use this method in the meantime.
     ///
     /// ```
-    /// use futures::executor::ThreadPool;
+    /// use futures::executor::{block_on, ThreadPool};
     /// use futures::task::SpawnExt;
     ///
     /// let executor = ThreadPool::new().unwrap();
     ///
     /// let future = async { /* ... */ };
     /// executor.spawn(future).unwrap();
+    ///
+    /// let handle = executor.spawn(async { 1 }).unwrap();
+    /// assert_eq!(block_on(handle), Ok(1));
     /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn spawn<Fut>(&self, future: Fut) -> Result<JoinHandle<Fut::Output>, SpawnError>
+    where
+        Fut: Future + Send + 'static,
+        Fut::Output: Send,
+    {
+        let (future, handle) = joinable(future);
+        self.spawn_obj(FutureObj::new(Box::new(future)))?;
+        Ok(handle)
+    }
+
+    /// Spawns a task that polls the given future with output `()` to
+    /// completion.
+    ///
+    /// This method returns a [`Result`] that contains a [`SpawnError`] if
+    /// spawning fails. On targets without atomic CAS operations, where
+    /// `JoinHandle` isn't available, this replaces the `spawn` method
+    /// returning one.
+    #[cfg(futures_no_atomic_cas)]
     #[cfg(feature = "alloc")]
     fn spawn<Fut>(&self, future: Fut) -> Result<(), SpawnError>
     where
@@ -55,7 +79,9 @@ pub trait SpawnExt: Spawn {
     ///
     /// This method returns a [`Result`] that contains a [`RemoteHandle`](crate::future::RemoteHandle), or, if
     /// spawning fails, a [`SpawnError`]. [`RemoteHandle`](crate::future::RemoteHandle) is a future that
-    /// resolves to the output of the spawned future.
+    /// resolves to the output of the spawned future. Unlike the [`JoinHandle`]
+    /// returned by `spawn`, it cancels the task when dropped, and resumes the
+    /// panic of the task if it panics.
     ///
     /// ```
     /// use futures::executor::{block_on, ThreadPool};
@@ -77,7 +103,7 @@ pub trait SpawnExt: Spawn {
         Fut::Output: Send,
     {
         let (future, handle) = future.remote_handle();
-        self.spawn(future)?;
+        self.spawn_obj(FutureObj::new(Box::new(future)))?;
         Ok(handle)
     }
 
@@ -95,15 +121,12 @@ pub trait SpawnExt: Spawn {
 
 /// Extension trait for `LocalSpawn`.
 pub trait LocalSpawnExt: LocalSpawn {
-    /// Spawns a task that polls the given future with output `()` to
-    /// completion.
-    ///
-    /// This method returns a [`Result`] that contains a [`SpawnError`] if
-    /// spawning fails.
+    /// Spawns a task that polls the given future to completion.
     ///
-    /// You can use [`spawn_with_handle`](SpawnExt::spawn_with_handle) if
-    /// you want to spawn a future with output other than `()` or if you want
-    /// to be able to await its completion.
+    /// This method returns a [`Result`] that contains a [`JoinHandle`] to the
+    /// task, or, if spawning fails, a [`SpawnError`]. The handle can be
+    /// awaited for the output of the future, or used to abort the task.
+    /// Dropping it lets the task run in the background.
     ///
     /// Note this method will eventually be replaced with the upcoming
     /// `Spawn::spawn` method which will take a `dyn Future` as input.
@@ -114,12 +137,34 @@ pub trait LocalSpawnExt: LocalSpawn {
     /// use futures::executor::LocalPool;
     /// use futures::task::LocalSpawnExt;
     ///
-    /// let executor = LocalPool::new();
+    /// let mut executor = LocalPool::new();
     /// let spawner = executor.spawner();
     ///
     /// let future = async { /* ... */ };
     /// spawner.spawn_local(future).unwrap();
+    ///
+    /// let handle = spawner.spawn_local(async { 1 }).unwrap();
+    /// assert_eq!(executor.run_until(handle), Ok(1));
     /// ```
+    #[cfg(not(futures_no_atomic_cas))]
+    #[cfg(feature = "alloc")]
+    fn spawn_local<Fut>(&self, future: Fut) -> Result<JoinHandle<Fut::Output>, SpawnError>
+    where
+        Fut: Future + 'static,
+    {
+        let (future, handle) = joinable(future);
+        self.spawn_local_obj(LocalFutureObj::new(Box::new(future)))?;
+        Ok(handle)
+    }
+
+    /// Spawns a task that polls the given future with output `()` to
+    /// completion.
+    ///
+    /// This method returns a [`Result`] that contains a [`SpawnError`] if
+    /// spawning fails. On targets without atomic CAS operations, where
+    /// `JoinHandle` isn't available, this replaces the `spawn_local` method
+    /// returning one.
+    #[cfg(futures_no_atomic_cas)]
     #[cfg(feature = "alloc")]
     fn spawn_local<Fut>(&self, future: Fut) -> Result<(), SpawnError>
     where
@@ -133,7 +178,9 @@ pub trait LocalSpawnExt: LocalSpawn {
     ///
     /// This method returns a [`Result`] that contains a [`RemoteHandle`](crate::future::RemoteHandle), or, if
     /// spawning fails, a [`SpawnError`]. [`RemoteHandle`](crate::future::RemoteHandle) is a future that
-    /// resolves to the output of the spawned future.
+    /// resolves to the output of the spawned future. Unlike the [`JoinHandle`]
+    /// returned by `spawn`, it cancels the task when dropped, and resumes the
+    /// panic of the task if it panics.
     ///
     /// ```
     /// use futures::executor::LocalPool;
@@ -157,7 +204,7 @@ pub trait LocalSpawnExt: LocalSpawn {
         Fut: Future + 'static,
     {
         let (future, handle) = future.remote_handle();
-        self.spawn_local(future)?;
+        self.spawn_local_obj(LocalFutureObj::new(Box::new(future)))?;
         Ok(handle)
     }
 }
@@ -0,0 +1,214 @@
+use core::cell::RefCell;
+use core::fmt;
+use core::mem;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+/// Declares new task-local keys of type [`LocalKey`](crate::task::LocalKey).
+///
+/// The syntax is the same as for [`thread_local!`](std::thread_local), but
+/// without initial values: a task-local value is only set within a future
+/// run with [`LocalKey::scope`](crate::task::LocalKey::scope).
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::task_local;
+///
+/// task_local! {
+///     static REQUEST_ID: u64;
+/// }
+///
+/// async fn handle() -> u64 {
+///     REQUEST_ID.get()
+/// }
+///
+/// assert_eq!(REQUEST_ID.scope(42, handle()).await, 42);
+/// assert!(REQUEST_ID.try_with(|_| ()).is_err());
+/// # });
+/// ```
+#[macro_export]
+macro_rules! task_local {
+    () => {};
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty; $($rest:tt)*) => {
+        $crate::__task_local_inner!($(#[$attr])* $vis $name, $t);
+        $crate::task_local!($($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty) => {
+        $crate::__task_local_inner!($(#[$attr])* $vis $name, $t);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __task_local_inner {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $t:ty) => {
+        $(#[$attr])*
+        $vis static $name: $crate::task::LocalKey<$t> = {
+            $crate::task::__thread_local! {
+                static __KEY: ::core::cell::RefCell<::core::option::Option<$t>> =
+                    ::core::cell::RefCell::new(::core::option::Option::None);
+            }
+
+            $crate::task::LocalKey { inner: &__KEY }
+        };
+    };
+}
+
+/// A key for task-local data, declared with the
+/// [`task_local!`](crate::task_local) macro.
+///
+/// Unlike thread-local data, task-local data follows a task across `.await`
+/// points, whichever thread of an executor polls it. The value is stored in
+/// the future returned by [`scope`](LocalKey::scope), and made available to
+/// the code run by that future each time it is polled.
+pub struct LocalKey<T: 'static> {
+    #[doc(hidden)]
+    pub inner: &'static std::thread::LocalKey<RefCell<Option<T>>>,
+}
+
+impl<T: 'static> LocalKey<T> {
+    /// Sets the value of the key to `value` while `future` runs.
+    ///
+    /// The value is available to `future` each time it is polled, and to
+    /// the futures it awaits, and nowhere else. Scopes of the same key can be
+    /// nested, in which case the innermost value is visible.
+    pub fn scope<F>(&'static self, value: T, future: F) -> TaskLocalFuture<T, F>
+    where
+        F: Future,
+    {
+        TaskLocalFuture { local: self, slot: Some(value), future }
+    }
+
+    /// Sets the value of the key to `value` while the closure `f` runs.
+    ///
+    /// This is the synchronous version of [`scope`](LocalKey::scope).
+    pub fn sync_scope<F, R>(&'static self, value: T, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let mut slot = Some(value);
+        let _guard = Guard::enter(self, &mut slot);
+        f()
+    }
+
+    /// Calls `f` with a reference to the value of the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a [`scope`](LocalKey::scope) of the key.
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.try_with(f).expect("cannot access a task-local value outside of its scope")
+    }
+
+    /// Calls `f` with a reference to the value of the key, if called within
+    /// a [`scope`](LocalKey::scope) of the key.
+    pub fn try_with<F, R>(&'static self, f: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.inner
+            .try_with(|cell| cell.borrow().as_ref().map(f))
+            .ok()
+            .flatten()
+            .ok_or(AccessError { _priv: () })
+    }
+
+    /// Returns a copy of the value of the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a [`scope`](LocalKey::scope) of the key.
+    pub fn get(&'static self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+}
+
+impl<T: 'static> fmt::Debug for LocalKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("LocalKey { .. }")
+    }
+}
+
+// Swaps the value of a scope into the thread-local storage of its key, and
+// swaps it back when dropped, even on panics.
+struct Guard<'a, T: 'static> {
+    local: &'static LocalKey<T>,
+    slot: &'a mut Option<T>,
+}
+
+impl<'a, T: 'static> Guard<'a, T> {
+    fn enter(local: &'static LocalKey<T>, slot: &'a mut Option<T>) -> Self {
+        local.inner.with(|cell| {
+            let mut value = cell
+                .try_borrow_mut()
+                .expect("cannot enter a task-local scope while the value of its key is borrowed");
+            mem::swap(slot, &mut *value);
+        });
+        Self { local, slot }
+    }
+}
+
+impl<T: 'static> Drop for Guard<'_, T> {
+    fn drop(&mut self) {
+        let slot = &mut *self.slot;
+        // The thread-local storage may already be destroyed if the thread is
+        // exiting, in which case the value is lost anyway.
+        let _ = self.local.inner.try_with(|cell| mem::swap(slot, &mut *cell.borrow_mut()));
+    }
+}
+
+pin_project! {
+    /// Future for the [`LocalKey::scope`] method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TaskLocalFuture<T: 'static, F> {
+        local: &'static LocalKey<T>,
+        slot: Option<T>,
+        #[pin]
+        future: F,
+    }
+}
+
+impl<T: 'static, F: Future> Future for TaskLocalFuture<T, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = Guard::enter(this.local, this.slot);
+        this.future.poll(cx)
+    }
+}
+
+impl<T: fmt::Debug + 'static, F: fmt::Debug> fmt::Debug for TaskLocalFuture<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskLocalFuture")
+            .field("value", &self.slot)
+            .field("future", &self.future)
+            .finish()
+    }
+}
+
+/// An error returned by [`LocalKey::try_with`] outside of a scope of the key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessError {
+    _priv: (),
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("task-local value not set")
+    }
+}
+
+impl std::error::Error for AccessError {}
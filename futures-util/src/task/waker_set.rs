@@ -0,0 +1,155 @@
+use futures_core::task::{Context, Poll, Waker};
+use slab::Slab;
+use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
+
+/// A set of waiting tasks, woken one at a time in the order in which they
+/// started waiting, or all at once.
+///
+/// This is the building block of synchronization primitives whose tasks wait
+/// for a resource, such as [`Semaphore`](crate::lock::Semaphore). The set
+/// doesn't synchronize anything itself: it's meant to be stored alongside
+/// the state of the primitive, behind the same lock.
+///
+/// A task starts waiting with [`insert`](WakerSet::insert), which returns a
+/// key identifying it, and polls [`poll_woken`](WakerSet::poll_woken) with
+/// that key until it has been woken. A task which stops waiting early, for
+/// example because its future is dropped, must call
+/// [`remove`](WakerSet::remove): if it had been woken by
+/// [`wake_one`](WakerSet::wake_one) in the meantime, the wakeup should be
+/// passed on to another task so that it isn't lost.
+///
+/// # Examples
+///
+/// ```
+/// use futures::task::{noop_waker, Context, Poll, WakerSet};
+///
+/// let waker = noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+/// let mut set = WakerSet::new();
+///
+/// let first = set.insert(cx.waker());
+/// let second = set.insert(cx.waker());
+///
+/// assert!(set.wake_one());
+/// assert_eq!(set.poll_woken(first, &mut cx), Poll::Ready(()));
+/// assert_eq!(set.poll_woken(second, &mut cx), Poll::Pending);
+///
+/// // The second task gives up, and there is nothing to pass on.
+/// assert!(!set.remove(second));
+/// assert!(set.is_empty());
+/// ```
+pub struct WakerSet {
+    entries: Slab<Entry>,
+    // The keys of the entries which haven't been woken yet, in the order in
+    // which they were inserted.
+    queue: VecDeque<usize>,
+}
+
+enum Entry {
+    Waiting(Waker),
+    // The entry has been woken, and is removed once it is polled.
+    Woken,
+}
+
+impl Default for WakerSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WakerSet {
+    /// Creates a new, empty set.
+    pub fn new() -> Self {
+        Self { entries: Slab::new(), queue: VecDeque::new() }
+    }
+
+    /// Returns the number of tasks in the set which haven't been woken yet.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns `true` if no task in the set is waiting to be woken.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Adds a waiting task to the set, returning the key identifying it.
+    pub fn insert(&mut self, waker: &Waker) -> usize {
+        let key = self.entries.insert(Entry::Waiting(waker.clone()));
+        self.queue.push_back(key);
+        key
+    }
+
+    /// Checks whether the task identified by `key` has been woken.
+    ///
+    /// If it has, its key is removed from the set and `Poll::Ready(())` is
+    /// returned; the key must not be used afterwards. Otherwise, the waker
+    /// of `cx` is stored to be woken in place of the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` isn't in the set.
+    pub fn poll_woken(&mut self, key: usize, cx: &mut Context<'_>) -> Poll<()> {
+        match &mut self.entries[key] {
+            Entry::Waiting(waker) => {
+                if !waker.will_wake(cx.waker()) {
+                    *waker = cx.waker().clone();
+                }
+                Poll::Pending
+            }
+            Entry::Woken => {
+                self.entries.remove(key);
+                Poll::Ready(())
+            }
+        }
+    }
+
+    /// Removes the task identified by `key` from the set, returning `true` if
+    /// it had been woken by [`wake_one`](WakerSet::wake_one) or
+    /// [`wake_all`](WakerSet::wake_all).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` isn't in the set.
+    pub fn remove(&mut self, key: usize) -> bool {
+        match self.entries.remove(key) {
+            Entry::Waiting(_) => {
+                self.queue.retain(|&k| k != key);
+                false
+            }
+            Entry::Woken => true,
+        }
+    }
+
+    /// Wakes the task which has been waiting the longest, returning `false`
+    /// if no task is waiting.
+    pub fn wake_one(&mut self) -> bool {
+        match self.queue.pop_front() {
+            Some(key) => {
+                if let Entry::Waiting(waker) = mem::replace(&mut self.entries[key], Entry::Woken) {
+                    waker.wake();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Wakes all the waiting tasks, returning how many were woken.
+    pub fn wake_all(&mut self) -> usize {
+        let n = self.queue.len();
+        while self.wake_one() {}
+        n
+    }
+}
+
+impl fmt::Debug for WakerSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WakerSet")
+            .field("waiting", &self.queue.len())
+            .field("woken", &(self.entries.len() - self.queue.len()))
+            .finish()
+    }
+}
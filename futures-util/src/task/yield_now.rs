@@ -0,0 +1,53 @@
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+
+/// Yields to the other tasks of the executor once.
+///
+/// The returned future is pending the first time it is polled, after waking
+/// its task right away, so that the executor polls it again after giving the
+/// other tasks which are ready a chance to run. This allows long CPU-bound
+/// sections of a task to share the thread.
+///
+/// Unlike [`consume_budget`](super::consume_budget), this always yields,
+/// whatever the budget of the task.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::task::yield_now;
+///
+/// let mut sum = 0u64;
+/// for i in 0..10_000 {
+///     sum += i;
+///     if i % 1000 == 0 {
+///         yield_now().await;
+///     }
+/// }
+/// assert_eq!(sum, 49_995_000);
+/// # });
+/// ```
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+/// Future for the [`yield_now`] function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
@@ -125,11 +125,13 @@ pub use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
 pub use futures_util::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 
 // Macro reexports
-pub use futures_core::ready; // Readiness propagation
+pub use futures_core::{ready, try_ready}; // Readiness propagation
 pub use futures_util::pin_mut;
 #[cfg(feature = "std")]
 #[cfg(feature = "async-await")]
 pub use futures_util::select;
+#[cfg(feature = "std")]
+pub use futures_util::task_local; // Task-local data
 #[cfg(feature = "async-await")]
 pub use futures_util::{join, pending, poll, select_biased, try_join}; // Async-await
 
@@ -139,7 +141,7 @@ pub use futures_util::{future, never, sink, stream, task};
 
 #[cfg(feature = "std")]
 #[cfg(feature = "async-await")]
-pub use futures_util::stream_select;
+pub use futures_util::{stream_select, try_stream};
 
 #[cfg(feature = "alloc")]
 #[doc(inline)]
@@ -149,8 +151,37 @@ pub use futures_channel as channel;
 pub use futures_util::lock;
 
 #[cfg(feature = "std")]
-#[doc(inline)]
-pub use futures_util::io;
+pub mod io {
+    //! Asynchronous I/O.
+    //!
+    //! This module is the asynchronous version of `std::io`. It defines four
+    //! traits, [`AsyncRead`], [`AsyncWrite`], [`AsyncSeek`], and [`AsyncBufRead`],
+    //! which mirror the `Read`, `Write`, `Seek`, and `BufRead` traits of the
+    //! standard library. However, these traits integrate with the asynchronous
+    //! task system, so that if an I/O object isn't ready for reading (or writing),
+    //! the thread is not blocked, and instead the current task is queued to be
+    //! woken when I/O is ready.
+    //!
+    //! In addition, the [`AsyncReadExt`], [`AsyncWriteExt`], [`AsyncSeekExt`], and
+    //! [`AsyncBufReadExt`] extension traits offer a variety of useful combinators
+    //! for operating with asynchronous I/O objects, including ways to work with
+    //! them using futures, streams and sinks. The [`codec`] module turns I/O
+    //! objects into streams and sinks of the frames of a protocol.
+    //!
+    //! Inside an SGX enclave, the `sgx_fs` module gives access to the protected
+    //! files of the enclave.
+    //!
+    //! This module is only available when the `std` feature of this
+    //! library is activated, and it is activated by default.
+
+    #[doc(inline)]
+    pub use futures_util::io::*;
+
+    #[cfg(feature = "phala-sgx")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "phala-sgx")))]
+    #[doc(inline)]
+    pub use futures_executor::sgx_fs;
+}
 
 #[cfg(feature = "executor")]
 #[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
@@ -6,6 +6,7 @@ use futures::stream::StreamExt;
 use futures::task::{Context, Poll};
 use futures::{
     join, pending, pin_mut, poll, select, select_biased, stream, stream_select, try_join,
+    try_stream,
 };
 use std::mem;
 
@@ -346,6 +347,52 @@ fn stream_select() {
     });
 }
 
+#[test]
+fn stream_macro() {
+    block_on(async {
+        let (mut tx, mut rx) = mpsc::channel::<i32>(1);
+        let doubled = stream! {
+            yield 0;
+            while let Some(x) = rx.next().await {
+                yield x * 2;
+            }
+        };
+        pin_mut!(doubled);
+
+        assert_eq!(doubled.next().await, Some(0));
+        tx.send(1).await.unwrap();
+        assert_eq!(doubled.next().await, Some(2));
+        tx.send(2).await.unwrap();
+        drop(tx);
+        assert_eq!(doubled.next().await, Some(4));
+        assert_eq!(doubled.next().await, None);
+        assert_eq!(doubled.next().await, None);
+    });
+}
+
+#[test]
+fn try_stream_macro() {
+    fn parse(
+        input: &str,
+    ) -> impl futures::Stream<Item = Result<u32, std::num::ParseIntError>> + '_ {
+        try_stream! {
+            for part in input.split(',') {
+                yield part.parse::<u32>()?;
+            }
+        }
+    }
+
+    block_on(async {
+        let items: Vec<_> = parse("1,2").collect().await;
+        assert_eq!(items, vec![Ok(1), Ok(2)]);
+
+        let items: Vec<_> = parse("1,x,3").collect().await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0], Ok(1));
+        assert!(items[1].is_err());
+    });
+}
+
 #[test]
 fn join_size() {
     let fut = async {
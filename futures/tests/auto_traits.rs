@@ -72,12 +72,149 @@ pub mod channel {
     use super::*;
     use futures::channel::*;
 
+    assert_impl!(bounded::Receiver<()>: Send);
+    assert_not_impl!(bounded::Receiver<*const ()>: Send);
+    assert_impl!(bounded::Receiver<()>: Sync);
+    assert_not_impl!(bounded::Receiver<*const ()>: Sync);
+    assert_impl!(bounded::Receiver<PhantomPinned>: Unpin);
+
+    assert_impl!(bounded::SendError: Send);
+    assert_impl!(bounded::SendError: Sync);
+    assert_impl!(bounded::SendError: Unpin);
+
+    assert_impl!(bounded::Sender<()>: Send);
+    assert_not_impl!(bounded::Sender<*const ()>: Send);
+    assert_impl!(bounded::Sender<()>: Sync);
+    assert_not_impl!(bounded::Sender<*const ()>: Sync);
+    assert_impl!(bounded::Sender<PhantomPinned>: Unpin);
+
+    assert_impl!(bounded::TryRecvError: Send);
+    assert_impl!(bounded::TryRecvError: Sync);
+    assert_impl!(bounded::TryRecvError: Unpin);
+
+    assert_impl!(bounded::TrySendError<()>: Send);
+    assert_not_impl!(bounded::TrySendError<*const ()>: Send);
+    assert_impl!(bounded::TrySendError<()>: Sync);
+    assert_not_impl!(bounded::TrySendError<*const ()>: Sync);
+    assert_impl!(bounded::TrySendError<()>: Unpin);
+    assert_not_impl!(bounded::TrySendError<PhantomPinned>: Unpin);
+
+    assert_impl!(broadcast::Lagged: Send);
+    assert_impl!(broadcast::Lagged: Sync);
+    assert_impl!(broadcast::Lagged: Unpin);
+
+    assert_impl!(broadcast::Receiver<()>: Send);
+    assert_not_impl!(broadcast::Receiver<*const ()>: Send);
+    assert_impl!(broadcast::Receiver<()>: Sync);
+    assert_not_impl!(broadcast::Receiver<*const ()>: Sync);
+    assert_impl!(broadcast::Receiver<PhantomPinned>: Unpin);
+
+    assert_impl!(broadcast::SendError<()>: Send);
+    assert_not_impl!(broadcast::SendError<*const ()>: Send);
+    assert_impl!(broadcast::SendError<()>: Sync);
+    assert_not_impl!(broadcast::SendError<*const ()>: Sync);
+    assert_impl!(broadcast::SendError<()>: Unpin);
+    assert_not_impl!(broadcast::SendError<PhantomPinned>: Unpin);
+
+    assert_impl!(broadcast::Sender<()>: Send);
+    assert_not_impl!(broadcast::Sender<*const ()>: Send);
+    assert_impl!(broadcast::Sender<()>: Sync);
+    assert_not_impl!(broadcast::Sender<*const ()>: Sync);
+    assert_impl!(broadcast::Sender<PhantomPinned>: Unpin);
+
+    assert_impl!(broadcast::TryRecvError: Send);
+    assert_impl!(broadcast::TryRecvError: Sync);
+    assert_impl!(broadcast::TryRecvError: Unpin);
+
+    assert_impl!(keyed::Receiver<(), ()>: Send);
+    assert_not_impl!(keyed::Receiver<(), *const ()>: Send);
+    assert_not_impl!(keyed::Receiver<*const (), ()>: Send);
+    assert_impl!(keyed::Receiver<(), ()>: Sync);
+    assert_not_impl!(keyed::Receiver<(), *const ()>: Sync);
+    assert_not_impl!(keyed::Receiver<*const (), ()>: Sync);
+    assert_impl!(keyed::Receiver<PhantomPinned, PhantomPinned>: Unpin);
+
+    assert_impl!(keyed::SendError<(), ()>: Send);
+    assert_not_impl!(keyed::SendError<(), *const ()>: Send);
+    assert_impl!(keyed::SendError<(), ()>: Sync);
+    assert_not_impl!(keyed::SendError<(), *const ()>: Sync);
+    assert_impl!(keyed::SendError<(), ()>: Unpin);
+    assert_not_impl!(keyed::SendError<PhantomPinned, ()>: Unpin);
+
+    assert_impl!(keyed::Sender<(), ()>: Send);
+    assert_not_impl!(keyed::Sender<(), *const ()>: Send);
+    assert_not_impl!(keyed::Sender<*const (), ()>: Send);
+    assert_impl!(keyed::Sender<(), ()>: Sync);
+    assert_not_impl!(keyed::Sender<(), *const ()>: Sync);
+    assert_impl!(keyed::Sender<PhantomPinned, PhantomPinned>: Unpin);
+
+    assert_impl!(keyed::TryRecvError: Send);
+    assert_impl!(keyed::TryRecvError: Sync);
+    assert_impl!(keyed::TryRecvError: Unpin);
+
+    assert_impl!(mpmc::Receiver<()>: Send);
+    assert_not_impl!(mpmc::Receiver<*const ()>: Send);
+    assert_impl!(mpmc::Receiver<()>: Sync);
+    assert_not_impl!(mpmc::Receiver<*const ()>: Sync);
+    assert_impl!(mpmc::Receiver<PhantomPinned>: Unpin);
+
+    assert_impl!(mpmc::SendError: Send);
+    assert_impl!(mpmc::SendError: Sync);
+    assert_impl!(mpmc::SendError: Unpin);
+
+    assert_impl!(mpmc::Sender<()>: Send);
+    assert_not_impl!(mpmc::Sender<*const ()>: Send);
+    assert_impl!(mpmc::Sender<()>: Sync);
+    assert_not_impl!(mpmc::Sender<*const ()>: Sync);
+    assert_impl!(mpmc::Sender<PhantomPinned>: Unpin);
+
+    assert_impl!(mpmc::TryRecvError: Send);
+    assert_impl!(mpmc::TryRecvError: Sync);
+    assert_impl!(mpmc::TryRecvError: Unpin);
+
+    assert_impl!(mpmc::TrySendError<()>: Send);
+    assert_not_impl!(mpmc::TrySendError<*const ()>: Send);
+    assert_impl!(mpmc::TrySendError<()>: Sync);
+    assert_not_impl!(mpmc::TrySendError<*const ()>: Sync);
+    assert_impl!(mpmc::TrySendError<()>: Unpin);
+    assert_not_impl!(mpmc::TrySendError<PhantomPinned>: Unpin);
+
+    assert_impl!(mpsc::Closed<'_, ()>: Send);
+    assert_not_impl!(mpsc::Closed<'_, *const ()>: Send);
+    assert_impl!(mpsc::Closed<'_, ()>: Sync);
+    assert_not_impl!(mpsc::Closed<'_, *const ()>: Sync);
+    assert_impl!(mpsc::Closed<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(mpsc::Drain<'_, ()>: Send);
+    assert_not_impl!(mpsc::Drain<'_, *const ()>: Send);
+    assert_impl!(mpsc::Drain<'_, ()>: Sync);
+    assert_not_impl!(mpsc::Drain<'_, *const ()>: Sync);
+    assert_impl!(mpsc::Drain<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(mpsc::Permit<'_, ()>: Send);
+    assert_not_impl!(mpsc::Permit<'_, *const ()>: Send);
+    assert_impl!(mpsc::Permit<'_, ()>: Sync);
+    assert_not_impl!(mpsc::Permit<'_, *const ()>: Sync);
+    assert_impl!(mpsc::Permit<'_, PhantomPinned>: Unpin);
+
     assert_impl!(mpsc::Receiver<()>: Send);
     assert_not_impl!(mpsc::Receiver<*const ()>: Send);
     assert_impl!(mpsc::Receiver<()>: Sync);
     assert_not_impl!(mpsc::Receiver<*const ()>: Sync);
     assert_impl!(mpsc::Receiver<PhantomPinned>: Unpin);
 
+    assert_impl!(mpsc::RecvMany<'_, ()>: Send);
+    assert_not_impl!(mpsc::RecvMany<'_, *const ()>: Send);
+    assert_impl!(mpsc::RecvMany<'_, ()>: Sync);
+    assert_not_impl!(mpsc::RecvMany<'_, *const ()>: Sync);
+    assert_impl!(mpsc::RecvMany<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(mpsc::Reserve<'_, ()>: Send);
+    assert_not_impl!(mpsc::Reserve<'_, *const ()>: Send);
+    assert_impl!(mpsc::Reserve<'_, ()>: Sync);
+    assert_not_impl!(mpsc::Reserve<'_, *const ()>: Sync);
+    assert_impl!(mpsc::Reserve<'_, PhantomPinned>: Unpin);
+
     assert_impl!(mpsc::SendError: Send);
     assert_impl!(mpsc::SendError: Sync);
     assert_impl!(mpsc::SendError: Unpin);
@@ -99,6 +236,18 @@ pub mod channel {
     assert_impl!(mpsc::TrySendError<()>: Unpin);
     assert_not_impl!(mpsc::TrySendError<PhantomPinned>: Unpin);
 
+    assert_impl!(mpsc::UnboundedClosed<'_, ()>: Send);
+    assert_not_impl!(mpsc::UnboundedClosed<'_, *const ()>: Send);
+    assert_impl!(mpsc::UnboundedClosed<'_, ()>: Sync);
+    assert_not_impl!(mpsc::UnboundedClosed<'_, *const ()>: Sync);
+    assert_impl!(mpsc::UnboundedClosed<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(mpsc::UnboundedDrain<'_, ()>: Send);
+    assert_not_impl!(mpsc::UnboundedDrain<'_, *const ()>: Send);
+    assert_impl!(mpsc::UnboundedDrain<'_, ()>: Sync);
+    assert_not_impl!(mpsc::UnboundedDrain<'_, *const ()>: Sync);
+    assert_impl!(mpsc::UnboundedDrain<'_, PhantomPinned>: Unpin);
+
     assert_impl!(mpsc::UnboundedReceiver<()>: Send);
     assert_not_impl!(mpsc::UnboundedReceiver<*const ()>: Send);
     assert_impl!(mpsc::UnboundedReceiver<()>: Sync);
@@ -111,6 +260,24 @@ pub mod channel {
     assert_not_impl!(mpsc::UnboundedReceiver<*const ()>: Sync);
     assert_impl!(mpsc::UnboundedReceiver<PhantomPinned>: Unpin);
 
+    assert_impl!(mpsc::UnboundedRecvMany<'_, ()>: Send);
+    assert_not_impl!(mpsc::UnboundedRecvMany<'_, *const ()>: Send);
+    assert_impl!(mpsc::UnboundedRecvMany<'_, ()>: Sync);
+    assert_not_impl!(mpsc::UnboundedRecvMany<'_, *const ()>: Sync);
+    assert_impl!(mpsc::UnboundedRecvMany<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(mpsc::WeakSender<()>: Send);
+    assert_not_impl!(mpsc::WeakSender<*const ()>: Send);
+    assert_impl!(mpsc::WeakSender<()>: Sync);
+    assert_not_impl!(mpsc::WeakSender<*const ()>: Sync);
+    assert_impl!(mpsc::WeakSender<PhantomPinned>: Unpin);
+
+    assert_impl!(mpsc::WeakUnboundedSender<()>: Send);
+    assert_not_impl!(mpsc::WeakUnboundedSender<*const ()>: Send);
+    assert_impl!(mpsc::WeakUnboundedSender<()>: Sync);
+    assert_not_impl!(mpsc::WeakUnboundedSender<*const ()>: Sync);
+    assert_impl!(mpsc::WeakUnboundedSender<PhantomPinned>: Unpin);
+
     assert_impl!(oneshot::Canceled: Send);
     assert_impl!(oneshot::Canceled: Sync);
     assert_impl!(oneshot::Canceled: Unpin);
@@ -132,6 +299,94 @@ pub mod channel {
     assert_impl!(oneshot::Sender<()>: Sync);
     assert_not_impl!(oneshot::Sender<*const ()>: Sync);
     assert_impl!(oneshot::Sender<PhantomPinned>: Unpin);
+
+    assert_impl!(priority::Receiver<()>: Send);
+    assert_not_impl!(priority::Receiver<*const ()>: Send);
+    assert_impl!(priority::Receiver<()>: Sync);
+    assert_not_impl!(priority::Receiver<*const ()>: Sync);
+    assert_impl!(priority::Receiver<PhantomPinned>: Unpin);
+
+    assert_impl!(priority::SendError: Send);
+    assert_impl!(priority::SendError: Sync);
+    assert_impl!(priority::SendError: Unpin);
+
+    assert_impl!(priority::Sender<()>: Send);
+    assert_not_impl!(priority::Sender<*const ()>: Send);
+    assert_impl!(priority::Sender<()>: Sync);
+    assert_not_impl!(priority::Sender<*const ()>: Sync);
+    assert_impl!(priority::Sender<PhantomPinned>: Unpin);
+
+    assert_impl!(priority::TryRecvError: Send);
+    assert_impl!(priority::TryRecvError: Sync);
+    assert_impl!(priority::TryRecvError: Unpin);
+
+    assert_impl!(priority::TrySendError<()>: Send);
+    assert_not_impl!(priority::TrySendError<*const ()>: Send);
+    assert_impl!(priority::TrySendError<()>: Sync);
+    assert_not_impl!(priority::TrySendError<*const ()>: Sync);
+    assert_impl!(priority::TrySendError<()>: Unpin);
+    assert_not_impl!(priority::TrySendError<PhantomPinned>: Unpin);
+
+    assert_impl!(spsc::Receiver<()>: Send);
+    assert_not_impl!(spsc::Receiver<*const ()>: Send);
+    assert_impl!(spsc::Receiver<()>: Sync);
+    assert_not_impl!(spsc::Receiver<*const ()>: Sync);
+    assert_impl!(spsc::Receiver<PhantomPinned>: Unpin);
+
+    assert_impl!(spsc::SendError: Send);
+    assert_impl!(spsc::SendError: Sync);
+    assert_impl!(spsc::SendError: Unpin);
+
+    assert_impl!(spsc::Sender<()>: Send);
+    assert_not_impl!(spsc::Sender<*const ()>: Send);
+    assert_impl!(spsc::Sender<()>: Sync);
+    assert_not_impl!(spsc::Sender<*const ()>: Sync);
+    assert_impl!(spsc::Sender<PhantomPinned>: Unpin);
+
+    assert_impl!(spsc::TryRecvError: Send);
+    assert_impl!(spsc::TryRecvError: Sync);
+    assert_impl!(spsc::TryRecvError: Unpin);
+
+    assert_impl!(spsc::TrySendError<()>: Send);
+    assert_not_impl!(spsc::TrySendError<*const ()>: Send);
+    assert_impl!(spsc::TrySendError<()>: Sync);
+    assert_not_impl!(spsc::TrySendError<*const ()>: Sync);
+    assert_impl!(spsc::TrySendError<()>: Unpin);
+    assert_not_impl!(spsc::TrySendError<PhantomPinned>: Unpin);
+
+    assert_impl!(watch::Changed<'_, ()>: Send);
+    assert_not_impl!(watch::Changed<'_, *const ()>: Send);
+    assert_impl!(watch::Changed<'_, ()>: Sync);
+    assert_not_impl!(watch::Changed<'_, *const ()>: Sync);
+    assert_impl!(watch::Changed<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(watch::Receiver<()>: Send);
+    assert_not_impl!(watch::Receiver<*const ()>: Send);
+    assert_impl!(watch::Receiver<()>: Sync);
+    assert_not_impl!(watch::Receiver<*const ()>: Sync);
+    assert_impl!(watch::Receiver<PhantomPinned>: Unpin);
+
+    assert_impl!(watch::RecvError: Send);
+    assert_impl!(watch::RecvError: Sync);
+    assert_impl!(watch::RecvError: Unpin);
+
+    assert_not_impl!(watch::Ref<'_, ()>: Send);
+    assert_impl!(watch::Ref<'_, ()>: Sync);
+    assert_not_impl!(watch::Ref<'_, *const ()>: Sync);
+    assert_impl!(watch::Ref<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(watch::SendError<()>: Send);
+    assert_not_impl!(watch::SendError<*const ()>: Send);
+    assert_impl!(watch::SendError<()>: Sync);
+    assert_not_impl!(watch::SendError<*const ()>: Sync);
+    assert_impl!(watch::SendError<()>: Unpin);
+    assert_not_impl!(watch::SendError<PhantomPinned>: Unpin);
+
+    assert_impl!(watch::Sender<()>: Send);
+    assert_not_impl!(watch::Sender<*const ()>: Send);
+    assert_impl!(watch::Sender<()>: Sync);
+    assert_not_impl!(watch::Sender<*const ()>: Sync);
+    assert_impl!(watch::Sender<PhantomPinned>: Unpin);
 }
 
 /// Assert Send/Sync/Unpin for all public types in `futures::compat`.
@@ -181,6 +436,24 @@ pub mod executor {
     use super::*;
     use futures::executor::*;
 
+    assert_impl!(BlockingIo<()>: Send);
+    assert_not_impl!(BlockingIo<*const ()>: Send);
+    assert_impl!(BlockingIo<()>: Sync);
+    assert_not_impl!(BlockingIo<*const ()>: Sync);
+    assert_impl!(BlockingIo<PhantomPinned>: Unpin);
+
+    assert_impl!(BlockingRead<futures::io::Cursor<Vec<u8>>>: Send);
+    assert_not_impl!(BlockingRead<Pin<Box<dyn futures::io::AsyncRead>>>: Send);
+    assert_impl!(BlockingRead<futures::io::Cursor<Vec<u8>>>: Sync);
+    assert_not_impl!(BlockingRead<Pin<Box<dyn futures::io::AsyncRead>>>: Sync);
+    assert_impl!(BlockingRead<futures::io::Cursor<Vec<u8>>>: Unpin);
+
+    assert_impl!(BlockingSink<SendSink>: Send);
+    assert_not_impl!(BlockingSink<LocalSink>: Send);
+    assert_impl!(BlockingSink<SyncSink>: Sync);
+    assert_not_impl!(BlockingSink<LocalSink>: Sync);
+    assert_impl!(BlockingSink<UnpinSink>: Unpin);
+
     assert_impl!(BlockingStream<SendStream>: Send);
     assert_not_impl!(BlockingStream<LocalStream>: Send);
     assert_impl!(BlockingStream<SyncStream>: Sync);
@@ -189,6 +462,19 @@ pub mod executor {
     // BlockingStream requires `S: Unpin`
     // assert_not_impl!(BlockingStream<PinnedStream>: Unpin);
 
+    assert_impl!(BlockingWrite<futures::io::Cursor<Vec<u8>>>: Send);
+    assert_not_impl!(BlockingWrite<Pin<Box<dyn futures::io::AsyncWrite>>>: Send);
+    assert_impl!(BlockingWrite<futures::io::Cursor<Vec<u8>>>: Sync);
+    assert_not_impl!(BlockingWrite<Pin<Box<dyn futures::io::AsyncWrite>>>: Sync);
+    assert_impl!(BlockingWrite<futures::io::Cursor<Vec<u8>>>: Unpin);
+
+    assert_impl!(Elapsed<SendFuture>: Send);
+    assert_not_impl!(Elapsed<LocalFuture>: Send);
+    assert_impl!(Elapsed<SyncFuture>: Sync);
+    assert_not_impl!(Elapsed<LocalFuture>: Sync);
+    assert_impl!(Elapsed<UnpinFuture>: Unpin);
+    assert_not_impl!(Elapsed<PinnedFuture>: Unpin);
+
     assert_impl!(Enter: Send);
     assert_impl!(Enter: Sync);
     assert_impl!(Enter: Unpin);
@@ -197,6 +483,28 @@ pub mod executor {
     assert_impl!(EnterError: Sync);
     assert_impl!(EnterError: Unpin);
 
+    assert_impl!(FromBlockingIter<std::vec::IntoIter<()>>: Send);
+    assert_not_impl!(FromBlockingIter<std::vec::IntoIter<*const ()>>: Send);
+    assert_impl!(FromBlockingIter<std::vec::IntoIter<()>>: Sync);
+    assert_not_impl!(FromBlockingIter<std::vec::IntoIter<*const ()>>: Sync);
+    assert_impl!(FromBlockingIter<std::vec::IntoIter<PhantomPinned>>: Unpin);
+
+    assert_impl!(IdleStrategy: Send);
+    assert_impl!(IdleStrategy: Sync);
+    assert_impl!(IdleStrategy: Unpin);
+
+    assert_impl!(JoinNext<'_, ()>: Send);
+    assert_not_impl!(JoinNext<'_, *const ()>: Send);
+    assert_impl!(JoinNext<'_, ()>: Sync);
+    assert_not_impl!(JoinNext<'_, *const ()>: Sync);
+    assert_impl!(JoinNext<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(JoinSet<()>: Send);
+    assert_not_impl!(JoinSet<*const ()>: Send);
+    assert_impl!(JoinSet<()>: Sync);
+    assert_not_impl!(JoinSet<*const ()>: Sync);
+    assert_impl!(JoinSet<PhantomPinned>: Unpin);
+
     assert_not_impl!(LocalPool: Send);
     assert_not_impl!(LocalPool: Sync);
     assert_impl!(LocalPool: Unpin);
@@ -205,6 +513,48 @@ pub mod executor {
     assert_not_impl!(LocalSpawner: Sync);
     assert_impl!(LocalSpawner: Unpin);
 
+    assert_impl!(Priority: Send);
+    assert_impl!(Priority: Sync);
+    assert_impl!(Priority: Unpin);
+
+    assert_not_impl!(Scope<'_, '_>: Send);
+    assert_not_impl!(Scope<'_, '_>: Sync);
+    assert_impl!(Scope<'_, '_>: Unpin);
+
+    assert_impl!(Sleep: Send);
+    assert_impl!(Sleep: Sync);
+    assert_impl!(Sleep: Unpin);
+
+    assert_impl!(SpawnBlocking<()>: Send);
+    assert_not_impl!(SpawnBlocking<*const ()>: Send);
+    assert_impl!(SpawnBlocking<()>: Sync);
+    assert_not_impl!(SpawnBlocking<*const ()>: Sync);
+    assert_impl!(SpawnBlocking<PhantomPinned>: Unpin);
+
+    assert_impl!(TaskId: Send);
+    assert_impl!(TaskId: Sync);
+    assert_impl!(TaskId: Unpin);
+
+    assert_impl!(TaskInfo: Send);
+    assert_impl!(TaskInfo: Sync);
+    assert_impl!(TaskInfo: Unpin);
+
+    assert_impl!(TaskState: Send);
+    assert_impl!(TaskState: Sync);
+    assert_impl!(TaskState: Unpin);
+
+    assert_impl!(TestClock: Send);
+    assert_impl!(TestClock: Sync);
+    assert_impl!(TestClock: Unpin);
+
+    assert_not_impl!(TestPool: Send);
+    assert_not_impl!(TestPool: Sync);
+    assert_impl!(TestPool: Unpin);
+
+    assert_not_impl!(TestSpawner: Send);
+    assert_not_impl!(TestSpawner: Sync);
+    assert_impl!(TestSpawner: Unpin);
+
     assert_impl!(ThreadPool: Send);
     assert_impl!(ThreadPool: Sync);
     assert_impl!(ThreadPool: Unpin);
@@ -212,6 +562,36 @@ pub mod executor {
     assert_impl!(ThreadPoolBuilder: Send);
     assert_impl!(ThreadPoolBuilder: Sync);
     assert_impl!(ThreadPoolBuilder: Unpin);
+
+    assert_impl!(time::BoundedClock<()>: Send);
+    assert_not_impl!(time::BoundedClock<*const ()>: Send);
+    assert_impl!(time::BoundedClock<()>: Sync);
+    assert_not_impl!(time::BoundedClock<*const ()>: Sync);
+    assert_impl!(time::BoundedClock<()>: Unpin);
+    assert_not_impl!(time::BoundedClock<PhantomPinned>: Unpin);
+
+    assert_impl!(time::Elapsed: Send);
+    assert_impl!(time::Elapsed: Sync);
+    assert_impl!(time::Elapsed: Unpin);
+
+    assert_impl!(time::Sleep: Send);
+    assert_impl!(time::Sleep: Sync);
+    assert_impl!(time::Sleep: Unpin);
+
+    assert_impl!(time::SystemClock: Send);
+    assert_impl!(time::SystemClock: Sync);
+    assert_impl!(time::SystemClock: Unpin);
+
+    assert_impl!(time::Timeout<()>: Send);
+    assert_not_impl!(time::Timeout<*const ()>: Send);
+    assert_impl!(time::Timeout<()>: Sync);
+    assert_not_impl!(time::Timeout<*const ()>: Sync);
+    assert_impl!(time::Timeout<()>: Unpin);
+    assert_not_impl!(time::Timeout<PhantomPinned>: Unpin);
+
+    assert_impl!(time::Timer: Send);
+    assert_impl!(time::Timer: Sync);
+    assert_impl!(time::Timer: Unpin);
 }
 
 /// Assert Send/Sync/Unpin for all public types in `futures::future`.
@@ -648,7 +1028,7 @@ pub mod future {
 /// Assert Send/Sync/Unpin for all public types in `futures::io`.
 pub mod io {
     use super::*;
-    use futures::io::{Sink, *};
+    use futures::io::{codec::*, Sink, *};
 
     assert_impl!(AllowStdIo<()>: Send);
     assert_not_impl!(AllowStdIo<*const ()>: Send);
@@ -696,6 +1076,14 @@ pub mod io {
     assert_impl!(Copy<(), PhantomPinned>: Unpin);
     assert_not_impl!(Copy<PhantomPinned, ()>: Unpin);
 
+    assert_impl!(CopyBidirectional<'_, (), ()>: Send);
+    assert_not_impl!(CopyBidirectional<'_, (), *const ()>: Send);
+    assert_not_impl!(CopyBidirectional<'_, *const (), ()>: Send);
+    assert_impl!(CopyBidirectional<'_, (), ()>: Sync);
+    assert_not_impl!(CopyBidirectional<'_, (), *const ()>: Sync);
+    assert_not_impl!(CopyBidirectional<'_, *const (), ()>: Sync);
+    assert_impl!(CopyBidirectional<'_, PhantomPinned, PhantomPinned>: Unpin);
+
     assert_impl!(CopyBuf<(), ()>: Send);
     assert_not_impl!(CopyBuf<(), *const ()>: Send);
     assert_not_impl!(CopyBuf<*const (), ()>: Send);
@@ -712,6 +1100,10 @@ pub mod io {
     assert_impl!(Cursor<()>: Unpin);
     assert_not_impl!(Cursor<PhantomPinned>: Unpin);
 
+    assert_impl!(DuplexStream: Send);
+    assert_impl!(DuplexStream: Sync);
+    assert_impl!(DuplexStream: Unpin);
+
     assert_impl!(Empty: Send);
     assert_impl!(Empty: Sync);
     assert_impl!(Empty: Unpin);
@@ -729,6 +1121,15 @@ pub mod io {
     assert_impl!(Flush<'_, ()>: Unpin);
     assert_not_impl!(Flush<'_, PhantomPinned>: Unpin);
 
+    assert_impl!(Framed<(), ()>: Send);
+    assert_not_impl!(Framed<*const (), ()>: Send);
+    assert_not_impl!(Framed<(), *const ()>: Send);
+    assert_impl!(Framed<(), ()>: Sync);
+    assert_not_impl!(Framed<*const (), ()>: Sync);
+    assert_not_impl!(Framed<(), *const ()>: Sync);
+    assert_impl!(Framed<(), PhantomPinned>: Unpin);
+    assert_not_impl!(Framed<PhantomPinned, ()>: Unpin);
+
     assert_impl!(IntoSink<(), ()>: Send);
     assert_not_impl!(IntoSink<(), *const ()>: Send);
     assert_not_impl!(IntoSink<*const (), ()>: Send);
@@ -738,6 +1139,33 @@ pub mod io {
     assert_impl!(IntoSink<(), PhantomPinned>: Unpin);
     assert_not_impl!(IntoSink<PhantomPinned, ()>: Unpin);
 
+    assert_impl!(IntoStream<()>: Send);
+    assert_not_impl!(IntoStream<*const ()>: Send);
+    assert_impl!(IntoStream<()>: Sync);
+    assert_not_impl!(IntoStream<*const ()>: Sync);
+    assert_impl!(IntoStream<()>: Unpin);
+    assert_not_impl!(IntoStream<PhantomPinned>: Unpin);
+
+    assert_impl!(LengthDelimitedCodec: Send);
+    assert_impl!(LengthDelimitedCodec: Sync);
+    assert_impl!(LengthDelimitedCodec: Unpin);
+
+    assert_impl!(LengthDelimitedCodecBuilder: Send);
+    assert_impl!(LengthDelimitedCodecBuilder: Sync);
+    assert_impl!(LengthDelimitedCodecBuilder: Unpin);
+
+    assert_impl!(Limited<(), (), ()>: Send);
+    assert_not_impl!(Limited<(), (), *const ()>: Send);
+    assert_not_impl!(Limited<(), *const (), ()>: Send);
+    assert_not_impl!(Limited<*const (), (), ()>: Send);
+    assert_impl!(Limited<(), (), ()>: Sync);
+    assert_not_impl!(Limited<(), (), *const ()>: Sync);
+    assert_not_impl!(Limited<(), *const (), ()>: Sync);
+    assert_not_impl!(Limited<*const (), (), ()>: Sync);
+    assert_impl!(Limited<(), PhantomPinned, ()>: Unpin);
+    assert_not_impl!(Limited<(), (), PhantomPinned>: Unpin);
+    assert_not_impl!(Limited<PhantomPinned, (), ()>: Unpin);
+
     assert_impl!(Lines<()>: Send);
     assert_not_impl!(Lines<*const ()>: Send);
     assert_impl!(Lines<()>: Sync);
@@ -745,6 +1173,10 @@ pub mod io {
     assert_impl!(Lines<()>: Unpin);
     assert_not_impl!(Lines<PhantomPinned>: Unpin);
 
+    assert_impl!(LinesCodec: Send);
+    assert_impl!(LinesCodec: Sync);
+    assert_impl!(LinesCodec: Unpin);
+
     assert_impl!(Read<'_, ()>: Send);
     assert_not_impl!(Read<'_, *const ()>: Send);
     assert_impl!(Read<'_, ()>: Sync);
@@ -752,6 +1184,10 @@ pub mod io {
     assert_impl!(Read<'_, ()>: Unpin);
     assert_not_impl!(Read<'_, PhantomPinned>: Unpin);
 
+    assert_impl!(ReadBuf<'_>: Send);
+    assert_impl!(ReadBuf<'_>: Sync);
+    assert_impl!(ReadBuf<'_>: Unpin);
+
     assert_impl!(ReadExact<'_, ()>: Send);
     assert_not_impl!(ReadExact<'_, *const ()>: Send);
     assert_impl!(ReadExact<'_, ()>: Sync);
@@ -834,6 +1270,18 @@ pub mod io {
     assert_impl!(Take<()>: Unpin);
     assert_not_impl!(Take<PhantomPinned>: Unpin);
 
+    assert_impl!(Timeout<(), (), ()>: Send);
+    assert_not_impl!(Timeout<(), (), *const ()>: Send);
+    assert_not_impl!(Timeout<(), *const (), ()>: Send);
+    assert_not_impl!(Timeout<*const (), (), ()>: Send);
+    assert_impl!(Timeout<(), (), ()>: Sync);
+    assert_not_impl!(Timeout<(), (), *const ()>: Sync);
+    assert_not_impl!(Timeout<(), *const (), ()>: Sync);
+    assert_not_impl!(Timeout<*const (), (), ()>: Sync);
+    assert_impl!(Timeout<(), PhantomPinned, ()>: Unpin);
+    assert_not_impl!(Timeout<(), (), PhantomPinned>: Unpin);
+    assert_not_impl!(Timeout<PhantomPinned, (), ()>: Unpin);
+
     assert_impl!(Window<()>: Send);
     assert_not_impl!(Window<*const ()>: Send);
     assert_impl!(Window<()>: Sync);
@@ -881,6 +1329,18 @@ pub mod io {
     assert_not_impl!(WriteVectored<'_, *const ()>: Sync);
     assert_impl!(WriteVectored<'_, ()>: Unpin);
     assert_not_impl!(WriteVectored<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(untrusted::HostFd: Send);
+    assert_impl!(untrusted::HostFd: Sync);
+    assert_impl!(untrusted::HostFd: Unpin);
+
+    assert_impl!(untrusted::Interest: Send);
+    assert_impl!(untrusted::Interest: Sync);
+    assert_impl!(untrusted::Interest: Unpin);
+
+    assert_impl!(untrusted::Reactor: Send);
+    assert_impl!(untrusted::Reactor: Sync);
+    assert_impl!(untrusted::Reactor: Unpin);
 }
 
 /// Assert Send/Sync/Unpin for all public types in `futures::lock`.
@@ -888,6 +1348,26 @@ pub mod lock {
     use super::*;
     use futures::lock::*;
 
+    assert_impl!(Acquire<'_>: Send);
+    assert_impl!(Acquire<'_>: Sync);
+    assert_impl!(Acquire<'_>: Unpin);
+
+    assert_impl!(AcquireOwned: Send);
+    assert_impl!(AcquireOwned: Sync);
+    assert_impl!(AcquireOwned: Unpin);
+
+    assert_impl!(Barrier: Send);
+    assert_impl!(Barrier: Sync);
+    assert_impl!(Barrier: Unpin);
+
+    assert_impl!(BarrierWait<'_>: Send);
+    assert_impl!(BarrierWait<'_>: Sync);
+    assert_impl!(BarrierWait<'_>: Unpin);
+
+    assert_impl!(BarrierWaitResult: Send);
+    assert_impl!(BarrierWaitResult: Sync);
+    assert_impl!(BarrierWaitResult: Unpin);
+
     #[cfg(feature = "bilock")]
     assert_impl!(BiLock<()>: Send);
     #[cfg(feature = "bilock")]
@@ -921,6 +1401,40 @@ pub mod lock {
     #[cfg(feature = "bilock")]
     assert_impl!(BiLockGuard<'_, PhantomPinned>: Unpin);
 
+    assert_impl!(Condvar: Send);
+    assert_impl!(Condvar: Sync);
+    assert_impl!(Condvar: Unpin);
+
+    assert_impl!(CondvarWait<'_, ()>: Send);
+    assert_not_impl!(CondvarWait<'_, *const ()>: Send);
+    assert_impl!(CondvarWait<'_, ()>: Sync);
+    assert_not_impl!(CondvarWait<'_, *const ()>: Sync);
+    assert_impl!(CondvarWait<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(GetOrInit<'_, (), (), ()>: Send);
+    assert_not_impl!(GetOrInit<'_, *const (), (), ()>: Send);
+    assert_not_impl!(GetOrInit<'_, std::cell::Cell<()>, (), ()>: Send);
+    assert_not_impl!(GetOrInit<'_, (), *const (), ()>: Send);
+    assert_not_impl!(GetOrInit<'_, (), (), *const ()>: Send);
+    assert_impl!(GetOrInit<'_, (), (), ()>: Sync);
+    assert_not_impl!(GetOrInit<'_, *const (), (), ()>: Sync);
+    assert_not_impl!(GetOrInit<'_, (), *const (), ()>: Sync);
+    assert_not_impl!(GetOrInit<'_, (), (), *const ()>: Sync);
+    assert_impl!(GetOrInit<'_, PhantomPinned, PhantomPinned, ()>: Unpin);
+    assert_not_impl!(GetOrInit<'_, (), (), PhantomPinned>: Unpin);
+
+    assert_impl!(GetOrTryInit<'_, (), (), ()>: Send);
+    assert_not_impl!(GetOrTryInit<'_, *const (), (), ()>: Send);
+    assert_not_impl!(GetOrTryInit<'_, std::cell::Cell<()>, (), ()>: Send);
+    assert_not_impl!(GetOrTryInit<'_, (), *const (), ()>: Send);
+    assert_not_impl!(GetOrTryInit<'_, (), (), *const ()>: Send);
+    assert_impl!(GetOrTryInit<'_, (), (), ()>: Sync);
+    assert_not_impl!(GetOrTryInit<'_, *const (), (), ()>: Sync);
+    assert_not_impl!(GetOrTryInit<'_, (), *const (), ()>: Sync);
+    assert_not_impl!(GetOrTryInit<'_, (), (), *const ()>: Sync);
+    assert_impl!(GetOrTryInit<'_, PhantomPinned, PhantomPinned, ()>: Unpin);
+    assert_not_impl!(GetOrTryInit<'_, (), (), PhantomPinned>: Unpin);
+
     assert_impl!(MappedMutexGuard<'_, (), ()>: Send);
     assert_not_impl!(MappedMutexGuard<'_, (), *const ()>: Send);
     assert_not_impl!(MappedMutexGuard<'_, *const (), ()>: Send);
@@ -947,6 +1461,59 @@ pub mod lock {
     assert_impl!(MutexLockFuture<'_, *const ()>: Sync);
     assert_impl!(MutexLockFuture<'_, PhantomPinned>: Unpin);
 
+    assert_impl!(Notified<'_>: Send);
+    assert_impl!(Notified<'_>: Sync);
+    assert_impl!(Notified<'_>: Unpin);
+
+    assert_impl!(Notify: Send);
+    assert_impl!(Notify: Sync);
+    assert_impl!(Notify: Unpin);
+
+    assert_impl!(OnceCell<()>: Send);
+    assert_not_impl!(OnceCell<*const ()>: Send);
+    assert_impl!(OnceCell<()>: Sync);
+    assert_not_impl!(OnceCell<*const ()>: Sync);
+    assert_not_impl!(OnceCell<std::cell::Cell<()>>: Sync);
+    assert_impl!(OnceCell<()>: Unpin);
+    assert_not_impl!(OnceCell<PhantomPinned>: Unpin);
+
+    assert_impl!(OwnedMutexGuard<()>: Send);
+    assert_not_impl!(OwnedMutexGuard<*const ()>: Send);
+    assert_impl!(OwnedMutexGuard<()>: Sync);
+    assert_not_impl!(OwnedMutexGuard<*const ()>: Sync);
+    assert_impl!(OwnedMutexGuard<PhantomPinned>: Unpin);
+
+    assert_impl!(OwnedMutexLockFuture<()>: Send);
+    assert_not_impl!(OwnedMutexLockFuture<*const ()>: Send);
+    assert_impl!(OwnedMutexLockFuture<*const ()>: Sync);
+    assert_impl!(OwnedMutexLockFuture<PhantomPinned>: Unpin);
+
+    assert_impl!(OwnedRwLockReadFuture<()>: Send);
+    assert_not_impl!(OwnedRwLockReadFuture<*const ()>: Send);
+    assert_impl!(OwnedRwLockReadFuture<*const ()>: Sync);
+    assert_impl!(OwnedRwLockReadFuture<PhantomPinned>: Unpin);
+
+    assert_impl!(OwnedRwLockReadGuard<()>: Send);
+    assert_not_impl!(OwnedRwLockReadGuard<*const ()>: Send);
+    assert_impl!(OwnedRwLockReadGuard<()>: Sync);
+    assert_not_impl!(OwnedRwLockReadGuard<*const ()>: Sync);
+    assert_impl!(OwnedRwLockReadGuard<PhantomPinned>: Unpin);
+
+    assert_impl!(OwnedRwLockWriteFuture<()>: Send);
+    assert_not_impl!(OwnedRwLockWriteFuture<*const ()>: Send);
+    assert_impl!(OwnedRwLockWriteFuture<*const ()>: Sync);
+    assert_impl!(OwnedRwLockWriteFuture<PhantomPinned>: Unpin);
+
+    assert_impl!(OwnedRwLockWriteGuard<()>: Send);
+    assert_not_impl!(OwnedRwLockWriteGuard<*const ()>: Send);
+    assert_impl!(OwnedRwLockWriteGuard<()>: Sync);
+    assert_not_impl!(OwnedRwLockWriteGuard<*const ()>: Sync);
+    assert_impl!(OwnedRwLockWriteGuard<PhantomPinned>: Unpin);
+
+    assert_impl!(OwnedSemaphorePermit: Send);
+    assert_impl!(OwnedSemaphorePermit: Sync);
+    assert_impl!(OwnedSemaphorePermit: Unpin);
+
     #[cfg(feature = "bilock")]
     assert_impl!(ReuniteError<()>: Send);
     #[cfg(feature = "bilock")]
@@ -957,6 +1524,48 @@ pub mod lock {
     assert_not_impl!(ReuniteError<*const ()>: Sync);
     #[cfg(feature = "bilock")]
     assert_impl!(ReuniteError<PhantomPinned>: Unpin);
+
+    assert_impl!(RwLock<()>: Send);
+    assert_not_impl!(RwLock<*const ()>: Send);
+    assert_impl!(RwLock<()>: Sync);
+    assert_not_impl!(RwLock<*const ()>: Sync);
+    assert_not_impl!(RwLock<std::cell::Cell<()>>: Sync);
+    assert_impl!(RwLock<()>: Unpin);
+    assert_not_impl!(RwLock<PhantomPinned>: Unpin);
+
+    assert_impl!(RwLockPriority: Send);
+    assert_impl!(RwLockPriority: Sync);
+    assert_impl!(RwLockPriority: Unpin);
+
+    assert_impl!(RwLockReadFuture<'_, ()>: Send);
+    assert_not_impl!(RwLockReadFuture<'_, *const ()>: Send);
+    assert_impl!(RwLockReadFuture<'_, *const ()>: Sync);
+    assert_impl!(RwLockReadFuture<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(RwLockReadGuard<'_, ()>: Send);
+    assert_not_impl!(RwLockReadGuard<'_, *const ()>: Send);
+    assert_impl!(RwLockReadGuard<'_, ()>: Sync);
+    assert_not_impl!(RwLockReadGuard<'_, *const ()>: Sync);
+    assert_impl!(RwLockReadGuard<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(RwLockWriteFuture<'_, ()>: Send);
+    assert_not_impl!(RwLockWriteFuture<'_, *const ()>: Send);
+    assert_impl!(RwLockWriteFuture<'_, *const ()>: Sync);
+    assert_impl!(RwLockWriteFuture<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(RwLockWriteGuard<'_, ()>: Send);
+    assert_not_impl!(RwLockWriteGuard<'_, *const ()>: Send);
+    assert_impl!(RwLockWriteGuard<'_, ()>: Sync);
+    assert_not_impl!(RwLockWriteGuard<'_, *const ()>: Sync);
+    assert_impl!(RwLockWriteGuard<'_, PhantomPinned>: Unpin);
+
+    assert_impl!(Semaphore: Send);
+    assert_impl!(Semaphore: Sync);
+    assert_impl!(Semaphore: Unpin);
+
+    assert_impl!(SemaphorePermit<'_>: Send);
+    assert_impl!(SemaphorePermit<'_>: Sync);
+    assert_impl!(SemaphorePermit<'_>: Unpin);
 }
 
 /// Assert Send/Sync/Unpin for all public types in `futures::sink`.
@@ -974,6 +1583,16 @@ pub mod sink {
     assert_impl!(Buffer<(), PhantomPinned>: Unpin);
     assert_not_impl!(Buffer<PhantomPinned, ()>: Unpin);
 
+    assert_impl!(BufferTimed<SendSink<(), ()>, ()>: Send);
+    assert_not_impl!(BufferTimed<SendSink<(), *const ()>, ()>: Send);
+    assert_not_impl!(BufferTimed<SendSink<*const (), ()>, *const ()>: Send);
+    assert_not_impl!(BufferTimed<LocalSink<(), ()>, ()>: Send);
+    assert_impl!(BufferTimed<SendSink<(), ()>, ()>: Sync);
+    assert_not_impl!(BufferTimed<SendSink<(), *const ()>, ()>: Sync);
+    assert_not_impl!(BufferTimed<SendSink<*const (), ()>, *const ()>: Sync);
+    assert_not_impl!(BufferTimed<LocalSink<(), ()>, ()>: Sync);
+    assert_impl!(BufferTimed<PinnedSink, PhantomPinned>: Unpin);
+
     assert_impl!(Close<'_, (), *const ()>: Send);
     assert_not_impl!(Close<'_, *const (), ()>: Send);
     assert_impl!(Close<'_, (), *const ()>: Sync);
@@ -997,6 +1616,12 @@ pub mod sink {
     assert_not_impl!(Fanout<(), PhantomPinned>: Unpin);
     assert_not_impl!(Fanout<PhantomPinned, ()>: Unpin);
 
+    assert_impl!(FanoutVec<()>: Send);
+    assert_not_impl!(FanoutVec<*const ()>: Send);
+    assert_impl!(FanoutVec<()>: Sync);
+    assert_not_impl!(FanoutVec<*const ()>: Sync);
+    assert_impl!(FanoutVec<PhantomPinned>: Unpin);
+
     assert_impl!(Feed<'_, (), ()>: Send);
     assert_not_impl!(Feed<'_, (), *const ()>: Send);
     assert_not_impl!(Feed<'_, *const (), ()>: Send);
@@ -1013,6 +1638,17 @@ pub mod sink {
     assert_impl!(Flush<'_, (), PhantomPinned>: Unpin);
     assert_not_impl!(Flush<'_, PhantomPinned, ()>: Unpin);
 
+    assert_impl!(Linger<SendSink<(), ()>, (), (), ()>: Send);
+    assert_not_impl!(Linger<SendSink<(), ()>, (), (), *const ()>: Send);
+    assert_not_impl!(Linger<SendSink<(), ()>, (), *const (), ()>: Send);
+    assert_not_impl!(Linger<LocalSink<(), ()>, (), (), ()>: Send);
+    assert_impl!(Linger<SendSink<(), ()>, (), (), ()>: Sync);
+    assert_not_impl!(Linger<SendSink<(), ()>, (), (), *const ()>: Sync);
+    assert_not_impl!(Linger<SendSink<(), ()>, (), *const (), ()>: Sync);
+    assert_not_impl!(Linger<LocalSink<(), ()>, (), (), ()>: Sync);
+    assert_impl!(Linger<PinnedSink, PhantomPinned, PhantomPinned, ()>: Unpin);
+    assert_not_impl!(Linger<SendSink<(), ()>, (), (), PhantomPinned>: Unpin);
+
     assert_impl!(sink::Send<'_, (), ()>: Send);
     assert_not_impl!(sink::Send<'_, (), *const ()>: Send);
     assert_not_impl!(sink::Send<'_, *const (), ()>: Send);
@@ -1160,6 +1796,15 @@ pub mod stream {
     assert_impl!(Collect<(), PhantomPinned>: Unpin);
     assert_not_impl!(Collect<PhantomPinned, ()>: Unpin);
 
+    assert_impl!(CollectInto<'_, (), ()>: Send);
+    assert_not_impl!(CollectInto<'_, *const (), ()>: Send);
+    assert_not_impl!(CollectInto<'_, (), *const ()>: Send);
+    assert_impl!(CollectInto<'_, (), ()>: Sync);
+    assert_not_impl!(CollectInto<'_, *const (), ()>: Sync);
+    assert_not_impl!(CollectInto<'_, (), *const ()>: Sync);
+    assert_impl!(CollectInto<'_, (), PhantomPinned>: Unpin);
+    assert_not_impl!(CollectInto<'_, PhantomPinned, ()>: Unpin);
+
     assert_impl!(Concat<SendStream<()>>: Send);
     assert_not_impl!(Concat<SendStream>: Send);
     assert_not_impl!(Concat<LocalStream>: Send);
@@ -1169,6 +1814,13 @@ pub mod stream {
     assert_impl!(Concat<UnpinStream>: Unpin);
     assert_not_impl!(Concat<PinnedStream>: Unpin);
 
+    assert_impl!(Count<()>: Send);
+    assert_not_impl!(Count<*const ()>: Send);
+    assert_impl!(Count<()>: Sync);
+    assert_not_impl!(Count<*const ()>: Sync);
+    assert_impl!(Count<()>: Unpin);
+    assert_not_impl!(Count<PhantomPinned>: Unpin);
+
     assert_impl!(Cycle<()>: Send);
     assert_not_impl!(Cycle<*const ()>: Send);
     assert_impl!(Cycle<()>: Sync);
@@ -1176,6 +1828,26 @@ pub mod stream {
     assert_impl!(Cycle<()>: Unpin);
     assert_not_impl!(Cycle<PhantomPinned>: Unpin);
 
+    assert_impl!(Dedup<SendStream<()>>: Send);
+    assert_not_impl!(Dedup<SendStream>: Send);
+    assert_not_impl!(Dedup<LocalStream>: Send);
+    assert_impl!(Dedup<SyncStream<()>>: Sync);
+    assert_not_impl!(Dedup<SyncStream>: Sync);
+    assert_not_impl!(Dedup<LocalStream>: Sync);
+    assert_impl!(Dedup<UnpinStream>: Unpin);
+    assert_not_impl!(Dedup<PinnedStream>: Unpin);
+
+    assert_impl!(DedupByKey<(), (), ()>: Send);
+    assert_not_impl!(DedupByKey<*const (), (), ()>: Send);
+    assert_not_impl!(DedupByKey<(), *const (), ()>: Send);
+    assert_not_impl!(DedupByKey<(), (), *const ()>: Send);
+    assert_impl!(DedupByKey<(), (), ()>: Sync);
+    assert_not_impl!(DedupByKey<*const (), (), ()>: Sync);
+    assert_not_impl!(DedupByKey<(), *const (), ()>: Sync);
+    assert_not_impl!(DedupByKey<(), (), *const ()>: Sync);
+    assert_impl!(DedupByKey<(), PhantomPinned, PhantomPinned>: Unpin);
+    assert_not_impl!(DedupByKey<PhantomPinned, (), ()>: Unpin);
+
     assert_impl!(Empty<()>: Send);
     assert_not_impl!(Empty<*const ()>: Send);
     assert_impl!(Empty<()>: Sync);
@@ -1588,6 +2260,18 @@ pub mod stream {
     assert_impl!(StreamFuture<()>: Unpin);
     assert_not_impl!(StreamFuture<PhantomPinned>: Unpin);
 
+    assert_impl!(SwitchMap<(), (), ()>: Send);
+    assert_not_impl!(SwitchMap<*const (), (), ()>: Send);
+    assert_not_impl!(SwitchMap<(), *const (), ()>: Send);
+    assert_not_impl!(SwitchMap<(), (), *const ()>: Send);
+    assert_impl!(SwitchMap<(), (), ()>: Sync);
+    assert_not_impl!(SwitchMap<*const (), (), ()>: Sync);
+    assert_not_impl!(SwitchMap<(), *const (), ()>: Sync);
+    assert_not_impl!(SwitchMap<(), (), *const ()>: Sync);
+    assert_impl!(SwitchMap<(), (), PhantomPinned>: Unpin);
+    assert_not_impl!(SwitchMap<PhantomPinned, (), ()>: Unpin);
+    assert_not_impl!(SwitchMap<(), PhantomPinned, ()>: Unpin);
+
     assert_impl!(Take<()>: Send);
     assert_not_impl!(Take<*const ()>: Send);
     assert_impl!(Take<()>: Sync);
@@ -1814,6 +2498,15 @@ pub mod stream {
     assert_impl!(Unzip<(), PhantomPinned, PhantomPinned>: Unpin);
     assert_not_impl!(Unzip<PhantomPinned, (), ()>: Unpin);
 
+    assert_impl!(Windows<SendStream<()>>: Send);
+    assert_not_impl!(Windows<SendStream>: Send);
+    assert_not_impl!(Windows<LocalStream>: Send);
+    assert_impl!(Windows<SyncStream<()>>: Sync);
+    assert_not_impl!(Windows<SyncStream>: Sync);
+    assert_not_impl!(Windows<LocalStream>: Sync);
+    assert_impl!(Windows<UnpinStream>: Unpin);
+    assert_not_impl!(Windows<PinnedStream>: Unpin);
+
     assert_impl!(Zip<SendStream<()>, SendStream<()>>: Send);
     assert_not_impl!(Zip<SendStream, SendStream<()>>: Send);
     assert_not_impl!(Zip<SendStream<()>, SendStream>: Send);
@@ -1869,23 +2562,62 @@ pub mod task {
     use super::*;
     use futures::task::*;
 
+    assert_impl!(AccessError: Send);
+    assert_impl!(AccessError: Sync);
+    assert_impl!(AccessError: Unpin);
+
     assert_impl!(AtomicWaker: Send);
     assert_impl!(AtomicWaker: Sync);
     assert_impl!(AtomicWaker: Unpin);
 
+    assert_impl!(ConsumeBudget: Send);
+    assert_impl!(ConsumeBudget: Sync);
+    assert_impl!(ConsumeBudget: Unpin);
+
     assert_impl!(FutureObj<*const ()>: Send);
     assert_not_impl!(FutureObj<()>: Sync);
     assert_impl!(FutureObj<PhantomPinned>: Unpin);
 
+    assert_impl!(JoinError: Send);
+    assert_impl!(JoinError: Sync);
+    assert_impl!(JoinError: Unpin);
+
+    assert_impl!(JoinHandle<()>: Send);
+    assert_not_impl!(JoinHandle<*const ()>: Send);
+    assert_impl!(JoinHandle<()>: Sync);
+    assert_not_impl!(JoinHandle<*const ()>: Sync);
+    assert_impl!(JoinHandle<PhantomPinned>: Unpin);
+
     assert_not_impl!(LocalFutureObj<()>: Send);
     assert_not_impl!(LocalFutureObj<()>: Sync);
     assert_impl!(LocalFutureObj<PhantomPinned>: Unpin);
 
+    assert_impl!(LocalKey<*const ()>: Send);
+    assert_impl!(LocalKey<*const ()>: Sync);
+    assert_impl!(LocalKey<*const ()>: Unpin);
+
     assert_impl!(SpawnError: Send);
     assert_impl!(SpawnError: Sync);
     assert_impl!(SpawnError: Unpin);
 
+    assert_impl!(TaskLocalFuture<(), SendFuture>: Send);
+    assert_not_impl!(TaskLocalFuture<*const (), SendFuture>: Send);
+    assert_not_impl!(TaskLocalFuture<(), LocalFuture>: Send);
+    assert_impl!(TaskLocalFuture<(), SyncFuture>: Sync);
+    assert_not_impl!(TaskLocalFuture<*const (), SyncFuture>: Sync);
+    assert_not_impl!(TaskLocalFuture<(), LocalFuture>: Sync);
+    assert_impl!(TaskLocalFuture<PhantomPinned, UnpinFuture>: Unpin);
+    assert_not_impl!(TaskLocalFuture<(), PinnedFuture>: Unpin);
+
     assert_impl!(WakerRef<'_>: Send);
     assert_impl!(WakerRef<'_>: Sync);
     assert_impl!(WakerRef<'_>: Unpin);
+
+    assert_impl!(WakerSet: Send);
+    assert_impl!(WakerSet: Sync);
+    assert_impl!(WakerSet: Unpin);
+
+    assert_impl!(YieldNow: Send);
+    assert_impl!(YieldNow: Sync);
+    assert_impl!(YieldNow: Unpin);
 }
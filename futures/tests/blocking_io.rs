@@ -0,0 +1,110 @@
+use futures::executor::{block_on, BlockingIo};
+use futures::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use std::io::{self, Cursor, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct SharedWriter {
+    data: Arc<Mutex<Vec<u8>>>,
+    flushes: Arc<Mutex<usize>>,
+}
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        *self.flushes.lock().unwrap() += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn read() {
+    let mut reader = BlockingIo::new(&b"hello world"[..]);
+    let mut buf = [0; 3];
+    let mut output = Vec::new();
+    block_on(async {
+        loop {
+            let n = reader.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            output.extend_from_slice(&buf[..n]);
+        }
+    });
+    assert_eq!(output, b"hello world");
+}
+
+#[test]
+fn write_and_flush() {
+    let writer = SharedWriter::default();
+    let mut io = BlockingIo::new(writer.clone());
+    block_on(async {
+        io.write_all(b"hello ").await.unwrap();
+        io.write_all(b"world").await.unwrap();
+        io.flush().await.unwrap();
+        // Nothing was written since the last flush.
+        io.flush().await.unwrap();
+    });
+    assert_eq!(&*writer.data.lock().unwrap(), b"hello world");
+    assert_eq!(*writer.flushes.lock().unwrap(), 1);
+}
+
+#[test]
+fn write_error() {
+    struct Failing;
+
+    impl Write for Failing {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "boom"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut io = BlockingIo::new(Failing);
+    block_on(async {
+        // The write returns once its data is buffered, and its error is
+        // returned by the next operation.
+        assert_eq!(io.write(b"data").await.unwrap(), 4);
+        assert_eq!(io.flush().await.unwrap_err().to_string(), "boom");
+    });
+}
+
+#[test]
+fn seek() {
+    let mut io = BlockingIo::new(Cursor::new(Vec::new()));
+    let mut output = String::new();
+    block_on(async {
+        io.write_all(b"hello world").await.unwrap();
+        assert_eq!(io.seek(SeekFrom::Start(6)).await.unwrap(), 6);
+        io.read_to_string(&mut output).await.unwrap();
+        assert_eq!(io.seek(SeekFrom::End(-11)).await.unwrap(), 0);
+        io.write_all(b"jello").await.unwrap();
+        assert_eq!(io.seek(SeekFrom::Current(-5)).await.unwrap(), 0);
+        io.read_to_string(&mut output).await.unwrap();
+    });
+    assert_eq!(output, "worldjello world");
+}
+
+#[test]
+fn seek_buffered() {
+    let io = BlockingIo::new(Cursor::new(b"first\nsecond\n".to_vec()));
+    let mut reader = BufReader::new(io);
+    let mut line = String::new();
+    block_on(async {
+        reader.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "first\n");
+        // The data read ahead by the `BufReader` is accounted for.
+        assert_eq!(reader.seek(SeekFrom::Current(0)).await.unwrap(), 6);
+        assert_eq!(reader.seek(SeekFrom::Current(-6)).await.unwrap(), 0);
+        line.clear();
+        reader.read_line(&mut line).await.unwrap();
+    });
+    assert_eq!(line, "first\n");
+}
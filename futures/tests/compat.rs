@@ -13,3 +13,24 @@ fn can_use_01_futures_in_a_03_future_running_on_a_01_executor() {
     let mut runtime = Runtime::new().unwrap();
     runtime.block_on(f.boxed().compat()).unwrap();
 }
+
+#[test]
+fn compat_executor_reports_shutdown() {
+    use futures::task::{FutureObj, Spawn, SpawnError, SpawnExt};
+    use tokio::prelude::future::{self as future01, ExecuteErrorKind, Executor};
+
+    struct ShutDown;
+
+    impl Spawn for ShutDown {
+        fn spawn_obj(&self, _: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+            Err(SpawnError::shutdown())
+        }
+
+        fn status(&self) -> Result<(), SpawnError> {
+            Err(SpawnError::shutdown())
+        }
+    }
+
+    let error = ShutDown.compat().execute(future01::ok(())).unwrap_err();
+    assert!(matches!(error.kind(), ExecuteErrorKind::Shutdown));
+}
@@ -118,6 +118,7 @@ fn test_buffered_reader() {
     block_on(async {
         let inner: &[u8] = &[5, 6, 7, 0, 1, 2, 3, 4];
         let mut reader = BufReader::with_capacity(2, inner);
+        assert_eq!(reader.capacity(), 2);
 
         let mut buf = [0, 0, 0];
         let nread = reader.read(&mut buf).await.unwrap();
@@ -104,6 +104,17 @@ fn buf_writer_inner_flushes() {
     assert_eq!(w, [0, 1]);
 }
 
+#[test]
+fn buf_writer_close_flushes() {
+    let mut w = BufWriter::with_capacity(3, Vec::new());
+    assert_eq!(w.capacity(), 3);
+    block_on(w.write(&[0, 1])).unwrap();
+    assert_eq!(*w.get_ref(), []);
+    block_on(w.close()).unwrap();
+    assert_eq!(w.buffer(), []);
+    assert_eq!(*w.get_ref(), [0, 1]);
+}
+
 #[test]
 fn buf_writer_seek() {
     // FIXME: when https://github.com/rust-lang/futures-rs/issues/1510 fixed,
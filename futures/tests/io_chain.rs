@@ -0,0 +1,35 @@
+use futures::executor::block_on;
+use futures::io::{AsyncBufReadExt, AsyncReadExt, Cursor};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use futures_test::io::AsyncReadTestExt;
+
+#[test]
+fn read_to_end() {
+    let mut reader = Cursor::new(&b"HELO "[..]).chain(Cursor::new(&b"world"[..]));
+    let mut buf = Vec::new();
+    block_on(reader.read_to_end(&mut buf)).unwrap();
+    assert_eq!(buf, b"HELO world");
+
+    let (first, second) = reader.into_inner();
+    assert_eq!(first.position(), 5);
+    assert_eq!(second.position(), 5);
+}
+
+#[test]
+fn empty_first() {
+    let mut reader = Cursor::new(&b""[..]).chain(Cursor::new(&b"data"[..]));
+    let mut buf = [0; 4];
+    block_on(reader.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"data");
+}
+
+#[test]
+fn lines_across_readers() {
+    let prefix = Cursor::new(&b"first li"[..]);
+    let rest = stream::iter(vec![&b"ne\nsecond"[..], &b" line\n"[..]])
+        .map(Ok)
+        .into_async_read()
+        .interleave_pending();
+    let lines: Vec<String> = block_on(prefix.chain(rest).lines().try_collect()).unwrap();
+    assert_eq!(lines, ["first line", "second line"]);
+}
@@ -0,0 +1,75 @@
+use futures::executor::block_on;
+use futures::io::codec::{Decoder, Framed, LengthDelimitedCodec, LinesCodec};
+use futures::io::{self, Cursor};
+use futures::sink::SinkExt;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use futures_test::io::AsyncReadTestExt;
+
+#[test]
+fn lines_read() {
+    let reader = stream::iter(vec![&b"first li"[..], &b"ne\r\nsecond\n"[..], &b"last"[..]])
+        .map(Ok)
+        .into_async_read()
+        .interleave_pending();
+    let lines: Vec<String> =
+        block_on(Framed::new(reader, LinesCodec::new()).try_collect()).unwrap();
+    assert_eq!(lines, ["first line", "second", "last"]);
+}
+
+#[test]
+fn lines_max_length() {
+    let reader = Cursor::new(&b"short\nthis line is too long\nnever read\n"[..]);
+    let mut framed = Framed::new(reader, LinesCodec::new_with_max_length(10));
+    assert_eq!(block_on(framed.next()).unwrap().unwrap(), "short");
+    let err = block_on(framed.next()).unwrap().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    // Decoding errors end the stream.
+    assert!(block_on(framed.next()).is_none());
+}
+
+#[test]
+fn lines_invalid_utf8() {
+    let mut codec = LinesCodec::new();
+    let mut buf = b"\xff\n".to_vec();
+    assert_eq!(codec.decode(&mut buf).unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn length_delimited_roundtrip() {
+    let codec = LengthDelimitedCodec::builder().length_field_length(3).little_endian().create();
+    let mut framed = Framed::new(Cursor::new(Vec::new()), codec);
+    block_on(async {
+        framed.send(&b"hello"[..]).await.unwrap();
+        framed.send(&b""[..]).await.unwrap();
+        framed.send(&b"world"[..]).await.unwrap();
+    });
+    let data = framed.into_inner().into_inner();
+    assert_eq!(data, b"\x05\x00\x00hello\x00\x00\x00\x05\x00\x00world");
+
+    // Read the frames byte by byte.
+    let reader = stream::iter(data.into_iter().map(|b| Ok(vec![b]))).into_async_read();
+    let frames: Vec<Vec<u8>> = block_on(Framed::new(reader, codec).try_collect()).unwrap();
+    assert_eq!(frames, [&b"hello"[..], b"", b"world"]);
+}
+
+#[test]
+fn length_delimited_max_frame_length() {
+    let codec = LengthDelimitedCodec::builder().max_frame_length(4).create();
+    let mut framed = Framed::new(Cursor::new(Vec::new()), codec);
+    let err = block_on(framed.send(&b"hello"[..])).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let reader = Cursor::new(&b"\x00\x00\x00\x05hello"[..]);
+    let mut framed = Framed::new(reader, codec);
+    let err = block_on(framed.next()).unwrap().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn length_delimited_truncated() {
+    let reader = Cursor::new(&b"\x00\x00\x00\x05hel"[..]);
+    let mut framed = Framed::new(reader, LengthDelimitedCodec::new());
+    let err = block_on(framed.next()).unwrap().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert!(block_on(framed.next()).is_none());
+}
@@ -0,0 +1,131 @@
+use futures::executor::block_on;
+use futures::io::{self, AsyncRead, AsyncWrite, Cursor};
+use futures::task::{Context, Poll};
+use std::pin::Pin;
+
+// Fails each other call with `ErrorKind::Interrupted`.
+struct Interrupting<T> {
+    inner: T,
+    interrupt: bool,
+}
+
+impl<T> Interrupting<T> {
+    fn new(inner: T) -> Self {
+        Self { inner, interrupt: true }
+    }
+
+    fn interrupt(&mut self) -> bool {
+        self.interrupt = !self.interrupt;
+        !self.interrupt
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Interrupting<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.interrupt() {
+            return Poll::Ready(Err(io::ErrorKind::Interrupted.into()));
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Interrupting<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.interrupt() {
+            return Poll::Ready(Err(io::ErrorKind::Interrupted.into()));
+        }
+        // Write a byte at a time, to be interrupted again.
+        Pin::new(&mut self.inner).poll_write(cx, &buf[..1])
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[test]
+fn copy_retries_interrupted() {
+    let reader = Interrupting::new(Cursor::new(vec![1, 2, 3, 4]));
+    let mut writer = Interrupting::new(Vec::new());
+
+    assert_eq!(block_on(io::copy(reader, &mut writer)).unwrap(), 4);
+    assert_eq!(writer.inner, [1, 2, 3, 4]);
+}
+
+#[test]
+fn copy_write_zero() {
+    let reader = Cursor::new(vec![1, 2, 3, 4]);
+    let mut buf = [0u8; 2];
+    let mut writer = Cursor::new(&mut buf[..]);
+
+    let err = block_on(io::copy_buf(reader, &mut writer)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    assert_eq!(buf, [1, 2]);
+}
+
+// Reads from `input` and writes into `output`.
+struct Conn {
+    input: Cursor<Vec<u8>>,
+    output: Vec<u8>,
+    closed: bool,
+}
+
+impl Conn {
+    fn new(input: &[u8]) -> Self {
+        Self { input: Cursor::new(input.to_vec()), output: Vec::new(), closed: false }
+    }
+}
+
+impl AsyncRead for Conn {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.input).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Conn {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.output).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.closed = true;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn copy_bidirectional() {
+    let mut client = Conn::new(b"request");
+    let mut server = Conn::new(b"a longer response");
+
+    let (sent, received) = block_on(io::copy_bidirectional(&mut client, &mut server)).unwrap();
+    assert_eq!((sent, received), (7, 17));
+    assert_eq!(server.output, b"request");
+    assert_eq!(client.output, b"a longer response");
+    assert!(client.closed);
+    assert!(server.closed);
+}
@@ -0,0 +1,50 @@
+use futures::executor::block_on;
+use futures::future::join;
+use futures::io::{self, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use futures::task::{noop_waker_ref, Context, Poll};
+use std::pin::Pin;
+
+#[test]
+fn transfer_larger_than_buffer() {
+    let (mut client, mut server) = io::duplex(16);
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+
+    let write = async {
+        client.write_all(&data).await.unwrap();
+        client.close().await.unwrap();
+    };
+    let mut received = Vec::new();
+    let read = server.read_to_end(&mut received);
+
+    let ((), res) = block_on(join(write, read));
+    assert_eq!(res.unwrap(), 1000);
+    assert_eq!(received, data);
+}
+
+#[test]
+fn write_waits_for_room() {
+    let (mut client, mut server) = io::duplex(4);
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    assert!(matches!(Pin::new(&mut client).poll_write(&mut cx, b"hello"), Poll::Ready(Ok(4))));
+    assert!(Pin::new(&mut client).poll_write(&mut cx, b"o").is_pending());
+
+    let mut buf = [0; 2];
+    block_on(server.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"he");
+    assert!(matches!(Pin::new(&mut client).poll_write(&mut cx, b"o"), Poll::Ready(Ok(1))));
+}
+
+#[test]
+fn drop_closes_both_directions() {
+    let (mut client, mut server) = io::duplex(16);
+    block_on(server.write_all(b"bye")).unwrap();
+    drop(server);
+
+    let mut buf = Vec::new();
+    assert_eq!(block_on(client.read_to_end(&mut buf)).unwrap(), 3);
+    assert_eq!(buf, b"bye");
+
+    let err = block_on(client.write_all(b"hello")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+}
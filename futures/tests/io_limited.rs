@@ -0,0 +1,51 @@
+use futures::channel::oneshot;
+use futures::executor::block_on;
+use futures::future::{self, FutureExt};
+use futures::io::{self, AsyncBufReadExt, AsyncReadExt, AsyncWrite, Cursor};
+use futures::task::{noop_waker_ref, Context, Poll};
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::time::Duration;
+
+#[test]
+fn write_waits_for_refill() {
+    let timers = Rc::new(RefCell::new(Vec::new()));
+    let new_timer = {
+        let timers = timers.clone();
+        move |delay| {
+            let (tx, rx) = oneshot::channel::<()>();
+            timers.borrow_mut().push((delay, tx));
+            rx.map(|_| ())
+        }
+    };
+    let mut writer = io::limited(Vec::new(), 10, 4, new_timer);
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    assert!(matches!(
+        Pin::new(&mut writer).poll_write(&mut cx, b"hello world"),
+        Poll::Ready(Ok(4))
+    ));
+    assert!(Pin::new(&mut writer).poll_write(&mut cx, b"o world").is_pending());
+
+    // Refilling 4 bytes at 10 bytes per second takes 400ms.
+    let (delay, tx) = timers.borrow_mut().remove(0);
+    assert_eq!(delay, Duration::from_millis(400));
+    tx.send(()).unwrap();
+
+    assert!(matches!(Pin::new(&mut writer).poll_write(&mut cx, b"o world"), Poll::Ready(Ok(4))));
+    assert_eq!(writer.get_ref(), b"hello wo");
+}
+
+#[test]
+fn read_in_bursts() {
+    let mut reader = io::limited(Cursor::new(vec![1; 10]), 4, 4, |_| future::ready(()));
+    let mut buf = [0; 10];
+
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 4);
+    assert_eq!(block_on(reader.fill_buf()).unwrap(), [1; 4]);
+    reader.consume_unpin(4);
+
+    let mut rest = Vec::new();
+    assert_eq!(block_on(reader.read_to_end(&mut rest)).unwrap(), 2);
+}
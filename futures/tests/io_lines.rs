@@ -1,6 +1,6 @@
 use futures::executor::block_on;
 use futures::future::{Future, FutureExt};
-use futures::io::{AsyncBufReadExt, Cursor};
+use futures::io::{AsyncBufReadExt, AsyncReadExt, Cursor};
 use futures::stream::{self, StreamExt, TryStreamExt};
 use futures::task::Poll;
 use futures_test::io::AsyncReadTestExt;
@@ -58,3 +58,14 @@ fn maybe_pending() {
     assert_eq!(run_next!(s), "".to_string());
     assert!(run(s.next()).is_none());
 }
+
+#[test]
+fn into_inner() {
+    let buf = Cursor::new(&b"HELO\r\nraw data"[..]);
+    let mut s = buf.lines();
+    assert_eq!(block_on_next!(s), "HELO".to_string());
+
+    let mut rest = String::new();
+    block_on(s.into_inner().read_to_string(&mut rest)).unwrap();
+    assert_eq!(rest, "raw data");
+}
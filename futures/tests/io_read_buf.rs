@@ -0,0 +1,118 @@
+use futures::executor::block_on;
+use futures::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader, Cursor, ReadBuf};
+use futures::task::{noop_waker_ref, Context, Poll};
+use std::mem::MaybeUninit;
+use std::pin::Pin;
+
+fn poll_read_buf<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut ReadBuf<'_>) {
+    let mut cx = Context::from_waker(noop_waker_ref());
+    match Pin::new(reader).poll_read_buf(&mut cx, buf) {
+        Poll::Ready(res) => res.unwrap(),
+        Poll::Pending => panic!("reader is pending"),
+    }
+}
+
+#[test]
+fn regions() {
+    let mut storage = [MaybeUninit::<u8>::uninit(); 8];
+    let mut buf = ReadBuf::uninit(&mut storage);
+    assert_eq!(buf.capacity(), 8);
+    assert_eq!(buf.initialized().len(), 0);
+
+    buf.put_slice(b"ab");
+    assert_eq!(buf.filled(), b"ab");
+    assert_eq!(buf.initialized(), b"ab");
+
+    assert_eq!(buf.initialize_unfilled_to(2), [0, 0]);
+    buf.advance(1);
+    assert_eq!(buf.filled(), b"ab\0");
+    assert_eq!(buf.initialized().len(), 4);
+    assert_eq!(buf.remaining(), 5);
+
+    // The initialized part never shrinks.
+    buf.clear();
+    assert_eq!(buf.filled(), b"");
+    assert_eq!(buf.initialized().len(), 4);
+    assert_eq!(buf.initialize_unfilled().len(), 8);
+}
+
+#[test]
+#[should_panic(expected = "filled must not become larger than initialized")]
+fn advance_past_initialized() {
+    let mut storage = [MaybeUninit::<u8>::uninit(); 8];
+    let mut buf = ReadBuf::uninit(&mut storage);
+    buf.advance(1);
+}
+
+#[test]
+fn slice_and_cursor() {
+    let mut storage = [MaybeUninit::<u8>::uninit(); 4];
+    let mut buf = ReadBuf::uninit(&mut storage);
+
+    let mut slice = &b"hello"[..];
+    poll_read_buf(&mut slice, &mut buf);
+    assert_eq!(buf.filled(), b"hell");
+    assert_eq!(slice, b"o");
+
+    buf.clear();
+    let mut cursor = Cursor::new(b"world");
+    poll_read_buf(&mut cursor, &mut buf);
+    poll_read_buf(&mut cursor, &mut buf);
+    assert_eq!(buf.filled(), b"worl");
+    assert_eq!(cursor.position(), 4);
+}
+
+#[test]
+fn take() {
+    let mut storage = [MaybeUninit::<u8>::uninit(); 8];
+    let mut buf = ReadBuf::uninit(&mut storage);
+
+    let mut reader = Cursor::new(b"hello world").take(5);
+    poll_read_buf(&mut reader, &mut buf);
+    assert_eq!(buf.filled(), b"hello");
+    poll_read_buf(&mut reader, &mut buf);
+    assert_eq!(buf.filled(), b"hello");
+    assert_eq!(reader.limit(), 0);
+}
+
+#[test]
+fn buf_reader() {
+    let mut storage = [MaybeUninit::<u8>::uninit(); 4];
+    let mut buf = ReadBuf::uninit(&mut storage);
+
+    let mut reader = BufReader::with_capacity(8, Cursor::new(b"hello world"));
+    assert_eq!(block_on(reader.fill_buf()).unwrap(), b"hello wo");
+    poll_read_buf(&mut reader, &mut buf);
+    assert_eq!(buf.filled(), b"hell");
+    assert_eq!(reader.buffer(), b"o wo");
+
+    let mut rest = Vec::new();
+    block_on(reader.read_to_end(&mut rest)).unwrap();
+    assert_eq!(rest, b"o world");
+}
+
+// Only implements `poll_read`, reading a few bytes at a time.
+struct Bytes(Vec<u8>);
+
+impl AsyncRead for Bytes {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let n = buf.len().min(self.0.len()).min(3);
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0.drain(..n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[test]
+fn read_to_end_default_impl() {
+    let data: Vec<u8> = (1..=100).collect();
+    let mut buf = vec![0xff];
+    let n = block_on(Bytes(data.clone()).read_to_end(&mut buf)).unwrap();
+    assert_eq!(n, 100);
+    assert_eq!(buf[0], 0xff);
+    assert_eq!(&buf[1..], &data[..]);
+}
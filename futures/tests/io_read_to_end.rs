@@ -6,7 +6,7 @@ use futures::{
 use std::pin::Pin;
 
 #[test]
-#[should_panic(expected = "assertion failed: n <= buf.len()")]
+#[should_panic(expected = "filled must not become larger than initialized")]
 fn issue2310() {
     struct MyRead {
         first: bool,
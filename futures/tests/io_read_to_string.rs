@@ -1,6 +1,6 @@
 use futures::executor::block_on;
 use futures::future::{Future, FutureExt};
-use futures::io::{AsyncReadExt, Cursor};
+use futures::io::{self, AsyncReadExt, Cursor};
 use futures::stream::{self, StreamExt, TryStreamExt};
 use futures::task::Poll;
 use futures_test::io::AsyncReadTestExt;
@@ -23,6 +23,15 @@ fn read_to_string() {
     assert!(block_on(c.read_to_string(&mut v)).is_err());
 }
 
+#[test]
+fn invalid_utf8_keeps_buf() {
+    let mut c = Cursor::new(&b"abc\xff"[..]);
+    let mut v = String::from("prefix ");
+    let err = block_on(c.read_to_string(&mut v)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(v, "prefix ");
+}
+
 #[test]
 fn interleave_pending() {
     fn run<F: Future + Unpin>(mut f: F) -> F::Output {
@@ -0,0 +1,32 @@
+use futures::executor::block_on;
+use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+
+#[test]
+fn reunite() {
+    let (mut reader, mut writer) = Cursor::new(vec![0; 4]).split();
+    assert!(reader.is_pair_of(&writer));
+    assert!(writer.is_pair_of(&reader));
+
+    block_on(writer.write_all(b"ab")).unwrap();
+    let mut buf = [0; 2];
+    block_on(reader.read_exact(&mut buf)).unwrap();
+    assert_eq!(buf, [0, 0]);
+
+    let cursor = reader.reunite(writer).unwrap();
+    assert_eq!(cursor.position(), 4);
+    assert_eq!(cursor.into_inner(), b"ab\0\0");
+}
+
+#[test]
+fn reunite_mismatched() {
+    let (reader1, writer1) = Cursor::new(vec![1]).split();
+    let (reader2, writer2) = Cursor::new(vec![2]).split();
+    assert!(!reader1.is_pair_of(&writer2));
+
+    let err = reader1.reunite(writer2).unwrap_err();
+    let (reader1, writer2) = (err.0, err.1);
+    let cursor1 = writer1.reunite(reader1).unwrap();
+    let cursor2 = reader2.reunite(writer2).unwrap();
+    assert_eq!(cursor1.into_inner(), [1]);
+    assert_eq!(cursor2.into_inner(), [2]);
+}
@@ -0,0 +1,48 @@
+use futures::executor::block_on;
+use futures::io::{AsyncBufReadExt, AsyncReadExt, Cursor};
+
+#[test]
+fn length_prefixed_bodies() {
+    let mut reader = Cursor::new(&b"\x05hello\x03foo"[..]);
+    let mut bodies = Vec::new();
+    block_on(async {
+        loop {
+            let mut len = [0];
+            if reader.read(&mut len).await.unwrap() == 0 {
+                break;
+            }
+            let mut body = String::new();
+            (&mut reader).take(u64::from(len[0])).read_to_string(&mut body).await.unwrap();
+            bodies.push(body);
+        }
+    });
+    assert_eq!(bodies, ["hello", "foo"]);
+}
+
+#[test]
+fn set_limit() {
+    let mut take = Cursor::new(&b"12345678"[..]).take(2);
+    let mut buf = Vec::new();
+    block_on(take.read_to_end(&mut buf)).unwrap();
+    assert_eq!(buf, b"12");
+    assert_eq!(take.limit(), 0);
+
+    take.set_limit(3);
+    block_on(take.read_to_end(&mut buf)).unwrap();
+    assert_eq!(buf, b"12345");
+    assert_eq!(take.into_inner().position(), 5);
+}
+
+#[test]
+fn buf_read_limit() {
+    let mut take = Cursor::new(&b"line one\nline two\n"[..]).take(4);
+    let mut line = String::new();
+    block_on(take.read_line(&mut line)).unwrap();
+    assert_eq!(line, "line");
+
+    // Consuming more than the limit doesn't reset it.
+    take.set_limit(1);
+    take.consume_unpin(10);
+    assert_eq!(take.limit(), 0);
+    assert_eq!(take.get_ref().position(), 5);
+}
@@ -0,0 +1,45 @@
+use futures::executor::block_on;
+use futures::future;
+use futures::io::{self, AsyncReadExt, AsyncWriteExt, Cursor};
+use futures_test::io::{AsyncReadTestExt, AsyncWriteTestExt};
+
+#[test]
+fn stalled_read_times_out() {
+    let reader = Cursor::new(&b"hello"[..]).interleave_pending();
+    let mut reader = io::timeout(reader, || future::ready(()));
+    let mut buf = [0; 5];
+
+    let err = block_on(reader.read(&mut buf)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    // The operation can be retried.
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+}
+
+#[test]
+fn stalled_write_times_out() {
+    let writer = Cursor::new(Vec::new()).interleave_pending_write();
+    let mut writer = io::timeout(writer, || future::ready(()));
+
+    let err = block_on(writer.write(b"hello")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    assert_eq!(block_on(writer.write(b"hello")).unwrap(), 5);
+}
+
+#[test]
+fn ready_operations_never_time_out() {
+    let mut io = io::timeout(Cursor::new(Vec::new()), || future::ready(()));
+    block_on(io.write_all(b"hello")).unwrap();
+    block_on(io.flush()).unwrap();
+    assert_eq!(io.get_ref().get_ref(), b"hello");
+}
+
+#[test]
+fn pending_timer() {
+    let reader = Cursor::new(&b"hello"[..]).interleave_pending();
+    let mut reader = io::timeout(reader, future::pending::<()>);
+    let mut output = String::new();
+    block_on(reader.read_to_string(&mut output)).unwrap();
+    assert_eq!(output, "hello");
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "io-tokio")]
+
+use futures::executor::block_on;
+use futures::io::{
+    AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, Cursor, SeekFrom, TokioCompat,
+};
+
+// Wrapping twice goes through tokio's I/O traits and back to those of this
+// crate.
+fn round_trip<T>(io: T) -> TokioCompat<TokioCompat<T>> {
+    TokioCompat::new(TokioCompat::new(io))
+}
+
+#[test]
+fn tokio_compat_read() {
+    let mut reader = round_trip(Cursor::new(vec![1, 2, 3, 4, 5]));
+    let mut buf = [0; 2];
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 2);
+    assert_eq!(buf, [1, 2]);
+
+    let mut output = Vec::new();
+    assert_eq!(block_on(reader.read_to_end(&mut output)).unwrap(), 3);
+    assert_eq!(output, [3, 4, 5]);
+}
+
+#[test]
+fn tokio_compat_buf_read() {
+    let mut reader = round_trip(Cursor::new(&b"hello\nworld"[..]));
+    let mut line = String::new();
+    assert_eq!(block_on(reader.read_line(&mut line)).unwrap(), 6);
+    assert_eq!(line, "hello\n");
+}
+
+#[test]
+fn tokio_compat_write() {
+    let mut writer = round_trip(Cursor::new(Vec::new()));
+    block_on(writer.write_all(b"hello")).unwrap();
+    block_on(writer.close()).unwrap();
+    assert_eq!(writer.into_inner().into_inner().into_inner(), b"hello");
+}
+
+#[test]
+fn tokio_compat_seek() {
+    let mut io = round_trip(Cursor::new(vec![1, 2, 3, 4, 5]));
+    assert_eq!(block_on(io.seek(SeekFrom::End(-2))).unwrap(), 3);
+    assert_eq!(block_on(io.seek(SeekFrom::Current(-1))).unwrap(), 2);
+
+    let mut output = Vec::new();
+    block_on(io.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, [3, 4, 5]);
+}
@@ -0,0 +1,190 @@
+use futures::io::untrusted::{Host, Interest, RawFd, Reactor};
+use futures::io::{AsyncRead, AsyncSeek, AsyncWrite, SeekFrom};
+use futures::task::Poll;
+use futures_test::task::{new_count_waker, noop_context};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Context;
+
+// How many bytes a pipe of the mock host holds.
+const PIPE_CAPACITY: usize = 4;
+
+// A host whose file descriptors are in-memory pipes, reading back what was
+// written to them.
+#[derive(Clone, Default)]
+struct MockHost(Arc<Mutex<State>>);
+
+#[derive(Default)]
+struct State {
+    pipes: HashMap<RawFd, VecDeque<u8>>,
+    watches: Vec<(RawFd, Interest, usize)>,
+    closed: Vec<RawFd>,
+}
+
+impl MockHost {
+    fn with_pipes(fds: &[RawFd]) -> Self {
+        let host = Self::default();
+        for &fd in fds {
+            host.0.lock().unwrap().pipes.insert(fd, VecDeque::new());
+        }
+        host
+    }
+
+    fn push(&self, fd: RawFd, data: &[u8]) {
+        self.0.lock().unwrap().pipes.get_mut(&fd).unwrap().extend(data);
+    }
+
+    fn take(&self, fd: RawFd) -> Vec<u8> {
+        self.0.lock().unwrap().pipes.get_mut(&fd).unwrap().drain(..).collect()
+    }
+
+    fn closed(&self) -> Vec<RawFd> {
+        self.0.lock().unwrap().closed.clone()
+    }
+}
+
+fn pipe(state: &mut State, fd: RawFd) -> io::Result<&mut VecDeque<u8>> {
+    state.pipes.get_mut(&fd).ok_or_else(|| io::Error::from_raw_os_error(9))
+}
+
+impl Host for MockHost {
+    fn read(&self, fd: RawFd, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.0.lock().unwrap();
+        let pipe = pipe(&mut state, fd)?;
+        if pipe.is_empty() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        let n = buf.len().min(pipe.len());
+        for (dst, src) in buf.iter_mut().zip(pipe.drain(..n)) {
+            *dst = src;
+        }
+        Ok(n)
+    }
+
+    fn write(&self, fd: RawFd, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.0.lock().unwrap();
+        let pipe = pipe(&mut state, fd)?;
+        let n = buf.len().min(PIPE_CAPACITY - pipe.len());
+        if n == 0 && !buf.is_empty() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        pipe.extend(&buf[..n]);
+        Ok(n)
+    }
+
+    fn close(&self, fd: RawFd) -> io::Result<()> {
+        let mut state = self.0.lock().unwrap();
+        state.pipes.remove(&fd);
+        state.closed.push(fd);
+        Ok(())
+    }
+
+    fn watch(&self, fd: RawFd, interest: Interest, token: usize) -> io::Result<()> {
+        self.0.lock().unwrap().watches.push((fd, interest, token));
+        Ok(())
+    }
+
+    fn poll_events(&self, tokens: &mut Vec<usize>, _block: bool) -> io::Result<()> {
+        let mut state = self.0.lock().unwrap();
+        let State { pipes, watches, .. } = &mut *state;
+        watches.retain(|&(fd, interest, token)| {
+            let ready = pipes.get(&fd).map_or(true, |pipe| match interest {
+                Interest::Read => !pipe.is_empty(),
+                Interest::Write => pipe.len() < PIPE_CAPACITY,
+            });
+            if ready {
+                tokens.push(token);
+            }
+            !ready
+        });
+        Ok(())
+    }
+
+    fn wake(&self) {}
+}
+
+#[test]
+fn read_waits_for_host() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let host = MockHost::with_pipes(&[3]);
+    let reactor = Reactor::new(host.clone());
+    let mut fd = reactor.open(3);
+
+    let mut buf = [0; 8];
+    assert!(Pin::new(&mut fd).poll_read(&mut cx, &mut buf).is_pending());
+    assert_eq!(reactor.turn(false).unwrap(), 0);
+    assert_eq!(counter, 0);
+
+    host.push(3, b"abc");
+    assert_eq!(reactor.turn(false).unwrap(), 1);
+    assert_eq!(counter, 1);
+    assert!(matches!(Pin::new(&mut fd).poll_read(&mut cx, &mut buf), Poll::Ready(Ok(3))));
+    assert_eq!(&buf[..3], b"abc");
+
+    // Waiting again reuses the watch of the task.
+    assert!(Pin::new(&mut fd).poll_read(&mut cx, &mut buf).is_pending());
+    host.push(3, b"d");
+    assert_eq!(reactor.turn(false).unwrap(), 1);
+    assert_eq!(counter, 2);
+    assert!(matches!(Pin::new(&mut fd).poll_read(&mut cx, &mut buf), Poll::Ready(Ok(1))));
+}
+
+#[test]
+fn write_waits_for_host() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let host = MockHost::with_pipes(&[4]);
+    let reactor = Reactor::new(host.clone());
+    let mut fd = reactor.open(4);
+
+    let mut data = &b"hello world"[..];
+    let mut written = Vec::new();
+    while !data.is_empty() {
+        match Pin::new(&mut fd).poll_write(&mut cx, data) {
+            Poll::Ready(Ok(n)) => data = &data[n..],
+            Poll::Ready(Err(e)) => panic!("{}", e),
+            Poll::Pending => {
+                // The pipe is full until the host drains it.
+                assert_eq!(reactor.turn(false).unwrap(), 0);
+                written.extend(host.take(4));
+                assert_eq!(reactor.turn(false).unwrap(), 1);
+            }
+        }
+    }
+    written.extend(host.take(4));
+    assert_eq!(written, b"hello world");
+    assert_eq!(counter, 2);
+}
+
+#[test]
+fn seek_unsupported_by_default() {
+    let reactor = Reactor::new(MockHost::with_pipes(&[3]));
+    let mut fd = reactor.open(3);
+    let res = Pin::new(&mut fd).poll_seek(&mut noop_context(), SeekFrom::Start(0));
+    assert!(matches!(res, Poll::Ready(Err(_))));
+    assert!(matches!(Pin::new(&mut fd).poll_flush(&mut noop_context()), Poll::Ready(Ok(()))));
+}
+
+#[test]
+fn drop_closes_fd() {
+    let host = MockHost::with_pipes(&[3, 4]);
+    let reactor = Reactor::new(host.clone());
+
+    let fd = reactor.open(3);
+    assert_eq!(fd.as_raw_fd(), 3);
+    drop(fd);
+    assert_eq!(host.closed(), [3]);
+
+    // A file descriptor given back isn't closed.
+    let fd = reactor.open(4);
+    assert_eq!(fd.into_raw_fd(), 4);
+    assert_eq!(host.closed(), [3]);
+
+    // Reading from a closed file descriptor fails.
+    let mut fd = reactor.open(3);
+    let res = Pin::new(&mut fd).poll_read(&mut noop_context(), &mut [0; 1]);
+    assert!(matches!(res, Poll::Ready(Err(_))));
+}
@@ -0,0 +1,102 @@
+use futures::channel::mpsc;
+use futures::executor::{block_on, LocalPool, ThreadPool};
+use futures::future::{join, FutureExt};
+use futures::lock::Barrier;
+use futures::stream::StreamExt;
+use futures::task::{Context, LocalSpawnExt, Poll, SpawnExt};
+use futures_test::task::{new_count_waker, panic_context};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[test]
+fn barrier_releases_all_waiters() {
+    let barrier = Barrier::new(3);
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut first = barrier.wait();
+    let mut second = barrier.wait();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+    assert_eq!(counter, 0);
+
+    let res = barrier.wait().poll_unpin(&mut panic_context());
+    assert_eq!(res.map(|res| res.is_leader()), Poll::Ready(true));
+    assert_eq!(counter, 2);
+
+    let first = first.poll_unpin(&mut panic_context());
+    let second = second.poll_unpin(&mut panic_context());
+    assert_eq!(first.map(|res| res.is_leader()), Poll::Ready(false));
+    assert_eq!(second.map(|res| res.is_leader()), Poll::Ready(false));
+}
+
+#[test]
+fn barrier_is_reusable() {
+    let barrier = Barrier::new(2);
+    for _ in 0..3 {
+        let (a, b) = block_on(join(barrier.wait(), barrier.wait()));
+        assert!(a.is_leader() != b.is_leader());
+    }
+}
+
+#[test]
+fn barrier_dropped_waiter_stops_counting() {
+    let barrier = Barrier::new(2);
+    let mut waiter = barrier.wait();
+    assert!(waiter.poll_unpin(&mut panic_context()).is_pending());
+    drop(waiter);
+
+    let mut waiter = barrier.wait();
+    assert!(waiter.poll_unpin(&mut panic_context()).is_pending());
+}
+
+#[test]
+fn barrier_thread_pool() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let pool = ThreadPool::builder().pool_size(4).create().unwrap();
+
+    let num_tasks = 16;
+    let barrier = Arc::new(Barrier::new(num_tasks));
+    for _ in 0..num_tasks {
+        let tx = tx.clone();
+        let barrier = barrier.clone();
+        pool.spawn(async move {
+            let res = barrier.wait().await;
+            tx.unbounded_send(res.is_leader()).unwrap();
+        })
+        .unwrap();
+    }
+    drop(tx);
+
+    let leaders = block_on(async {
+        let mut leaders = 0;
+        while let Some(is_leader) = rx.next().await {
+            leaders += is_leader as usize;
+        }
+        leaders
+    });
+    assert_eq!(leaders, 1);
+}
+
+#[test]
+fn barrier_local_pool() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+
+    let barrier = Rc::new(Barrier::new(4));
+    let done = Rc::new(Cell::new(0));
+    for _ in 0..4 {
+        let barrier = barrier.clone();
+        let done = done.clone();
+        spawner
+            .spawn_local(async move {
+                barrier.wait().await;
+                done.set(done.get() + 1);
+            })
+            .unwrap();
+    }
+
+    pool.run();
+    assert_eq!(done.get(), 4);
+}
@@ -0,0 +1,107 @@
+use futures::channel::mpsc;
+use futures::executor::{block_on, ThreadPool};
+use futures::future::FutureExt;
+use futures::lock::{Condvar, Mutex, MutexGuard};
+use futures::stream::StreamExt;
+use futures::task::{Context, Poll, SpawnExt};
+use futures_test::task::{new_count_waker, panic_context};
+use std::sync::Arc;
+
+#[test]
+fn condvar_wait_releases_mutex() {
+    let mutex = Mutex::new(0);
+    let condvar = Condvar::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut wait = condvar.wait(mutex.try_lock().unwrap());
+    assert!(wait.poll_unpin(&mut cx).is_pending());
+    *mutex.try_lock().unwrap() += 1;
+    assert_eq!(counter, 0);
+
+    let guard = mutex.try_lock().unwrap();
+    assert!(condvar.notify_one());
+    assert_eq!(counter, 1);
+    // The mutex has to be locked again before the wait completes.
+    assert!(wait.poll_unpin(&mut cx).is_pending());
+    drop(guard);
+    assert_eq!(counter, 2);
+
+    assert_eq!(wait.poll_unpin(&mut panic_context()).map(|guard| *guard), Poll::Ready(1));
+    assert!(!condvar.notify_one());
+}
+
+#[test]
+fn condvar_notify_all() {
+    let mutex = Mutex::new(());
+    let condvar = Condvar::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut wait1 = condvar.wait(mutex.try_lock().unwrap());
+    let mut wait2 = condvar.wait(mutex.try_lock().unwrap());
+    assert!(wait1.poll_unpin(&mut cx).is_pending());
+    assert!(wait2.poll_unpin(&mut cx).is_pending());
+
+    assert_eq!(condvar.notify_all(), 2);
+    assert_eq!(counter, 2);
+    let guard = block_on(&mut wait1);
+    assert!(std::ptr::eq(MutexGuard::mutex(&guard), &mutex));
+    assert!(wait2.poll_unpin(&mut cx).is_pending());
+    drop(guard);
+    assert_eq!(counter, 3);
+    drop(block_on(wait2));
+    assert!(mutex.try_lock().is_some());
+}
+
+#[test]
+fn condvar_dropped_waiter_passes_notification_on() {
+    let mutex = Mutex::new(());
+    let condvar = Condvar::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let wait1 = condvar.wait(mutex.try_lock().unwrap());
+    let mut wait2 = condvar.wait(mutex.try_lock().unwrap());
+    assert!(wait2.poll_unpin(&mut cx).is_pending());
+
+    assert!(condvar.notify_one());
+    assert_eq!(counter, 0);
+    drop(wait1);
+    assert_eq!(counter, 1);
+    assert!(wait2.poll_unpin(&mut cx).is_ready());
+}
+
+#[test]
+fn condvar_contested() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let pool = ThreadPool::builder().pool_size(16).create().unwrap();
+
+    let state = Arc::new((Mutex::new(0), Condvar::new()));
+    let num_tasks = 100;
+
+    pool.spawn({
+        let state = state.clone();
+        async move {
+            let (mutex, condvar) = &*state;
+            let mut guard = mutex.lock().await;
+            while *guard < num_tasks {
+                guard = condvar.wait(guard).await;
+            }
+            tx.unbounded_send(*guard).unwrap();
+        }
+    })
+    .unwrap();
+
+    for _ in 0..num_tasks {
+        let state = state.clone();
+        pool.spawn(async move {
+            let (mutex, condvar) = &*state;
+            *mutex.lock().await += 1;
+            condvar.notify_all();
+        })
+        .unwrap();
+    }
+
+    assert_eq!(block_on(rx.next()), Some(num_tasks));
+}
@@ -1,9 +1,9 @@
 use futures::channel::mpsc;
 use futures::executor::{block_on, ThreadPool};
 use futures::future::{ready, FutureExt};
-use futures::lock::Mutex;
+use futures::lock::{Mutex, OwnedMutexGuard};
 use futures::stream::StreamExt;
-use futures::task::{Context, SpawnExt};
+use futures::task::{Context, Poll, SpawnExt};
 use futures_test::future::FutureTestExt;
 use futures_test::task::{new_count_waker, panic_context};
 use std::sync::Arc;
@@ -34,6 +34,76 @@ fn mutex_wakes_waiters() {
     assert!(waiter.poll_unpin(&mut panic_context()).is_ready());
 }
 
+#[test]
+fn mutex_wakes_waiters_in_order() {
+    let mutex = Mutex::new(());
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let lock = mutex.try_lock().unwrap();
+    let mut first = mutex.lock();
+    let mut second = mutex.lock();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    drop(lock);
+    assert_eq!(counter, 1);
+    // The lock is handed to the first waiter.
+    assert!(mutex.try_lock().is_none());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    let lock = match first.poll_unpin(&mut panic_context()) {
+        Poll::Ready(lock) => lock,
+        Poll::Pending => panic!("first waiter didn't get the lock"),
+    };
+    drop(lock);
+    assert_eq!(counter, 2);
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn mutex_dropped_waiter_passes_lock_on() {
+    let mutex = Mutex::new(());
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let lock = mutex.try_lock().unwrap();
+    let mut first = mutex.lock();
+    let mut second = mutex.lock();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    drop(lock);
+    drop(first);
+    assert_eq!(counter, 2);
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn mutex_lock_owned() {
+    let mutex = Arc::new(Mutex::new(0));
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut lock = mutex.clone().try_lock_owned().unwrap();
+    assert!(Arc::ptr_eq(OwnedMutexGuard::mutex(&lock), &mutex));
+    let mut waiter = mutex.clone().lock_owned();
+    assert!(waiter.poll_unpin(&mut cx).is_pending());
+
+    *lock += 1;
+    drop(lock);
+    assert_eq!(counter, 1);
+
+    let lock = match waiter.poll_unpin(&mut panic_context()) {
+        Poll::Ready(lock) => lock,
+        Poll::Pending => panic!("waiter didn't get the lock"),
+    };
+    assert_eq!(*lock, 1);
+    assert!(mutex.clone().try_lock_owned().is_none());
+    drop(lock);
+    assert!(mutex.try_lock().is_some());
+}
+
 #[test]
 fn mutex_contested() {
     let (tx, mut rx) = mpsc::unbounded();
@@ -64,3 +134,34 @@ fn mutex_contested() {
         assert_eq!(num_tasks, *lock);
     })
 }
+
+#[test]
+fn mutex_contested_owned() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let pool = ThreadPool::builder().pool_size(16).create().unwrap();
+
+    let tx = Arc::new(tx);
+    let mutex = Arc::new(Mutex::new(0));
+
+    let num_tasks = 1000;
+    for _ in 0..num_tasks {
+        let tx = tx.clone();
+        let mutex = mutex.clone();
+        pool.spawn(async move {
+            let mut lock = mutex.lock_owned().await;
+            ready(()).pending_once().await;
+            *lock += 1;
+            tx.unbounded_send(()).unwrap();
+            drop(lock);
+        })
+        .unwrap();
+    }
+
+    block_on(async {
+        for _ in 0..num_tasks {
+            rx.next().await.unwrap();
+        }
+        let lock = mutex.lock().await;
+        assert_eq!(num_tasks, *lock);
+    })
+}
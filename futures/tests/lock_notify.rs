@@ -0,0 +1,81 @@
+use futures::executor::block_on;
+use futures::future::{join, FutureExt};
+use futures::lock::Notify;
+use futures::task::Context;
+use futures_test::task::{new_count_waker, panic_context};
+
+#[test]
+fn notify_one_stores_permit() {
+    let notify = Notify::new();
+    notify.notify_one();
+    notify.notify_one();
+
+    assert!(notify.notified().poll_unpin(&mut panic_context()).is_ready());
+    // Only one permit is stored.
+    assert!(notify.notified().poll_unpin(&mut panic_context()).is_pending());
+}
+
+#[test]
+fn notify_one_wakes_waiters_in_order() {
+    let notify = Notify::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut first = notify.notified();
+    let mut second = notify.notified();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    notify.notify_one();
+    assert_eq!(counter, 1);
+    assert!(second.poll_unpin(&mut cx).is_pending());
+    assert!(first.poll_unpin(&mut panic_context()).is_ready());
+
+    notify.notify_one();
+    assert_eq!(counter, 2);
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn notify_one_passed_on_when_dropped() {
+    let notify = Notify::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut first = notify.notified();
+    let mut second = notify.notified();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    notify.notify_one();
+    drop(first);
+    assert_eq!(counter, 2);
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn notify_waiters() {
+    let notify = Notify::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut first = notify.notified();
+    let mut second = notify.notified();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+
+    notify.notify_waiters();
+    assert_eq!(counter, 1);
+    assert!(first.poll_unpin(&mut panic_context()).is_ready());
+    // Futures created before the call are notified even if not polled yet.
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+    // No permit is stored.
+    assert!(notify.notified().poll_unpin(&mut panic_context()).is_pending());
+}
+
+#[test]
+fn notify_across_tasks() {
+    let notify = Notify::new();
+    block_on(join(notify.notified(), async {
+        notify.notify_one();
+    }));
+}
@@ -0,0 +1,102 @@
+use futures::channel::{mpsc, oneshot};
+use futures::executor::{block_on, ThreadPool};
+use futures::future::{self, Future, FutureExt};
+use futures::lock::OnceCell;
+use futures::pin_mut;
+use futures::stream::StreamExt;
+use futures::task::{Context, Poll, SpawnExt};
+use futures_test::task::{new_count_waker, panic_context};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn once_cell_set_and_get() {
+    let mut cell = OnceCell::new();
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.set(1), Ok(()));
+    assert_eq!(cell.set(2), Err(2));
+    assert_eq!(cell.get(), Some(&1));
+    *cell.get_mut().unwrap() += 1;
+    assert_eq!(cell.into_inner(), Some(2));
+
+    let cell = OnceCell::from(3);
+    let value = cell.get_or_init(|| future::ready(4)).now_or_never().unwrap();
+    assert_eq!(*value, 3);
+}
+
+#[test]
+fn once_cell_waiters_share_value() {
+    let cell = OnceCell::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (tx, rx) = oneshot::channel();
+
+    let first = cell.get_or_init(|| async { rx.await.unwrap() });
+    let mut second = cell.get_or_init(|| future::ready(2));
+    pin_mut!(first);
+    assert!(first.as_mut().poll(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+    assert_eq!(cell.set(0), Err(0));
+
+    tx.send(1).unwrap();
+    assert_eq!(first.poll(&mut cx), Poll::Ready(&1));
+    assert_eq!(counter, 2);
+    assert_eq!(second.poll_unpin(&mut panic_context()), Poll::Ready(&1));
+}
+
+#[test]
+fn once_cell_failed_init_passes_on() {
+    let cell = OnceCell::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut first = cell.get_or_try_init(|| future::pending::<Result<i32, ()>>());
+    let mut second = cell.get_or_try_init(|| future::ready(Err(())));
+    let mut third = cell.get_or_try_init(|| future::ready(Ok::<_, ()>(3)));
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+    assert!(third.poll_unpin(&mut cx).is_pending());
+
+    // The first initializer is cancelled, the second one fails, and the
+    // third one succeeds.
+    drop(first);
+    assert_eq!(counter, 1);
+    assert_eq!(second.poll_unpin(&mut cx), Poll::Ready(Err(())));
+    assert_eq!(counter, 2);
+    assert_eq!(third.poll_unpin(&mut panic_context()), Poll::Ready(Ok(&3)));
+    assert_eq!(cell.get(), Some(&3));
+}
+
+#[test]
+fn once_cell_contested() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let pool = ThreadPool::builder().pool_size(16).create().unwrap();
+
+    let cell = Arc::new(OnceCell::new());
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let num_tasks = 100;
+    for i in 0..num_tasks {
+        let tx = tx.clone();
+        let cell = cell.clone();
+        let runs = runs.clone();
+        pool.spawn(async move {
+            let value = cell
+                .get_or_init(|| async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    i
+                })
+                .await;
+            tx.unbounded_send(*value).unwrap();
+        })
+        .unwrap();
+    }
+
+    block_on(async {
+        let first = rx.next().await.unwrap();
+        for _ in 1..num_tasks {
+            assert_eq!(rx.next().await.unwrap(), first);
+        }
+    });
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
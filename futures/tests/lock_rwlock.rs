@@ -0,0 +1,191 @@
+use futures::channel::mpsc;
+use futures::executor::{block_on, ThreadPool};
+use futures::future::{ready, FutureExt};
+use futures::lock::{OwnedRwLockWriteGuard, RwLock, RwLockPriority};
+use futures::stream::StreamExt;
+use futures::task::{Context, Poll, SpawnExt};
+use futures_test::future::FutureTestExt;
+use futures_test::task::{new_count_waker, panic_context};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn rwlock_readers_share() {
+    let lock = RwLock::new(1);
+    let first = lock.try_read().unwrap();
+    let second = lock.read().now_or_never().unwrap();
+    assert_eq!(*first + *second, 2);
+    assert!(lock.try_write().is_none());
+
+    drop((first, second));
+    let mut write = lock.try_write().unwrap();
+    *write += 1;
+    assert!(lock.try_read().is_none());
+    drop(write);
+    assert_eq!(lock.into_inner(), 2);
+}
+
+#[test]
+fn rwlock_writer_wakes_readers() {
+    let lock = RwLock::new(());
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let write = lock.try_write().unwrap();
+    let mut first = lock.read();
+    let mut second = lock.read();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    drop(write);
+    assert_eq!(counter, 2);
+    assert!(first.poll_unpin(&mut panic_context()).is_ready());
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn rwlock_write_priority() {
+    let lock = RwLock::new(());
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let read = lock.try_read().unwrap();
+    let mut writer = lock.write();
+    assert!(writer.poll_unpin(&mut cx).is_pending());
+
+    // New readers wait behind the writer.
+    assert!(lock.try_read().is_none());
+    let mut reader = lock.read();
+    assert!(reader.poll_unpin(&mut cx).is_pending());
+
+    drop(read);
+    assert_eq!(counter, 1);
+    let write = match writer.poll_unpin(&mut panic_context()) {
+        Poll::Ready(write) => write,
+        Poll::Pending => panic!("writer didn't get the lock"),
+    };
+    assert!(reader.poll_unpin(&mut cx).is_pending());
+
+    drop(write);
+    assert_eq!(counter, 2);
+    assert!(reader.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn rwlock_read_priority() {
+    let lock = RwLock::with_priority((), RwLockPriority::Read);
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let read = lock.try_read().unwrap();
+    let mut writer = lock.write();
+    assert!(writer.poll_unpin(&mut cx).is_pending());
+
+    // New readers don't wait for the writer.
+    let other_read = lock.try_read().unwrap();
+    drop(read);
+    assert_eq!(counter, 0);
+    drop(other_read);
+    assert_eq!(counter, 1);
+    assert!(writer.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn rwlock_dropped_writer_lets_readers_in() {
+    let lock = RwLock::new(());
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let read = lock.try_read().unwrap();
+    let mut writer = lock.write();
+    let mut reader = lock.read();
+    assert!(writer.poll_unpin(&mut cx).is_pending());
+    assert!(reader.poll_unpin(&mut cx).is_pending());
+
+    drop(writer);
+    assert_eq!(counter, 1);
+    assert!(reader.poll_unpin(&mut panic_context()).is_ready());
+    drop(read);
+}
+
+#[test]
+fn rwlock_dropped_waiter_passes_lock_on() {
+    let lock = RwLock::new(());
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let write = lock.try_write().unwrap();
+    let mut first = lock.write();
+    let mut second = lock.write();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    drop(write);
+    drop(first);
+    assert_eq!(counter, 2);
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn rwlock_owned_guards() {
+    let lock = Arc::new(RwLock::new(0));
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let read = lock.clone().try_read_owned().unwrap();
+    let mut writer = lock.clone().write_owned();
+    assert!(writer.poll_unpin(&mut cx).is_pending());
+    drop(read);
+    assert_eq!(counter, 1);
+
+    let mut write = match writer.poll_unpin(&mut panic_context()) {
+        Poll::Ready(write) => write,
+        Poll::Pending => panic!("writer didn't get the lock"),
+    };
+    assert!(Arc::ptr_eq(OwnedRwLockWriteGuard::lock(&write), &lock));
+    *write += 1;
+    assert!(lock.clone().try_read_owned().is_none());
+    drop(write);
+
+    let read = lock.clone().read_owned().now_or_never().unwrap();
+    assert_eq!(*read, 1);
+    assert!(lock.clone().try_write_owned().is_none());
+}
+
+#[test]
+fn rwlock_contested() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let pool = ThreadPool::builder().pool_size(16).create().unwrap();
+
+    let lock = Arc::new(RwLock::new(0));
+    let readers = Arc::new(AtomicUsize::new(0));
+
+    let num_tasks = 1000;
+    for i in 0..num_tasks {
+        let tx = tx.clone();
+        let lock = lock.clone();
+        let readers = readers.clone();
+        pool.spawn(async move {
+            if i % 4 == 0 {
+                let mut write = lock.write_owned().await;
+                assert_eq!(readers.load(Ordering::SeqCst), 0);
+                ready(()).pending_once().await;
+                *write += 1;
+            } else {
+                let _read = lock.read_owned().await;
+                readers.fetch_add(1, Ordering::SeqCst);
+                ready(()).pending_once().await;
+                readers.fetch_sub(1, Ordering::SeqCst);
+            }
+            tx.unbounded_send(()).unwrap();
+        })
+        .unwrap();
+    }
+
+    block_on(async {
+        for _ in 0..num_tasks {
+            rx.next().await.unwrap();
+        }
+        assert_eq!(*lock.read().await, num_tasks / 4);
+    })
+}
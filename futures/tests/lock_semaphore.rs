@@ -0,0 +1,124 @@
+use futures::channel::mpsc;
+use futures::executor::{block_on, ThreadPool};
+use futures::future::{ready, FutureExt};
+use futures::lock::Semaphore;
+use futures::stream::StreamExt;
+use futures::task::{Context, Poll, SpawnExt};
+use futures_test::future::FutureTestExt;
+use futures_test::task::{new_count_waker, panic_context};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn semaphore_try_acquire() {
+    let semaphore = Semaphore::new(2);
+    let first = semaphore.try_acquire().unwrap();
+    let _second = semaphore.try_acquire().unwrap();
+    assert!(semaphore.try_acquire().is_none());
+    assert_eq!(semaphore.available_permits(), 0);
+
+    drop(first);
+    assert_eq!(semaphore.available_permits(), 1);
+    assert!(semaphore.try_acquire().is_some());
+}
+
+#[test]
+fn semaphore_wakes_waiters_in_order() {
+    let semaphore = Semaphore::new(1);
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let permit = semaphore.try_acquire().unwrap();
+    let mut first = semaphore.acquire();
+    let mut second = semaphore.acquire();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    drop(permit);
+    assert_eq!(counter, 1);
+    // The permit is reserved for the first waiter.
+    assert!(semaphore.try_acquire().is_none());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    let permit = match first.poll_unpin(&mut panic_context()) {
+        Poll::Ready(permit) => permit,
+        Poll::Pending => panic!("first waiter didn't get the permit"),
+    };
+    drop(permit);
+    assert_eq!(counter, 2);
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn semaphore_dropped_waiter_passes_permit_on() {
+    let semaphore = Semaphore::new(1);
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let permit = semaphore.try_acquire().unwrap();
+    let mut first = semaphore.acquire();
+    let mut second = semaphore.acquire();
+    assert!(first.poll_unpin(&mut cx).is_pending());
+    assert!(second.poll_unpin(&mut cx).is_pending());
+
+    drop(permit);
+    drop(first);
+    assert_eq!(counter, 2);
+    assert!(second.poll_unpin(&mut panic_context()).is_ready());
+}
+
+#[test]
+fn semaphore_add_permits_and_forget() {
+    let semaphore = Arc::new(Semaphore::new(0));
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut waiter = semaphore.clone().acquire_owned();
+    assert!(waiter.poll_unpin(&mut cx).is_pending());
+
+    semaphore.add_permits(2);
+    assert_eq!(counter, 1);
+    assert_eq!(semaphore.available_permits(), 1);
+
+    let permit = match waiter.poll_unpin(&mut panic_context()) {
+        Poll::Ready(permit) => permit,
+        Poll::Pending => panic!("waiter didn't get a permit"),
+    };
+    assert!(Arc::ptr_eq(permit.semaphore(), &semaphore));
+    permit.forget();
+    assert_eq!(semaphore.available_permits(), 1);
+
+    semaphore.clone().try_acquire_owned().unwrap().forget();
+    assert_eq!(semaphore.available_permits(), 0);
+}
+
+#[test]
+fn semaphore_limits_concurrency() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let pool = ThreadPool::builder().pool_size(8).create().unwrap();
+
+    let semaphore = Arc::new(Semaphore::new(3));
+    let active = Arc::new(AtomicUsize::new(0));
+
+    let num_tasks = 100;
+    for _ in 0..num_tasks {
+        let tx = tx.clone();
+        let semaphore = semaphore.clone();
+        let active = active.clone();
+        pool.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            assert!(active.fetch_add(1, Ordering::SeqCst) < 3);
+            ready(()).pending_once().await;
+            active.fetch_sub(1, Ordering::SeqCst);
+            tx.unbounded_send(()).unwrap();
+        })
+        .unwrap();
+    }
+
+    block_on(async {
+        for _ in 0..num_tasks {
+            rx.next().await.unwrap();
+        }
+    });
+    assert_eq!(semaphore.available_permits(), 3);
+}
@@ -3,7 +3,7 @@ use futures::{
     future::{self, FutureExt},
     join, ready,
     task::Poll,
-    try_join,
+    try_join, try_ready,
 };
 
 #[test]
@@ -14,6 +14,15 @@ fn ready() {
     }))
 }
 
+#[test]
+fn try_ready() {
+    block_on(future::poll_fn(|_| {
+        try_ready!(Poll::Ready(Ok::<(), ()>(())),);
+        Poll::Ready(Ok::<(), ()>(()))
+    }))
+    .unwrap()
+}
+
 #[test]
 fn poll() {
     use futures::poll;
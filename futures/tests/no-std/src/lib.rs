@@ -14,6 +14,14 @@ pub use futures_task::ArcWake as _;
 #[cfg(target_has_atomic = "ptr")]
 pub use futures_channel::oneshot as _;
 
+#[cfg(feature = "futures-channel-alloc")]
+#[cfg(target_has_atomic = "ptr")]
+pub use futures_channel::bounded as _;
+
+#[cfg(feature = "futures-channel-alloc")]
+#[cfg(target_has_atomic = "ptr")]
+pub use futures_channel::spsc as _;
+
 #[cfg(any(feature = "futures", feature = "futures-alloc"))]
 #[cfg(target_has_atomic = "ptr")]
 pub use futures::task::AtomicWaker as _;
@@ -1,11 +1,11 @@
 use futures::channel::{mpsc, oneshot};
-use futures::executor::block_on;
-use futures::future::{self, poll_fn, Future, FutureExt, TryFutureExt};
+use futures::executor::{block_on, LocalPool};
+use futures::future::{self, poll_fn, Either, Future, FutureExt, TryFutureExt};
 use futures::never::Never;
 use futures::ready;
 use futures::sink::{self, Sink, SinkErrInto, SinkExt};
 use futures::stream::{self, Stream, StreamExt};
-use futures::task::{self, ArcWake, Context, Poll, Waker};
+use futures::task::{self, ArcWake, Context, LocalSpawnExt, Poll, Waker};
 use futures_test::task::panic_context;
 use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
@@ -212,6 +212,23 @@ fn either_sink() {
         if true { Vec::<i32>::new().left_sink() } else { VecDeque::<i32>::new().right_sink() };
 
     Pin::new(&mut s).start_send(0).unwrap();
+
+    for &left in &[true, false] {
+        let mut s =
+            if left { Vec::<i32>::new().left_sink() } else { VecDeque::<i32>::new().right_sink() };
+        block_on(s.send_all(&mut stream::iter(vec![Ok::<_, Never>(1), Ok(2)]))).unwrap();
+        block_on(s.close()).unwrap();
+        match s {
+            Either::Left(v) => {
+                assert!(left);
+                assert_eq!(v, vec![1, 2]);
+            }
+            Either::Right(v) => {
+                assert!(!left);
+                assert_eq!(v, VecDeque::from(vec![1, 2]));
+            }
+        }
+    }
 }
 
 #[test]
@@ -235,6 +252,17 @@ fn vecdeque_sink() {
     assert_eq!(deque.pop_front(), None);
 }
 
+#[test]
+fn string_sink() {
+    let mut s = String::new();
+    Pin::new(&mut s).start_send('a').unwrap();
+    Pin::new(&mut s).start_send("bc").unwrap();
+    assert_eq!(s, "abc");
+
+    block_on(stream::iter(vec!["d", "e"]).map(Ok).forward(&mut s)).unwrap();
+    assert_eq!(s, "abcde");
+}
+
 #[test]
 fn send() {
     let mut v = Vec::new();
@@ -253,16 +281,43 @@ fn send() {
 fn send_all() {
     let mut v = Vec::new();
 
-    block_on(v.send_all(&mut stream::iter(vec![0, 1]).map(Ok))).unwrap();
+    block_on(v.send_all(&mut stream::iter(vec![0, 1]).map(Ok::<_, Never>))).unwrap();
     assert_eq!(v, vec![0, 1]);
 
-    block_on(v.send_all(&mut stream::iter(vec![2, 3]).map(Ok))).unwrap();
+    block_on(v.send_all(&mut stream::iter(vec![2, 3]).map(Ok::<_, Never>))).unwrap();
     assert_eq!(v, vec![0, 1, 2, 3]);
 
-    block_on(v.send_all(&mut stream::iter(vec![4, 5]).map(Ok))).unwrap();
+    block_on(v.send_all(&mut stream::iter(vec![4, 5]).map(Ok::<_, Never>))).unwrap();
     assert_eq!(v, vec![0, 1, 2, 3, 4, 5]);
 }
 
+#[test]
+fn send_all_try_stream_by_ref() {
+    #[derive(Debug, PartialEq)]
+    struct StreamError;
+
+    #[derive(Debug, PartialEq)]
+    struct SinkError;
+
+    impl From<StreamError> for SinkError {
+        fn from(_: StreamError) -> Self {
+            SinkError
+        }
+    }
+
+    let mut sink = Vec::new().sink_map_err(|e: Never| -> SinkError { match e {} });
+    let mut stream = stream::iter(vec![Ok(0), Ok(1), Err(StreamError), Ok(2)]);
+
+    block_on(sink.send_all(&mut (&mut stream).take(1))).unwrap();
+    assert_eq!(sink.get_ref(), &[0]);
+
+    assert_eq!(block_on(sink.send_all(&mut stream)), Err(SinkError));
+    assert_eq!(sink.get_ref(), &[0, 1]);
+
+    block_on(sink.send_all(&mut stream)).unwrap();
+    assert_eq!(sink.get_ref(), &[0, 1, 2]);
+}
+
 // Test that `start_send` on an `mpsc` channel does indeed block when the
 // channel is full
 #[test]
@@ -360,6 +415,33 @@ fn with_propagates_poll_ready() {
     }));
 }
 
+// Check that `with_flat_map` buffers the items of the produced stream that the
+// inner sink isn't ready for, and only accepts a new item once they are sent.
+#[test]
+fn with_flat_map_propagates_poll_ready() {
+    let (tx, mut rx) = mpsc::channel::<i32>(0);
+    let mut tx = tx.with_flat_map(|item: i32| {
+        stream::iter(vec![Ok::<i32, mpsc::SendError>(item), Ok(item + 1)])
+    });
+
+    block_on(future::lazy(|_| {
+        flag_cx(|flag, cx| {
+            let mut tx = Pin::new(&mut tx);
+
+            assert_eq!(tx.as_mut().poll_ready(cx), Poll::Ready(Ok(())));
+            assert_eq!(tx.as_mut().start_send(0), Ok(()));
+
+            // The channel only has room for the first item of the stream.
+            assert_eq!(tx.as_mut().poll_ready(cx), Poll::Pending);
+            assert!(!flag.take());
+            sassert_next(&mut rx, 0);
+            assert!(flag.take());
+            assert_eq!(tx.as_mut().poll_ready(cx), Poll::Ready(Ok(())));
+            sassert_next(&mut rx, 1);
+        })
+    }));
+}
+
 // test that the `with` sink doesn't require the underlying sink to flush,
 // but doesn't claim to be flushed until the underlying sink is
 #[test]
@@ -382,6 +464,24 @@ fn with_flush_propagate() {
     })
 }
 
+// test that `feed` hands items to the sink without waiting for it to flush
+#[test]
+fn feed_does_not_flush() {
+    let mut sink = ManualFlush::new();
+    flag_cx(|flag, cx| {
+        unwrap(sink.feed(Some(0)).poll_unpin(cx));
+        unwrap(sink.feed(Some(1)).poll_unpin(cx));
+
+        {
+            let mut task = sink.send(Some(2));
+            assert!(task.poll_unpin(cx).is_pending());
+            assert!(!flag.take());
+        }
+        assert_eq!(sink.force_flush(), vec![0, 1, 2]);
+        assert!(flag.take());
+    })
+}
+
 // test that `Clone` is implemented on `with` sinks
 #[test]
 fn with_implements_clone() {
@@ -441,12 +541,82 @@ fn buffer() {
     })
 }
 
+// test that buffered items are flushed once the timer armed by the first one
+// fires, even though nothing is sent after them, and that an explicit flush
+// disarms it
+#[test]
+fn buffer_timed() {
+    // A sink which only hands its items over when flushed.
+    struct Batches {
+        unflushed: Vec<i32>,
+        flushed: Rc<RefCell<Vec<i32>>>,
+    }
+
+    impl Sink<i32> for Batches {
+        type Error = Never;
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Never>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, item: i32) -> Result<(), Never> {
+            self.unflushed.push(item);
+            Ok(())
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Never>> {
+            let items = mem::replace(&mut self.unflushed, Vec::new());
+            self.flushed.borrow_mut().extend(items);
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Never>> {
+            self.poll_flush(cx)
+        }
+    }
+
+    let mut pool = LocalPool::new();
+    let flushed = Rc::new(RefCell::new(Vec::new()));
+    let timer = Rc::new(RefCell::new(None));
+    let timer2 = timer.clone();
+    let batches = Batches { unflushed: Vec::new(), flushed: flushed.clone() };
+    let (mut sink, linger) = batches.buffer_timed(4, move || {
+        let (tx, rx) = oneshot::channel::<()>();
+        *timer2.borrow_mut() = Some(tx);
+        rx.map(|_| ())
+    });
+    pool.spawner().spawn_local(linger).unwrap();
+
+    pool.run_until(sink.feed(0)).unwrap();
+    pool.run_until(sink.feed(1)).unwrap();
+    pool.run_until_stalled();
+    assert!(flushed.borrow().is_empty());
+
+    // Nothing else is sent, but the timer firing flushes the batch.
+    timer.borrow_mut().take().unwrap().send(()).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(*flushed.borrow(), [0, 1]);
+    assert!(timer.borrow().is_none());
+
+    pool.run_until(sink.feed(2)).unwrap();
+    pool.run_until_stalled();
+    let tx = timer.borrow_mut().take().unwrap();
+    pool.run_until(sink.flush()).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(*flushed.borrow(), [0, 1, 2]);
+    assert!(tx.is_canceled());
+
+    // Dropping the sink completes the linger task.
+    drop(sink);
+    pool.run();
+}
+
 #[test]
 fn fanout_smoke() {
     let sink1 = Vec::new();
     let sink2 = Vec::new();
     let mut sink = sink1.fanout(sink2);
-    block_on(sink.send_all(&mut stream::iter(vec![1, 2, 3]).map(Ok))).unwrap();
+    block_on(sink.send_all(&mut stream::iter(vec![1, 2, 3]).map(Ok::<_, Never>))).unwrap();
     let (sink1, sink2) = sink.into_inner();
     assert_eq!(sink1, vec![1, 2, 3]);
     assert_eq!(sink2, vec![1, 2, 3]);
@@ -498,6 +668,25 @@ fn sink_map_err() {
     assert_eq!(Pin::new(&mut tx.sink_map_err(|_| ())).start_send(()), Err(()));
 }
 
+#[test]
+fn drain() {
+    // `Drain` is cloneable even if the items aren't.
+    struct NotClone;
+
+    let mut drain = sink::drain::<NotClone>();
+    let mut drain2 = drain.clone();
+    let mut cx = panic_context();
+    assert_eq!(Pin::new(&mut drain).poll_ready(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(Pin::new(&mut drain).start_send(NotClone), Ok(()));
+    assert_eq!(Pin::new(&mut drain2).poll_flush(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(Pin::new(&mut drain2).poll_close(&mut cx), Poll::Ready(Ok(())));
+
+    block_on(async {
+        let mut stream = stream::iter(0..10).map(Ok::<_, Never>);
+        sink::drain().send_all(&mut stream).await.unwrap();
+    });
+}
+
 #[test]
 fn sink_unfold() {
     block_on(poll_fn(|cx| {
@@ -530,6 +719,30 @@ fn sink_unfold() {
     }))
 }
 
+#[test]
+fn sink_unfold_error() {
+    block_on(poll_fn(|cx| {
+        let unfold = sink::unfold(0, |count, i: i32| async move {
+            if i < 0 {
+                Err(count)
+            } else {
+                Ok(count + 1)
+            }
+        });
+        futures::pin_mut!(unfold);
+        assert_eq!(unfold.as_mut().poll_ready(cx), Poll::Ready(Ok(())));
+        assert_eq!(unfold.as_mut().start_send(1), Ok(()));
+        assert_eq!(unfold.as_mut().poll_ready(cx), Poll::Ready(Ok(())));
+        assert_eq!(unfold.as_mut().start_send(-1), Ok(()));
+        assert_eq!(unfold.as_mut().poll_flush(cx), Poll::Ready(Err(1)));
+
+        // The failed future must not be polled again.
+        assert_eq!(unfold.as_mut().poll_close(cx), Poll::Ready(Ok(())));
+
+        Poll::Ready(())
+    }))
+}
+
 #[test]
 fn err_into() {
     #[derive(Copy, Clone, Debug, PartialEq, Eq)]
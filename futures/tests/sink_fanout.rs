@@ -1,7 +1,7 @@
 use futures::channel::mpsc;
 use futures::executor::block_on;
-use futures::future::join3;
-use futures::sink::SinkExt;
+use futures::future::{self, join3};
+use futures::sink::{self, FanoutErrorPolicy, SinkExt};
 use futures::stream::{self, StreamExt};
 
 #[test]
@@ -22,3 +22,40 @@ fn it_works() {
     assert_eq!(vec1, expected);
     assert_eq!(vec2, expected);
 }
+
+#[test]
+fn fanout_all() {
+    let (txs, rxs): (Vec<_>, Vec<_>) = (0..3).map(|i| mpsc::channel(i)).unzip();
+    let tx = sink::fanout_all(txs).sink_map_err(|_| ());
+
+    let src = stream::iter((0..10).map(Ok));
+    let fwd = src.forward(tx);
+
+    let collect = future::join_all(rxs.into_iter().map(|rx| rx.collect::<Vec<_>>()));
+    let (_, vecs) = block_on(future::join(fwd, collect));
+
+    let expected = (0..10).collect::<Vec<_>>();
+    assert_eq!(vecs, vec![expected.clone(), expected.clone(), expected]);
+}
+
+#[test]
+fn fanout_all_error_policy() {
+    block_on(async {
+        let (tx1, rx1) = mpsc::unbounded();
+        let (tx2, rx2) = mpsc::unbounded();
+        drop(rx2);
+
+        let mut fanout = sink::fanout_all(vec![tx1.clone(), tx2.clone()]);
+        assert!(fanout.send(0).await.is_err());
+        drop(fanout);
+
+        let mut fanout =
+            sink::fanout_all(vec![tx1, tx2]).error_policy(FanoutErrorPolicy::DropFailing);
+        fanout.send(1).await.unwrap();
+        assert_eq!(fanout.len(), 1);
+        fanout.send(2).await.unwrap();
+        drop(fanout);
+
+        assert_eq!(rx1.collect::<Vec<_>>().await, vec![1, 2]);
+    });
+}
@@ -2,10 +2,10 @@ use futures::channel::mpsc;
 use futures::executor::block_on;
 use futures::future::{self, Future};
 use futures::sink::SinkExt;
-use futures::stream::{self, StreamExt};
-use futures::task::Poll;
+use futures::stream::{self, Stream, StreamExt};
+use futures::task::{Context, Poll};
 use futures::FutureExt;
-use futures_test::task::noop_context;
+use futures_test::task::{new_count_waker, noop_context};
 
 #[test]
 fn select() {
@@ -49,6 +49,92 @@ fn scan() {
     });
 }
 
+#[test]
+fn count() {
+    block_on(async {
+        assert_eq!(stream::iter(0..10).count().await, 10);
+        assert_eq!(stream::empty::<()>().count().await, 0);
+    });
+}
+
+#[test]
+fn collect_into() {
+    block_on(async {
+        let mut set = std::collections::BTreeSet::new();
+        set.insert(0);
+
+        let res = stream::iter(vec![3, 1, 3, 2]).collect_into(&mut set).await;
+        res.insert(4);
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+
+        let map: std::collections::HashMap<_, _> =
+            stream::iter(vec![(1, 'a'), (2, 'b')]).collect().await;
+        assert_eq!(map[&2], 'b');
+    });
+}
+
+#[test]
+fn dedup() {
+    block_on(async {
+        let values: Vec<_> = stream::iter(vec![1, 1, 2, 3, 3, 3, 2, 2, 1]).dedup().collect().await;
+        assert_eq!(values, vec![1, 2, 3, 2, 1]);
+
+        let values: Vec<_> = stream::iter(vec![(1, 'a'), (1, 'b'), (2, 'c'), (1, 'd')])
+            .dedup_by_key(|&(k, _)| k)
+            .collect()
+            .await;
+        assert_eq!(values, vec![(1, 'a'), (2, 'c'), (1, 'd')]);
+    });
+}
+
+#[test]
+fn switch_map() {
+    block_on(async {
+        let (outer_tx, outer_rx) = mpsc::unbounded::<mpsc::UnboundedReceiver<i32>>();
+        let mut s = outer_rx.switch_map(|rx| rx);
+
+        let (tx1, rx1) = mpsc::unbounded();
+        outer_tx.unbounded_send(rx1).unwrap();
+        tx1.unbounded_send(1).unwrap();
+        assert_eq!(s.next().await, Some(1));
+
+        // A new outer item replaces the previous inner stream, even though it
+        // still has items queued.
+        let (tx2, rx2) = mpsc::unbounded();
+        tx1.unbounded_send(2).unwrap();
+        outer_tx.unbounded_send(rx2).unwrap();
+        tx2.unbounded_send(3).unwrap();
+        assert_eq!(s.next().await, Some(3));
+        assert!(tx1.is_closed());
+
+        // The stream keeps going while the last inner stream is alive.
+        drop(outer_tx);
+        assert!(s.next().poll_unpin(&mut noop_context()).is_pending());
+        tx2.unbounded_send(4).unwrap();
+        drop(tx2);
+        assert_eq!(s.next().await, Some(4));
+        assert_eq!(s.next().await, None);
+    });
+}
+
+#[test]
+fn switch_map_always_ready_outer() {
+    // An outer stream that is always ready doesn't keep the stream from
+    // yielding the items of its latest inner stream.
+    let s = stream::iter(0..).switch_map(|i| stream::iter(Some(i)));
+    let items = block_on(s.take(3).collect::<Vec<_>>());
+    assert_eq!(items.len(), 3);
+    assert!(items.windows(2).all(|w| w[0] < w[1]));
+
+    // Nor from returning when its inner streams are pending, in which case it
+    // wakes itself to drain the rest of the outer stream.
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut s = stream::iter(0..).switch_map(|_| stream::pending::<()>());
+    assert!(s.poll_next_unpin(&mut cx).is_pending());
+    assert_eq!(counter, 1);
+}
+
 #[test]
 fn take_until() {
     fn make_stop_fut(stop_on: u32) -> impl Future<Output = ()> {
@@ -129,6 +215,14 @@ fn ready_chunks_panic_on_cap_zero() {
     let _ = rx1.ready_chunks(0);
 }
 
+#[test]
+#[should_panic]
+fn windows_panic_on_size_zero() {
+    let (_, rx1) = mpsc::channel::<()>(1);
+
+    let _ = rx1.windows(0);
+}
+
 #[test]
 fn ready_chunks() {
     let (mut tx, rx1) = mpsc::channel::<i32>(16);
@@ -149,3 +243,17 @@ fn ready_chunks() {
         assert_eq!(s.next().await.unwrap(), vec![4]);
     });
 }
+
+#[test]
+fn windows() {
+    block_on(async {
+        let values: Vec<_> = stream::iter(1..=5).windows(3).collect().await;
+        assert_eq!(values, vec![vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 5]]);
+
+        let values: Vec<Vec<i32>> = stream::iter(1..=2).windows(3).collect().await;
+        assert!(values.is_empty());
+
+        let stream = stream::iter(1..=5).windows(3);
+        assert_eq!(stream.size_hint(), (3, Some(3)));
+    });
+}
@@ -1,4 +1,5 @@
 use futures::executor::block_on;
+use futures::never::Never;
 use futures::sink::{Sink, SinkExt};
 use futures::stream::{self, Stream, StreamExt};
 use futures::task::{Context, Poll};
@@ -44,13 +45,22 @@ fn test_split() {
     }
 
     let mut dest: Vec<i32> = Vec::new();
+    let mut other_dest: Vec<i32> = Vec::new();
     {
         let join = Join { stream: stream::iter(vec![10, 20, 30]), sink: &mut dest };
+        let other = Join { stream: stream::iter(Vec::new()), sink: &mut other_dest };
 
         let (sink, stream) = join.split();
+        let (other_sink, other_stream) = other.split();
+        assert!(sink.is_pair_of(&stream));
+        assert!(stream.is_pair_of(&sink));
+        assert!(!sink.is_pair_of(&other_stream));
+        assert!(!other_stream.is_pair_of(&sink));
+        drop((other_sink, other_stream));
+
         let join = sink.reunite(stream).expect("test_split: reunite error");
         let (mut sink, stream) = join.split();
-        let mut stream = stream.map(Ok);
+        let mut stream = stream.map(Ok::<_, Never>);
         block_on(sink.send_all(&mut stream)).unwrap();
     }
     assert_eq!(dest, vec![10, 20, 30]);
@@ -56,6 +56,24 @@ fn ref_wake_same() {
     assert!(w2.will_wake(&w3));
 }
 
+#[test]
+fn waker_ref_borrows_arc() {
+    let some_w = Arc::new(CountingWaker::new());
+
+    let w1 = task::waker_ref(&some_w);
+    assert_eq!(1, Arc::strong_count(&some_w));
+    w1.wake_by_ref();
+    assert_eq!(1, some_w.wakes());
+
+    // Cloning the borrowed waker creates an owned one.
+    let w2 = w1.clone();
+    assert_eq!(2, Arc::strong_count(&some_w));
+    drop(w1);
+    w2.wake();
+    assert_eq!(2, some_w.wakes());
+    assert_eq!(1, Arc::strong_count(&some_w));
+}
+
 #[test]
 fn proper_refcount_on_wake_panic() {
     struct PanicWaker;
@@ -1,6 +1,7 @@
 use futures::executor::block_on;
 use futures::future::poll_fn;
 use futures::task::{AtomicWaker, Poll};
+use futures_test::task::new_count_waker;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -46,3 +47,19 @@ fn basic() {
 
     t.join().unwrap();
 }
+
+#[test]
+fn wake_by_ref_keeps_waker() {
+    let atomic_waker = AtomicWaker::new();
+    let (waker, count) = new_count_waker();
+    atomic_waker.register(&waker);
+
+    atomic_waker.wake_by_ref();
+    atomic_waker.wake_by_ref();
+    assert_eq!(count, 2);
+
+    assert!(atomic_waker.take().is_some());
+    atomic_waker.wake_by_ref();
+    atomic_waker.wake();
+    assert_eq!(count, 2);
+}
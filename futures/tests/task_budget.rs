@@ -0,0 +1,83 @@
+use futures::channel::mpsc;
+use futures::executor::{block_on, LocalPool};
+use futures::future::poll_fn;
+use futures::stream::StreamExt;
+use futures::task::{consume_budget, noop_waker_ref, with_budget, Context, LocalSpawnExt, Poll};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+#[test]
+fn unbudgeted_outside_of_executors() {
+    let mut cx = Context::from_waker(noop_waker_ref());
+    for _ in 0..10_000 {
+        assert_eq!(Pin::new(&mut consume_budget()).poll(&mut cx), Poll::Ready(()));
+    }
+}
+
+#[test]
+fn exhausted_budget_yields() {
+    let mut cx = Context::from_waker(noop_waker_ref());
+    with_budget(|| {
+        let mut consumed = 0;
+        while Pin::new(&mut consume_budget()).poll(&mut cx).is_ready() {
+            consumed += 1;
+        }
+        assert!(consumed > 0);
+        assert!(consumed < 10_000);
+
+        // A nested budget is fresh, and the outer one is restored afterwards.
+        with_budget(|| assert!(Pin::new(&mut consume_budget()).poll(&mut cx).is_ready()));
+        assert!(Pin::new(&mut consume_budget()).poll(&mut cx).is_pending());
+    });
+}
+
+#[test]
+fn block_on_repolls_yielding_future() {
+    let mut polls = 0;
+    let mut fut = Box::pin(async {
+        for _ in 0..10_000 {
+            consume_budget().await;
+        }
+    });
+    block_on(poll_fn(|cx| {
+        polls += 1;
+        fut.as_mut().poll(cx)
+    }));
+    assert!(polls > 1);
+}
+
+#[test]
+fn receiver_yields_to_other_tasks() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+
+    let (tx, mut rx) = mpsc::unbounded();
+    for i in 0..10_000 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+
+    let received = Rc::new(Cell::new(0));
+    let received_by_other = Rc::new(Cell::new(None));
+    {
+        let received = received.clone();
+        spawner
+            .spawn_local(async move {
+                while rx.next().await.is_some() {
+                    received.set(received.get() + 1);
+                }
+            })
+            .unwrap();
+    }
+    {
+        let received = received.clone();
+        let received_by_other = received_by_other.clone();
+        spawner.spawn_local(async move { received_by_other.set(Some(received.get())) }).unwrap();
+    }
+
+    pool.run();
+    assert_eq!(received.get(), 10_000);
+    assert!(received_by_other.get().unwrap() < 10_000);
+}
@@ -0,0 +1,92 @@
+use futures::channel::oneshot;
+use futures::executor::{block_on, LocalPool, ThreadPool};
+use futures::task::{yield_now, LocalSpawnExt, SpawnExt};
+use futures::task_local;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+task_local! {
+    static NUMBER: u32;
+    static NAME: &'static str;
+}
+
+#[test]
+fn scope_across_await() {
+    let (tx, rx) = oneshot::channel::<()>();
+    let result = Rc::new(RefCell::new(None));
+    let fut = NUMBER.scope(1, {
+        let result = result.clone();
+        async move {
+            assert_eq!(NUMBER.get(), 1);
+            rx.await.unwrap();
+            *result.borrow_mut() = Some(NUMBER.with(|n| n + 1));
+        }
+    });
+
+    let mut pool = LocalPool::new();
+    pool.spawner().spawn_local(fut).unwrap();
+    pool.run_until_stalled();
+    assert!(NUMBER.try_with(|_| ()).is_err());
+    tx.send(()).unwrap();
+    pool.run();
+    assert_eq!(*result.borrow(), Some(2));
+}
+
+#[test]
+fn nested_scopes() {
+    block_on(NUMBER.scope(1, async {
+        NAME.scope("inner", async {
+            assert_eq!(NUMBER.scope(2, async { NUMBER.get() }).await, 2);
+            assert_eq!(NUMBER.get(), 1);
+            assert_eq!(NAME.get(), "inner");
+        })
+        .await;
+        assert!(NAME.try_with(|_| ()).is_err());
+    }));
+    assert_eq!(NUMBER.sync_scope(3, || NUMBER.get()), 3);
+}
+
+#[test]
+fn local_pool_interleaved() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    for n in 0..3 {
+        let log = log.clone();
+        let fut = NUMBER.scope(n, async move {
+            for _ in 0..2 {
+                log.borrow_mut().push(NUMBER.get());
+                yield_now().await;
+            }
+        });
+        spawner.spawn_local(fut).unwrap();
+    }
+    pool.run();
+
+    assert_eq!(*log.borrow(), [0, 1, 2, 0, 1, 2]);
+}
+
+#[test]
+fn thread_pool() {
+    let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    for n in 0..8 {
+        let tx = tx.clone();
+        let fut = NUMBER.scope(n, async move {
+            let (wake_tx, wake_rx) = oneshot::channel();
+            thread::spawn(move || wake_tx.send(()).unwrap());
+            wake_rx.await.unwrap();
+            tx.send((n, NUMBER.get())).unwrap();
+        });
+        pool.spawn(fut).unwrap();
+    }
+    drop(tx);
+
+    let mut results: Vec<_> = rx.iter().collect();
+    results.sort_unstable();
+    assert_eq!(results, (0..8).map(|n| (n, n)).collect::<Vec<_>>());
+}
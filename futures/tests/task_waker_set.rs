@@ -0,0 +1,70 @@
+use futures::task::{Context, Poll, WakerSet};
+use futures_test::task::{new_count_waker, noop_context};
+
+#[test]
+fn wakes_in_order() {
+    let (first_waker, first_count) = new_count_waker();
+    let (second_waker, second_count) = new_count_waker();
+    let mut set = WakerSet::new();
+    let first = set.insert(&first_waker);
+    let second = set.insert(&second_waker);
+    assert_eq!(set.len(), 2);
+
+    assert!(set.wake_one());
+    assert_eq!(first_count, 1);
+    assert_eq!(second_count, 0);
+    assert_eq!(set.poll_woken(second, &mut Context::from_waker(&second_waker)), Poll::Pending);
+    assert_eq!(set.poll_woken(first, &mut Context::from_waker(&first_waker)), Poll::Ready(()));
+
+    assert!(set.wake_one());
+    assert_eq!(second_count, 1);
+    assert!(!set.wake_one());
+    assert!(set.is_empty());
+}
+
+#[test]
+fn poll_woken_updates_waker() {
+    let (old_waker, old_count) = new_count_waker();
+    let (new_waker, new_count) = new_count_waker();
+    let mut set = WakerSet::new();
+    let key = set.insert(&old_waker);
+
+    assert_eq!(set.poll_woken(key, &mut Context::from_waker(&new_waker)), Poll::Pending);
+    set.wake_one();
+    assert_eq!(old_count, 0);
+    assert_eq!(new_count, 1);
+}
+
+#[test]
+fn remove_reports_lost_wakeup() {
+    let mut cx = noop_context();
+    let mut set = WakerSet::new();
+    let first = set.insert(cx.waker());
+    let second = set.insert(cx.waker());
+    let third = set.insert(cx.waker());
+
+    // Removing a task which is still waiting keeps the order of the others.
+    assert!(!set.remove(second));
+    set.wake_one();
+    assert_eq!(set.poll_woken(third, &mut cx), Poll::Pending);
+
+    // The first task was woken but gives up, so the wakeup is passed on.
+    assert!(set.remove(first));
+    set.wake_one();
+    assert_eq!(set.poll_woken(third, &mut cx), Poll::Ready(()));
+}
+
+#[test]
+fn wake_all() {
+    let (waker, count) = new_count_waker();
+    let mut set = WakerSet::new();
+    let keys: Vec<_> = (0..3).map(|_| set.insert(&waker)).collect();
+
+    assert_eq!(set.wake_all(), 3);
+    assert_eq!(count, 3);
+    assert!(set.is_empty());
+    for key in keys {
+        assert_eq!(set.poll_woken(key, &mut Context::from_waker(&waker)), Poll::Ready(()));
+    }
+    assert_eq!(set.wake_all(), 0);
+}
@@ -0,0 +1,42 @@
+use futures::executor::LocalPool;
+use futures::task::{yield_now, LocalSpawnExt};
+use futures_test::task::new_count_waker;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+#[test]
+fn pending_once_and_woken() {
+    let (waker, count) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = yield_now();
+
+    assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Pending);
+    assert_eq!(count, 1);
+    assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(()));
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn tasks_take_turns() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    for task in 0..2 {
+        let log = log.clone();
+        spawner
+            .spawn_local(async move {
+                for step in 0..3 {
+                    log.borrow_mut().push((task, step));
+                    yield_now().await;
+                }
+            })
+            .unwrap();
+    }
+    pool.run();
+
+    assert_eq!(*log.borrow(), [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2), (1, 2)]);
+}
@@ -0,0 +1,547 @@
+use futures::channel::oneshot;
+use futures::executor::{
+    self, block_on, IdleStrategy, Instrument, Park, Priority, TaskId, TaskState, ThreadPool, Unpark,
+};
+use futures::future;
+use futures::task::{Spawn, SpawnExt};
+use std::panic;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+#[test]
+fn shutdown_waits_for_tasks() {
+    let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let done = Arc::new(AtomicBool::new(false));
+
+    let done2 = done.clone();
+    pool.spawn_ok(async move {
+        thread::sleep(Duration::from_millis(50));
+        done2.store(true, Ordering::SeqCst);
+    });
+
+    assert_eq!(pool.shutdown(Duration::from_secs(10)), 0);
+    assert!(done.load(Ordering::SeqCst));
+    assert!(pool.spawn(async {}).unwrap_err().is_shutdown());
+    assert!(pool.status().unwrap_err().is_shutdown());
+}
+
+#[test]
+fn shutdown_abandons_pending_tasks() {
+    let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    pool.spawn_ok(future::pending());
+    pool.spawn_ok(async {});
+
+    assert_eq!(pool.shutdown(Duration::from_millis(50)), 1);
+    assert_eq!(pool.shutdown(Duration::from_millis(50)), 0);
+}
+
+#[test]
+fn shutdown_rejects_new_tasks() {
+    let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let pool2 = pool.clone();
+    pool.spawn_ok(async move {
+        while pool2.status().is_ok() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        tx.send(pool2.spawn(async {}).unwrap_err().is_shutting_down()).unwrap();
+    });
+
+    assert_eq!(pool.shutdown(Duration::from_secs(10)), 0);
+    assert!(rx.recv().unwrap());
+}
+
+#[test]
+fn shutdown_drops_new_tasks() {
+    let spawned = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let (spawned2, completed2) = (spawned.clone(), completed.clone());
+    let pool = ThreadPool::builder()
+        .pool_size(2)
+        .on_spawn(move || {
+            spawned2.fetch_add(1, Ordering::SeqCst);
+        })
+        .on_complete(move || {
+            completed2.fetch_add(1, Ordering::SeqCst);
+        })
+        .create()
+        .unwrap();
+    assert_eq!(pool.shutdown(Duration::from_secs(10)), 0);
+
+    let (tx, rx) = oneshot::channel::<()>();
+    pool.spawn_ok(async move {
+        let _tx = tx;
+    });
+    assert!(block_on(rx).is_err());
+    assert_eq!(pool.alive_tasks(), 0);
+    assert_eq!(pool.queued_tasks(), 0);
+    assert_eq!(spawned.load(Ordering::SeqCst), 1);
+    assert_eq!(completed.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn metrics() {
+    let spawned = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let (spawned2, completed2) = (spawned.clone(), completed.clone());
+    let pool = ThreadPool::builder()
+        .pool_size(2)
+        .on_spawn(move || {
+            spawned2.fetch_add(1, Ordering::SeqCst);
+        })
+        .on_complete(move || {
+            completed2.fetch_add(1, Ordering::SeqCst);
+        })
+        .create()
+        .unwrap();
+    assert_eq!(pool.size(), 2);
+
+    let (tx, rx) = mpsc::channel();
+    for _ in 0..4 {
+        let tx = tx.clone();
+        pool.spawn_ok(async move {
+            thread::sleep(Duration::from_millis(10));
+            tx.send(()).unwrap();
+        });
+    }
+    pool.spawn_ok(future::pending());
+    for _ in 0..4 {
+        rx.recv().unwrap();
+    }
+
+    assert_eq!(pool.shutdown(Duration::from_millis(50)), 1);
+    assert_eq!(pool.alive_tasks(), 1);
+    assert_eq!(pool.queued_tasks(), 0);
+    assert_eq!(pool.total_polls(), 5);
+    assert!(pool.busy_time(0) + pool.busy_time(1) >= Duration::from_millis(40));
+    assert_eq!(spawned.load(Ordering::SeqCst), 5);
+    assert_eq!(completed.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn spawn_from_tasks() {
+    let pool = ThreadPool::builder().pool_size(4).create().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    for i in 0..100 {
+        let (pool2, tx) = (pool.clone(), tx.clone());
+        pool.spawn_ok(async move {
+            for j in 0..10 {
+                let tx = tx.clone();
+                pool2.spawn_ok(async move { tx.send(i * 10 + j).unwrap() });
+            }
+        });
+    }
+    drop(tx);
+
+    let mut received: Vec<_> = rx.iter().take(1000).collect();
+    received.sort_unstable();
+    assert_eq!(received, (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn priorities() {
+    let pool = ThreadPool::builder().pool_size(1).priority_levels(2).create().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    // Keep the single worker busy while the tasks are spawned.
+    let (block_tx, block_rx) = mpsc::channel::<()>();
+    pool.spawn_ok(async move { block_rx.recv().unwrap() });
+    for i in 0..40 {
+        let tx = tx.clone();
+        pool.spawn_ok(async move { tx.send((0, i)).unwrap() });
+    }
+    for i in 0..40 {
+        let tx = tx.clone();
+        pool.spawn_with_priority(async move { tx.send((1, i)).unwrap() }, Priority::new(1))
+            .unwrap();
+    }
+    drop(tx);
+    block_tx.send(()).unwrap();
+
+    let order: Vec<_> = rx.iter().collect();
+    assert_eq!(order.len(), 80);
+    // High priority tasks run first, but low priority ones aren't starved.
+    assert_eq!(order[0], (1, 0));
+    let first_low = order.iter().position(|&(level, _)| level == 0).unwrap();
+    assert!(first_low < 40);
+    // Each level runs in order.
+    for level in 0..2 {
+        let tasks: Vec<_> = order.iter().filter(|t| t.0 == level).map(|t| t.1).collect();
+        assert_eq!(tasks, (0..40).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn instrument() {
+    struct Recorder(Arc<Mutex<Vec<(&'static str, TaskId)>>>);
+
+    impl Instrument for Recorder {
+        fn on_poll_start(&self, task: TaskId) {
+            self.0.lock().unwrap().push(("start", task));
+        }
+
+        fn on_poll_end(&self, task: TaskId, _duration: Duration) {
+            self.0.lock().unwrap().push(("end", task));
+        }
+
+        fn on_wake(&self, task: TaskId) {
+            self.0.lock().unwrap().push(("wake", task));
+        }
+    }
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let pool =
+        ThreadPool::builder().pool_size(1).instrument(Recorder(events.clone())).create().unwrap();
+    let (tx, rx) = oneshot::channel();
+    pool.spawn_ok(async move { rx.await.unwrap() });
+
+    while events.lock().unwrap().len() < 2 {
+        thread::sleep(Duration::from_millis(1));
+    }
+    tx.send(()).unwrap();
+    assert_eq!(pool.shutdown(Duration::from_secs(10)), 0);
+
+    let events = events.lock().unwrap();
+    let task = events[0].1;
+    assert!(events.iter().all(|&(_, id)| id == task));
+    let names: Vec<_> = events.iter().map(|&(name, _)| name).collect();
+    assert_eq!(names, ["start", "end", "wake", "start", "end"]);
+}
+
+#[test]
+fn join_handle() {
+    let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+
+    let handle = pool.spawn(async { 1 + 2 }).unwrap();
+    assert_eq!(block_on(handle), Ok(3));
+
+    let handle = pool.spawn(future::pending::<()>()).unwrap();
+    assert!(!handle.is_finished());
+    handle.abort();
+    let err = block_on(handle).unwrap_err();
+    assert!(err.is_aborted());
+
+    let (tx, rx) = oneshot::channel();
+    pool.spawn(async move { tx.send(()).unwrap() }).unwrap().detach();
+    block_on(rx).unwrap();
+}
+
+#[test]
+fn resize() {
+    let started = Arc::new(AtomicUsize::new(0));
+    let stopped = Arc::new(AtomicUsize::new(0));
+    let (started2, stopped2) = (started.clone(), stopped.clone());
+    let pool = ThreadPool::builder()
+        .pool_size(1)
+        .max_size(3)
+        .after_start(move |_| {
+            started2.fetch_add(1, Ordering::SeqCst);
+        })
+        .before_stop(move |_| {
+            stopped2.fetch_add(1, Ordering::SeqCst);
+        })
+        .create()
+        .unwrap();
+    let wait_for = |counter: &AtomicUsize, n| {
+        while counter.load(Ordering::SeqCst) != n {
+            thread::sleep(Duration::from_millis(1));
+        }
+    };
+
+    assert_eq!(pool.resize(5).unwrap(), 3);
+    assert_eq!(pool.size(), 3);
+    wait_for(&started, 3);
+
+    // A task blocking its worker keeps running while the pool shrinks.
+    let (tx, rx) = mpsc::channel();
+    let blocked = pool.spawn(async move { rx.recv().unwrap() }).unwrap();
+    let others: Vec<_> = (0..3).map(|i| pool.spawn(async move { i }).unwrap()).collect();
+    assert_eq!(pool.resize(0).unwrap(), 1);
+    tx.send(()).unwrap();
+    block_on(blocked).unwrap();
+    for (i, handle) in others.into_iter().enumerate() {
+        assert_eq!(block_on(handle), Ok(i));
+    }
+    wait_for(&stopped, 2);
+
+    // The remaining worker runs the tasks.
+    assert_eq!(block_on(pool.spawn(async { 1 }).unwrap()), Ok(1));
+    assert_eq!(pool.resize(2).unwrap(), 2);
+    wait_for(&started, 4);
+}
+
+#[test]
+fn panic_handler() {
+    let (tx, rx) = mpsc::channel();
+    let pool = ThreadPool::builder()
+        .pool_size(1)
+        .panic_handler(move |task, payload| {
+            tx.send((task, *payload.downcast::<&str>().unwrap())).unwrap();
+        })
+        .create()
+        .unwrap();
+
+    let handle = pool.spawn(async { panic!("boom") }).unwrap();
+    let (_, payload) = rx.recv().unwrap();
+    assert_eq!(payload, "boom");
+    assert!(!block_on(handle).unwrap_err().is_aborted());
+
+    // The worker keeps running tasks.
+    assert_eq!(block_on(pool.spawn(async { 1 }).unwrap()), Ok(1));
+}
+
+#[test]
+fn spawn_blocking() {
+    let pool = ThreadPool::builder().pool_size(1).create().unwrap();
+
+    // The blocked functions don't stall the worker of the pool.
+    let (tx, rx) = mpsc::channel::<()>();
+    let rx = Arc::new(Mutex::new(rx));
+    let blocked: Vec<_> = (0..4)
+        .map(|_| {
+            let rx = rx.clone();
+            pool.spawn(executor::spawn_blocking(move || rx.lock().unwrap().recv().is_err()))
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(block_on(pool.spawn(async { 1 }).unwrap()), Ok(1));
+    drop(tx);
+    for handle in blocked {
+        assert_eq!(block_on(handle), Ok(true));
+    }
+
+    // A panic is propagated to the awaiting task.
+    let res = panic::catch_unwind(|| block_on(executor::spawn_blocking(|| panic!("boom"))));
+    assert_eq!(*res.unwrap_err().downcast::<&str>().unwrap(), "boom");
+}
+
+#[test]
+fn from_blocking_iter() {
+    use futures::stream::{FusedStream, StreamExt};
+
+    // The iterator blocks on a channel fed by another thread.
+    let (tx, rx) = mpsc::channel();
+    let mut stream = executor::from_blocking_iter(rx.into_iter());
+    let feeder = thread::spawn(move || {
+        for i in 0..3 {
+            thread::sleep(Duration::from_millis(10));
+            tx.send(i).unwrap();
+        }
+    });
+
+    assert_eq!(block_on((&mut stream).collect::<Vec<_>>()), [0, 1, 2]);
+    assert!(stream.is_terminated());
+    assert_eq!(block_on(stream.next()), None);
+    feeder.join().unwrap();
+}
+
+#[test]
+fn keep_alive() {
+    let parked = Arc::new(AtomicUsize::new(0));
+    let stopped = Arc::new(AtomicUsize::new(0));
+    let (parked2, unparked2, stopped2) = (parked.clone(), parked.clone(), stopped.clone());
+    let pool = ThreadPool::builder()
+        .pool_size(3)
+        .min_size(1)
+        .keep_alive(Duration::from_millis(10))
+        .on_park(move |_| {
+            parked2.fetch_add(1, Ordering::SeqCst);
+        })
+        .on_unpark(move |_| {
+            unparked2.fetch_sub(1, Ordering::SeqCst);
+        })
+        .before_stop(move |_| {
+            stopped2.fetch_add(1, Ordering::SeqCst);
+        })
+        .create()
+        .unwrap();
+
+    // The idle workers stop down to the minimum size.
+    while stopped.load(Ordering::SeqCst) != 2 {
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(pool.size(), 1);
+
+    // A worker is started again for a task while the other one is blocked.
+    let (tx, rx) = mpsc::channel();
+    let blocked = pool.spawn(async move { rx.recv().unwrap() }).unwrap();
+    assert_eq!(block_on(pool.spawn(async { 1 }).unwrap()), Ok(1));
+    assert!(pool.size() > 1);
+    tx.send(()).unwrap();
+    block_on(blocked).unwrap();
+
+    // The remaining worker is parked.
+    while parked.load(Ordering::SeqCst) != 1 {
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn idle_strategy() {
+    let parked = Arc::new(AtomicUsize::new(0));
+    let parked2 = parked.clone();
+    let pool = ThreadPool::builder()
+        .pool_size(2)
+        .idle_strategy(IdleStrategy::new(100, 10))
+        .on_park(move |_| {
+            parked2.fetch_add(1, Ordering::SeqCst);
+        })
+        .create()
+        .unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    for i in 0..100 {
+        let tx = tx.clone();
+        pool.spawn_ok(async move { tx.send(i).unwrap() });
+    }
+    let mut received: Vec<_> = rx.iter().take(100).collect();
+    received.sort_unstable();
+    assert_eq!(received, (0..100).collect::<Vec<_>>());
+
+    // The workers park once done spinning, and are woken up for new tasks.
+    while parked.load(Ordering::SeqCst) < 2 {
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(block_on(pool.spawn(async { 1 }).unwrap()), Ok(1));
+    assert_eq!(pool.shutdown(Duration::from_secs(10)), 0);
+}
+
+#[test]
+fn park_with() {
+    struct CountPark {
+        thread: Thread,
+        parks: Arc<AtomicUsize>,
+        unparks: Arc<AtomicUsize>,
+    }
+
+    struct CountUnpark {
+        thread: Thread,
+        unparks: Arc<AtomicUsize>,
+    }
+
+    impl Park for CountPark {
+        type Unpark = CountUnpark;
+
+        fn unpark(&self) -> CountUnpark {
+            CountUnpark { thread: self.thread.clone(), unparks: self.unparks.clone() }
+        }
+
+        fn park(&mut self) {
+            self.parks.fetch_add(1, Ordering::SeqCst);
+            thread::park();
+        }
+    }
+
+    impl Unpark for CountUnpark {
+        fn unpark(&self) {
+            self.unparks.fetch_add(1, Ordering::SeqCst);
+            self.thread.unpark();
+        }
+    }
+
+    let parks = Arc::new(AtomicUsize::new(0));
+    let unparks = Arc::new(AtomicUsize::new(0));
+    let (parks2, unparks2) = (parks.clone(), unparks.clone());
+    let pool = ThreadPool::builder()
+        .pool_size(2)
+        .park_with(move |_| CountPark {
+            thread: thread::current(),
+            parks: parks2.clone(),
+            unparks: unparks2.clone(),
+        })
+        .create()
+        .unwrap();
+
+    // A task spawned while both workers are parked unparks one of them.
+    while parks.load(Ordering::SeqCst) < 2 {
+        thread::sleep(Duration::from_millis(1));
+    }
+    let task = pool.spawn(async { 1 }).unwrap();
+    assert_eq!(unparks.load(Ordering::SeqCst), 1);
+    assert_eq!(block_on(task), Ok(1));
+
+    for i in 0..10 {
+        assert_eq!(block_on(pool.spawn(async move { i }).unwrap()), Ok(i));
+    }
+    assert_eq!(pool.shutdown(Duration::from_secs(10)), 0);
+}
+
+#[test]
+fn spawn_futures_of_any_size() {
+    struct DropCount(Arc<AtomicUsize>);
+
+    impl Drop for DropCount {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[repr(align(64))]
+    struct Aligned(u8);
+
+    let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = mpsc::channel();
+
+    // A small future, stored in its task.
+    let (tx1, guard) = (tx.clone(), DropCount(dropped.clone()));
+    pool.spawn_ok(async move {
+        let _guard = guard;
+        tx1.send(1).unwrap();
+    });
+    // A large future, boxed.
+    let (tx2, guard, buf) = (tx.clone(), DropCount(dropped.clone()), [2; 1024]);
+    pool.spawn_ok(async move {
+        let _guard = guard;
+        tx2.send(buf[1023]).unwrap();
+    });
+    // A future aligned to more than the storage of its task, boxed.
+    let (tx3, guard, aligned) = (tx, DropCount(dropped.clone()), Aligned(3));
+    pool.spawn_ok(async move {
+        let _guard = guard;
+        tx3.send(aligned.0).unwrap();
+    });
+
+    let mut received: Vec<_> = rx.iter().collect();
+    received.sort_unstable();
+    assert_eq!(received, [1, 2, 3]);
+    while dropped.load(Ordering::SeqCst) != 3 {
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn dump_tasks() {
+    let pool = ThreadPool::builder().pool_size(1).task_dump(true).create().unwrap();
+    let (tx, rx) = oneshot::channel::<()>();
+    pool.spawn_ok(async {
+        rx.await.unwrap();
+    });
+    let line = line!() - 3;
+    pool.spawn(future::pending::<()>()).unwrap();
+
+    let tasks = loop {
+        let tasks = pool.dump_tasks();
+        if tasks.iter().all(|task| task.state() == TaskState::Idle) {
+            break tasks;
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert_eq!(tasks.len(), 2);
+    assert!(tasks[0].id() < tasks[1].id());
+    let spawned_at = tasks[0].spawned_at().unwrap();
+    assert_eq!((spawned_at.file(), spawned_at.line()), (file!(), line));
+    assert!(tasks[0].future_type().is_some());
+    assert_eq!(tasks[0].polls(), 1);
+    assert!(tasks[0].since_last_poll().is_some());
+    assert!(tasks[1].spawned_at().is_none());
+
+    tx.send(()).unwrap();
+    while pool.dump_tasks().len() != 1 {
+        thread::sleep(Duration::from_millis(1));
+    }
+}